@@ -30,9 +30,9 @@ use smallvec::SmallVec;
 use std::{mem, sync::Arc};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{
-    Avatar, AvatarAvailabilityIndicator, Button, Color, ContextMenu, Facepile, Icon, IconButton,
-    IconName, IconSize, Indicator, Label, ListHeader, ListItem, Tooltip, prelude::*,
-    tooltip_container,
+    Avatar, AvatarAvailabilityIndicator, Button, Color, ContextMenu, ElevationIndex, Facepile,
+    Icon, IconButton, IconName, IconSize, Indicator, Label, ListHeader, ListItem, Tooltip,
+    prelude::*, tooltip_container,
 };
 use util::{ResultExt, TryFutureExt, maybe};
 use workspace::{
@@ -3173,7 +3173,8 @@ impl Render for DraggedChannelView {
         let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
         h_flex()
             .font_family(ui_font)
-            .bg(cx.theme().colors().background)
+            .bg(ElevationIndex::DraggedElement.bg(cx))
+            .shadow(ElevationIndex::DraggedElement.shadow(cx))
             .w(self.width)
             .p_1()
             .gap_1()
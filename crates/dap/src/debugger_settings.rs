@@ -43,6 +43,26 @@ pub struct DebuggerSettings {
     ///
     /// Default: Bottom
     pub dock: DebugPanelDockPosition,
+    /// Whether `runInTerminal` requests should run their command in the session's
+    /// debug terminal when its shell is idle and has the same working directory
+    /// and environment, rather than spawning a new terminal.
+    ///
+    /// Default: true
+    pub reuse_terminal: bool,
+    /// Whether to expand the locals scope of the variable list when execution stops.
+    ///
+    /// Default: true
+    pub expand_locals_on_stop: bool,
+    /// How many levels of nested variables inside the locals scope to expand
+    /// eagerly when execution stops. 0 only expands the scope itself.
+    ///
+    /// Default: 0
+    pub expand_locals_depth: usize,
+    /// Whether to close a session's tab once the program being debugged finishes on its own,
+    /// instead of leaving it open to inspect its output.
+    ///
+    /// Default: false
+    pub close_ended_sessions: bool,
 }
 
 impl Default for DebuggerSettings {
@@ -55,6 +75,10 @@ impl Default for DebuggerSettings {
             log_dap_communications: true,
             format_dap_log_messages: true,
             dock: DebugPanelDockPosition::Bottom,
+            reuse_terminal: true,
+            expand_locals_on_stop: true,
+            expand_locals_depth: 0,
+            close_ended_sessions: false,
         }
     }
 }
@@ -32,10 +32,8 @@ impl CodeLldbDebugAdapter {
         configuration
             .as_object_mut()
             .context("CodeLLDB is not a valid json object")?
-            .insert(
-                "name".into(),
-                Value::String(String::from(task_definition.label.as_ref())),
-            );
+            .entry("name")
+            .or_insert_with(|| Value::String(String::from(task_definition.label.as_ref())));
 
         let request = self.validate_config(&configuration)?;
 
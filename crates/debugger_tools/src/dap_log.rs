@@ -9,8 +9,9 @@ use futures::{
     channel::mpsc::{UnboundedSender, unbounded},
 };
 use gpui::{
-    App, AppContext, Context, Empty, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
-    ParentElement, Render, SharedString, Styled, Subscription, WeakEntity, Window, actions, div,
+    App, AppContext, Context, Empty, Entity, EventEmitter, FocusHandle, Focusable, Global,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, WeakEntity, Window,
+    actions, div,
 };
 use project::{
     Project,
@@ -21,11 +22,14 @@ use settings::Settings as _;
 use std::{
     borrow::Cow,
     collections::{HashMap, VecDeque},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 use util::maybe;
 use workspace::{
-    ToolbarItemEvent, ToolbarItemView, Workspace,
+    SaveIntent, ToolbarItemEvent, ToolbarItemView, Workspace,
     item::Item,
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
     ui::{Button, Clickable, ContextMenu, Label, LabelCommon, PopoverMenu, h_flex},
@@ -55,6 +59,9 @@ struct ProjectState {
 struct DebugAdapterState {
     log_messages: VecDeque<String>,
     rpc_messages: RpcMessages,
+    /// Shared with the adapter client's RPC log handler, so that a session whose protocol log
+    /// is turned off, as it is until the user turns it on, doesn't copy or forward any messages.
+    rpc_log_enabled: Arc<AtomicBool>,
 }
 
 struct RpcMessages {
@@ -96,6 +103,7 @@ impl DebugAdapterState {
         Self {
             log_messages: VecDeque::new(),
             rpc_messages: RpcMessages::new(),
+            rpc_log_enabled: Arc::new(AtomicBool::new(false)),
         }
     }
 }
@@ -220,7 +228,14 @@ impl LogStore {
             );
             rpc_messages.last_message_kind = Some(kind);
         }
-        Self::add_debug_client_entry(&mut rpc_messages.messages, id, message, LogKind::Rpc, cx);
+        let timestamp = chrono::Local::now().format("%H:%M:%S%.3f");
+        Self::add_debug_client_entry(
+            &mut rpc_messages.messages,
+            id,
+            format!("// {timestamp}\n{message}"),
+            LogKind::Rpc,
+            cx,
+        );
 
         cx.notify();
     }
@@ -296,13 +311,16 @@ impl LogStore {
             .or_insert_with(DebugAdapterState::new);
 
         let io_tx = self.rpc_tx.clone();
+        let rpc_log_enabled = client_state.rpc_log_enabled.clone();
 
         let client = client.read(cx).adapter_client()?;
         client.add_log_handler(
             move |io_kind, message| {
-                io_tx
-                    .unbounded_send((client_id, io_kind, message.to_string()))
-                    .ok();
+                if rpc_log_enabled.load(Ordering::Relaxed) {
+                    io_tx
+                        .unbounded_send((client_id, io_kind, message.to_string()))
+                        .ok();
+                }
             },
             LogKind::Rpc,
         );
@@ -325,6 +343,29 @@ impl LogStore {
         cx.notify();
     }
 
+    pub fn rpc_log_enabled(&self, client_id: SessionId) -> bool {
+        self.debug_clients
+            .get(&client_id)
+            .is_some_and(|state| state.rpc_log_enabled.load(Ordering::Relaxed))
+    }
+
+    pub fn set_rpc_log_enabled(
+        &mut self,
+        client_id: SessionId,
+        enabled: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(state) = self.debug_clients.get_mut(&client_id) else {
+            return;
+        };
+        state.rpc_log_enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            // Start the next capture with a fresh direction header.
+            state.rpc_messages.last_message_kind = None;
+        }
+        cx.notify();
+    }
+
     fn log_messages_for_client(&mut self, client_id: SessionId) -> Option<&mut VecDeque<String>> {
         Some(&mut self.debug_clients.get_mut(&client_id)?.log_messages)
     }
@@ -588,7 +629,8 @@ impl DapLogView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let rpc_log = self.log_store.update(cx, |log_store, _| {
+        let rpc_log = self.log_store.update(cx, |log_store, cx| {
+            log_store.set_rpc_log_enabled(client_id, true, cx);
             log_store
                 .rpc_messages_for_client(client_id)
                 .map(|state| log_contents(&state))
@@ -686,8 +728,13 @@ impl Render for DapLogView {
 
 actions!(dev, [OpenDebugAdapterLogs]);
 
+struct GlobalLogStore(Entity<LogStore>);
+
+impl Global for GlobalLogStore {}
+
 pub fn init(cx: &mut App) {
     let log_store = cx.new(|cx| LogStore::new(cx));
+    cx.set_global(GlobalLogStore(log_store.clone()));
 
     cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(_window) = window else {
@@ -720,6 +767,56 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// Turns the protocol log of the given session on and shows its messages, or, when that log is
+/// already on screen, closes it and stops recording the session's messages.
+pub fn toggle_protocol_log(
+    workspace: &mut Workspace,
+    session_id: SessionId,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(log_store) = cx
+        .try_global::<GlobalLogStore>()
+        .map(|store| store.0.clone())
+    else {
+        return;
+    };
+    let open_view = workspace
+        .items_of_type::<DapLogView>(cx)
+        .find(|view| view.read(cx).current_view == Some((session_id, LogKind::Rpc)));
+
+    let enabled = log_store.read(cx).rpc_log_enabled(session_id);
+    match open_view {
+        Some(view) if enabled => {
+            log_store.update(cx, |log_store, cx| {
+                log_store.set_rpc_log_enabled(session_id, false, cx)
+            });
+            if let Some(pane) = workspace.pane_for(&view) {
+                let item_id = view.entity_id();
+                pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(item_id, SaveIntent::Skip, window, cx)
+                        .detach_and_log_err(cx);
+                });
+            }
+        }
+        Some(view) => {
+            log_store.update(cx, |log_store, cx| {
+                log_store.set_rpc_log_enabled(session_id, true, cx)
+            });
+            workspace.activate_item(&view, true, true, window, cx);
+        }
+        None => {
+            let project = workspace.project().clone();
+            let log_view = cx.new(|cx| {
+                let mut log_view = DapLogView::new(project, log_store, window, cx);
+                log_view.show_rpc_trace_for_server(session_id, window, cx);
+                log_view
+            });
+            workspace.add_item_to_active_pane(Box::new(log_view), None, true, window, cx);
+        }
+    }
+}
+
 impl Item for DapLogView {
     type Event = EditorEvent;
 
@@ -1,24 +1,37 @@
 use dap::{DapRegistry, DebugRequest};
 use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{AnyElement, Subscription, Task, WeakEntity};
 use gpui::{AppContext, DismissEvent, Entity, EventEmitter, Focusable, Render};
-use gpui::{Subscription, WeakEntity};
+use parking_lot::Mutex;
 use picker::{Picker, PickerDelegate};
 use task::ZedDebugConfig;
 use util::debug_panic;
 
 use std::sync::Arc;
-use sysinfo::System;
+use std::time::Duration;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 use ui::{Context, Tooltip, prelude::*};
 use ui::{ListItem, ListItemSpacing};
 use workspace::{ModalView, Workspace};
 
 use crate::debugger_panel::DebugPanel;
 
+/// How often the list of processes is refreshed while the modal is open.
+const PROCESS_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+
 #[derive(Debug, Clone)]
 pub(super) struct Candidate {
     pub(super) pid: u32,
     pub(super) name: SharedString,
     pub(super) command: Vec<String>,
+    /// When the process started, in seconds since the Unix epoch.
+    pub(super) start_time: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessSort {
+    Name,
+    RecentlyStarted,
 }
 
 pub(crate) struct AttachModalDelegate {
@@ -28,6 +41,12 @@ pub(crate) struct AttachModalDelegate {
     pub(crate) definition: ZedDebugConfig,
     workspace: WeakEntity<Workspace>,
     candidates: Arc<[Candidate]>,
+    sort: ProcessSort,
+    /// The process to keep selected once the matches are updated for a refreshed process list.
+    reselect_pid: Option<u32>,
+    refresh_task: Option<Task<()>>,
+    /// Kept across refreshes, so that each one only updates the processes it already knows of.
+    system: Arc<Mutex<System>>,
 }
 
 impl AttachModalDelegate {
@@ -36,19 +55,92 @@ impl AttachModalDelegate {
         definition: ZedDebugConfig,
         candidates: Arc<[Candidate]>,
     ) -> Self {
-        Self {
+        let mut this = Self {
             workspace,
             definition,
-            candidates,
+            candidates: Arc::default(),
             selected_index: 0,
             matches: Vec::default(),
             placeholder_text: Arc::from("Select the process you want to attach the debugger to"),
+            sort: ProcessSort::Name,
+            reselect_pid: None,
+            refresh_task: None,
+            system: Arc::new(Mutex::new(System::new())),
+        };
+        this.set_candidates(candidates.to_vec());
+        this
+    }
+
+    fn set_candidates(&mut self, mut candidates: Vec<Candidate>) {
+        self.reselect_pid = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.candidates.get(hit.candidate_id))
+            .map(|candidate| candidate.pid);
+
+        match self.sort {
+            ProcessSort::Name => {
+                candidates.sort_by(|a, b| a.name.cmp(&b.name).then(a.pid.cmp(&b.pid)))
+            }
+            ProcessSort::RecentlyStarted => {
+                candidates.sort_by(|a, b| b.start_time.cmp(&a.start_time).then(b.pid.cmp(&a.pid)))
+            }
         }
+        self.candidates = candidates.into();
+    }
+
+    pub(crate) fn set_sort(&mut self, sort: ProcessSort) {
+        self.sort = sort;
+        self.set_candidates(self.candidates.to_vec());
+    }
+
+    /// Lists the running processes again, in the background since that can take a while.
+    fn refresh_processes(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let system = self.system.clone();
+        self.refresh_task = Some(cx.spawn_in(window, async move |picker, cx| {
+            let processes = cx
+                .background_executor()
+                .spawn(async move { running_processes(&mut system.lock()) })
+                .await;
+            picker
+                .update_in(cx, |picker, window, cx| {
+                    picker.delegate.set_candidates(processes);
+                    picker.refresh(window, cx);
+                })
+                .ok();
+        }));
     }
 }
 
+/// Lists the running processes, refreshing only what the list shows of them rather than
+/// everything `System` can gather.
+fn running_processes(system: &mut System) -> Vec<Candidate> {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    system
+        .processes()
+        .values()
+        .map(|process| {
+            let name = process.name().to_string_lossy().into_owned();
+            Candidate {
+                name: name.into(),
+                pid: process.pid().as_u32(),
+                command: process
+                    .cmd()
+                    .iter()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .collect::<Vec<_>>(),
+                start_time: process.start_time(),
+            }
+        })
+        .collect()
+}
+
 pub struct AttachModal {
     _subscription: Subscription,
+    _refresh_task: Option<Task<()>>,
     pub(crate) picker: Entity<Picker<AttachModalDelegate>>,
 }
 
@@ -60,25 +152,28 @@ impl AttachModal {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let mut processes: Box<[_]> = System::new_all()
-            .processes()
-            .values()
-            .map(|process| {
-                let name = process.name().to_string_lossy().into_owned();
-                Candidate {
-                    name: name.into(),
-                    pid: process.pid().as_u32(),
-                    command: process
-                        .cmd()
-                        .iter()
-                        .map(|s| s.to_string_lossy().to_string())
-                        .collect::<Vec<_>>(),
+        let mut system = System::new();
+        let processes = running_processes(&mut system).into();
+        let mut this = Self::with_processes(workspace, definition, processes, modal, window, cx);
+        this.picker.update(cx, |picker, _| {
+            picker.delegate.system = Arc::new(Mutex::new(system));
+        });
+
+        let picker = this.picker.downgrade();
+        this._refresh_task = Some(cx.spawn_in(window, async move |_, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(PROCESS_REFRESH_INTERVAL)
+                    .await;
+                let refreshed = picker.update_in(cx, |picker, window, cx| {
+                    picker.delegate.refresh_processes(window, cx);
+                });
+                if refreshed.is_err() {
+                    break;
                 }
-            })
-            .collect();
-        processes.sort_by_key(|k| k.name.clone());
-        let processes = processes.into_iter().collect();
-        Self::with_processes(workspace, definition, processes, modal, window, cx)
+            }
+        }));
+        this
     }
 
     pub(super) fn with_processes(
@@ -101,6 +196,7 @@ impl AttachModal {
             _subscription: cx.subscribe(&picker, |_, _, _, cx| {
                 cx.emit(DismissEvent);
             }),
+            _refresh_task: None,
             picker,
         }
     }
@@ -194,7 +290,17 @@ impl PickerDelegate for AttachModalDelegate {
 
                 delegate.matches = matches;
 
-                if delegate.matches.is_empty() {
+                let reselected_index = delegate.reselect_pid.take().and_then(|pid| {
+                    delegate.matches.iter().position(|hit| {
+                        delegate
+                            .candidates
+                            .get(hit.candidate_id)
+                            .is_some_and(|candidate| candidate.pid == pid)
+                    })
+                });
+                if let Some(ix) = reselected_index {
+                    delegate.selected_index = ix;
+                } else if delegate.matches.is_empty() {
                     delegate.selected_index = 0;
                 } else {
                     delegate.selected_index =
@@ -256,6 +362,48 @@ impl PickerDelegate for AttachModalDelegate {
         cx.emit(DismissEvent);
     }
 
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        let (sort_label, next_sort) = match self.sort {
+            ProcessSort::Name => ("Sort by Start Time", ProcessSort::RecentlyStarted),
+            ProcessSort::RecentlyStarted => ("Sort by Name", ProcessSort::Name),
+        };
+
+        Some(
+            h_flex()
+                .w_full()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .p_1()
+                .gap_4()
+                .child(
+                    Button::new("refresh-processes", "Refresh")
+                        .icon(IconName::RotateCw)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .icon_position(IconPosition::Start)
+                        .on_click(cx.listener(|picker, _, window, cx| {
+                            picker.delegate.refresh_processes(window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("sort-processes", sort_label)
+                        .icon(IconName::ListTree)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .icon_position(IconPosition::Start)
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            picker.delegate.set_sort(next_sort);
+                            picker.refresh(window, cx);
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -318,3 +466,16 @@ pub(crate) fn _process_names(modal: &AttachModal, cx: &mut Context<AttachModal>)
             .collect::<Vec<_>>()
     })
 }
+
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) fn _process_pids(modal: &AttachModal, cx: &mut Context<AttachModal>) -> Vec<u32> {
+    modal.picker.update(cx, |picker, _| {
+        picker
+            .delegate
+            .matches
+            .iter()
+            .filter_map(|hit| picker.delegate.candidates.get(hit.candidate_id))
+            .map(|candidate| candidate.pid)
+            .collect::<Vec<_>>()
+    })
+}
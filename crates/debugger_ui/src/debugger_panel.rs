@@ -2,10 +2,11 @@ use crate::persistence::DebuggerPaneItem;
 use crate::session::DebugSession;
 use crate::session::running::RunningState;
 use crate::{
-    ClearAllBreakpoints, Continue, Detach, FocusBreakpointList, FocusConsole, FocusFrames,
-    FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables, Pause, Restart,
-    ShowStackTrace, StepBack, StepInto, StepOut, StepOver, Stop, ToggleIgnoreBreakpoints,
-    ToggleSessionPicker, ToggleThreadPicker, persistence,
+    ClearAllBreakpoints, Continue, CopyAllCallStacks, CopyCallStack, Detach, DisableAllBreakpoints,
+    EnableAllBreakpoints, FocusBreakpointList, FocusConsole, FocusFrames, FocusLoadedSources,
+    FocusModules, FocusTerminal, FocusVariables, Pause, Restart, ShowStackTrace, StepBack,
+    StepInto, StepIntoInstruction, StepOut, StepOutInstruction, StepOver, StepOverInstruction,
+    Stop, StopAll, ToggleIgnoreBreakpoints, ToggleSessionPicker, ToggleThreadPicker, persistence,
 };
 use anyhow::{Context as _, Result, anyhow};
 use command_palette_hooks::CommandPaletteFilter;
@@ -23,9 +24,13 @@ use gpui::{
 };
 
 use language::Buffer;
-use project::debugger::session::{Session, SessionStateEvent};
+use project::debugger::breakpoint_store::BreakpointState;
+use project::debugger::session::{Session, SessionEvent, SessionStateEvent};
 use project::{Fs, ProjectPath, WorktreeId};
-use project::{Project, debugger::session::ThreadStatus};
+use project::{
+    Project,
+    debugger::session::{ThreadId, ThreadStatus},
+};
 use rpc::proto::{self};
 use settings::Settings;
 use std::any::TypeId;
@@ -53,6 +58,12 @@ pub enum DebugPanelEvent {
     LoadedSource((SessionId, LoadedSourceEvent)),
     ClientShutdown(SessionId),
     CapabilitiesChanged(SessionId),
+    /// The panel switched to another session, along with the thread selected in it. Both are
+    /// `None` once no session is left to show.
+    ActiveSessionChanged {
+        session_id: Option<SessionId>,
+        thread_id: Option<ThreadId>,
+    },
 }
 
 actions!(debug_panel, [ToggleFocus]);
@@ -129,7 +140,14 @@ impl DebugPanel {
     }
 
     pub(crate) fn filter_action_types(&self, cx: &mut App) {
-        let (has_active_session, supports_restart, support_step_back, status) = self
+        let (
+            has_active_session,
+            supports_restart,
+            support_step_back,
+            supports_goto,
+            supports_granularity,
+            status,
+        ) = self
             .active_session()
             .map(|item| {
                 let running = item.read(cx).running_state().clone();
@@ -138,15 +156,18 @@ impl DebugPanel {
                     !running.read(cx).session().read(cx).is_terminated(),
                     caps.supports_restart_request.unwrap_or_default(),
                     caps.supports_step_back.unwrap_or_default(),
+                    caps.supports_goto_targets_request.unwrap_or_default(),
+                    caps.supports_stepping_granularity.unwrap_or_default(),
                     running.read(cx).thread_status(cx),
                 )
             })
-            .unwrap_or((false, false, false, None));
+            .unwrap_or((false, false, false, false, false, None));
 
         let filter = CommandPaletteFilter::global_mut(cx);
         let debugger_action_types = [
             TypeId::of::<Detach>(),
             TypeId::of::<Stop>(),
+            TypeId::of::<StopAll>(),
             TypeId::of::<ToggleIgnoreBreakpoints>(),
         ];
 
@@ -158,12 +179,21 @@ impl DebugPanel {
             TypeId::of::<StepInto>(),
             TypeId::of::<StepOut>(),
             TypeId::of::<ShowStackTrace>(),
+            TypeId::of::<CopyCallStack>(),
+            TypeId::of::<CopyAllCallStacks>(),
             TypeId::of::<editor::actions::DebuggerRunToCursor>(),
             TypeId::of::<editor::actions::DebuggerEvaluateSelectedText>(),
+            TypeId::of::<editor::actions::DebuggerHoverSelectedText>(),
         ];
 
         let step_back_action_type = [TypeId::of::<StepBack>()];
         let restart_action_type = [TypeId::of::<Restart>()];
+        let goto_action_type = [TypeId::of::<editor::actions::DebuggerSetNextStatement>()];
+        let instruction_step_action_types = [
+            TypeId::of::<StepOverInstruction>(),
+            TypeId::of::<StepIntoInstruction>(),
+            TypeId::of::<StepOutInstruction>(),
+        ];
 
         if has_active_session {
             filter.show_action_types(debugger_action_types.iter());
@@ -180,6 +210,18 @@ impl DebugPanel {
                 filter.hide_action_types(&step_back_action_type);
             }
 
+            if supports_goto && status == Some(ThreadStatus::Stopped) {
+                filter.show_action_types(goto_action_type.iter());
+            } else {
+                filter.hide_action_types(&goto_action_type);
+            }
+
+            if supports_granularity && status == Some(ThreadStatus::Stopped) {
+                filter.show_action_types(instruction_step_action_types.iter());
+            } else {
+                filter.hide_action_types(&instruction_step_action_types);
+            }
+
             match status {
                 Some(ThreadStatus::Running) => {
                     filter.show_action_types(running_action_types.iter());
@@ -199,6 +241,8 @@ impl DebugPanel {
             filter.hide_action_types(&debugger_action_types);
             filter.hide_action_types(&step_back_action_type);
             filter.hide_action_types(&restart_action_type);
+            filter.hide_action_types(&goto_action_type);
+            filter.hide_action_types(&instruction_step_action_types);
             filter.hide_action_types(&running_action_types);
             filter.hide_action_types(&stopped_action_type);
         }
@@ -221,6 +265,26 @@ impl DebugPanel {
                     )
                 });
 
+                workspace.register_action(|workspace, _: &DisableAllBreakpoints, _, cx| {
+                    workspace.project().read(cx).breakpoint_store().update(
+                        cx,
+                        |breakpoint_store, cx| {
+                            breakpoint_store
+                                .set_all_breakpoint_states(BreakpointState::Disabled, cx);
+                        },
+                    )
+                });
+
+                workspace.register_action(|workspace, _: &EnableAllBreakpoints, _, cx| {
+                    workspace.project().read(cx).breakpoint_store().update(
+                        cx,
+                        |breakpoint_store, cx| {
+                            breakpoint_store
+                                .set_all_breakpoint_states(BreakpointState::Enabled, cx);
+                        },
+                    )
+                });
+
                 cx.observe_new::<DebugPanel>(|debug_panel, _, cx| {
                     Self::filter_action_types(debug_panel, cx);
                 })
@@ -232,6 +296,20 @@ impl DebugPanel {
                     });
                 })
                 .detach();
+
+                // Adapters may announce new capabilities mid-session (e.g. step back becoming
+                // available after attaching), which doesn't otherwise notify the panel.
+                cx.subscribe(
+                    &debug_panel,
+                    |_, debug_panel, event: &DebugPanelEvent, cx| {
+                        if let DebugPanelEvent::CapabilitiesChanged(_) = event {
+                            debug_panel.update(cx, |debug_panel, cx| {
+                                Self::filter_action_types(debug_panel, cx);
+                            });
+                        }
+                    },
+                )
+                .detach();
                 workspace.set_debugger_provider(DebuggerProvider(debug_panel.clone()));
 
                 debug_panel
@@ -339,6 +417,7 @@ impl DebugPanel {
     pub(crate) fn handle_restart_request(
         &mut self,
         mut curr_session: Entity<Session>,
+        reason: SharedString,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -366,6 +445,10 @@ impl DebugPanel {
                 let session = dap_store.new_session(label, adapter, None, cx);
 
                 let task = session.update(cx, |session, cx| {
+                    session.push_console_message(
+                        format!("Restarted by relaunching the debug adapter, since {reason}"),
+                        cx,
+                    );
                     session.boot(binary, worktree, dap_store_handle.downgrade(), cx)
                 });
                 (session, task)
@@ -389,7 +472,13 @@ impl DebugPanel {
         };
 
         let dap_store_handle = self.project.read(cx).dap_store().clone();
-        let label = parent_session.read(cx).label().clone();
+        // Adapters usually name child sessions after what they debug, such as a subprocess.
+        let label = request
+            .configuration
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(SharedString::from)
+            .unwrap_or_else(|| parent_session.read(cx).label());
         let adapter = parent_session.read(cx).adapter().clone();
         let mut binary = parent_session.read(cx).binary().clone();
         binary.request_args = request.clone();
@@ -423,11 +512,6 @@ impl DebugPanel {
         else {
             return;
         };
-        session.update(cx, |this, cx| {
-            this.running_state().update(cx, |this, cx| {
-                this.serialize_layout(window, cx);
-            });
-        });
         let session_id = session.update(cx, |this, cx| this.session_id(cx));
         let should_prompt = self
             .project
@@ -449,26 +533,59 @@ impl DebugPanel {
                     return;
                 }
             }
-            session.update(cx, |session, cx| session.shutdown(cx)).ok();
-            this.update(cx, |this, cx| {
-                this.sessions.retain(|other| entity_id != other.entity_id());
-
-                if let Some(active_session_id) = this
-                    .active_session
-                    .as_ref()
-                    .map(|session| session.entity_id())
-                {
-                    if active_session_id == entity_id {
-                        this.active_session = this.sessions.first().cloned();
-                    }
-                }
-                cx.notify()
-            })
-            .ok();
+            this.update(cx, |this, cx| this.shut_down_session(entity_id, cx))
+                .ok();
         })
         .detach();
     }
 
+    /// Saves the layout of the session's panes, shuts the session down and removes it from the
+    /// panel.
+    fn shut_down_session(&mut self, entity_id: EntityId, cx: &mut Context<Self>) {
+        let Some(session) = self
+            .sessions
+            .iter()
+            .find(|other| entity_id == other.entity_id())
+            .cloned()
+        else {
+            return;
+        };
+        session.update(cx, |this, cx| {
+            this.running_state()
+                .update(cx, |this, cx| this.save_layout(cx))
+                .detach();
+            this.shutdown(cx);
+        });
+        self.sessions.retain(|other| entity_id != other.entity_id());
+
+        if let Some(active_session_id) = self
+            .active_session
+            .as_ref()
+            .map(|session| session.entity_id())
+        {
+            if active_session_id == entity_id {
+                let first_session = self.sessions.first().cloned();
+                self.set_active_session(first_session, cx);
+            }
+        }
+        cx.notify()
+    }
+
+    /// Shuts down every session in the panel, terminating or disconnecting from each debuggee
+    /// as its adapter supports, and removes them from the panel.
+    pub(crate) fn stop_all_sessions(&mut self, cx: &mut Context<Self>) {
+        // Clearing the active session first keeps each removal from activating the next one.
+        self.set_active_session(None, cx);
+        let entity_ids = self
+            .sessions
+            .iter()
+            .map(|session| session.entity_id())
+            .collect::<Vec<_>>();
+        for entity_id in entity_ids {
+            self.shut_down_session(entity_id, cx);
+        }
+    }
+
     pub(crate) fn deploy_context_menu(
         &mut self,
         position: Point<Pixels>,
@@ -713,10 +830,19 @@ impl DebugPanel {
                                             ))
                                             .tooltip({
                                                 let focus_handle = focus_handle.clone();
+                                                let meta = if capabilities
+                                                    .supports_restart_request
+                                                    .unwrap_or_default()
+                                                {
+                                                    "Restarts the program in place"
+                                                } else {
+                                                    "Relaunches the debug adapter"
+                                                };
                                                 move |window, cx| {
-                                                    Tooltip::for_action_in(
+                                                    Tooltip::with_meta_in(
                                                         "Restart",
-                                                        &Restart,
+                                                        Some(&Restart),
+                                                        meta,
                                                         &focus_handle,
                                                         window,
                                                         cx,
@@ -889,10 +1015,37 @@ impl DebugPanel {
                 this.go_to_selected_stack_frame(window, cx);
             });
         });
-        self.active_session = Some(session_item);
+        self.set_active_session(Some(session_item), cx);
         cx.notify();
     }
 
+    fn set_active_session(
+        &mut self,
+        session: Option<Entity<DebugSession>>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_session == session {
+            return;
+        }
+        self.active_session = session;
+
+        let (session_id, thread_id) = self
+            .active_session
+            .as_ref()
+            .map(|session| {
+                let running_state = session.read(cx).running_state().read(cx);
+                (
+                    Some(running_state.session_id()),
+                    running_state.selected_thread_id(),
+                )
+            })
+            .unwrap_or_default();
+        cx.emit(DebugPanelEvent::ActiveSessionChanged {
+            session_id,
+            thread_id,
+        });
+    }
+
     pub(crate) fn save_scenario(
         &self,
         scenario: &DebugScenario,
@@ -965,6 +1118,21 @@ impl DebugPanel {
     }
 }
 
+/// Reports a session lifecycle event without anything about the program being debugged, such
+/// as its path or arguments.
+fn send_session_telemetry(event: &'static str, session: &Session) {
+    let request = session.request_kind().map(|request| match request {
+        dap::StartDebuggingRequestArgumentsRequest::Launch => "launch",
+        dap::StartDebuggingRequestArgumentsRequest::Attach => "attach",
+    });
+    telemetry::event!(
+        event,
+        adapter = session.adapter().to_string(),
+        request = request,
+        is_child_session = session.parent_session().is_some(),
+    );
+}
+
 async fn register_session_inner(
     this: &WeakEntity<DebugPanel>,
     session: Entity<Session>,
@@ -976,12 +1144,49 @@ async fn register_session_inner(
             &session,
             window,
             move |this, session, event: &SessionStateEvent, window, cx| match event {
-                SessionStateEvent::Restart => {
-                    this.handle_restart_request(session.clone(), window, cx);
+                SessionStateEvent::Restart { reason } => {
+                    this.handle_restart_request(session.clone(), reason.clone(), window, cx);
                 }
                 SessionStateEvent::SpawnChildSession { request } => {
                     this.handle_start_debugging_request(request, session.clone(), window, cx);
                 }
+                SessionStateEvent::Running => {
+                    send_session_telemetry("Debugger Session Running", session.read(cx));
+                }
+                SessionStateEvent::Shutdown => {
+                    send_session_telemetry("Debugger Session Ended", session.read(cx));
+
+                    // Sessions stopped before their program finished are left open.
+                    let program_ended = session.read(cx).exit_code().is_some();
+                    if program_ended && DebuggerSettings::get_global(cx).close_ended_sessions {
+                        let session_id = session.read(cx).session_id();
+                        let debug_session = this
+                            .sessions
+                            .iter()
+                            .find(|debug_session| {
+                                debug_session.read(cx).session_id(cx) == session_id
+                            })
+                            .map(|debug_session| debug_session.entity_id());
+                        if let Some(entity_id) = debug_session {
+                            this.close_session(entity_id, window, cx);
+                        }
+                    }
+                }
+            },
+        )
+        .detach();
+        let mut has_stopped = false;
+        cx.subscribe(
+            &session,
+            move |_, session, event: &SessionEvent, cx| match event {
+                SessionEvent::CapabilitiesChanged => {
+                    let session_id = session.read(cx).session_id();
+                    cx.emit(DebugPanelEvent::CapabilitiesChanged(session_id));
+                }
+                SessionEvent::Stopped(_) if !has_stopped => {
+                    has_stopped = true;
+                    send_session_telemetry("Debugger Session First Stopped", session.read(cx));
+                }
                 _ => {}
             },
         )
@@ -1,19 +1,21 @@
-use dap::debugger_settings::DebuggerSettings;
+use dap::{SteppingGranularity, debugger_settings::DebuggerSettings};
 use debugger_panel::{DebugPanel, ToggleFocus};
 use editor::Editor;
 use feature_flags::{DebuggerFeatureFlag, FeatureFlagViewExt};
 use gpui::{App, EntityInputHandler, actions};
+use loaded_source_picker::LoadedSourcePicker;
 use new_session_modal::NewSessionModal;
 use project::debugger::{self, breakpoint_store::SourceBreakpoint};
 use session::DebugSession;
 use settings::Settings;
 use stack_trace_view::StackTraceView;
 use util::maybe;
-use workspace::{ItemHandle, ShutdownDebugAdapters, Workspace};
+use workspace::{ItemHandle, ShutdownDebugAdapters, Workspace, notifications::NotifyTaskExt};
 
 pub mod attach_modal;
 pub mod debugger_panel;
 mod dropdown_menus;
+mod loaded_source_picker;
 mod new_session_modal;
 mod persistence;
 pub(crate) mod session;
@@ -34,9 +36,15 @@ actions!(
         StepOver,
         StepOut,
         StepBack,
+        StepOverInstruction,
+        StepIntoInstruction,
+        StepOutInstruction,
         Stop,
+        StopAll,
         ToggleIgnoreBreakpoints,
         ClearAllBreakpoints,
+        DisableAllBreakpoints,
+        EnableAllBreakpoints,
         FocusConsole,
         FocusVariables,
         FocusBreakpointList,
@@ -44,9 +52,13 @@ actions!(
         FocusModules,
         FocusLoadedSources,
         FocusTerminal,
+        OpenLoadedSource,
         ShowStackTrace,
+        CopyCallStack,
+        CopyAllCallStacks,
         ToggleThreadPicker,
         ToggleSessionPicker,
+        ToggleProtocolLog,
     ]
 );
 
@@ -128,6 +140,62 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &StepOut, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| item.step_out(cx))
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepOverInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| {
+                                if item.supports_stepping_granularity(cx) {
+                                    item.step_over_by(SteppingGranularity::Instruction, cx)
+                                }
+                            })
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepIntoInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| {
+                                if item.supports_stepping_granularity(cx) {
+                                    item.step_in_by(SteppingGranularity::Instruction, cx)
+                                }
+                            })
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepOutInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| {
+                                if item.supports_stepping_granularity(cx) {
+                                    item.step_out_by(SteppingGranularity::Instruction, cx)
+                                }
+                            })
+                        }
+                    }
+                })
                 .register_action(|workspace, _: &StepBack, _, cx| {
                     if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
                         if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
@@ -152,6 +220,13 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &StopAll, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        cx.defer(move |cx| {
+                            debug_panel.update(cx, |panel, cx| panel.stop_all_sessions(cx))
+                        })
+                    }
+                })
                 .register_action(|workspace, _: &ToggleIgnoreBreakpoints, _, cx| {
                     if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
                         if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
@@ -163,6 +238,43 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &CopyCallStack, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item
+                                .update(cx, |item, cx| item.copy_call_stack(cx))
+                                .detach();
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &ToggleProtocolLog, window, cx| {
+                    let session_id = workspace.panel::<DebugPanel>(cx).and_then(|panel| {
+                        panel
+                            .read(cx)
+                            .active_session()
+                            .map(|session| session.read(cx).session_id(cx))
+                    });
+                    if let Some(session_id) = session_id {
+                        debugger_tools::toggle_protocol_log(workspace, session_id, window, cx);
+                    }
+                })
+                .register_action(|workspace, _: &CopyAllCallStacks, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item
+                                .update(cx, |item, cx| item.copy_all_call_stacks(cx))
+                                .detach();
+                        }
+                    }
+                })
                 .register_action(
                     |workspace: &mut Workspace, _: &ShutdownDebugAdapters, _window, cx| {
                         workspace.project().update(cx, |project, cx| {
@@ -206,7 +318,12 @@ pub fn init(cx: &mut App) {
                 )
                 .register_action(|workspace: &mut Workspace, _: &Start, window, cx| {
                     NewSessionModal::show(workspace, window, cx);
-                });
+                })
+                .register_action(
+                    |workspace: &mut Workspace, _: &OpenLoadedSource, window, cx| {
+                        LoadedSourcePicker::show(workspace, window, cx);
+                    },
+                );
         })
     })
     .detach();
@@ -215,7 +332,7 @@ pub fn init(cx: &mut App) {
         move |editor: &mut Editor, _, cx| {
             editor
                 .register_action(cx.listener(
-                    move |editor, _: &editor::actions::DebuggerRunToCursor, _, cx| {
+                    move |editor, _: &editor::actions::DebuggerRunToCursor, window, cx| {
                         maybe!({
                             let debug_panel =
                                 editor.workspace()?.read(cx).panel::<DebugPanel>(cx)?;
@@ -234,6 +351,7 @@ pub fn init(cx: &mut App) {
 
                             let source_breakpoint = SourceBreakpoint {
                                 row: position.row,
+                                column: None,
                                 path,
                                 message: None,
                                 condition: None,
@@ -245,11 +363,51 @@ pub fn init(cx: &mut App) {
                                 session.running_state().update(cx, |state, cx| {
                                     if let Some(thread_id) = state.selected_thread_id() {
                                         state.session().update(cx, |session, cx| {
-                                            session.run_to_position(
-                                                source_breakpoint,
-                                                thread_id,
-                                                cx,
-                                            );
+                                            session
+                                                .run_to_position(source_breakpoint, thread_id, cx)
+                                                .detach_and_notify_err(window, cx);
+                                        })
+                                    }
+                                });
+                            });
+
+                            Some(())
+                        });
+                    },
+                ))
+                .detach();
+
+            editor
+                .register_action(cx.listener(
+                    move |editor, _: &editor::actions::DebuggerSetNextStatement, _, cx| {
+                        maybe!({
+                            let debug_panel =
+                                editor.workspace()?.read(cx).panel::<DebugPanel>(cx)?;
+                            let cursor_point: language::Point = editor.selections.newest(cx).head();
+                            let active_session = debug_panel.read(cx).active_session()?;
+
+                            let (buffer, position, _) = editor
+                                .buffer()
+                                .read(cx)
+                                .point_to_buffer_point(cursor_point, cx)?;
+
+                            let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                            active_session.update(cx, |session, cx| {
+                                session.running_state().update(cx, |state, cx| {
+                                    if let Some(thread_id) = state.selected_thread_id() {
+                                        state.session().update(cx, |session, cx| {
+                                            session
+                                                .set_next_statement(
+                                                    path,
+                                                    position.row,
+                                                    thread_id,
+                                                    cx,
+                                                )
+                                                .detach_and_log_err(cx);
                                         })
                                     }
                                 });
@@ -1,6 +1,6 @@
 use gpui::Entity;
 use project::debugger::session::{ThreadId, ThreadStatus};
-use ui::{ContextMenu, DropdownMenu, DropdownStyle, Indicator, prelude::*};
+use ui::{ContextMenu, DropdownMenu, DropdownStyle, Tooltip, prelude::*};
 
 use crate::{
     debugger_panel::DebugPanel,
@@ -19,42 +19,15 @@ impl DebugPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<impl IntoElement> {
-        if let Some(running_state) = running_state {
+        if running_state.is_some() {
             let sessions = self.sessions().clone();
             let weak = cx.weak_entity();
-            let running_state = running_state.read(cx);
-            let label = if let Some(active_session) = active_session {
-                active_session.read(cx).session(cx).read(cx).label()
+            let trigger = if let Some(active_session) = active_session {
+                active_session.read(cx).label_element(cx)
             } else {
-                SharedString::new_static("Unknown Session")
+                DebugPanel::dropdown_label("Unknown Session").into_any_element()
             };
 
-            let is_terminated = running_state.session().read(cx).is_terminated();
-            let session_state_indicator = {
-                if is_terminated {
-                    Some(Indicator::dot().color(Color::Error))
-                } else {
-                    match running_state.thread_status(cx).unwrap_or_default() {
-                        project::debugger::session::ThreadStatus::Stopped => {
-                            Some(Indicator::dot().color(Color::Conflict))
-                        }
-                        _ => Some(Indicator::dot().color(Color::Success)),
-                    }
-                }
-            };
-
-            let trigger = h_flex()
-                .gap_2()
-                .when_some(session_state_indicator, |this, indicator| {
-                    this.child(indicator)
-                })
-                .justify_between()
-                .child(
-                    DebugPanel::dropdown_label(label)
-                        .when(is_terminated, |this| this.strikethrough()),
-                )
-                .into_any_element();
-
             Some(
                 DropdownMenu::new_with_element(
                     "debugger-session-list",
@@ -164,15 +137,69 @@ impl DebugPanel {
                 DropdownMenu::new_with_element(
                     ("thread-list", session_id.0),
                     trigger,
-                    ContextMenu::build(window, cx, move |mut this, _, _| {
+                    ContextMenu::build(window, cx, move |mut this, _, cx| {
+                        let session = running_state.read(cx).session().clone();
                         for (thread, _) in threads {
                             let running_state = running_state.clone();
-                            let thread_id = thread.id;
-                            this = this.entry(thread.name, None, move |window, cx| {
-                                running_state.update(cx, |running_state, cx| {
-                                    running_state.select_thread(ThreadId(thread_id), window, cx);
-                                });
-                            });
+                            let session = session.clone();
+                            let thread_id = ThreadId(thread.id);
+                            let thread_name = SharedString::from(thread.name);
+                            this = this.custom_entry(
+                                move |_, cx| {
+                                    let status = session.read(cx).thread_status(thread_id);
+                                    let session = session.clone();
+                                    h_flex()
+                                        .w_full()
+                                        .gap_2()
+                                        .justify_between()
+                                        .child(Label::new(thread_name.clone()))
+                                        .child(
+                                            h_flex()
+                                                .gap_1()
+                                                .child(
+                                                    Label::new(status.label())
+                                                        .size(LabelSize::Small)
+                                                        .color(Color::Muted),
+                                                )
+                                                .map(|this| match status {
+                                                    ThreadStatus::Running => this.child(
+                                                        IconButton::new(
+                                                            ("pause-thread", thread_id.0),
+                                                            IconName::DebugPause,
+                                                        )
+                                                        .icon_size(IconSize::Small)
+                                                        .tooltip(Tooltip::text("Pause Thread"))
+                                                        .on_click(move |_, _, cx| {
+                                                            session.update(cx, |session, cx| {
+                                                                session.pause_thread(thread_id, cx)
+                                                            })
+                                                        }),
+                                                    ),
+                                                    ThreadStatus::Stopped => this.child(
+                                                        IconButton::new(
+                                                            ("continue-thread", thread_id.0),
+                                                            IconName::DebugContinue,
+                                                        )
+                                                        .icon_size(IconSize::Small)
+                                                        .tooltip(Tooltip::text("Continue Thread"))
+                                                        .on_click(move |_, _, cx| {
+                                                            session.update(cx, |session, cx| {
+                                                                session
+                                                                    .continue_thread(thread_id, cx)
+                                                            })
+                                                        }),
+                                                    ),
+                                                    _ => this,
+                                                }),
+                                        )
+                                        .into_any_element()
+                                },
+                                move |window, cx| {
+                                    running_state.update(cx, |running_state, cx| {
+                                        running_state.select_thread(thread_id, window, cx);
+                                    });
+                                },
+                            );
                         }
                         this
                     }),
@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use editor::Editor;
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, Focusable, Render, Subscription, WeakEntity};
+use picker::{Picker, PickerDelegate};
+use project::debugger::session::{Session, SessionEvent, adapter_source_path};
+use ui::{Context, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, OpenVisible, Workspace};
+
+use crate::debugger_panel::DebugPanel;
+
+pub(crate) struct LoadedSourcePickerDelegate {
+    selected_index: usize,
+    matches: Vec<StringMatch>,
+    sources: Arc<[dap::Source]>,
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+}
+
+/// Lists the sources the debug adapter reported as loaded, including ones that aren't on disk,
+/// whose contents are fetched from the adapter when they're opened.
+pub struct LoadedSourcePicker {
+    picker: Entity<Picker<LoadedSourcePickerDelegate>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LoadedSourcePicker {
+    pub(crate) fn show(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(session) = workspace.panel::<DebugPanel>(cx).and_then(|panel| {
+            let active_session = panel.read(cx).active_session()?;
+            Some(
+                active_session
+                    .read(cx)
+                    .running_state()
+                    .read(cx)
+                    .session()
+                    .clone(),
+            )
+        }) else {
+            return;
+        };
+
+        let weak_workspace = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(session, weak_workspace, window, cx)
+        });
+    }
+
+    fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let sources = Self::sources(&session, cx);
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                LoadedSourcePickerDelegate {
+                    selected_index: 0,
+                    matches: Vec::new(),
+                    sources,
+                    session: session.clone(),
+                    workspace,
+                },
+                window,
+                cx,
+            )
+        });
+
+        // The adapter is only asked for its sources when they're first read, so they may arrive
+        // after the picker opens.
+        let session_subscription = cx.subscribe_in(&session, window, {
+            let picker = picker.clone();
+            move |_, session, event, window, cx| {
+                if let SessionEvent::LoadedSources = event {
+                    let sources = Self::sources(session, cx);
+                    picker.update(cx, |picker, cx| {
+                        picker.delegate.sources = sources;
+                        picker.refresh(window, cx);
+                    });
+                }
+            }
+        });
+
+        Self {
+            _subscriptions: vec![
+                cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent)),
+                session_subscription,
+            ],
+            picker,
+        }
+    }
+
+    fn sources(session: &Entity<Session>, cx: &mut App) -> Arc<[dap::Source]> {
+        session.update(cx, |session, cx| session.loaded_sources(cx).into())
+    }
+}
+
+impl Render for LoadedSourcePicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("LoadedSourcePicker")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for LoadedSourcePicker {}
+
+impl Focusable for LoadedSourcePicker {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for LoadedSourcePicker {}
+
+fn source_label(source: &dap::Source) -> String {
+    source
+        .name
+        .clone()
+        .or_else(|| source.path.clone())
+        .unwrap_or_else(|| "Unnamed source".to_string())
+}
+
+impl LoadedSourcePickerDelegate {
+    fn open_source(
+        &self,
+        source: dap::Source,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let adapter_provided = source
+            .source_reference
+            .is_some_and(|reference| reference > 0);
+        if !adapter_provided {
+            let Some(path) = source.path.clone() else {
+                return;
+            };
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .open_abs_path(
+                            PathBuf::from(path),
+                            OpenOptions {
+                                visible: Some(OpenVisible::None),
+                                ..Default::default()
+                            },
+                            window,
+                            cx,
+                        )
+                        .detach_and_log_err(cx);
+                })
+                .ok();
+            return;
+        }
+
+        // Sources that only the adapter has are written to a file of their own, so that they
+        // open like any other file: once per session, and with breakpoints that can be sent
+        // back to the adapter by reference.
+        let Some(source_reference) = source.source_reference else {
+            return;
+        };
+        let session = self.session.clone();
+        let path = adapter_source_path(
+            session.read(cx).session_id(),
+            source_reference,
+            &source_label(&source),
+        );
+        let workspace = self.workspace.clone();
+        window
+            .spawn(cx, async move |cx| {
+                let fs = workspace.read_with(cx, |workspace, cx| {
+                    workspace.project().read(cx).fs().clone()
+                })?;
+                // Rewriting a source that's already open would reload its buffer.
+                if !fs.is_file(&path).await {
+                    let content = session
+                        .update(cx, |session, cx| session.source_content(source, cx))?
+                        .await?;
+                    if let Some(dir) = path.parent() {
+                        fs.create_dir(dir).await?;
+                    }
+                    fs.atomic_write(path.clone(), content.to_string()).await?;
+                }
+
+                let item = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_abs_path(
+                            path,
+                            OpenOptions {
+                                visible: Some(OpenVisible::None),
+                                ..Default::default()
+                            },
+                            window,
+                            cx,
+                        )
+                    })?
+                    .await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update(cx, |editor, _| editor.set_read_only(true))?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for LoadedSourcePickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Open a source loaded by the debugger".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let candidates = self
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(id, source)| StringMatchCandidate::new(id, &source_label(source)))
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                true,
+                100,
+                &Default::default(),
+                cx.background_executor().clone(),
+            )
+            .await;
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let source = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.sources.get(hit.candidate_id))
+            .cloned();
+        if let Some(source) = source {
+            self.open_source(source, window, cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let source = self.sources.get(hit.candidate_id)?;
+
+        Some(
+            ListItem::new(SharedString::from(format!("loaded-source-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .items_start()
+                        .child(Label::new(source_label(source)))
+                        .when_some(source.path.clone(), |this, path| {
+                            this.child(Label::new(path).size(LabelSize::Small).color(Color::Muted))
+                        }),
+                ),
+        )
+    }
+}
@@ -10,7 +10,9 @@ use std::{
 };
 
 use dap::{
-    DapRegistry, DebugRequest, TelemetrySpawnLocation, adapters::DebugAdapterName, send_telemetry,
+    DapRegistry, DebugRequest, TelemetrySpawnLocation,
+    adapters::{DebugAdapter, DebugAdapterName},
+    send_telemetry,
 };
 use editor::{Editor, EditorElement, EditorStyle};
 use fuzzy::{StringMatch, StringMatchCandidate};
@@ -31,7 +33,7 @@ use ui::{
     Toggleable, Window, div, h_flex, relative, rems, v_flex,
 };
 use util::ResultExt;
-use workspace::{ModalView, Workspace, pane};
+use workspace::{ModalView, Toast, Workspace, notifications::NotificationId, pane};
 
 use crate::{attach_modal::AttachModal, debugger_panel::DebugPanel};
 
@@ -173,9 +175,14 @@ impl NewSessionModal {
             NewSessionMode::Attach => self.attach_mode.update(cx, |this, cx| {
                 this.clone().render(window, cx).into_any_element()
             }),
-            NewSessionMode::Custom => self.custom_mode.update(cx, |this, cx| {
-                this.clone().render(dap_menu, window, cx).into_any_element()
-            }),
+            NewSessionMode::Custom => {
+                let preset_menu = self.preset_drop_down_menu(window, cx);
+                self.custom_mode.update(cx, |this, cx| {
+                    this.clone()
+                        .render(dap_menu, preset_menu, window, cx)
+                        .into_any_element()
+                })
+            }
             NewSessionMode::Launch => v_flex()
                 .w(rems(34.))
                 .child(self.launch_picker.clone())
@@ -192,6 +199,32 @@ impl NewSessionModal {
     }
 
     fn debug_scenario(&self, debugger: &str, cx: &App) -> Option<DebugScenario> {
+        let session_scenario = self.zed_debug_config(debugger, cx)?;
+        let mut scenario = cx
+            .global::<DapRegistry>()
+            .adapter(&session_scenario.adapter)
+            .and_then(|adapter| adapter.config_from_zed_format(session_scenario).ok())?;
+
+        if let NewSessionMode::Custom = self.mode {
+            if let Some(preset) = self
+                .custom_mode
+                .read(cx)
+                .preset
+                .as_ref()
+                .filter(|preset| &*preset.adapter == debugger)
+            {
+                merge_preset_config(&mut scenario.config, &preset.config);
+                scenario.build = scenario.build.or_else(|| preset.build.clone());
+                scenario.tcp_connection = scenario
+                    .tcp_connection
+                    .or_else(|| preset.tcp_connection.clone());
+            }
+        }
+
+        Some(scenario)
+    }
+
+    fn zed_debug_config(&self, debugger: &str, cx: &App) -> Option<ZedDebugConfig> {
         let request = match self.mode {
             NewSessionMode::Custom => Some(DebugRequest::Launch(
                 self.custom_mode.read(cx).debug_request(cx),
@@ -209,16 +242,55 @@ impl NewSessionModal {
             None
         };
 
-        let session_scenario = ZedDebugConfig {
+        Some(ZedDebugConfig {
             adapter: debugger.to_owned().into(),
             label,
             request: request,
             stop_on_entry,
+        })
+    }
+
+    /// Adapters that can't stop on entry leave the option out of the configuration they build.
+    fn supports_stop_on_entry(adapter: &dyn DebugAdapter, config: &ZedDebugConfig) -> bool {
+        let configuration = |stop_on_entry| {
+            adapter
+                .config_from_zed_format(ZedDebugConfig {
+                    stop_on_entry: Some(stop_on_entry),
+                    ..config.clone()
+                })
+                .ok()
+                .map(|scenario| scenario.config)
         };
+        configuration(true) != configuration(false)
+    }
 
-        cx.global::<DapRegistry>()
-            .adapter(&session_scenario.adapter)
-            .and_then(|adapter| adapter.config_from_zed_format(session_scenario).ok())
+    fn warn_if_stop_on_entry_unsupported(&self, debugger: &str, cx: &mut Context<Self>) {
+        let Some(config) = self
+            .zed_debug_config(debugger, cx)
+            .filter(|config| config.stop_on_entry == Some(true))
+        else {
+            return;
+        };
+        let Some(adapter) = cx.global::<DapRegistry>().adapter(&config.adapter) else {
+            return;
+        };
+        if Self::supports_stop_on_entry(adapter.as_ref(), &config) {
+            return;
+        }
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<Self>(),
+                        format!(
+                            "{debugger} can't stop on entry. Set a breakpoint where the program starts instead."
+                        ),
+                    ),
+                    cx,
+                )
+            })
+            .ok();
     }
 
     fn start_new_session(&self, window: &mut Window, cx: &mut Context<Self>) {
@@ -237,6 +309,7 @@ impl NewSessionModal {
             log::error!("debug config not found in mode: {}", self.mode);
             return;
         };
+        self.warn_if_stop_on_entry_unsupported(debugger, cx);
 
         let debug_panel = self.debug_panel.clone();
         let Some(task_contexts) = self.task_contexts(cx) else {
@@ -284,6 +357,79 @@ impl NewSessionModal {
         self.launch_picker.read(cx).delegate.task_contexts.clone()
     }
 
+    fn launch_presets(&self, cx: &App) -> Vec<DebugScenario> {
+        self.launch_picker.read(cx).delegate.launch_presets.clone()
+    }
+
+    fn apply_preset(&mut self, preset: DebugScenario, window: &mut Window, cx: &mut Context<Self>) {
+        self.debugger = Some(DebugAdapterName(preset.adapter.clone()));
+        self.custom_mode.update(cx, |custom_mode, cx| {
+            custom_mode.apply_preset(preset, window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Fills an untouched form in from the project's first launch configuration for the
+    /// selected debugger, so it doesn't have to be typed out again.
+    fn apply_default_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(debugger) = self.debugger.as_ref() else {
+            return;
+        };
+        let custom_mode = self.custom_mode.read(cx);
+        if custom_mode.preset.is_some() || !custom_mode.program.read(cx).is_empty(cx) {
+            return;
+        }
+        if let Some(preset) = self
+            .launch_presets(cx)
+            .into_iter()
+            .find(|preset| preset.adapter == debugger.0)
+        {
+            self.apply_preset(preset, window, cx);
+        }
+    }
+
+    fn preset_drop_down_menu(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<DropdownMenu> {
+        let presets = self.launch_presets(cx);
+        if presets.is_empty() {
+            return None;
+        }
+
+        let label = self
+            .custom_mode
+            .read(cx)
+            .preset
+            .as_ref()
+            .map(|preset| preset.label.clone())
+            .unwrap_or_else(|| SELECT_PRESET_LABEL.clone());
+        let weak = cx.weak_entity();
+
+        Some(DropdownMenu::new(
+            "debug-preset-picker",
+            label,
+            ContextMenu::build(window, cx, move |mut menu, _, _| {
+                for preset in presets {
+                    let weak = weak.clone();
+                    menu = menu.entry(
+                        format!("{} ({})", preset.label, preset.adapter),
+                        None,
+                        move |window, cx| {
+                            weak.update(cx, |this, cx| {
+                                this.apply_preset(preset.clone(), window, cx);
+                            })
+                            .ok();
+                        },
+                    );
+                }
+
+                menu
+            }),
+        ))
+    }
+
     fn adapter_drop_down_menu(
         &mut self,
         window: &mut Window,
@@ -334,8 +480,12 @@ impl NewSessionModal {
                         weak.update(cx, |this, cx| {
                             this.debugger = Some(name.clone());
                             cx.notify();
-                            if let NewSessionMode::Attach = &this.mode {
-                                Self::update_attach_picker(&this.attach_mode, &name, window, cx);
+                            match this.mode {
+                                NewSessionMode::Attach => {
+                                    Self::update_attach_picker(&this.attach_mode, &name, window, cx)
+                                }
+                                NewSessionMode::Custom => this.apply_default_preset(window, cx),
+                                NewSessionMode::Launch => {}
                             }
                         })
                         .ok();
@@ -353,6 +503,7 @@ impl NewSessionModal {
 }
 
 static SELECT_DEBUGGER_LABEL: SharedString = SharedString::new_static("Select Debugger");
+static SELECT_PRESET_LABEL: SharedString = SharedString::new_static("Select Preset");
 
 #[derive(Clone)]
 enum NewSessionMode {
@@ -524,6 +675,7 @@ impl Render for NewSessionModal {
                                 move |_, window, cx| {
                                     this.update(cx, |this, cx| {
                                         this.mode = NewSessionMode::Custom;
+                                        this.apply_default_preset(window, cx);
                                         this.mode_focus_handle(cx).focus(window);
                                     })
                                     .ok();
@@ -736,6 +888,9 @@ pub(super) struct CustomMode {
     program: Entity<Editor>,
     cwd: Entity<Editor>,
     stop_on_entry: ToggleState,
+    /// The project's launch configuration the form was filled in from. Its fields that the form
+    /// doesn't show are still sent to the adapter.
+    preset: Option<DebugScenario>,
 }
 
 impl CustomMode {
@@ -767,9 +922,30 @@ impl CustomMode {
             program,
             cwd,
             stop_on_entry: ToggleState::Unselected,
+            preset: None,
         })
     }
 
+    fn apply_preset(&mut self, preset: DebugScenario, window: &mut Window, cx: &mut App) {
+        let command = preset_command(&preset.config);
+        self.program.update(cx, |editor, cx| {
+            editor.set_text(command, window, cx);
+        });
+        if let Some(cwd) = preset.config.get("cwd").and_then(serde_json::Value::as_str) {
+            self.cwd.update(cx, |editor, cx| {
+                editor.set_text(cwd, window, cx);
+            });
+        }
+        if let Some(stop_on_entry) = preset
+            .config
+            .get("stopOnEntry")
+            .and_then(serde_json::Value::as_bool)
+        {
+            self.stop_on_entry = stop_on_entry.into();
+        }
+        self.preset = Some(preset);
+    }
+
     fn load(&mut self, cwd: PathBuf, window: &mut Window, cx: &mut App) {
         self.cwd.update(cx, |editor, cx| {
             if editor.is_empty(cx) {
@@ -817,6 +993,7 @@ impl CustomMode {
     fn render(
         &mut self,
         adapter_menu: DropdownMenu,
+        preset_menu: Option<DropdownMenu>,
         window: &mut Window,
         cx: &mut ui::Context<Self>,
     ) -> impl IntoElement {
@@ -835,6 +1012,18 @@ impl CustomMode {
                     .gap(ui::DynamicSpacing::Base08.rems(cx))
                     .child(adapter_menu),
             )
+            .when_some(preset_menu, |this, preset_menu| {
+                this.child(
+                    h_flex()
+                        .child(
+                            Label::new("Preset")
+                                .size(ui::LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .gap(ui::DynamicSpacing::Base08.rems(cx))
+                        .child(preset_menu),
+                )
+            })
             .child(render_editor(&self.program, window, cx))
             .child(render_editor(&self.cwd, window, cx))
             .child(
@@ -905,6 +1094,9 @@ pub(super) struct DebugScenarioDelegate {
     task_contexts: Option<Arc<TaskContexts>>,
     divider_index: Option<usize>,
     last_used_candidate_index: Option<usize>,
+    /// The launch configurations defined in the project's files, offered to fill in a custom
+    /// session from.
+    launch_presets: Vec<DebugScenario>,
 }
 
 impl DebugScenarioDelegate {
@@ -919,6 +1111,7 @@ impl DebugScenarioDelegate {
             task_contexts: None,
             divider_index: None,
             last_used_candidate_index: None,
+            launch_presets: Vec::new(),
         }
     }
 
@@ -972,6 +1165,21 @@ impl DebugScenarioDelegate {
             self.last_used_candidate_index = Some(recent.len() - 1);
         }
 
+        self.launch_presets = scenarios
+            .iter()
+            .filter(|(kind, scenario)| {
+                matches!(
+                    kind,
+                    TaskSourceKind::Worktree { .. } | TaskSourceKind::AbsPath { .. }
+                ) && scenario
+                    .config
+                    .get("request")
+                    .and_then(serde_json::Value::as_str)
+                    == Some("launch")
+            })
+            .map(|(_, scenario)| scenario.clone())
+            .collect();
+
         let dap_registry = cx.global::<DapRegistry>();
 
         self.candidates = recent
@@ -1145,6 +1353,52 @@ impl PickerDelegate for DebugScenarioDelegate {
     }
 }
 
+/// The command line a launch configuration runs, written the way the custom form reads it back:
+/// environment variables first, then the program and its arguments.
+fn preset_command(config: &serde_json::Value) -> String {
+    let env = config
+        .get("env")
+        .and_then(serde_json::Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some(format!("{key}={}", value.as_str()?)));
+    let program = config
+        .get("program")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+    let args = config
+        .get("args")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.as_str().map(str::to_string));
+
+    env.chain(program)
+        .chain(args)
+        .map(|word| {
+            let quoted = shlex::try_quote(&word).map(Cow::into_owned);
+            quoted.unwrap_or(word)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Adds the preset's fields that the configuration built from the form doesn't set, so that
+/// adapter-specific options survive being edited in the form. Fields the form fills in are left
+/// out, since leaving them empty there is meant to clear them.
+fn merge_preset_config(config: &mut serde_json::Value, preset: &serde_json::Value) {
+    const FORM_FIELDS: [&str; 5] = ["program", "args", "cwd", "env", "stopOnEntry"];
+
+    let (Some(config), Some(preset)) = (config.as_object_mut(), preset.as_object()) else {
+        return;
+    };
+    for (key, value) in preset {
+        if !FORM_FIELDS.contains(&key.as_str()) {
+            config.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
 pub(crate) fn resolve_path(path: &mut String) {
     if path.starts_with('~') {
         let home = paths::home_dir().to_string_lossy().to_string();
@@ -1162,6 +1416,104 @@ pub(crate) fn resolve_path(path: &mut String) {
 #[cfg(test)]
 mod tests {
     use paths::home_dir;
+    use serde_json::json;
+
+    #[test]
+    fn test_preset_command() {
+        let config = json!({
+            "request": "launch",
+            "program": "./target/debug/app",
+            "args": ["--name", "two words"],
+            "env": { "RUST_LOG": "debug" },
+        });
+        assert_eq!(
+            shlex::split(&super::preset_command(&config)),
+            Some(vec![
+                "RUST_LOG=debug".to_string(),
+                "./target/debug/app".to_string(),
+                "--name".to_string(),
+                "two words".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_preset_config() {
+        let mut config = json!({ "program": "main.py", "cwd": "/project" });
+        super::merge_preset_config(
+            &mut config,
+            &json!({ "program": "old.py", "args": ["--old"], "justMyCode": false }),
+        );
+        assert_eq!(
+            config,
+            json!({ "program": "main.py", "cwd": "/project", "justMyCode": false })
+        );
+    }
+
+    #[test]
+    fn test_preset_command_quoting() {
+        let command = |config| super::preset_command(&config);
+
+        assert_eq!(
+            command(json!({ "program": "app", "args": ["--verbose", "-n", "3"] })),
+            "app --verbose -n 3"
+        );
+
+        let args = ["two words", "it's", "\"quoted\"", "$HOME", "a\\b", ""];
+        let command = command(json!({
+            "program": "/path with spaces/app",
+            "args": args,
+            "env": { "GREETING": "hello world" },
+        }));
+        let mut expected = vec![
+            "GREETING=hello world".to_string(),
+            "/path with spaces/app".to_string(),
+        ];
+        expected.extend(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(shlex::split(&command), Some(expected));
+    }
+
+    #[test]
+    fn test_preset_command_env_order() {
+        let command = super::preset_command(&json!({
+            "program": "app",
+            "env": { "ZED_LAST": "1", "ALPHA": "2", "NUMBER": 3, "MIDDLE": "4" },
+        }));
+        // Variables keep the order they were written in, and ones that aren't strings are
+        // left out.
+        assert_eq!(command, "ZED_LAST=1 ALPHA=2 MIDDLE=4 app");
+    }
+
+    #[test]
+    fn test_merge_preset_config_keeps_adapter_fields() {
+        let mut config = json!({
+            "request": "launch",
+            "program": "main.py",
+            "justMyCode": true,
+        });
+        super::merge_preset_config(
+            &mut config,
+            &json!({
+                "request": "attach",
+                "program": "old.py",
+                "env": { "DEBUG": "1" },
+                "stopOnEntry": true,
+                "justMyCode": false,
+                "console": "integratedTerminal",
+                "pathMappings": [{ "localRoot": "/project", "remoteRoot": "/app" }],
+            }),
+        );
+        assert_eq!(
+            config,
+            json!({
+                "request": "launch",
+                "program": "main.py",
+                "justMyCode": true,
+                "console": "integratedTerminal",
+                "pathMappings": [{ "localRoot": "/project", "remoteRoot": "/app" }],
+            })
+        );
+    }
 
     #[test]
     fn test_normalize_paths() {
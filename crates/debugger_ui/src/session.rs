@@ -6,12 +6,12 @@ use gpui::{
     App, Axis, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task, WeakEntity,
 };
 use project::Project;
-use project::debugger::session::Session;
+use project::debugger::session::{Session, ThreadStatus};
 use project::worktree_store::WorktreeStore;
 use rpc::proto;
 use running::RunningState;
 use std::{cell::OnceCell, sync::OnceLock};
-use ui::{Indicator, prelude::*};
+use ui::{Indicator, Tooltip, prelude::*};
 use workspace::{
     CollaboratorId, FollowableItem, ViewId, Workspace,
     item::{self, Item},
@@ -129,39 +129,68 @@ impl DebugSession {
     pub(crate) fn label_element(&self, cx: &App) -> AnyElement {
         let label = self.label(cx);
 
-        let is_terminated = self
-            .running_state
-            .read(cx)
-            .session()
-            .read(cx)
-            .is_terminated();
-        let icon = {
-            if is_terminated {
-                Some(Indicator::dot().color(Color::Error))
-            } else {
-                match self
-                    .running_state
-                    .read(cx)
-                    .thread_status(cx)
-                    .unwrap_or_default()
-                {
-                    project::debugger::session::ThreadStatus::Stopped => {
-                        Some(Indicator::dot().color(Color::Conflict))
-                    }
-                    _ => Some(Indicator::dot().color(Color::Success)),
-                }
+        let running_state = self.running_state.read(cx);
+        let session = running_state.session().read(cx);
+        let is_terminated = session.is_terminated();
+        let thread_status = running_state.thread_status(cx).unwrap_or_default();
+        let indicator_color = if is_terminated {
+            Color::Error
+        } else {
+            match thread_status {
+                ThreadStatus::Running => Color::Success,
+                ThreadStatus::Stopped => Color::Conflict,
+                ThreadStatus::Stepping => Color::Info,
+                ThreadStatus::Exited | ThreadStatus::Ended => Color::Disabled,
             }
         };
 
+        // Threads that are gone can't be resumed, so say so rather than only dimming the dot.
+        let finished_badge = matches!(thread_status, ThreadStatus::Exited | ThreadStatus::Ended)
+            .then(|| {
+                let tooltip = match (thread_status, session.exit_code()) {
+                    (ThreadStatus::Ended, Some(exit_code)) => {
+                        format!("{} (exit code {exit_code})", thread_status.description())
+                    }
+                    _ => thread_status.description().to_string(),
+                };
+                div()
+                    .id("debug-session-finished")
+                    .px_1()
+                    .rounded_sm()
+                    .bg(cx.theme().colors().element_background)
+                    .child(
+                        Label::new(thread_status.label())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .tooltip(Tooltip::text(tooltip))
+            });
+
+        let adapter = session.adapter();
+        let details = match running_state
+            .selected_thread_id()
+            .and_then(|thread_id| session.thread_name(thread_id))
+        {
+            Some(thread_name) => format!("{adapter} · {thread_name}"),
+            None => adapter.to_string(),
+        };
+
         h_flex()
             .gap_2()
-            .when_some(icon, |this, indicator| this.child(indicator))
+            .child(Indicator::dot().color(indicator_color))
             .justify_between()
             .child(
                 Label::new(label)
                     .size(LabelSize::Small)
                     .when(is_terminated, |this| this.strikethrough()),
             )
+            .children(finished_badge)
+            .child(
+                Label::new(details)
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
             .into_any_element()
     }
 }
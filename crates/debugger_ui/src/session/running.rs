@@ -18,15 +18,16 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, RunInTerminalRequestArgumentsKind,
+    RunInTerminalResponse, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
 };
 use futures::{SinkExt, channel::mpsc};
 use gpui::{
-    Action as _, AnyView, AppContext, Axis, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
-    NoAction, Pixels, Point, Subscription, Task, WeakEntity,
+    Action as _, AnyView, AppContext, AsyncWindowContext, Axis, ClipboardItem, Entity, EntityId,
+    EventEmitter, FocusHandle, Focusable, NoAction, Pixels, Point, Subscription, Task, WeakEntity,
 };
 use language::Buffer;
 use loaded_source_list::LoadedSourceList;
@@ -44,12 +45,13 @@ use task::{
     BuildTaskDefinition, DebugScenario, ShellBuilder, SpawnInTerminal, TaskContext, ZedDebugConfig,
     substitute_variables_in_str,
 };
+use terminal::Terminal;
 use terminal_view::TerminalView;
 use ui::{
-    ActiveTheme, AnyElement, App, ButtonCommon as _, Clickable as _, Context, FluentBuilder,
-    IconButton, IconName, IconSize, InteractiveElement, IntoElement, Label, LabelCommon as _,
-    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Tab, Tooltip,
-    VisibleOnHover, VisualContext, Window, div, h_flex, v_flex,
+    ActiveTheme, AnyElement, App, ButtonCommon as _, Clickable as _, Color, Context, FluentBuilder,
+    Icon, IconButton, IconName, IconSize, InteractiveElement, IntoElement, Label, LabelCommon as _,
+    LabelSize, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Tab,
+    Tooltip, VisibleOnHover, VisualContext, Window, div, h_flex, v_flex,
 };
 use util::ResultExt;
 use variable_list::VariableList;
@@ -77,6 +79,7 @@ pub struct RunningState {
     active_pane: Option<Entity<Pane>>,
     pane_close_subscriptions: HashMap<EntityId, Subscription>,
     dock_axis: Axis,
+    stopped_banner_dismissed: bool,
     _schedule_serialize: Option<Task<()>>,
 }
 
@@ -121,14 +124,81 @@ impl Render for RunningState {
         self.variable_list.update(cx, |this, cx| {
             this.disabled(thread_status != ThreadStatus::Stopped, cx);
         });
+        let stopped_banner = (thread_status == ThreadStatus::Stopped
+            && !self.stopped_banner_dismissed)
+            .then(|| self.render_stopped_banner(cx))
+            .flatten();
         v_flex()
             .size_full()
             .key_context("DebugSessionItem")
             .track_focus(&self.focus_handle(cx))
+            .children(stopped_banner)
             .child(h_flex().flex_1().child(pane))
     }
 }
 
+impl RunningState {
+    fn render_stopped_banner(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let session = self.session.read(cx);
+        let event = session.last_stopped_event()?;
+        let is_exception = matches!(event.reason, dap::StoppedEventReason::Exception);
+        let reason = event
+            .description
+            .clone()
+            .unwrap_or_else(|| match event.reason {
+                dap::StoppedEventReason::DataBreakpoint => "Stopped: value changed".to_string(),
+                _ => format!("Stopped: {:?}", event.reason),
+            });
+        let (icon, color, background) = if is_exception {
+            (
+                IconName::XCircle,
+                Color::Error,
+                cx.theme().status().error_background,
+            )
+        } else {
+            (
+                IconName::Info,
+                Color::Info,
+                cx.theme().status().info_background,
+            )
+        };
+
+        Some(
+            h_flex()
+                .id("debugger-stopped-banner")
+                .w_full()
+                .px_2()
+                .py_1()
+                .gap_2()
+                .bg(background)
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(Label::new(reason).size(LabelSize::Small).color(color))
+                .when_some(event.text.clone(), |this, text| {
+                    this.child(
+                        Label::new(text)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line()
+                            .truncate(),
+                    )
+                })
+                .child(div().flex_1())
+                .child(
+                    IconButton::new("dismiss-stopped-banner", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.stopped_banner_dismissed = true;
+                            cx.notify();
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
 pub(crate) struct SubView {
     inner: AnyView,
     item_focus_handle: FocusHandle,
@@ -493,8 +563,107 @@ pub(crate) fn new_debugger_pane(
     ret
 }
 
+/// Reports the shell Zed spawned for the terminal, and the process running in its foreground
+/// when that's something other than the shell.
+fn run_in_terminal_response(terminal: &Terminal) -> RunInTerminalResponse {
+    let shell_pid = terminal.pty_info.pid_getter().fallback_pid();
+    let foreground_pid = terminal.pty_info.pid().map(|pid| pid.as_u32());
+    RunInTerminalResponse {
+        process_id: foreground_pid
+            .filter(|pid| *pid != shell_pid)
+            .map(u64::from),
+        shell_process_id: Some(u64::from(shell_pid)),
+    }
+}
+
+/// Whether the terminal is a plain shell that is waiting for input, rather than one running a
+/// program that anything typed into it would be sent to.
+fn shell_is_idle(terminal: &Terminal) -> bool {
+    terminal.task().is_none() && run_in_terminal_response(terminal).process_id.is_none()
+}
+
+/// How often, and how many times, to check whether a command typed into a reused shell started.
+const COMMAND_START_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const COMMAND_START_POLLS: usize = 100;
+
+/// Waits for the command just typed into the terminal's shell to take over its foreground, so
+/// that its pid is the one reported. If it doesn't within two seconds, only the shell's pid is.
+async fn started_process_response(
+    terminal: &Entity<Terminal>,
+    cx: &mut AsyncWindowContext,
+) -> Result<RunInTerminalResponse> {
+    for _ in 0..COMMAND_START_POLLS {
+        let response = terminal.read_with(cx, |terminal, _| run_in_terminal_response(terminal))?;
+        if response.process_id.is_some() {
+            return Ok(response);
+        }
+        cx.background_executor()
+            .timer(COMMAND_START_POLL_INTERVAL)
+            .await;
+    }
+    terminal.read_with(cx, |terminal, _| run_in_terminal_response(terminal))
+}
+
+/// Builds the command that opens the system terminal and runs `args` in it, from `cwd` and with
+/// `env` added to its environment.
+pub(crate) fn external_terminal_command(
+    args: &[String],
+    title: Option<&str>,
+    cwd: &Option<PathBuf>,
+    env: &HashMap<String, String>,
+) -> Result<std::process::Command> {
+    let (program, program_args) = args
+        .split_first()
+        .context("no command to run in the system terminal")?;
+
+    if cfg!(target_os = "macos") {
+        // Terminal.app runs the command in a shell of its own, so the working directory and
+        // environment are set up by the command line itself.
+        let mut command_line = Vec::new();
+        if let Some(cwd) = cwd {
+            command_line.push("cd".to_string());
+            command_line.push(shlex::try_quote(&cwd.to_string_lossy())?.into_owned());
+            command_line.push("&&".to_string());
+        }
+        if !env.is_empty() {
+            command_line.push("env".to_string());
+            for (key, value) in env {
+                command_line.push(shlex::try_quote(&format!("{key}={value}"))?.into_owned());
+            }
+        }
+        command_line.push(shlex::try_join(args.iter().map(String::as_str))?);
+        let command_line = command_line.join(" ");
+        let script = format!(
+            "tell application \"Terminal\"\nactivate\ndo script \"{}\"\nend tell",
+            command_line.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let mut command = util::command::new_std_command("osascript");
+        command.arg("-e").arg(script);
+        return Ok(command);
+    }
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = util::command::new_std_command("cmd");
+        command.args(["/C", "start", title.unwrap_or("Debuggee"), "cmd", "/K"]);
+        command
+    } else {
+        let terminal =
+            std::env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".to_string());
+        let mut command = util::command::new_std_command(terminal);
+        command.arg("-e");
+        command
+    };
+    command.arg(program).args(program_args).envs(env);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    Ok(command)
+}
+
 pub struct DebugTerminal {
     pub terminal: Option<Entity<TerminalView>>,
+    /// The environment the terminal was spawned with, which requests reusing it must match.
+    env: HashMap<String, String>,
     focus_handle: FocusHandle,
 }
 
@@ -502,6 +671,7 @@ impl DebugTerminal {
     fn empty(cx: &mut Context<Self>) -> Self {
         Self {
             terminal: None,
+            env: HashMap::default(),
             focus_handle: cx.focus_handle(),
         }
     }
@@ -614,6 +784,19 @@ impl RunningState {
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
+                        this.stopped_banner_dismissed = false;
+                        // Stopping on entry was asked for, so the entry point is shown even when
+                        // the adapter hints that focus should stay where it is.
+                        let thread_id = (*thread_id).or_else(|| {
+                            this.session
+                                .read(cx)
+                                .last_stopped_event()
+                                .filter(|event| {
+                                    matches!(event.reason, dap::StoppedEventReason::Entry)
+                                })
+                                .and_then(|event| event.thread_id)
+                                .map(ThreadId)
+                        });
                         let panel = this
                             .workspace
                             .update(cx, |workspace, cx| {
@@ -624,7 +807,7 @@ impl RunningState {
                             .flatten();
 
                         if let Some(thread_id) = thread_id {
-                            this.select_thread(*thread_id, window, cx);
+                            this.select_thread(thread_id, window, cx);
                         }
                         if let Some(panel) = panel {
                             let id = this.session_id;
@@ -639,6 +822,7 @@ impl RunningState {
                         let threads = this.session.update(cx, |this, cx| this.threads(cx));
                         this.select_current_thread(&threads, window, cx);
                     }
+                    SessionEvent::Continued => cx.notify(),
                     SessionEvent::CapabilitiesLoaded => {
                         let capabilities = this.capabilities(cx);
                         if !capabilities.supports_modules_request.unwrap_or(false) {
@@ -723,6 +907,7 @@ impl RunningState {
             pane_close_subscriptions,
             debug_terminal,
             dock_axis,
+            stopped_banner_dismissed: false,
             _schedule_serialize: None,
         }
     }
@@ -882,6 +1067,7 @@ impl RunningState {
                     this.ensure_pane_item(DebuggerPaneItem::Terminal, window, cx);
                     this.debug_terminal.update(cx, |debug_terminal, cx| {
                         debug_terminal.terminal = Some(terminal_view);
+                        debug_terminal.env = task.resolved.env.clone();
                         cx.notify();
                     });
                 })?;
@@ -944,7 +1130,7 @@ impl RunningState {
     fn handle_run_in_terminal(
         &self,
         request: &RunInTerminalRequestArguments,
-        mut sender: mpsc::Sender<Result<u32>>,
+        mut sender: mpsc::Sender<Result<RunInTerminalResponse>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
@@ -987,7 +1173,81 @@ impl RunningState {
             }
         }
 
+        if request.kind == Some(RunInTerminalRequestArgumentsKind::External) {
+            let command =
+                external_terminal_command(&request.args, request.title.as_deref(), &cwd, &envs);
+            return cx.background_spawn(async move {
+                let response = command.and_then(|mut command| {
+                    let mut child = command.spawn().context("opening the system terminal")?;
+                    let terminal_pid = child.id();
+                    // Reap the terminal once it's closed.
+                    std::thread::spawn(move || child.wait());
+                    // The debuggee runs in a shell inside the terminal, whose pid isn't known.
+                    Ok(RunInTerminalResponse {
+                        process_id: None,
+                        shell_process_id: Some(u64::from(terminal_pid)),
+                    })
+                });
+                anyhow::Ok(sender.send(response).await?)
+            });
+        }
+
+        // A shell spawned with the same working directory and environment can serve the request,
+        // running its command if it has one, as long as it isn't running anything already.
+        if DebuggerSettings::get_global(cx).reuse_terminal {
+            let debug_terminal = self.debug_terminal.read(cx);
+            let existing_shell = debug_terminal
+                .terminal
+                .as_ref()
+                .filter(|_| debug_terminal.env == envs)
+                .map(|view| view.read(cx).terminal().clone())
+                .filter(|terminal| {
+                    let terminal = terminal.read(cx);
+                    shell_is_idle(terminal)
+                        && cwd
+                            .as_ref()
+                            .is_none_or(|cwd| terminal.working_directory().as_ref() == Some(cwd))
+                });
+            if let Some(terminal) = existing_shell {
+                let command_line = command
+                    .as_ref()
+                    .map(|command| {
+                        shlex::try_join(
+                            std::iter::once(command.as_str())
+                                .chain(args.iter().map(String::as_str)),
+                        )
+                        .map_err(|error| anyhow!("can't run {command:?} in a shell: {error}"))
+                    })
+                    .transpose();
+                return cx.spawn_in(window, async move |running, cx| {
+                    let response = match command_line {
+                        Ok(Some(command_line)) => {
+                            terminal.update(cx, |terminal, _| {
+                                terminal.input(format!("{command_line}\n"))
+                            })?;
+                            started_process_response(&terminal, cx).await
+                        }
+                        Ok(None) => terminal
+                            .read_with(cx, |terminal, _| Ok(run_in_terminal_response(terminal)))?,
+                        Err(error) => Err(error),
+                    };
+                    if response.is_ok() {
+                        running.update_in(cx, |running, window, cx| {
+                            running.ensure_pane_item(DebuggerPaneItem::Terminal, window, cx);
+                        })?;
+                    }
+                    anyhow::Ok(sender.send(response).await?)
+                });
+            }
+        }
+
         let shell = project.read(cx).terminal_settings(&cwd, cx).shell.clone();
+        // Plain shells are spawned without the requested environment.
+        let terminal_env = if command.is_some() {
+            envs.clone()
+        } else {
+            HashMap::default()
+        };
         let kind = if let Some(command) = command {
             let title = request.title.clone().unwrap_or(command.clone());
             TerminalKind::Task(task::SpawnInTerminal {
@@ -1038,17 +1298,12 @@ impl RunningState {
                 running.ensure_pane_item(DebuggerPaneItem::Terminal, window, cx);
                 running.debug_terminal.update(cx, |debug_terminal, cx| {
                     debug_terminal.terminal = Some(terminal_view);
+                    debug_terminal.env = terminal_env;
                     cx.notify();
                 });
             })?;
 
-            terminal.read_with(cx, |terminal, _| {
-                terminal
-                    .pty_info
-                    .pid()
-                    .map(|pid| pid.as_u32())
-                    .context("Terminal was spawned but PID was not available")
-            })?
+            terminal.read_with(cx, |terminal, _| run_in_terminal_response(terminal))
         });
 
         cx.background_spawn(async move { anyhow::Ok(sender.send(terminal_task.await).await?) })
@@ -1215,6 +1470,20 @@ impl RunningState {
         }
     }
 
+    /// Saves the layout of the panes right away, for when the session is going away and would
+    /// take a scheduled save with it.
+    pub(crate) fn save_layout(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        self._schedule_serialize.take();
+        let adapter_name = self.session.read(cx).adapter();
+        let pane_layout =
+            persistence::build_serialized_layout(&self.panes.root, self.dock_axis, cx);
+        cx.background_spawn(async move {
+            persistence::serialize_pane_layout(adapter_name, pane_layout)
+                .await
+                .log_err();
+        })
+    }
+
     pub(crate) fn handle_pane_event(
         this: &mut RunningState,
         source_pane: &Entity<Pane>,
@@ -1401,41 +1670,64 @@ impl RunningState {
     }
 
     pub fn step_over(&mut self, cx: &mut Context<Self>) {
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        self.step_over_by(granularity, cx);
+    }
+
+    pub(crate) fn step_over_by(
+        &mut self,
+        granularity: SteppingGranularity,
+        cx: &mut Context<Self>,
+    ) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
-
         self.session().update(cx, |state, cx| {
             state.step_over(thread_id, granularity, cx);
         });
     }
 
     pub(crate) fn step_in(&mut self, cx: &mut Context<Self>) {
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        self.step_in_by(granularity, cx);
+    }
+
+    pub(crate) fn step_in_by(&mut self, granularity: SteppingGranularity, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
-
         self.session().update(cx, |state, cx| {
             state.step_in(thread_id, granularity, cx);
         });
     }
 
     pub(crate) fn step_out(&mut self, cx: &mut Context<Self>) {
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        self.step_out_by(granularity, cx);
+    }
+
+    pub(crate) fn step_out_by(&mut self, granularity: SteppingGranularity, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
-
         self.session().update(cx, |state, cx| {
             state.step_out(thread_id, granularity, cx);
         });
     }
 
+    /// Whether the adapter honors the granularity of step requests, which is required to step
+    /// by instruction.
+    pub(crate) fn supports_stepping_granularity(&self, cx: &App) -> bool {
+        self.session()
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default()
+    }
+
     pub(crate) fn step_back(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -1510,6 +1802,70 @@ impl RunningState {
         });
     }
 
+    /// Copies the current thread's whole call stack, including the frames that haven't been
+    /// loaded for display.
+    pub fn copy_call_stack(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let Some(thread_id) = self.thread_id else {
+            return Task::ready(());
+        };
+
+        let stack_trace = self
+            .session
+            .update(cx, |session, cx| session.full_stack_trace(thread_id, cx));
+        cx.spawn(async move |_, cx| {
+            let Some(stack_frames) = stack_trace.await.log_err() else {
+                return;
+            };
+            if stack_frames.is_empty() {
+                return;
+            }
+
+            cx.update(|cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(
+                    StackFrameList::format_stack_frames(&stack_frames),
+                ))
+            })
+            .ok();
+        })
+    }
+
+    /// Copies the whole call stack of every thread, fetching the frames that haven't been loaded.
+    pub fn copy_all_call_stacks(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let (threads, stack_traces): (Vec<_>, Vec<_>) = self.session.update(cx, |session, cx| {
+            session
+                .threads(cx)
+                .into_iter()
+                .map(|(thread, _)| {
+                    let stack_trace = session.full_stack_trace(ThreadId(thread.id), cx);
+                    (thread, stack_trace)
+                })
+                .unzip()
+        });
+        cx.spawn(async move |_, cx| {
+            let stack_traces = futures::future::join_all(stack_traces).await;
+            let text = threads
+                .into_iter()
+                .zip(stack_traces)
+                .map(|(thread, stack_frames)| {
+                    let stack_frames = stack_frames.log_err().unwrap_or_default();
+                    format!(
+                        "Thread {} ({}):\n{}",
+                        thread.id,
+                        thread.name,
+                        StackFrameList::format_stack_frames(&stack_frames)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if text.is_empty() {
+                return;
+            }
+
+            cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(text)))
+                .ok();
+        })
+    }
+
     pub fn toggle_ignore_breakpoints(&mut self, cx: &mut Context<Self>) {
         self.session.update(cx, |session, cx| {
             session.toggle_ignore_breakpoints(cx).detach();
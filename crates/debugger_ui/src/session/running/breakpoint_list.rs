@@ -6,8 +6,8 @@ use std::{
 use dap::ExceptionBreakpointsFilter;
 use editor::Editor;
 use gpui::{
-    AppContext, Entity, FocusHandle, Focusable, ListState, MouseButton, Stateful, Task, WeakEntity,
-    list,
+    AppContext, Entity, FocusHandle, Focusable, ListState, MouseButton, Stateful, Subscription,
+    Task, WeakEntity, list,
 };
 use language::Point;
 use project::{
@@ -19,10 +19,11 @@ use project::{
     worktree_store::WorktreeStore,
 };
 use ui::{
-    App, Clickable, Color, Context, Div, Icon, IconButton, IconName, Indicator, InteractiveElement,
+    App, Button, ButtonCommon, ButtonSize, Checkbox, Clickable, Color, Context, Disableable, Div,
+    FluentBuilder, Icon, IconButton, IconName, IconSize, Indicator, InteractiveElement,
     IntoElement, Label, LabelCommon, LabelSize, ListItem, ParentElement, Render, RenderOnce,
-    Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement, Styled, Tooltip, Window,
-    div, h_flex, px, v_flex,
+    Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement, Styled, ToggleState,
+    Tooltip, Window, div, h_flex, px, v_flex,
 };
 use util::{ResultExt, maybe};
 use workspace::Workspace;
@@ -38,6 +39,7 @@ pub(crate) struct BreakpointList {
     hide_scrollbar_task: Option<Task<()>>,
     show_scrollbar: bool,
     focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Focusable for BreakpointList {
@@ -73,6 +75,11 @@ impl BreakpointList {
                     breakpoint.render(window, cx).into_any_element()
                 },
             );
+            // Verification results arrive from the adapter after the breakpoints are sent.
+            let subscriptions = vec![
+                cx.observe(&breakpoint_store, |_, _, cx| cx.notify()),
+                cx.observe(&session, |_, _, cx| cx.notify()),
+            ];
             Self {
                 breakpoint_store,
                 worktree_store,
@@ -84,6 +91,7 @@ impl BreakpointList {
                 workspace,
                 session,
                 focus_handle: cx.focus_handle(),
+                _subscriptions: subscriptions,
             }
         })
     }
@@ -149,6 +157,7 @@ impl Render for BreakpointList {
     ) -> impl ui::IntoElement {
         let old_len = self.breakpoints.len();
         let breakpoints = self.breakpoint_store.read(cx).all_source_breakpoints(cx);
+        let session_id = self.session.read(cx).session_id();
         self.breakpoints.clear();
         let weak = cx.weak_entity();
         let breakpoints = breakpoints.into_iter().flat_map(|(path, mut breakpoints)| {
@@ -162,7 +171,11 @@ impl Render for BreakpointList {
                         .is_visible()
                         .then(|| Path::new(worktree.read(cx).root_name()).join(relative_path))
                 });
-            breakpoints.sort_by_key(|breakpoint| breakpoint.row);
+            breakpoints.sort_by_key(|breakpoint| (breakpoint.row, breakpoint.column));
+            let session_states = self
+                .breakpoint_store
+                .read(cx)
+                .breakpoint_session_states(&path, session_id, cx);
             let weak = weak.clone();
             breakpoints.into_iter().filter_map(move |breakpoint| {
                 debug_assert_eq!(&path, &breakpoint.path);
@@ -183,12 +196,20 @@ impl Render for BreakpointList {
                     .map(ToOwned::to_owned)
                     .map(SharedString::from)?;
                 let weak = weak.clone();
-                let line = format!("Line {}", breakpoint.row + 1).into();
+                let line = match breakpoint.column {
+                    Some(column) => format!("Line {}, Column {}", breakpoint.row + 1, column + 1),
+                    None => format!("Line {}", breakpoint.row + 1),
+                }
+                .into();
+                let verified = session_states
+                    .get(&(breakpoint.row, breakpoint.column))
+                    .map(|state| state.verified);
                 Some(BreakpointEntry {
                     kind: BreakpointEntryKind::LineBreakpoint(LineBreakpoint {
                         name,
                         dir,
                         line,
+                        verified,
                         breakpoint,
                     }),
                     weak,
@@ -207,8 +228,22 @@ impl Render for BreakpointList {
                     }),
                     weak: weak.clone(),
                 });
-        self.breakpoints
-            .extend(breakpoints.chain(exception_breakpoints));
+        let data_breakpoints =
+            self.session
+                .read(cx)
+                .data_breakpoints()
+                .map(|(data_id, description)| BreakpointEntry {
+                    kind: BreakpointEntryKind::DataBreakpoint(DataBreakpoint {
+                        data_id: data_id.clone(),
+                        description: description.clone(),
+                    }),
+                    weak: weak.clone(),
+                });
+        self.breakpoints.extend(
+            breakpoints
+                .chain(exception_breakpoints)
+                .chain(data_breakpoints),
+        );
         if self.breakpoints.len() != old_len {
             self.list_state.reset(self.breakpoints.len());
         }
@@ -226,6 +261,22 @@ impl Render for BreakpointList {
             }))
             .size_full()
             .m_0p5()
+            .child(
+                h_flex().justify_end().px_1().child(
+                    Button::new("clear-all-breakpoints", "Clear All")
+                        .size(ButtonSize::Compact)
+                        .label_size(LabelSize::Small)
+                        .disabled(self.breakpoints.iter().all(|entry| {
+                            !matches!(entry.kind, BreakpointEntryKind::LineBreakpoint(_))
+                        }))
+                        .tooltip(Tooltip::text("Remove every breakpoint"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.breakpoint_store
+                                .update(cx, |store, cx| store.clear_breakpoints(cx));
+                            cx.notify();
+                        })),
+                ),
+            )
             .child(list(self.list_state.clone()).flex_grow())
             .children(self.render_vertical_scrollbar(cx))
     }
@@ -235,6 +286,8 @@ struct LineBreakpoint {
     name: SharedString,
     dir: Option<SharedString>,
     line: SharedString,
+    /// Whether the session's adapter could bind the breakpoint, once it has said.
+    verified: Option<bool>,
     breakpoint: SourceBreakpoint,
 }
 
@@ -244,6 +297,7 @@ impl LineBreakpoint {
             name,
             dir,
             line,
+            verified,
             breakpoint,
         } = self;
         let icon_name = if breakpoint.state.is_enabled() {
@@ -253,25 +307,66 @@ impl LineBreakpoint {
         };
         let path = breakpoint.path;
         let row = breakpoint.row;
-        let indicator = div()
-            .id(SharedString::from(format!(
-                "breakpoint-ui-toggle-{:?}/{}:{}",
-                dir, name, line
-            )))
-            .cursor_pointer()
-            .tooltip(Tooltip::text(if breakpoint.state.is_enabled() {
-                "Disable Breakpoint"
-            } else {
-                "Enable Breakpoint"
-            }))
-            .on_click({
+        let column = breakpoint.column;
+        let condition = breakpoint
+            .condition
+            .as_ref()
+            .map(|condition| format!("when {condition}"))
+            .or_else(|| {
+                breakpoint
+                    .hit_condition
+                    .as_ref()
+                    .map(|hit_condition| format!("hit {hit_condition}"))
+            })
+            .or_else(|| {
+                breakpoint
+                    .message
+                    .as_ref()
+                    .map(|message| format!("log {message}"))
+            });
+        let verification = verified.map(|verified| {
+            div()
+                .id(SharedString::from(format!(
+                    "breakpoint-ui-verified-{:?}/{}:{}",
+                    dir, name, line
+                )))
+                .tooltip(Tooltip::text(if verified {
+                    "Verified by the debug adapter"
+                } else {
+                    "Not verified: the debug adapter couldn't set this breakpoint"
+                }))
+                .child(if verified {
+                    Icon::new(IconName::Check)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted)
+                } else {
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::XSmall)
+                        .color(Color::Warning)
+                })
+        });
+        let indicator = h_flex()
+            .gap_1()
+            .child(
+                Checkbox::new(
+                    SharedString::from(format!(
+                        "breakpoint-ui-toggle-{:?}/{}:{}",
+                        dir, name, line
+                    )),
+                    if breakpoint.state.is_enabled() {
+                        ToggleState::Selected
+                    } else {
+                        ToggleState::Unselected
+                    },
+                )
+                .on_click({
                 let weak = weak.clone();
                 let path = path.clone();
                 move |_, _, cx| {
                     weak.update(cx, |this, cx| {
                         this.breakpoint_store.update(cx, |this, cx| {
                             if let Some((buffer, breakpoint)) =
-                                this.breakpoint_at_row(&path, row, cx)
+                                this.breakpoint_at_position(&path, row, column, cx)
                             {
                                 this.toggle_breakpoint(
                                     buffer,
@@ -286,7 +381,8 @@ impl LineBreakpoint {
                     })
                     .ok();
                 }
-            })
+            }),
+            )
             .child(Indicator::icon(Icon::new(icon_name)).color(Color::Debugger))
             .on_mouse_down(MouseButton::Left, move |_, _, _| {});
         ListItem::new(SharedString::from(format!(
@@ -294,6 +390,7 @@ impl LineBreakpoint {
             dir, name, line
         )))
         .start_slot(indicator)
+        .when_some(verification, |item, verification| item.end_slot(verification))
         .rounded()
         .on_secondary_mouse_down(|_, _, cx| {
             cx.stop_propagation();
@@ -313,7 +410,7 @@ impl LineBreakpoint {
                     weak.update(cx, |this, cx| {
                         this.breakpoint_store.update(cx, |this, cx| {
                             if let Some((buffer, breakpoint)) =
-                                this.breakpoint_at_row(&path, row, cx)
+                                this.breakpoint_at_position(&path, row, column, cx)
                             {
                                 this.toggle_breakpoint(
                                     buffer,
@@ -403,10 +500,21 @@ impl LineBreakpoint {
                         })),
                 )
                 .child(
-                    Label::new(line)
-                        .size(LabelSize::XSmall)
-                        .color(Color::Muted)
-                        .line_height_style(ui::LineHeightStyle::UiLabel),
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Label::new(line)
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                                .line_height_style(ui::LineHeightStyle::UiLabel),
+                        )
+                        .children(condition.map(|condition| {
+                            Label::new(condition)
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                                .italic()
+                                .line_height_style(ui::LineHeightStyle::UiLabel)
+                        })),
                 ),
         )
     }
@@ -476,9 +584,61 @@ impl ExceptionBreakpoint {
     }
 }
 #[derive(Clone, Debug)]
+struct DataBreakpoint {
+    data_id: String,
+    description: SharedString,
+}
+
+impl DataBreakpoint {
+    fn render(self, list: WeakEntity<BreakpointList>) -> ListItem {
+        let data_id = self.data_id;
+        ListItem::new(SharedString::from(format!(
+            "data-breakpoint-ui-item-{data_id}"
+        )))
+        .rounded()
+        .on_secondary_mouse_down(|_, _, cx| {
+            cx.stop_propagation();
+        })
+        .start_slot(Indicator::icon(Icon::new(IconName::DebugBreakpoint)).color(Color::Debugger))
+        .end_hover_slot(
+            IconButton::new(
+                SharedString::from(format!("data-breakpoint-ui-item-{data_id}-remove")),
+                IconName::Close,
+            )
+            .tooltip(Tooltip::text("Remove Data Breakpoint"))
+            .on_click(move |_, _, cx| {
+                list.update(cx, |this, cx| {
+                    this.session.update(cx, |session, cx| {
+                        session.remove_data_breakpoint(&data_id, cx);
+                    });
+                })
+                .ok();
+            })
+            .icon_size(IconSize::XSmall),
+        )
+        .child(
+            div()
+                .py_1()
+                .gap_1()
+                .child(
+                    Label::new(self.description)
+                        .size(LabelSize::Small)
+                        .line_height_style(ui::LineHeightStyle::UiLabel),
+                )
+                .child(
+                    Label::new("Breaks when the value changes")
+                        .size(LabelSize::XSmall)
+                        .line_height_style(ui::LineHeightStyle::UiLabel)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+}
+#[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
+    DataBreakpoint(DataBreakpoint),
 }
 
 #[derive(Clone, Debug)]
@@ -495,6 +655,9 @@ impl RenderOnce for BreakpointEntry {
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 exception_breakpoint.render(self.weak)
             }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                data_breakpoint.render(self.weak)
+            }
         }
     }
 }
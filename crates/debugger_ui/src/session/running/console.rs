@@ -4,22 +4,39 @@ use super::{
 };
 use anyhow::Result;
 use collections::HashMap;
-use dap::OutputEvent;
-use editor::{Bias, CompletionProvider, Editor, EditorElement, EditorStyle, ExcerptId};
+use dap::{OutputEvent, OutputEventGroup};
+use editor::{
+    Anchor, Bias, CompletionProvider, Editor, EditorElement, EditorStyle, ExcerptId,
+    FoldPlaceholder, ToPoint as _,
+    display_map::{Crease, CreaseId, FoldId},
+};
 use fuzzy::StringMatchCandidate;
 use gpui::{
-    Context, Entity, FocusHandle, Focusable, Render, Subscription, Task, TextStyle, WeakEntity,
+    Context, Empty, Entity, FocusHandle, Focusable, Render, Subscription, Task, TextStyle,
+    WeakEntity,
 };
 use language::{Buffer, CodeLabel, ToOffset};
 use menu::Confirm;
+use multi_buffer::MultiBufferRow;
 use project::{
     Completion,
-    debugger::session::{CompletionsQuery, OutputToken, Session, SessionEvent},
+    debugger::session::{CompletionsQuery, OutputToken, Session, SessionEvent, SessionStateEvent},
 };
 use settings::Settings;
-use std::{cell::RefCell, rc::Rc, usize};
+use std::{cell::RefCell, ops::Range, rc::Rc, sync::Arc, usize};
 use theme::ThemeSettings;
-use ui::{Divider, prelude::*};
+use ui::{ButtonLike, Disclosure, Divider, prelude::*};
+
+const GROUP_INDENT: &str = "  ";
+
+/// An output group the adapter started and hasn't ended yet.
+struct OpenGroup {
+    /// Offset of the end of the group's header line in the console.
+    header_end: usize,
+    collapsed: bool,
+    /// The crease over the group's output so far, once it has any.
+    crease_id: Option<CreaseId>,
+}
 
 pub struct Console {
     console: Entity<Editor>,
@@ -31,6 +48,8 @@ pub struct Console {
     last_token: OutputToken,
     update_output_task: Task<()>,
     focus_handle: FocusHandle,
+    /// Groups nest, so output is indented by how many are open.
+    open_groups: Vec<OpenGroup>,
 }
 
 impl Console {
@@ -84,6 +103,15 @@ impl Console {
                     this.update_output(window, cx)
                 }
             }),
+            cx.subscribe_in(
+                &session,
+                window,
+                |this, _, event: &SessionStateEvent, window, cx| {
+                    if let SessionStateEvent::Shutdown = event {
+                        this.close_open_groups(window, cx);
+                    }
+                },
+            ),
             cx.on_focus_in(&focus_handle, window, |console, window, cx| {
                 if console.is_running(cx) {
                     console.query_bar.focus_handle(cx).focus(window);
@@ -91,7 +119,7 @@ impl Console {
             }),
         ];
 
-        Self {
+        let mut this = Self {
             session,
             console,
             query_bar,
@@ -101,7 +129,12 @@ impl Console {
             update_output_task: Task::ready(()),
             last_token: OutputToken(0),
             focus_handle,
-        }
+            open_groups: Vec::new(),
+        };
+        // The adapter may have written output before this console existed, and no further
+        // `ConsoleOutput` event is coming for it.
+        this.update_output(window, cx);
+        this
     }
 
     #[cfg(test)]
@@ -135,12 +168,36 @@ impl Console {
         window: &mut Window,
         cx: &mut App,
     ) {
+        let open_groups = &mut self.open_groups;
         self.console.update(cx, |console, cx| {
+            let start = console.buffer().read(cx).len(cx);
             let mut to_insert = String::default();
+            let mut closed_groups = Vec::new();
             for event in events {
-                use std::fmt::Write;
-
-                _ = write!(to_insert, "{}\n", event.output.trim_end());
+                let output = event.output.trim_end();
+                match event.group {
+                    Some(OutputEventGroup::Start | OutputEventGroup::StartCollapsed) => {
+                        push_output(&mut to_insert, output, open_groups.len());
+                        open_groups.push(OpenGroup {
+                            // Leaves out the newline, so that the header stays visible when folded.
+                            header_end: start + to_insert.len() - 1,
+                            collapsed: matches!(
+                                event.group,
+                                Some(OutputEventGroup::StartCollapsed)
+                            ),
+                            crease_id: None,
+                        });
+                    }
+                    Some(OutputEventGroup::End) => {
+                        if let Some(group) = open_groups.pop() {
+                            closed_groups.push((group, start + to_insert.len()));
+                        }
+                        if !output.is_empty() {
+                            push_output(&mut to_insert, output, open_groups.len());
+                        }
+                    }
+                    None => push_output(&mut to_insert, output, open_groups.len()),
+                }
             }
 
             console.set_read_only(false);
@@ -148,10 +205,36 @@ impl Console {
             console.insert(&to_insert, window, cx);
             console.set_read_only(true);
 
+            // Groups that are still open are made foldable too, so that the ones the adapter
+            // started collapsed are folded while their output streams in.
+            let end = console.buffer().read(cx).len(cx);
+            for (group, group_end) in closed_groups
+                .iter_mut()
+                .map(|(group, group_end)| (group, *group_end))
+                .chain(open_groups.iter_mut().map(|group| (group, end)))
+            {
+                update_group_crease(console, group, group_end, window, cx);
+            }
+
             cx.notify();
         });
     }
 
+    /// Ends the groups the adapter never closed, since no more output is coming for them.
+    fn close_open_groups(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.open_groups.is_empty() {
+            return;
+        }
+
+        let mut open_groups = std::mem::take(&mut self.open_groups);
+        self.console.update(cx, |console, cx| {
+            let end = console.buffer().read(cx).len(cx);
+            for group in open_groups.iter_mut().rev() {
+                update_group_crease(console, group, end, window, cx);
+            }
+        });
+    }
+
     pub fn evaluate(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
         let expression = self.query_bar.update(cx, |editor, cx| {
             let expression = editor.text(cx);
@@ -227,6 +310,122 @@ impl Console {
     }
 }
 
+fn push_output(text: &mut String, output: &str, depth: usize) {
+    use std::fmt::Write;
+
+    if depth == 0 {
+        _ = write!(text, "{output}\n");
+        return;
+    }
+
+    let indent = GROUP_INDENT.repeat(depth);
+    if output.is_empty() {
+        _ = write!(text, "{indent}\n");
+    }
+    for line in output.lines() {
+        _ = write!(text, "{indent}{line}\n");
+    }
+}
+
+/// Makes a group foldable under its header, where `end` is the offset just past the group's
+/// last line so far. The crease is replaced as the group's output grows, and a group that's folded
+/// is folded again over its new output. Groups the adapter started collapsed are folded as soon as
+/// they have output.
+fn update_group_crease(
+    console: &mut Editor,
+    group: &mut OpenGroup,
+    end: usize,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let end = end.saturating_sub(1);
+    if end <= group.header_end {
+        return;
+    }
+
+    let snapshot = console.buffer().read(cx).snapshot(cx);
+    let folded = match group.crease_id.take() {
+        Some(crease_id) => {
+            console.remove_creases([crease_id], cx);
+
+            // Only a fold starting at the header is the group's own, rather than that of a group
+            // it's nested in.
+            let header = group.header_end.to_point(&snapshot);
+            let header_newline = group.header_end..group.header_end + 1;
+            let (own_folds, outer_folds): (Vec<_>, Vec<_>) = console
+                .snapshot(window, cx)
+                .folds_in_range(header_newline.clone())
+                .map(|fold| fold.range.start.to_point(&snapshot) == header)
+                .partition(|is_own| *is_own);
+            // Unfolding removes every fold around the header, so the old fold is left under the
+            // new one while an outer group is folded too.
+            if !own_folds.is_empty() && outer_folds.is_empty() {
+                console.unfold_ranges(&[header_newline], false, false, cx);
+            }
+            !own_folds.is_empty()
+        }
+        None => group.collapsed,
+    };
+
+    let weak_console = cx.weak_entity();
+    let crease = Crease::inline(
+        snapshot.anchor_after(group.header_end)..snapshot.anchor_before(end),
+        FoldPlaceholder {
+            render: render_group_placeholder(weak_console.clone()),
+            merge_adjacent: false,
+            ..Default::default()
+        },
+        |_, _, _, _, _| Empty.into_any_element(),
+        render_group_toggle(weak_console),
+    );
+    group.crease_id = console.insert_creases([crease.clone()], cx).pop();
+    if folded {
+        console.fold_creases(vec![crease], false, window, cx);
+    }
+}
+
+fn render_group_placeholder(
+    console: WeakEntity<Editor>,
+) -> Arc<dyn Send + Sync + Fn(FoldId, Range<Anchor>, &mut App) -> AnyElement> {
+    Arc::new(move |fold_id, fold_range, _| {
+        let console = console.clone();
+        ButtonLike::new(fold_id)
+            .child(Label::new("…").color(Color::Muted))
+            .on_click(move |_, window, cx| {
+                console
+                    .update(cx, |console, cx| {
+                        let snapshot = console.buffer().read(cx).snapshot(cx);
+                        let row = MultiBufferRow(fold_range.start.to_point(&snapshot).row);
+                        console.unfold_at(row, window, cx);
+                    })
+                    .ok();
+            })
+            .into_any_element()
+    })
+}
+
+/// The console has no gutter, so each group's header has its fold toggle at the end of the line.
+fn render_group_toggle(
+    console: WeakEntity<Editor>,
+) -> impl Send + Sync + Fn(MultiBufferRow, bool, &mut Window, &mut App) -> AnyElement + 'static {
+    move |row, folded, _, _| {
+        let console = console.clone();
+        Disclosure::new(("console-group-toggle", row.0 as u64), !folded)
+            .on_click(move |_, window, cx| {
+                console
+                    .update(cx, |console, cx| {
+                        if folded {
+                            console.unfold_at(row, window, cx);
+                        } else {
+                            console.fold_at(row, window, cx);
+                        }
+                    })
+                    .ok();
+            })
+            .into_any_element()
+    }
+}
+
 impl Render for Console {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
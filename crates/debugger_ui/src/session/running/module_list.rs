@@ -12,6 +12,9 @@ use std::{path::Path, sync::Arc};
 use ui::{Scrollbar, ScrollbarState, prelude::*};
 use workspace::Workspace;
 
+/// How many rows from the end of the list scrolling has to get before more modules are loaded.
+const LOAD_MORE_THRESHOLD: usize = 20;
+
 pub struct ModuleList {
     scroll_handle: UniformListScrollHandle,
     selected_ix: Option<usize>,
@@ -279,12 +282,28 @@ impl ModuleList {
         self.select_ix(ix, cx);
     }
 
+    /// Asks for the next page of modules once the list is scrolled close to its end.
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if !self.session.read(cx).has_more_modules() {
+            return;
+        }
+        cx.defer(|this, cx| {
+            this.session
+                .update(cx, |session, cx| session.load_more_modules(cx));
+        });
+    }
+
     fn render_list(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         uniform_list(
             cx.entity(),
             "module-list",
             self.entries.len(),
-            |this, range, _window, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+            |this, range, _window, cx| {
+                if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                    this.load_more(cx);
+                }
+                range.map(|ix| this.render_entry(ix, cx)).collect()
+            },
         )
         .track_scroll(self.scroll_handle.clone())
         .size_full()
@@ -12,13 +12,16 @@ use gpui::{
 use crate::StackTraceView;
 use language::PointUtf16;
 use project::debugger::breakpoint_store::ActiveStackFrame;
-use project::debugger::session::{Session, SessionEvent, StackFrame};
+use project::debugger::session::{Session, SessionEvent, StackFrame, ThreadId};
 use project::{ProjectItem, ProjectPath};
 use ui::{Scrollbar, ScrollbarState, Tooltip, prelude::*};
 use workspace::{ItemHandle, Workspace};
 
 use super::RunningState;
 
+/// How many rows from the end of the list scrolling has to get before more frames are loaded.
+const LOAD_MORE_THRESHOLD: usize = 5;
+
 #[derive(Debug)]
 pub enum StackFrameListEvent {
     SelectedStackFrameChanged(StackFrameId),
@@ -31,6 +34,7 @@ pub struct StackFrameList {
     session: Entity<Session>,
     state: WeakEntity<RunningState>,
     entries: Vec<StackFrameEntry>,
+    has_more_stack_frames: bool,
     workspace: WeakEntity<Workspace>,
     selected_ix: Option<usize>,
     opened_stack_frame_id: Option<StackFrameId>,
@@ -58,7 +62,9 @@ impl StackFrameList {
 
         let _subscription =
             cx.subscribe_in(&session, window, |this, _, event, window, cx| match event {
-                SessionEvent::Threads => {
+                SessionEvent::Threads
+                | SessionEvent::Continued
+                | SessionEvent::MoreStackFrames(_) => {
                     this.schedule_refresh(false, window, cx);
                 }
                 SessionEvent::Stopped(..) | SessionEvent::StackTrace => {
@@ -75,6 +81,7 @@ impl StackFrameList {
             state,
             _subscription,
             entries: Default::default(),
+            has_more_stack_frames: false,
             selected_ix: None,
             opened_stack_frame_id: None,
             scroll_handle,
@@ -105,11 +112,15 @@ impl StackFrameList {
             .collect::<Vec<_>>()
     }
 
-    fn stack_frames(&self, cx: &mut App) -> Vec<StackFrame> {
+    fn thread_id(&self, cx: &App) -> Option<ThreadId> {
         self.state
             .read_with(cx, |state, _| state.thread_id)
             .ok()
             .flatten()
+    }
+
+    fn stack_frames(&self, cx: &mut App) -> Vec<StackFrame> {
+        self.thread_id(cx)
             .map(|thread_id| {
                 self.session
                     .update(cx, |this, cx| this.stack_frames(thread_id, cx))
@@ -125,6 +136,30 @@ impl StackFrameList {
             .collect()
     }
 
+    /// Formats stack frames as plain text, one frame per line with a `path:line` suffix.
+    pub(crate) fn format_stack_frames(stack_frames: &[dap::StackFrame]) -> String {
+        let mut text = String::new();
+        for stack_frame in stack_frames {
+            let path = stack_frame
+                .source
+                .as_ref()
+                .and_then(|source| source.path.as_ref().or(source.name.as_ref()));
+            match path {
+                Some(path) => {
+                    text.push_str(&format!(
+                        "{} ({}:{})\n",
+                        stack_frame.name, path, stack_frame.line
+                    ));
+                }
+                None => {
+                    text.push_str(&stack_frame.name);
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+
     pub fn opened_stack_frame_id(&self) -> Option<StackFrameId> {
         self.opened_stack_frame_id
     }
@@ -200,6 +235,9 @@ impl StackFrameList {
         }
 
         std::mem::swap(&mut self.entries, &mut entries);
+        self.has_more_stack_frames = self
+            .thread_id(cx)
+            .is_some_and(|thread_id| self.session.read(cx).has_more_stack_frames(thread_id));
 
         if let Some(ix) = first_stack_frame.filter(|_| open_first_stack_frame) {
             self.select_ix(Some(ix), cx);
@@ -510,6 +548,27 @@ impl StackFrameList {
             .into_any()
     }
 
+    fn render_load_more_entry(&self, cx: &mut Context<Self>) -> AnyElement {
+        h_flex()
+            .rounded_md()
+            .w_full()
+            .id("load-more-stack-frames")
+            .p_1()
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.load_more(cx);
+            }))
+            .hover(|style| style.bg(cx.theme().colors().element_hover).cursor_pointer())
+            .child(
+                Label::new("Load More Frames")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any()
+    }
+
     fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
         match &self.entries[ix] {
             StackFrameEntry::Normal(stack_frame) => self.render_normal_entry(ix, stack_frame, cx),
@@ -641,12 +700,40 @@ impl StackFrameList {
         self.activate_selected_entry(window, cx);
     }
 
+    /// Asks for the next page of frames once the list is scrolled close to its end.
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if !self.has_more_stack_frames {
+            return;
+        }
+        let Some(thread_id) = self.thread_id(cx) else {
+            return;
+        };
+        cx.defer(move |this, cx| {
+            this.session.update(cx, |session, cx| {
+                session.load_more_stack_frames(thread_id, cx)
+            });
+        });
+    }
+
     fn render_list(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         uniform_list(
             cx.entity(),
             "stack-frame-list",
-            self.entries.len(),
-            |this, range, _window, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+            self.entries.len() + usize::from(self.has_more_stack_frames),
+            |this, range, _window, cx| {
+                if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                    this.load_more(cx);
+                }
+                range
+                    .map(|ix| {
+                        if ix < this.entries.len() {
+                            this.render_entry(ix, cx)
+                        } else {
+                            this.render_load_more_entry(cx)
+                        }
+                    })
+                    .collect()
+            },
         )
         .track_scroll(self.scroll_handle.clone())
         .size_full()
@@ -1,19 +1,36 @@
 use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
-use dap::{ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference};
-use editor::Editor;
+use dap::{
+    ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference,
+    debugger_settings::DebuggerSettings,
+};
+use editor::{Editor, EditorEvent};
 use gpui::{
     AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle, Focusable,
-    Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, TextStyleRefinement,
+    Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, Task, TextStyleRefinement,
     UniformListScrollHandle, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use project::debugger::session::{Session, SessionEvent};
-use std::{collections::HashMap, ops::Range, sync::Arc};
+use settings::Settings;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    sync::Arc,
+};
 use ui::{ContextMenu, ListItem, Scrollbar, ScrollbarState, prelude::*};
-use util::{debug_panic, maybe};
+use util::{ResultExt as _, debug_panic, maybe};
 
 actions!(variable_list, [ExpandSelectedEntry, CollapseSelectedEntry]);
 
+/// Upper bound on variables expanded automatically in a single rebuild, so that
+/// eagerly expanding huge nested structures doesn't flood the adapter with requests.
+pub(crate) const MAX_EAGERLY_EXPANDED_VARIABLES: usize = 64;
+
+/// Bounds on re-fetching the variables that were expanded before the debuggee stopped again, so
+/// that stepping stays responsive when deeply nested structures were left open.
+pub(crate) const MAX_RESTORED_EXPANSION_DEPTH: usize = 8;
+pub(crate) const MAX_RESTORED_VARIABLE_FETCHES: usize = 128;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) struct EntryState {
     depth: usize,
@@ -139,6 +156,16 @@ pub struct VariableList {
     open_context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     focus_handle: FocusHandle,
     edited_path: Option<(EntryPath, Entity<Editor>)>,
+    filter_editor: Entity<Editor>,
+    filter_query: String,
+    /// Entries the user expanded since the debuggee last stopped, whose children are always
+    /// fetched. Other expanded entries were restored from before the stop, which is limited.
+    expanded_since_stop: HashSet<EntryPath>,
+    /// The variables fetched to restore expansions since the debuggee last stopped.
+    restored_fetches: HashSet<VariableReference>,
+    /// Restored entries past the limits, which keep their expansion for the next stop but are
+    /// shown collapsed.
+    capped_entries: HashSet<EntryPath>,
     disabled: bool,
     _subscriptions: Vec<Subscription>,
 }
@@ -151,6 +178,11 @@ impl VariableList {
         cx: &mut Context<Self>,
     ) -> Self {
         let focus_handle = cx.focus_handle();
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter variables", cx);
+            editor
+        });
 
         let _subscriptions = vec![
             cx.subscribe(&stack_frame_list, Self::handle_stack_frame_list_events),
@@ -159,6 +191,8 @@ impl VariableList {
                     this.selection.take();
                     this.edited_path.take();
                     this.selected_stack_frame_id.take();
+                    this.expanded_since_stop.clear();
+                    this.restored_fetches.clear();
                 }
                 SessionEvent::Variables => {
                     this.build_entries(cx);
@@ -169,6 +203,12 @@ impl VariableList {
                 this.edited_path.take();
                 cx.notify();
             }),
+            cx.subscribe(&filter_editor, |this, editor, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.filter_query = editor.read(cx).text(cx).trim().to_string();
+                    this.build_entries(cx);
+                }
+            }),
         ];
 
         let list_state = UniformListScrollHandle::default();
@@ -184,6 +224,11 @@ impl VariableList {
             open_context_menu: None,
             disabled: false,
             edited_path: None,
+            filter_editor,
+            filter_query: String::new(),
+            expanded_since_stop: HashSet::default(),
+            restored_fetches: HashSet::default(),
+            capped_entries: HashSet::default(),
             entries: Default::default(),
             entry_states: Default::default(),
         }
@@ -207,22 +252,23 @@ impl VariableList {
         };
 
         let mut entries = vec![];
+        self.capped_entries.clear();
         let scopes: Vec<_> = self.session.update(cx, |session, cx| {
             session.scopes(stack_frame_id, cx).iter().cloned().collect()
         });
 
         let mut contains_local_scope = false;
+        let settings = DebuggerSettings::get_global(cx);
+        let expand_locals = settings.expand_locals_on_stop;
+        let expand_locals_depth = settings.expand_locals_depth;
+        let mut local_scope_names = Vec::new();
+        let mut eager_expansion_budget = MAX_EAGERLY_EXPANDED_VARIABLES;
 
         let mut stack = scopes
             .into_iter()
             .rev()
             .filter(|scope| {
-                if scope
-                    .presentation_hint
-                    .as_ref()
-                    .map(|hint| *hint == ScopePresentationHint::Locals)
-                    .unwrap_or(scope.name.to_lowercase().starts_with("local"))
-                {
+                if Self::is_locals_scope(scope) {
                     contains_local_scope = true;
                 }
 
@@ -249,6 +295,12 @@ impl VariableList {
                 EntryKind::Scope(dap) => path = path.with_child(dap.name.clone().into()),
             }
 
+            if let EntryKind::Scope(scope) = &dap_kind {
+                if Self::is_locals_scope(scope) {
+                    local_scope_names.push(SharedString::from(scope.name.clone()));
+                }
+            }
+
             let var_state = self
                 .entry_states
                 .entry(path.clone())
@@ -256,29 +308,84 @@ impl VariableList {
                     state.parent_reference = container_reference;
                     state.has_children = variables_reference != 0;
                 })
-                .or_insert(EntryState {
-                    depth: path.indices.len(),
-                    is_expanded: dap_kind.as_scope().is_some_and(|scope| {
-                        (scopes_count == 1 && !contains_local_scope)
-                            || scope
-                                .presentation_hint
-                                .as_ref()
-                                .map(|hint| *hint == ScopePresentationHint::Locals)
-                                .unwrap_or(scope.name.to_lowercase().starts_with("local"))
-                    }),
-                    parent_reference: container_reference,
-                    has_children: variables_reference != 0,
+                .or_insert_with(|| {
+                    let is_expanded = match &dap_kind {
+                        EntryKind::Scope(scope) => {
+                            (scopes_count == 1 && !contains_local_scope)
+                                || (expand_locals && Self::is_locals_scope(scope))
+                        }
+                        EntryKind::Variable(_) => {
+                            // Top-level variables of a scope have two path indices: the scope and their own name.
+                            let within_depth = path.indices.len() <= expand_locals_depth + 1;
+                            let in_local_scope = path
+                                .indices
+                                .first()
+                                .is_some_and(|scope_name| local_scope_names.contains(scope_name));
+                            if expand_locals
+                                && within_depth
+                                && in_local_scope
+                                && variables_reference != 0
+                                && eager_expansion_budget > 0
+                            {
+                                eager_expansion_budget -= 1;
+                                true
+                            } else {
+                                false
+                            }
+                        }
+                    };
+
+                    EntryState {
+                        depth: path.indices.len(),
+                        is_expanded,
+                        parent_reference: container_reference,
+                        has_children: variables_reference != 0,
+                    }
                 });
 
+            let is_variable = matches!(dap_kind, EntryKind::Variable(_));
             entries.push(ListEntry {
                 dap_kind,
                 path: path.clone(),
             });
 
-            if var_state.is_expanded {
-                let children = self
-                    .session
-                    .update(cx, |session, cx| session.variables(variables_reference, cx));
+            // Expansion state outlives the variables references, so what was open before the
+            // stop is fetched again, up to a limit. Past it, the variable stays expanded but isn't
+            // fetched, so the expansion is still there to restore after the next stop.
+            let mut fetch_children = var_state.is_expanded;
+            if fetch_children
+                && is_variable
+                && !self.expanded_since_stop.contains(&path)
+                && !self.restored_fetches.contains(&variables_reference)
+            {
+                if path.indices.len() > MAX_RESTORED_EXPANSION_DEPTH
+                    || self.restored_fetches.len() >= MAX_RESTORED_VARIABLE_FETCHES
+                {
+                    fetch_children = false;
+                    self.capped_entries.insert(path.clone());
+                } else {
+                    self.restored_fetches.insert(variables_reference);
+                }
+            }
+
+            // While filtering, everything fetched so far is searched regardless of whether it's
+            // expanded, without touching the expansion state that's restored once the filter clears.
+            let children = if fetch_children {
+                Some(
+                    self.session
+                        .update(cx, |session, cx| session.variables(variables_reference, cx)),
+                )
+            } else if !self.filter_query.is_empty() && variables_reference != 0 {
+                Some(
+                    self.session
+                        .read(cx)
+                        .fetched_variables(variables_reference)
+                        .to_vec(),
+                )
+            } else {
+                None
+            };
+            if let Some(children) = children {
                 stack.extend(children.into_iter().rev().map(|child| {
                     (
                         variables_reference,
@@ -290,10 +397,53 @@ impl VariableList {
             }
         }
 
+        if !self.filter_query.is_empty() {
+            entries = Self::filter_entries(entries, &self.filter_query);
+        }
+
         self.entries = entries;
         cx.notify();
     }
 
+    /// Keeps the variables whose names match `query`, along with the scopes and variables that
+    /// contain them.
+    fn filter_entries(entries: Vec<ListEntry>, query: &str) -> Vec<ListEntry> {
+        let mut visible_ancestors = HashSet::default();
+        for entry in &entries {
+            if let EntryKind::Variable(variable) = &entry.dap_kind {
+                if Self::matches_filter(&variable.name, query) {
+                    let indices = &entry.path.indices;
+                    for len in 1..=indices.len() {
+                        visible_ancestors.insert(&indices[..len]);
+                    }
+                }
+            }
+        }
+
+        let is_visible = |entry: &ListEntry| visible_ancestors.contains(&entry.path.indices[..]);
+        entries
+            .iter()
+            .filter(|entry| is_visible(entry))
+            .cloned()
+            .collect()
+    }
+
+    fn matches_filter(name: &str, query: &str) -> bool {
+        let mut name_chars = name.chars().flat_map(char::to_lowercase);
+        query
+            .chars()
+            .flat_map(char::to_lowercase)
+            .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+    }
+
+    fn is_locals_scope(scope: &dap::Scope) -> bool {
+        scope
+            .presentation_hint
+            .as_ref()
+            .map(|hint| *hint == ScopePresentationHint::Locals)
+            .unwrap_or(scope.name.to_lowercase().starts_with("local"))
+    }
+
     fn handle_stack_frame_list_events(
         &mut self,
         _: Entity<StackFrameList>,
@@ -332,9 +482,20 @@ impl VariableList {
                     .get(ix)
                     .and_then(|entry| Some(entry).zip(self.entry_states.get(&entry.path)))?;
 
+                let mut state = *state;
+                if self.capped_entries.contains(&entry.path) {
+                    state.is_expanded = false;
+                }
+                if !self.filter_query.is_empty() {
+                    state.is_expanded = self.entries.get(ix + 1).is_some_and(|next| {
+                        next.path.indices.len() > entry.path.indices.len()
+                            && next.path.indices.starts_with(&entry.path.indices)
+                    });
+                }
+
                 match &entry.dap_kind {
-                    EntryKind::Variable(_) => Some(self.render_variable(entry, *state, window, cx)),
-                    EntryKind::Scope(_) => Some(self.render_scope(entry, *state, cx)),
+                    EntryKind::Variable(_) => Some(self.render_variable(entry, state, window, cx)),
+                    EntryKind::Scope(_) => Some(self.render_scope(entry, state, cx)),
                 }
             })
             .collect()
@@ -346,7 +507,12 @@ impl VariableList {
             return;
         };
 
-        entry.is_expanded = !entry.is_expanded;
+        entry.is_expanded = !entry.is_expanded || self.capped_entries.contains(var_path);
+        if entry.is_expanded {
+            self.expanded_since_stop.insert(var_path.clone());
+        } else {
+            self.expanded_since_stop.remove(var_path);
+        }
         self.build_entries(cx);
     }
 
@@ -463,7 +629,10 @@ impl VariableList {
                 return;
             };
 
-            if !entry_state.is_expanded || !entry_state.has_children {
+            if !entry_state.is_expanded
+                || !entry_state.has_children
+                || self.capped_entries.contains(selected_entry)
+            {
                 self.select_prev(&SelectPrevious, window, cx);
             } else {
                 entry_state.is_expanded = false;
@@ -484,15 +653,42 @@ impl VariableList {
                 return;
             };
 
-            if entry_state.is_expanded || !entry_state.has_children {
+            if (entry_state.is_expanded && !self.capped_entries.contains(selected_entry))
+                || !entry_state.has_children
+            {
                 self.select_next(&SelectNext, window, cx);
             } else {
                 entry_state.is_expanded = true;
+                self.expanded_since_stop.insert(selected_entry.clone());
                 self.build_entries(cx);
             }
         }
     }
 
+    /// Copies the variable's value. The value shown in the list may be abbreviated for large
+    /// structures, so when the variable can be evaluated the adapter is asked for the full text.
+    pub(crate) fn copy_variable_value(
+        &self,
+        variable: &dap::Variable,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let preview = variable.value.clone();
+        let Some(evaluate_name) = variable.evaluate_name.clone() else {
+            cx.write_to_clipboard(ClipboardItem::new_string(preview));
+            return Task::ready(());
+        };
+
+        let frame_id = self.selected_stack_frame_id;
+        let full_value = self.session.update(cx, |session, cx| {
+            session.evaluate_for_clipboard(evaluate_name, frame_id, cx)
+        });
+        cx.spawn(async move |_, cx| {
+            let value = full_value.await.log_err().unwrap_or(preview);
+            cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(value)))
+                .ok();
+        })
+    }
+
     fn deploy_variable_context_menu(
         &mut self,
         variable: ListEntry,
@@ -507,19 +703,50 @@ impl VariableList {
 
         let variable_value = dap_var.value.clone();
         let variable_name = dap_var.name.clone();
+        let evaluate_name = dap_var.evaluate_name.clone();
+        let copied_variable = dap_var.clone();
         let this = cx.entity().clone();
 
+        let data_breakpoint_request = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_data_breakpoints
+            .unwrap_or_default()
+            .then(|| self.entry_states.get(&variable.path))
+            .flatten()
+            .map(|state| {
+                (
+                    self.session.clone(),
+                    state.parent_reference,
+                    dap_var.name.clone(),
+                    self.selected_stack_frame_id,
+                )
+            });
+
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
-            menu.entry("Copy name", None, move |_, cx| {
-                cx.write_to_clipboard(ClipboardItem::new_string(variable_name.clone()))
-            })
-            .entry("Copy value", None, {
-                let variable_value = variable_value.clone();
-                move |_, cx| {
-                    cx.write_to_clipboard(ClipboardItem::new_string(variable_value.clone()))
-                }
-            })
-            .entry("Set value", None, move |window, cx| {
+            let menu = menu
+                .entry("Copy name", None, move |_, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(variable_name.clone()))
+                })
+                .entry("Copy value", None, {
+                    let this = this.clone();
+                    move |_, cx| {
+                        this.update(cx, |variable_list, cx| {
+                            variable_list.copy_variable_value(&copied_variable, cx)
+                        })
+                        .detach();
+                    }
+                });
+
+            let menu = match evaluate_name {
+                Some(evaluate_name) => menu.entry("Copy as expression", None, move |_, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(evaluate_name.clone()))
+                }),
+                None => menu,
+            };
+
+            menu.entry("Set value", None, move |window, cx| {
                 this.update(cx, |variable_list, cx| {
                     let editor = Self::create_variable_editor(&variable_value, window, cx);
                     variable_list.edited_path = Some((variable.path.clone(), editor));
@@ -527,6 +754,23 @@ impl VariableList {
                     cx.notify();
                 });
             })
+            .when_some(
+                data_breakpoint_request,
+                |menu, (session, parent_reference, name, frame_id)| {
+                    menu.entry("Break on Value Change", None, move |_, cx| {
+                        session
+                            .update(cx, |session, cx| {
+                                session.add_data_breakpoint(
+                                    parent_reference,
+                                    name.clone(),
+                                    frame_id,
+                                    cx,
+                                )
+                            })
+                            .detach_and_log_err(cx);
+                    })
+                },
+            )
         });
 
         cx.focus_view(&context_menu, window);
@@ -547,6 +791,12 @@ impl VariableList {
         self.open_context_menu = Some((context_menu, position, subscription));
     }
 
+    #[cfg(test)]
+    pub(crate) fn set_filter(&self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_editor
+            .update(cx, |editor, cx| editor.set_text(query, window, cx));
+    }
+
     #[track_caller]
     #[cfg(test)]
     pub(crate) fn assert_visual_entries(&self, expected: Vec<&str>) {
@@ -563,7 +813,11 @@ impl VariableList {
             visual_entries.push(format!(
                 "{}{} {}{}",
                 INDENT.repeat(state.depth - 1),
-                if state.is_expanded { "v" } else { ">" },
+                if state.is_expanded && !self.capped_entries.contains(&entry.path) {
+                    "v"
+                } else {
+                    ">"
+                },
                 entry.dap_kind.name(),
                 if self.selection.as_ref() == Some(&entry.path) {
                     " <=== selected"
@@ -947,6 +1201,14 @@ impl Render for VariableList {
             .on_action(cx.listener(Self::collapse_selected_entry))
             .on_action(cx.listener(Self::cancel_variable_edit))
             .on_action(cx.listener(Self::confirm_variable_edit))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.filter_editor.clone()),
+            )
             .child(
                 uniform_list(
                     cx.entity().clone(),
@@ -1,4 +1,8 @@
-use crate::{attach_modal::Candidate, tests::start_debug_session_with, *};
+use crate::{
+    attach_modal::{Candidate, ProcessSort},
+    tests::start_debug_session_with,
+    *,
+};
 use attach_modal::AttachModal;
 use dap::{FakeAdapter, adapters::DebugTaskDefinition};
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
@@ -117,16 +121,19 @@ async fn test_show_attach_modal_and_select_process(
                             pid: 0,
                             name: "fake-binary-1".into(),
                             command: vec![],
+                            start_time: 100,
                         },
                         Candidate {
                             pid: 3,
                             name: "real-binary-1".into(),
                             command: vec![],
+                            start_time: 300,
                         },
                         Candidate {
                             pid: 1,
                             name: "fake-binary-2".into(),
                             command: vec![],
+                            start_time: 200,
                         },
                     ]
                     .into_iter()
@@ -179,3 +186,84 @@ async fn test_show_attach_modal_and_select_process(
         })
         .unwrap();
 }
+
+#[gpui::test]
+async fn test_attach_modal_sorts_processes(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let attach_modal = workspace
+        .update(cx, |workspace, window, cx| {
+            let workspace_handle = cx.weak_entity();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                AttachModal::with_processes(
+                    workspace_handle,
+                    task::ZedDebugConfig {
+                        adapter: FakeAdapter::ADAPTER_NAME.into(),
+                        request: dap::DebugRequest::Attach(AttachRequest::default()),
+                        label: "attach example".into(),
+                        stop_on_entry: None,
+                    },
+                    vec![
+                        Candidate {
+                            pid: 7,
+                            name: "server".into(),
+                            command: vec!["server".into(), "--port=8080".into()],
+                            start_time: 100,
+                        },
+                        Candidate {
+                            pid: 4,
+                            name: "server".into(),
+                            command: vec!["server".into(), "--port=9090".into()],
+                            start_time: 300,
+                        },
+                        Candidate {
+                            pid: 9,
+                            name: "client".into(),
+                            command: vec![],
+                            start_time: 200,
+                        },
+                    ]
+                    .into_iter()
+                    .collect(),
+                    true,
+                    window,
+                    cx,
+                )
+            });
+
+            workspace.active_modal::<AttachModal>(cx).unwrap()
+        })
+        .unwrap();
+
+    cx.run_until_parked();
+
+    let pids = attach_modal.update(cx, |modal, cx| attach_modal::_process_pids(modal, cx));
+    assert_eq!(pids, vec![9, 4, 7]);
+
+    attach_modal.update_in(cx, |modal, window, cx| {
+        modal.picker.update(cx, |picker, cx| {
+            picker.delegate.set_sort(ProcessSort::RecentlyStarted);
+            picker.refresh(window, cx);
+        })
+    });
+    cx.run_until_parked();
+
+    let pids = attach_modal.update(cx, |modal, cx| attach_modal::_process_pids(modal, cx));
+    assert_eq!(pids, vec![4, 9, 7]);
+
+    // Processes with the same name can be told apart by their arguments.
+    attach_modal.update_in(cx, |modal, window, cx| {
+        modal.picker.update(cx, |picker, cx| {
+            picker.set_query("9090", window, cx);
+        })
+    });
+    cx.run_until_parked();
+
+    let pids = attach_modal.update(cx, |modal, cx| attach_modal::_process_pids(modal, cx));
+    assert_eq!(pids, vec![4]);
+}
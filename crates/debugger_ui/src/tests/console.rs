@@ -1,4 +1,5 @@
 use crate::{
+    session::running::console::Console,
     tests::{active_debug_session_panel, start_debug_session},
     *,
 };
@@ -7,6 +8,7 @@ use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{FakeFs, Project};
 use serde_json::json;
 use tests::{init_test, init_test_workspace};
+use unindent::Unindent as _;
 use util::path;
 
 #[gpui::test]
@@ -157,291 +159,254 @@ async fn test_handle_output_event(executor: BackgroundExecutor, cx: &mut TestApp
         .unwrap();
 }
 
-// #[gpui::test]
-// async fn test_grouped_output(executor: BackgroundExecutor, cx: &mut TestAppContext) {
-//     init_test(cx);
+#[gpui::test]
+async fn test_console_shows_output_received_before_it_was_created(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
 
-//     let fs = FakeFs::new(executor.clone());
+    let fs = FakeFs::new(executor.clone());
 
-//     fs.insert_tree(
-//         "/project",
-//         json!({
-//             "main.rs": "First line\nSecond line\nThird line\nFourth line",
-//         }),
-//     )
-//     .await;
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
 
-//     let project = Project::test(fs, ["/project".as_ref()], cx).await;
-//     let workspace = init_test_workspace(&project, cx).await;
-//     let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
 
-//     let task = project.update(cx, |project, cx| {
-//         project.start_debug_session(
-//             dap::test_config(dap::DebugRequestType::Launch, None, None),
-//             cx,
-//         )
-//     });
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
 
-//     let session = task.await.unwrap();
-//     let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    for output in ["First buffered line", "Second buffered line"] {
+        client
+            .fake_event(dap::messages::Events::Output(dap::OutputEvent {
+                category: Some(dap::OutputEventCategory::Stdout),
+                output: output.to_string(),
+                data: None,
+                variables_reference: None,
+                source: None,
+                line: None,
+                column: None,
+                group: None,
+                location_reference: None,
+            }))
+            .await;
+    }
 
-//     client
-//         .on_request::<StackTrace, _>(move |_, _| {
-//             Ok(dap::StackTraceResponse {
-//                 stack_frames: Vec::default(),
-//                 total_frames: None,
-//             })
-//         })
-//         .await;
+    cx.run_until_parked();
 
-//     client
-//         .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
-//             reason: dap::StoppedEventReason::Pause,
-//             description: None,
-//             thread_id: Some(1),
-//             preserve_focus_hint: None,
-//             text: None,
-//             all_threads_stopped: None,
-//             hit_breakpoint_ids: None,
-//         }))
-//         .await;
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    let console = running_state.update_in(cx, |running_state, window, cx| {
+        let session = running_state.session().clone();
+        let stack_frame_list = running_state.stack_frame_list().clone();
+        let variable_list = running_state.variable_list().clone();
+        cx.new(|cx| Console::new(session, stack_frame_list, variable_list, window, cx))
+    });
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: None,
-//             output: "First line".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    cx.run_until_parked();
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "First group".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::Start),
-//             location_reference: None,
-//         }))
-//         .await;
+    console.update(cx, |console, cx| {
+        assert_eq!(
+            "First buffered line\nSecond buffered line\n",
+            console.editor().read(cx).text(cx).as_str()
+        );
+    });
+}
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "First item in group 1".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+#[gpui::test]
+async fn test_grouped_output(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Second item in group 1".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    let fs = FakeFs::new(executor.clone());
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Second group".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::Start),
-//             location_reference: None,
-//         }))
-//         .await;
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "First item in group 2".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Second item in group 2".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "End group 2".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::End),
-//             location_reference: None,
-//         }))
-//         .await;
+    for (output, group) in [
+        ("First line", None),
+        ("First group", Some(dap::OutputEventGroup::Start)),
+        ("First item in group 1", None),
+        ("Second item in group 1", None),
+        ("Second group", Some(dap::OutputEventGroup::Start)),
+        ("First item in group 2", None),
+        ("Second item in group 2", None),
+        ("End group 2", Some(dap::OutputEventGroup::End)),
+        ("Third group", Some(dap::OutputEventGroup::StartCollapsed)),
+        ("First item in group 3", None),
+        ("Second item in group 3", None),
+        ("End group 3", Some(dap::OutputEventGroup::End)),
+        ("Third item in group 1", None),
+        ("Second item", Some(dap::OutputEventGroup::End)),
+    ] {
+        client
+            .fake_event(dap::messages::Events::Output(dap::OutputEvent {
+                category: Some(dap::OutputEventCategory::Stdout),
+                output: output.to_string(),
+                data: None,
+                variables_reference: None,
+                source: None,
+                line: None,
+                column: None,
+                group,
+                location_reference: None,
+            }))
+            .await;
+    }
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Third group".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::StartCollapsed),
-//             location_reference: None,
-//         }))
-//         .await;
+    cx.run_until_parked();
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "First item in group 3".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    let console = active_debug_session_panel(workspace, cx).update(cx, |item, cx| {
+        item.running_state().read(cx).console().clone()
+    });
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Second item in group 3".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    console.update(cx, |console, cx| {
+        console.editor().update(cx, |editor, cx| {
+            pretty_assertions::assert_eq!(
+                "
+                First line
+                First group
+                  First item in group 1
+                  Second item in group 1
+                  Second group
+                    First item in group 2
+                    Second item in group 2
+                  End group 2
+                  Third group
+                    First item in group 3
+                    Second item in group 3
+                  End group 3
+                  Third item in group 1
+                Second item
+                "
+                .unindent(),
+                editor.text(cx)
+            );
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "End group 3".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::End),
-//             location_reference: None,
-//         }))
-//         .await;
+            let display_text = editor.display_text(cx);
+            assert!(
+                display_text.contains("  Third group⋯\n  End group 3\n"),
+                "the collapsed group should be folded: {display_text:?}"
+            );
+            assert!(display_text.contains("    First item in group 2\n"));
+        })
+    });
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Third item in group 1".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: None,
-//             location_reference: None,
-//         }))
-//         .await;
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
 
-//     client
-//         .fake_event(dap::messages::Events::Output(dap::OutputEvent {
-//             category: Some(dap::OutputEventCategory::Stdout),
-//             output: "Second item".to_string(),
-//             data: None,
-//             variables_reference: None,
-//             source: None,
-//             line: None,
-//             column: None,
-//             group: Some(dap::OutputEventGroup::End),
-//             location_reference: None,
-//         }))
-//         .await;
+    shutdown_session.await.unwrap();
+}
 
-//     cx.run_until_parked();
+#[gpui::test]
+async fn test_collapsed_group_folds_when_it_starts(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
 
-//     active_debug_session_panel(workspace, cx).update(cx, |debug_panel_item, cx| {
-//         debug_panel_item
-//             .mode()
-//             .as_running()
-//             .unwrap()
-//             .update(cx, |running_state, cx| {
-//                 running_state.console().update(cx, |console, cx| {
-//                     console.editor().update(cx, |editor, cx| {
-//                         pretty_assertions::assert_eq!(
-//                             "
-//                         First line
-//                         First group
-//                             First item in group 1
-//                             Second item in group 1
-//                             Second group
-//                                 First item in group 2
-//                                 Second item in group 2
-//                             End group 2
-//                         ⋯    End group 3
-//                             Third item in group 1
-//                         Second item
-//                     "
-//                             .unindent(),
-//                             editor.display_text(cx)
-//                         );
-//                     })
-//                 });
-//             });
-//     });
+    let fs = FakeFs::new(executor.clone());
 
-//     let shutdown_session = project.update(cx, |project, cx| {
-//         project.dap_store().update(cx, |dap_store, cx| {
-//             dap_store.shutdown_session(session.read(cx).session_id(), cx)
-//         })
-//     });
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
 
-//     shutdown_session.await.unwrap();
-// }
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let send_output = |output: &'static str, group| {
+        client.fake_event(dap::messages::Events::Output(dap::OutputEvent {
+            category: Some(dap::OutputEventCategory::Stdout),
+            output: output.to_string(),
+            data: None,
+            variables_reference: None,
+            source: None,
+            line: None,
+            column: None,
+            group,
+            location_reference: None,
+        }))
+    };
+
+    send_output(
+        "Collapsed group",
+        Some(dap::OutputEventGroup::StartCollapsed),
+    )
+    .await;
+    send_output("First item", None).await;
+    cx.run_until_parked();
+
+    let console = active_debug_session_panel(workspace, cx).update(cx, |item, cx| {
+        item.running_state().read(cx).console().clone()
+    });
+    let display_text = |cx: &mut VisualTestContext| {
+        console.update(cx, |console, cx| {
+            console
+                .editor()
+                .update(cx, |editor, cx| editor.display_text(cx))
+        })
+    };
+
+    let text = display_text(cx);
+    assert!(
+        text.contains("Collapsed group⋯") && !text.contains("First item"),
+        "the group should be folded before it ends: {text:?}"
+    );
+
+    send_output("Second item", None).await;
+    cx.run_until_parked();
+    let text = display_text(cx);
+    assert!(
+        text.contains("Collapsed group⋯") && !text.contains("Second item"),
+        "output arriving in a folded group should stay hidden: {text:?}"
+    );
+
+    send_output("End group", Some(dap::OutputEventGroup::End)).await;
+    send_output("After the group", None).await;
+    cx.run_until_parked();
+    let text = display_text(cx);
+    assert!(
+        text.contains("Collapsed group⋯\nEnd group\nAfter the group"),
+        "the group should stay folded once it ends: {text:?}"
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
 
 // todo(debugger): enable this again
 // #[gpui::test]
@@ -66,11 +66,14 @@ async fn test_dap_logger_captures_all_session_rpc_messages(
     );
 
     assert!(
-        !log_store.read_with(cx, |log_store, _| log_store
+        log_store.read_with(cx, |log_store, _| log_store
             .rpc_messages_for_session_id(session_id)
             .is_empty()),
-        "We should have the initialization sequence in the log store"
+        "The protocol log should be off until it's turned on"
     );
+    log_store.update(cx, |log_store, cx| {
+        log_store.set_rpc_log_enabled(session_id, true, cx);
+    });
 
     // Set up basic responses for common requests
     client.on_request::<Threads, _>(move |_, _| {
@@ -104,4 +107,106 @@ async fn test_dap_logger_captures_all_session_rpc_messages(
             hit_breakpoint_ids: None,
         }))
         .await;
+    cx.run_until_parked();
+
+    assert!(
+        !log_store.read_with(cx, |log_store, _| log_store
+            .rpc_messages_for_session_id(session_id)
+            .is_empty()),
+        "Messages should be recorded once the protocol log is turned on"
+    );
+}
+
+#[gpui::test]
+async fn test_dap_logger_skips_sessions_with_protocol_log_disabled(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    let log_store_cell = std::rc::Rc::new(OnceCell::new());
+
+    cx.update(|cx| {
+        let log_store_cell = log_store_cell.clone();
+        cx.observe_new::<LogStore>(move |_, _, cx| {
+            log_store_cell.set(cx.entity()).unwrap();
+        })
+        .detach();
+        debugger_tools::init(cx);
+    });
+    init_test(cx);
+
+    let log_store = log_store_cell.get().unwrap().clone();
+
+    let fs = project::FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "fn main() {\n    println!(\"Hello, world!\");\n}"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let first_session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let first_session_id = first_session.read_with(cx, |session, _| session.session_id());
+    let first_client = first_session.update(cx, |session, _| session.adapter_client().unwrap());
+    let second_session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let second_session_id = second_session.read_with(cx, |session, _| session.session_id());
+    let second_client = second_session.update(cx, |session, _| session.adapter_client().unwrap());
+    cx.run_until_parked();
+
+    log_store.update(cx, |log_store, cx| {
+        log_store.set_rpc_log_enabled(second_session_id, true, cx);
+    });
+    let first_session_messages = log_store.read_with(cx, |log_store, _| {
+        log_store.rpc_messages_for_session_id(first_session_id)
+    });
+    let second_session_messages = log_store.read_with(cx, |log_store, _| {
+        log_store.rpc_messages_for_session_id(second_session_id)
+    });
+
+    for client in [&first_client, &second_client] {
+        client.on_request::<Threads, _>(move |_, _| {
+            Ok(dap::ThreadsResponse {
+                threads: vec![dap::Thread {
+                    id: 1,
+                    name: "Thread 1".into(),
+                }],
+            })
+        });
+        client.on_request::<StackTrace, _>(move |_, _| {
+            Ok(dap::StackTraceResponse {
+                stack_frames: Vec::default(),
+                total_frames: None,
+            })
+        });
+        client
+            .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+                reason: dap::StoppedEventReason::Pause,
+                description: None,
+                thread_id: Some(1),
+                preserve_focus_hint: None,
+                text: None,
+                all_threads_stopped: None,
+                hit_breakpoint_ids: None,
+            }))
+            .await;
+    }
+    cx.run_until_parked();
+
+    assert_eq!(
+        log_store.read_with(cx, |log_store, _| log_store
+            .rpc_messages_for_session_id(first_session_id)),
+        first_session_messages,
+        "A session with its protocol log turned off shouldn't record messages"
+    );
+    assert!(
+        log_store.read_with(cx, |log_store, _| log_store
+            .rpc_messages_for_session_id(second_session_id)
+            .len())
+            > second_session_messages.len(),
+        "Other sessions should keep recording their messages"
+    );
 }
@@ -4,8 +4,8 @@ use crate::{
     *,
 };
 use dap::{
-    ErrorResponse, Message, RunInTerminalRequestArguments, SourceBreakpoint,
-    StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
+    ErrorResponse, Message, RunInTerminalRequestArguments, RunInTerminalRequestArgumentsKind,
+    SourceBreakpoint, StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
@@ -17,7 +17,7 @@ use editor::{
     ActiveDebugLine, Editor, EditorMode, MultiBuffer,
     actions::{self},
 };
-use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use gpui::{BackgroundExecutor, SharedString, TestAppContext, VisualTestContext};
 use project::{
     FakeFs, Project,
     debugger::session::{ThreadId, ThreadStatus},
@@ -26,7 +26,7 @@ use serde_json::json;
 use std::{
     path::Path,
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
     },
 };
@@ -405,7 +405,7 @@ async fn test_handle_start_debugging_request(
     let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
     let client = session.update(cx, |session, _| session.adapter_client().unwrap());
 
-    let fake_config = json!({"one": "two"});
+    let fake_config = json!({"one": "two", "name": "Subprocess 1234"});
     let launched_with = Arc::new(parking_lot::Mutex::new(None));
 
     let _subscription = project::debugger::test::intercept_debug_sessions(cx, {
@@ -465,6 +465,16 @@ async fn test_handle_start_debugging_request(
                 .parent_session()
                 .unwrap();
             assert_eq!(parent_session, &sessions[0].read(cx).session(cx));
+            assert!(
+                parent_session
+                    .read(cx)
+                    .child_session_ids()
+                    .contains(&current_sessions[1].read(cx).session_id(cx))
+            );
+            assert_eq!(
+                current_sessions[1].read(cx).session(cx).read(cx).label(),
+                "Subprocess 1234"
+            );
 
             // We should preserve the original binary (params to spawn process etc.) except for launch params
             // (as they come from reverse spawn request).
@@ -554,6 +564,101 @@ async fn test_handle_error_run_in_terminal_reverse_request(
         .unwrap();
 }
 
+#[gpui::test]
+async fn test_external_run_in_terminal_reverse_request(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let send_response = Arc::new(AtomicBool::new(false));
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client
+        .on_response::<RunInTerminal, _>({
+            let send_response = send_response.clone();
+            move |response| {
+                send_response.store(true, Ordering::SeqCst);
+
+                // Without a command there is nothing to open the system terminal for.
+                assert!(!response.success);
+            }
+        })
+        .await;
+
+    client
+        .fake_reverse_request::<RunInTerminal>(RunInTerminalRequestArguments {
+            kind: Some(RunInTerminalRequestArgumentsKind::External),
+            title: None,
+            cwd: std::env::temp_dir().to_string_lossy().to_string(),
+            args: vec![],
+            env: None,
+            args_can_be_interpreted_by_shell: None,
+        })
+        .await;
+
+    cx.run_until_parked();
+
+    assert!(
+        send_response.load(std::sync::atomic::Ordering::SeqCst),
+        "Expected to receive response from reverse request"
+    );
+
+    workspace
+        .update(cx, |workspace, _window, cx| {
+            let debug_panel = workspace.panel::<DebugPanel>(cx).unwrap();
+            let session = debug_panel.read(cx).active_session().unwrap();
+            let running = session.read(cx).running_state();
+            assert!(running.read(cx).debug_terminal.read(cx).terminal.is_none());
+        })
+        .unwrap();
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_external_terminal_command() {
+    use std::ffi::OsStr;
+
+    let env = collections::HashMap::from_iter([("FOO".to_string(), "bar".to_string())]);
+    let command = crate::session::running::external_terminal_command(
+        &["node".into(), "main.js".into()],
+        None,
+        &Some("/project".into()),
+        &env,
+    )
+    .unwrap();
+
+    let terminal = std::env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".into());
+    assert_eq!(command.get_program(), terminal.as_str());
+    assert_eq!(
+        command.get_args().collect::<Vec<_>>(),
+        ["-e", "node", "main.js"]
+    );
+    assert_eq!(command.get_current_dir(), Some(Path::new("/project")));
+    assert_eq!(
+        command.get_envs().collect::<Vec<_>>(),
+        [(OsStr::new("FOO"), Some(OsStr::new("bar")))]
+    );
+
+    assert!(crate::session::running::external_terminal_command(&[], None, &None, &env).is_err());
+}
+
 #[gpui::test]
 async fn test_handle_start_debugging_reverse_request(
     executor: BackgroundExecutor,
@@ -1226,6 +1331,108 @@ async fn test_send_breakpoints_when_editor_has_been_saved(
     );
 }
 
+#[gpui::test]
+async fn test_resend_breakpoints_when_adapter_reinitializes(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let project_path = Path::new(path!("/project"));
+    let worktree = project
+        .update(cx, |project, cx| project.find_worktree(project_path, cx))
+        .expect("This worktree should exist in project")
+        .0;
+
+    let worktree_id = workspace
+        .update(cx, |_, _, cx| worktree.read(cx).id())
+        .unwrap();
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    client.on_request::<SetBreakpoints, _>(move |_, _| {
+        Ok(dap::SetBreakpointsResponse {
+            breakpoints: Vec::default(),
+        })
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_down(&actions::MoveDown, window, cx);
+        editor.toggle_breakpoint(&actions::ToggleBreakpoint, window, cx);
+    });
+
+    cx.run_until_parked();
+
+    let called_set_breakpoints = Arc::new(AtomicBool::new(false));
+    client.on_request::<SetBreakpoints, _>({
+        let called_set_breakpoints = called_set_breakpoints.clone();
+        move |_, args| {
+            assert_eq!(path!("/project/main.rs"), args.source.path.unwrap());
+            assert_eq!(
+                vec![SourceBreakpoint {
+                    line: 2,
+                    column: None,
+                    condition: None,
+                    hit_condition: None,
+                    log_message: None,
+                    mode: None
+                }],
+                args.breakpoints.unwrap()
+            );
+
+            called_set_breakpoints.store(true, Ordering::SeqCst);
+
+            Ok(dap::SetBreakpointsResponse {
+                breakpoints: Vec::default(),
+            })
+        }
+    });
+
+    // Adapters that restart the program in place send another initialized event, after which
+    // the breakpoints have to be configured again.
+    client
+        .fake_event(dap::messages::Events::Initialized(Some(Default::default())))
+        .await;
+
+    cx.run_until_parked();
+
+    assert!(
+        called_set_breakpoints.load(Ordering::SeqCst),
+        "SetBreakpoint request must be called after the adapter reinitializes"
+    );
+}
+
 #[gpui::test]
 async fn test_unsetting_breakpoints_on_clear_breakpoint_action(
     executor: BackgroundExecutor,
@@ -1745,3 +1952,564 @@ async fn test_active_debug_line_setting(executor: BackgroundExecutor, cx: &mut T
         );
     });
 }
+
+#[gpui::test]
+async fn test_command_palette_filter_updates_when_capabilities_change(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    cx.run_until_parked();
+
+    let step_back_hidden = |cx: &mut VisualTestContext| {
+        cx.update(|_, cx| {
+            command_palette_hooks::CommandPaletteFilter::try_global(cx)
+                .unwrap()
+                .is_hidden(&crate::StepBack)
+        })
+    };
+
+    assert!(
+        step_back_hidden(cx),
+        "Step back should be hidden while the adapter doesn't support it"
+    );
+
+    client
+        .fake_event(dap::messages::Events::Capabilities(
+            dap::CapabilitiesEvent {
+                capabilities: dap::Capabilities {
+                    supports_step_back: Some(true),
+                    ..Default::default()
+                },
+            },
+        ))
+        .await;
+
+    cx.run_until_parked();
+
+    assert!(
+        !step_back_hidden(cx),
+        "Step back should be shown once the adapter announces support for it"
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_threads_end_when_the_program_exits(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client
+        .fake_event(dap::messages::Events::Thread(dap::ThreadEvent {
+            reason: dap::ThreadEventReason::Exited,
+            thread_id: 2,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(ThreadStatus::Exited, session.thread_status(ThreadId(2)));
+        assert_eq!(ThreadStatus::Running, session.thread_status(ThreadId(1)));
+        assert_eq!(None, session.exit_code());
+    });
+
+    client
+        .fake_event(dap::messages::Events::Exited(dap::ExitedEvent {
+            exit_code: 0,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(ThreadStatus::Ended, session.thread_status(ThreadId(1)));
+        assert_eq!(ThreadStatus::Ended, session.thread_status(ThreadId(2)));
+        assert_eq!(Some(0), session.exit_code());
+    });
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_continued_event_resumes_threads(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![
+                dap::Thread {
+                    id: 1,
+                    name: "Thread 1".into(),
+                },
+                dap::Thread {
+                    id: 2,
+                    name: "Thread 2".into(),
+                },
+            ],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: Some(true),
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(ThreadStatus::Stopped, session.thread_status(ThreadId(1)));
+        assert_eq!(ThreadStatus::Stopped, session.thread_status(ThreadId(2)));
+    });
+
+    // Only the second thread resumes on its own.
+    client
+        .fake_event(dap::messages::Events::Continued(dap::ContinuedEvent {
+            thread_id: 2,
+            all_threads_continued: Some(false),
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(ThreadStatus::Stopped, session.thread_status(ThreadId(1)));
+        assert_eq!(ThreadStatus::Running, session.thread_status(ThreadId(2)));
+    });
+
+    client
+        .fake_event(dap::messages::Events::Continued(dap::ContinuedEvent {
+            thread_id: 1,
+            all_threads_continued: Some(true),
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(ThreadStatus::Running, session.thread_status(ThreadId(1)));
+        assert_eq!(ThreadStatus::Running, session.thread_status(ThreadId(2)));
+    });
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_data_breakpoints_are_sent_to_the_adapter(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_data_breakpoints: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::DataBreakpointInfo, _>(move |_, args| {
+        Ok(dap::DataBreakpointInfoResponse {
+            data_id: (args.name == "counter").then(|| {
+                format!(
+                    "{}:{}",
+                    args.variables_reference.unwrap_or_default(),
+                    args.name
+                )
+            }),
+            description: format!("Value of {}", args.name),
+            access_types: None,
+            can_persist: None,
+        })
+    });
+
+    let sent_data_ids = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<dap::requests::SetDataBreakpoints, _>({
+        let sent_data_ids = sent_data_ids.clone();
+        move |_, args| {
+            *sent_data_ids.lock().unwrap() = args
+                .breakpoints
+                .into_iter()
+                .map(|breakpoint| breakpoint.data_id)
+                .collect::<Vec<_>>();
+            Ok(dap::SetDataBreakpointsResponse {
+                breakpoints: Vec::default(),
+            })
+        }
+    });
+
+    session
+        .update(cx, |session, cx| {
+            session.add_data_breakpoint(5, "counter".into(), None, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        vec!["5:counter".to_string()],
+        *sent_data_ids.lock().unwrap()
+    );
+    session.update(cx, |session, _| {
+        assert_eq!(
+            vec![("5:counter".to_string(), "Value of counter".into())],
+            session
+                .data_breakpoints()
+                .map(|(data_id, description)| (data_id.clone(), description.clone()))
+                .collect::<Vec<(String, SharedString)>>()
+        );
+    });
+
+    let unwatchable = session.update(cx, |session, cx| {
+        session.add_data_breakpoint(5, "constant".into(), None, cx)
+    });
+    assert!(unwatchable.await.is_err());
+    cx.run_until_parked();
+    assert_eq!(
+        session.read_with(cx, |session, _| session.data_breakpoints().count()),
+        1
+    );
+
+    session.update(cx, |session, cx| {
+        session.remove_data_breakpoint("5:counter", cx)
+    });
+    cx.run_until_parked();
+
+    assert!(sent_data_ids.lock().unwrap().is_empty());
+    assert_eq!(
+        session.read_with(cx, |session, _| session.data_breakpoints().count()),
+        0
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_instruction_step_actions_send_instruction_granularity(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    let granularities = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Next, _>({
+        let granularities = granularities.clone();
+        move |_, args| {
+            granularities.lock().unwrap().push(args.granularity);
+            Ok(())
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    cx.dispatch_action(crate::StepOver);
+    cx.run_until_parked();
+    cx.dispatch_action(crate::StepOverInstruction);
+    cx.run_until_parked();
+
+    assert!(
+        matches!(
+            granularities.lock().unwrap().as_slice(),
+            [
+                Some(dap::SteppingGranularity::Line),
+                Some(dap::SteppingGranularity::Instruction)
+            ]
+        ),
+        "the plain step should use the configured granularity and the instruction step should override it"
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_active_session_changes_are_emitted(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let active_sessions = Arc::new(Mutex::new(Vec::new()));
+    workspace
+        .update(cx, |workspace, _window, cx| {
+            let debug_panel = workspace.panel::<DebugPanel>(cx).unwrap();
+            let active_sessions = active_sessions.clone();
+            cx.subscribe(
+                &debug_panel,
+                move |_, _, event: &debugger_panel::DebugPanelEvent, _| {
+                    if let debugger_panel::DebugPanelEvent::ActiveSessionChanged {
+                        session_id,
+                        ..
+                    } = event
+                    {
+                        active_sessions.lock().unwrap().push(*session_id);
+                    }
+                },
+            )
+            .detach();
+        })
+        .unwrap();
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let session_id = session.read_with(cx, |session, _| session.session_id());
+
+    cx.run_until_parked();
+
+    assert_eq!(*active_sessions.lock().unwrap(), vec![Some(session_id)]);
+
+    workspace
+        .update(cx, |workspace, _window, cx| {
+            let debug_panel = workspace.panel::<DebugPanel>(cx).unwrap();
+            debug_panel.update(cx, |debug_panel, cx| debug_panel.stop_all_sessions(cx));
+        })
+        .unwrap();
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        *active_sessions.lock().unwrap(),
+        vec![Some(session_id), None],
+        "Emptying the panel should report that no session is active"
+    );
+}
+
+#[gpui::test]
+async fn test_stop_all_closes_each_session(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    start_debug_session(&workspace, cx, |_| {}).unwrap();
+    start_debug_session(&workspace, cx, |_| {}).unwrap();
+    cx.run_until_parked();
+
+    let layout_key = format!(
+        "debugger_panel_-{}",
+        dap::adapters::FakeAdapter::ADAPTER_NAME
+    );
+    db::kvp::KEY_VALUE_STORE
+        .delete_kvp(layout_key)
+        .await
+        .unwrap();
+
+    let debug_panel = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.panel::<DebugPanel>(cx).unwrap()
+        })
+        .unwrap();
+    assert_eq!(
+        debug_panel.read_with(cx, |panel, _| panel.sessions().len()),
+        2
+    );
+
+    debug_panel.update(cx, |debug_panel, cx| debug_panel.stop_all_sessions(cx));
+    cx.run_until_parked();
+
+    debug_panel.read_with(cx, |panel, _| {
+        assert!(panel.sessions().is_empty());
+        assert!(panel.active_session().is_none());
+    });
+    assert!(
+        crate::persistence::get_serialized_layout(dap::adapters::FakeAdapter::ADAPTER_NAME)
+            .await
+            .is_some(),
+        "Stopping the sessions should save their layout, like closing them one by one does"
+    );
+}
@@ -10,7 +10,7 @@ use dap::{
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{FakeFs, Project};
 use std::sync::{
-    Arc,
+    Arc, Mutex,
     atomic::{AtomicBool, AtomicI32, Ordering},
 };
 use util::path;
@@ -215,3 +215,128 @@ async fn test_module_list(executor: BackgroundExecutor, cx: &mut TestAppContext)
         assert!(!actual_modules.contains(&changed_module));
     });
 }
+
+#[gpui::test]
+async fn test_module_list_is_paged(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_modules_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let make_module = |id: u32| dap::Module {
+        id: dap::ModuleId::Number(id),
+        name: format!("Module {id}"),
+        address_range: None,
+        date_time_stamp: None,
+        path: None,
+        symbol_file_path: None,
+        symbol_status: None,
+        version: None,
+        is_optimized: None,
+        is_user_code: None,
+    };
+    let modules = (0..250).map(make_module).collect::<Vec<_>>();
+
+    let requested_pages = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Modules, _>({
+        let requested_pages = requested_pages.clone();
+        let modules = modules.clone();
+        move |_, args| {
+            let start = args.start_module.unwrap_or_default() as usize;
+            let count = args
+                .module_count
+                .map_or(modules.len(), |count| count as usize);
+            requested_pages.lock().unwrap().push(start);
+
+            Ok(dap::ModulesResponse {
+                modules: modules.iter().skip(start).take(count).cloned().collect(),
+                total_modules: Some(modules.len() as u64),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.modules(cx);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        assert_eq!(session.modules(cx).len(), 200);
+        assert!(session.has_more_modules());
+    });
+
+    // A module from the next page is reported by an event before that page is loaded.
+    client
+        .fake_event(dap::messages::Events::Module(dap::ModuleEvent {
+            reason: dap::ModuleEventReason::New,
+            module: make_module(220),
+        }))
+        .await;
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| session.load_more_modules(cx));
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        assert_eq!(session.modules(cx).len(), 250);
+        assert!(!session.has_more_modules());
+        for module in &modules {
+            assert!(session.modules(cx).contains(module));
+        }
+    });
+    assert_eq!(*requested_pages.lock().unwrap(), vec![0, 200]);
+
+    // Stopping again refetches the modules from the first page, replacing the ones loaded before.
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.modules(cx);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        assert_eq!(session.modules(cx), &modules[..200]);
+        assert!(session.has_more_modules());
+    });
+    assert_eq!(*requested_pages.lock().unwrap(), vec![0, 200, 0]);
+}
@@ -5,13 +5,13 @@ use crate::{
 };
 use dap::{
     StackFrame,
-    requests::{Scopes, StackTrace, Threads},
+    requests::{Initialize, Scopes, StackTrace, Threads},
 };
 use editor::{Editor, ToPoint as _};
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
-use project::{FakeFs, Project};
+use project::{FakeFs, Project, debugger::session::ThreadId};
 use serde_json::json;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use unindent::Unindent as _;
 use util::path;
 
@@ -752,3 +752,254 @@ async fn test_collapsed_entries(executor: BackgroundExecutor, cx: &mut TestAppCo
         });
     });
 }
+
+#[gpui::test]
+async fn test_stack_frames_are_paged(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_delayed_stack_trace_loading: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    let stack_frames = (1..=45)
+        .map(|id| StackFrame {
+            id,
+            name: format!("Stack Frame {id}"),
+            source: None,
+            line: 1,
+            column: 1,
+            end_line: None,
+            end_column: None,
+            can_restart: None,
+            instruction_pointer_reference: None,
+            module_id: None,
+            presentation_hint: None,
+        })
+        .collect::<Vec<_>>();
+
+    let requested_pages = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<StackTrace, _>({
+        let requested_pages = requested_pages.clone();
+        let stack_frames = stack_frames.clone();
+        move |_, args| {
+            let start = args.start_frame.unwrap_or_default() as usize;
+            let levels = args
+                .levels
+                .map_or(stack_frames.len(), |levels| levels as usize);
+            requested_pages.lock().unwrap().push(start);
+
+            Ok(dap::StackTraceResponse {
+                stack_frames: stack_frames
+                    .iter()
+                    .skip(start)
+                    .take(levels)
+                    .cloned()
+                    .collect(),
+                total_frames: Some(stack_frames.len() as u64),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.threads(cx);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.stack_frames(ThreadId(1), cx);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        assert_eq!(session.stack_frames(ThreadId(1), cx).len(), 20);
+        assert!(session.has_more_stack_frames(ThreadId(1)));
+    });
+
+    session.update(cx, |session, cx| {
+        session.load_more_stack_frames(ThreadId(1), cx)
+    });
+    cx.run_until_parked();
+    session.update(cx, |session, cx| {
+        session.load_more_stack_frames(ThreadId(1), cx)
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        let loaded_frames = session
+            .stack_frames(ThreadId(1), cx)
+            .into_iter()
+            .map(|stack_frame| stack_frame.dap)
+            .collect::<Vec<_>>();
+        assert_eq!(loaded_frames, stack_frames);
+        assert!(!session.has_more_stack_frames(ThreadId(1)));
+    });
+    assert_eq!(*requested_pages.lock().unwrap(), vec![0, 20, 40]);
+}
+
+#[gpui::test]
+async fn test_copy_all_call_stacks(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_delayed_stack_trace_loading: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![
+                dap::Thread {
+                    id: 1,
+                    name: "main".into(),
+                },
+                dap::Thread {
+                    id: 2,
+                    name: "worker".into(),
+                },
+            ],
+        })
+    });
+
+    let stack_frame = |id: u64, source: Option<dap::Source>| StackFrame {
+        id,
+        name: format!("frame_{id}"),
+        source,
+        line: id,
+        column: 1,
+        end_line: None,
+        end_column: None,
+        can_restart: None,
+        instruction_pointer_reference: None,
+        module_id: None,
+        presentation_hint: None,
+    };
+    // More frames than fit in a page on the stopped thread, and one with a source on the other.
+    let main_frames = (1..=25).map(|id| stack_frame(id, None)).collect::<Vec<_>>();
+    let worker_frames = vec![stack_frame(
+        100,
+        Some(dap::Source {
+            name: Some("worker.rs".into()),
+            path: Some(path!("/project/worker.rs").into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+    )];
+
+    client.on_request::<StackTrace, _>({
+        let main_frames = main_frames.clone();
+        let worker_frames = worker_frames.clone();
+        move |_, args| {
+            let stack_frames = if args.thread_id == 1 {
+                &main_frames
+            } else {
+                &worker_frames
+            };
+            let start = args.start_frame.unwrap_or_default() as usize;
+            let levels = args
+                .levels
+                .map_or(stack_frames.len(), |levels| levels as usize);
+
+            Ok(dap::StackTraceResponse {
+                stack_frames: stack_frames
+                    .iter()
+                    .skip(start)
+                    .take(levels)
+                    .cloned()
+                    .collect(),
+                total_frames: Some(stack_frames.len() as u64),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.threads(cx);
+    });
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    running_state
+        .update(cx, |running_state, cx| {
+            running_state.copy_all_call_stacks(cx)
+        })
+        .await;
+
+    let main_stack = (1..=25)
+        .map(|id| format!("frame_{id}\n"))
+        .collect::<String>();
+    assert_eq!(
+        cx.read_from_clipboard().and_then(|item| item.text()),
+        Some(format!(
+            "Thread 1 (main):\n{main_stack}\nThread 2 (worker):\nframe_100 ({}:100)\n",
+            path!("/project/worker.rs")
+        ))
+    );
+}
@@ -1,23 +1,34 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, Ordering},
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
 use crate::{
     DebugPanel,
     persistence::DebuggerPaneItem,
-    session::running::variable_list::{CollapseSelectedEntry, ExpandSelectedEntry},
+    session::running::{
+        RunningState,
+        variable_list::{
+            CollapseSelectedEntry, EntryPath, ExpandSelectedEntry, MAX_EAGERLY_EXPANDED_VARIABLES,
+            MAX_RESTORED_EXPANSION_DEPTH, MAX_RESTORED_VARIABLE_FETCHES,
+        },
+    },
     tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
 };
 use collections::HashMap;
 use dap::{
     Scope, StackFrame, Variable,
-    requests::{Initialize, Launch, Scopes, StackTrace, Variables},
+    debugger_settings::DebuggerSettings,
+    requests::{Evaluate, Initialize, Launch, Scopes, StackTrace, Variables},
 };
-use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use gpui::{BackgroundExecutor, Entity, SharedString, TestAppContext, VisualTestContext};
 use menu::{SelectFirst, SelectNext, SelectPrevious};
-use project::{FakeFs, Project};
+use project::{FakeFs, Project, debugger::session::Session};
 use serde_json::json;
+use settings::SettingsStore;
 use unindent::Unindent as _;
 use util::path;
 
@@ -1813,3 +1824,566 @@ async fn test_it_fetches_scopes_variables_when_you_select_a_stack_frame(
         assert_eq!(variables, frame_2_variables,);
     });
 }
+
+fn variable(name: &str, value: &str, variables_reference: u64) -> Variable {
+    Variable {
+        name: name.into(),
+        value: value.into(),
+        type_: None,
+        presentation_hint: None,
+        evaluate_name: Some(name.into()),
+        variables_reference,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        declaration_location_reference: None,
+        value_location_reference: None,
+    }
+}
+
+fn stopped_event() -> dap::messages::Events {
+    dap::messages::Events::Stopped(dap::StoppedEvent {
+        reason: dap::StoppedEventReason::Step,
+        description: None,
+        thread_id: Some(1),
+        preserve_focus_hint: None,
+        text: None,
+        all_threads_stopped: None,
+        hit_breakpoint_ids: None,
+    })
+}
+
+/// Starts a session with the given capabilities and stops it in a single frame, whose only scope,
+/// "Locals", has the variables reference 1. Variables requests are answered from `variables`.
+/// Settings have to be set up beforehand, since they apply as the session stops.
+async fn start_stopped_session(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+    capabilities: dap::Capabilities,
+    variables: HashMap<u64, Vec<Variable>>,
+) -> (VisualTestContext, Entity<Session>, Entity<RunningState>) {
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let mut cx = VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, &mut cx, move |client| {
+        let capabilities = capabilities.clone();
+        client.on_request::<Initialize, _>(move |_, _| Ok(capabilities.clone()));
+    })
+    .unwrap();
+    let client = session.update(&mut cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| {
+        Ok(dap::ScopesResponse {
+            scopes: vec![Scope {
+                name: "Locals".into(),
+                presentation_hint: Some(dap::ScopePresentationHint::Locals),
+                variables_reference: 1,
+                named_variables: None,
+                indexed_variables: None,
+                expensive: false,
+                source: None,
+                line: None,
+                column: None,
+                end_line: None,
+                end_column: None,
+            }],
+        })
+    });
+    client.on_request::<Variables, _>(move |_, args| {
+        Ok(dap::VariablesResponse {
+            variables: variables
+                .get(&args.variables_reference)
+                .cloned()
+                .unwrap_or_default(),
+        })
+    });
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, &mut cx).update_in(&mut cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+    cx.run_until_parked();
+
+    (cx, session, running_state)
+}
+
+/// Copies the value of the only local, answering evaluate requests with the value in full, and
+/// returns the evaluation contexts that were asked for.
+async fn copy_value_of_local(
+    cx: &mut VisualTestContext,
+    session: &Entity<Session>,
+    running_state: &Entity<RunningState>,
+) -> Vec<Option<dap::EvaluateArgumentsContext>> {
+    let contexts = Arc::new(Mutex::new(Vec::new()));
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    client.on_request::<Evaluate, _>({
+        let contexts = contexts.clone();
+        move |_, args| {
+            assert_eq!(args.expression, "list");
+            contexts.lock().unwrap().push(args.context);
+            Ok(dap::EvaluateResponse {
+                result: "[1, 2, 3, 4]".into(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    let variable_list =
+        running_state.update(cx, |running_state, _| running_state.variable_list().clone());
+    let local = variable_list.update(cx, |variable_list, _| variable_list.variables()[0].clone());
+    variable_list
+        .update(cx, |variable_list, cx| {
+            variable_list.copy_variable_value(&local, cx)
+        })
+        .await;
+
+    assert_eq!(
+        cx.read_from_clipboard().and_then(|item| item.text()),
+        Some("[1, 2, 3, 4]".to_string())
+    );
+    contexts.lock().unwrap().clone()
+}
+
+#[gpui::test]
+async fn test_copy_value_uses_the_clipboard_context(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let (mut cx, session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities {
+            supports_clipboard_context: Some(true),
+            ..Default::default()
+        },
+        HashMap::from_iter([(1, vec![variable("list", "[1, 2, …]", 0)])]),
+    )
+    .await;
+
+    assert_eq!(
+        copy_value_of_local(&mut cx, &session, &running_state).await,
+        [Some(dap::EvaluateArgumentsContext::Clipboard)]
+    );
+}
+
+#[gpui::test]
+async fn test_copy_value_falls_back_to_the_repl_context(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let (mut cx, session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities::default(),
+        HashMap::from_iter([(1, vec![variable("list", "[1, 2, …]", 0)])]),
+    )
+    .await;
+
+    assert_eq!(
+        copy_value_of_local(&mut cx, &session, &running_state).await,
+        [Some(dap::EvaluateArgumentsContext::Repl)]
+    );
+}
+
+fn variable_path(indices: &[&str]) -> EntryPath {
+    EntryPath {
+        leaf_name: indices
+            .last()
+            .map(|name| SharedString::from(name.to_string())),
+        indices: indices
+            .iter()
+            .map(|name| SharedString::from(name.to_string()))
+            .collect(),
+    }
+}
+
+#[gpui::test]
+async fn test_filtering_variables(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let (mut cx, _session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities::default(),
+        HashMap::from_iter([
+            (
+                1,
+                vec![
+                    variable("user_name", "\"ferris\"", 0),
+                    variable("config", "Config { … }", 2),
+                    variable("count", "3", 0),
+                ],
+            ),
+            (
+                2,
+                vec![variable("timeout", "30", 0), variable("retries", "5", 0)],
+            ),
+        ]),
+    )
+    .await;
+    let cx = &mut cx;
+
+    let variable_list =
+        running_state.update(cx, |running_state, _| running_state.variable_list().clone());
+    variable_list.update(cx, |variable_list, cx| {
+        variable_list.toggle_entry(&variable_path(&["Locals", "config"]), cx)
+    });
+    cx.run_until_parked();
+
+    let unfiltered = vec![
+        "v Locals",
+        "    > user_name",
+        "    v config",
+        "        > timeout",
+        "        > retries",
+        "    > count",
+    ];
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(unfiltered.clone())
+    });
+
+    // A name containing the query matches it.
+    variable_list.update_in(cx, |variable_list, window, cx| {
+        variable_list.set_filter("count", window, cx)
+    });
+    cx.run_until_parked();
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(vec!["v Locals", "    > count"])
+    });
+
+    // So does one containing its characters in order, in any case.
+    variable_list.update_in(cx, |variable_list, window, cx| {
+        variable_list.set_filter("USNM", window, cx)
+    });
+    cx.run_until_parked();
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(vec!["v Locals", "    > user_name"])
+    });
+
+    // A nested match is shown under the variables that contain it.
+    variable_list.update_in(cx, |variable_list, window, cx| {
+        variable_list.set_filter("retr", window, cx)
+    });
+    cx.run_until_parked();
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(vec!["v Locals", "    v config", "        > retries"])
+    });
+
+    variable_list.update_in(cx, |variable_list, window, cx| {
+        variable_list.set_filter("", window, cx)
+    });
+    cx.run_until_parked();
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(unfiltered)
+    });
+}
+
+/// Answers variables requests from `variables` from now on, returning the references asked for.
+fn record_variables_requests(
+    session: &Entity<Session>,
+    variables: HashMap<u64, Vec<Variable>>,
+    cx: &mut VisualTestContext,
+) -> Arc<Mutex<Vec<u64>>> {
+    let requested_references = Arc::new(Mutex::new(Vec::new()));
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    client.on_request::<Variables, _>({
+        let requested_references = requested_references.clone();
+        move |_, args| {
+            requested_references
+                .lock()
+                .unwrap()
+                .push(args.variables_reference);
+            Ok(dap::VariablesResponse {
+                variables: variables
+                    .get(&args.variables_reference)
+                    .cloned()
+                    .unwrap_or_default(),
+            })
+        }
+    });
+    requested_references
+}
+
+/// Stops the session again, as a step would, and waits out the call stack's refresh debounce.
+async fn step(session: &Entity<Session>, cx: &mut VisualTestContext) {
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.run_until_parked();
+}
+
+#[gpui::test]
+async fn test_expanded_variables_are_restored_after_stepping(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    // A chain of nested variables, `level1` through `level10`, each holding the next one.
+    const LEVELS: u64 = 10;
+    let mut variables = HashMap::<u64, Vec<Variable>>::default();
+    for level in 1..=LEVELS {
+        let container_reference = if level == 1 { 1 } else { 10 + level - 1 };
+        let reference = if level == LEVELS { 0 } else { 10 + level };
+        variables
+            .entry(container_reference)
+            .or_default()
+            .push(variable(&format!("level{level}"), "{…}", reference));
+    }
+    let (mut cx, session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities::default(),
+        variables.clone(),
+    )
+    .await;
+    let cx = &mut cx;
+
+    let variable_list =
+        running_state.update(cx, |running_state, _| running_state.variable_list().clone());
+    let mut path = vec!["Locals".to_string()];
+    for level in 1..LEVELS {
+        path.push(format!("level{level}"));
+        let entry_path = variable_path(&path.iter().map(String::as_str).collect::<Vec<_>>());
+        variable_list.update(cx, |variable_list, cx| {
+            variable_list.toggle_entry(&entry_path, cx)
+        });
+        cx.run_until_parked();
+    }
+
+    let mut expanded_entries = vec!["v Locals".to_string()];
+    for level in 1..LEVELS {
+        expanded_entries.push(format!("{}v level{level}", "    ".repeat(level as usize)));
+    }
+    expanded_entries.push(format!("{}> level{LEVELS}", "    ".repeat(LEVELS as usize)));
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(expanded_entries.iter().map(String::as_str).collect())
+    });
+
+    let requested_references = record_variables_requests(&session, variables, cx);
+    step(&session, cx).await;
+
+    // Only the variables up to the depth limit are fetched again. The first one past it stays
+    // expanded, so that it's restored once it's fetched, but is shown collapsed.
+    let restored_levels = (MAX_RESTORED_EXPANSION_DEPTH - 1) as u64;
+    let mut restored_entries = expanded_entries[..=restored_levels as usize].to_vec();
+    restored_entries.push(format!(
+        "{}> level{}",
+        "    ".repeat(restored_levels as usize + 1),
+        restored_levels + 1
+    ));
+    variable_list.update(cx, |variable_list, _| {
+        variable_list.assert_visual_entries(restored_entries.iter().map(String::as_str).collect())
+    });
+
+    let mut requested_references = requested_references.lock().unwrap().clone();
+    requested_references.sort();
+    requested_references.dedup();
+    assert_eq!(
+        requested_references,
+        std::iter::once(1)
+            .chain((1..=restored_levels).map(|level| 10 + level))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[gpui::test]
+async fn test_restoring_expanded_variables_is_capped(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    // More expandable locals than are fetched again after a stop, each holding a single value.
+    let item_count = MAX_RESTORED_VARIABLE_FETCHES as u64 + 2;
+    let mut variables = HashMap::from_iter([(
+        1,
+        (0..item_count)
+            .map(|ix| variable(&format!("item{ix}"), "{…}", 100 + ix))
+            .collect::<Vec<_>>(),
+    )]);
+    for ix in 0..item_count {
+        variables.insert(100 + ix, vec![variable("value", "1", 0)]);
+    }
+    let (mut cx, session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities::default(),
+        variables.clone(),
+    )
+    .await;
+    let cx = &mut cx;
+
+    let variable_list =
+        running_state.update(cx, |running_state, _| running_state.variable_list().clone());
+    for ix in 0..item_count {
+        variable_list.update(cx, |variable_list, cx| {
+            variable_list.toggle_entry(&variable_path(&["Locals", &format!("item{ix}")]), cx)
+        });
+    }
+    cx.run_until_parked();
+    variable_list.update(cx, |variable_list, _| {
+        assert_eq!(variable_list.variables().len(), 2 * item_count as usize);
+    });
+
+    let requested_references = record_variables_requests(&session, variables, cx);
+    step(&session, cx).await;
+
+    let mut requested_references = requested_references.lock().unwrap().clone();
+    requested_references.sort();
+    requested_references.dedup();
+    assert_eq!(
+        requested_references
+            .iter()
+            .filter(|reference| **reference >= 100)
+            .count(),
+        MAX_RESTORED_VARIABLE_FETCHES
+    );
+    variable_list.update(cx, |variable_list, _| {
+        assert_eq!(
+            variable_list.variables().len(),
+            item_count as usize + MAX_RESTORED_VARIABLE_FETCHES
+        );
+    });
+}
+
+fn update_debugger_settings(cx: &mut TestAppContext, update: impl FnOnce(&mut DebuggerSettings)) {
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<DebuggerSettings>(cx, update);
+        });
+    });
+}
+
+/// More expandable locals than are expanded eagerly, each holding a single value.
+fn many_expandable_locals() -> HashMap<u64, Vec<Variable>> {
+    let item_count = MAX_EAGERLY_EXPANDED_VARIABLES as u64 + 6;
+    let mut variables = HashMap::from_iter([(
+        1,
+        (0..item_count)
+            .map(|ix| variable(&format!("item{ix}"), "{…}", 100 + ix))
+            .collect::<Vec<_>>(),
+    )]);
+    for ix in 0..item_count {
+        variables.insert(100 + ix, vec![variable("value", "1", 0)]);
+    }
+    variables
+}
+
+#[gpui::test]
+async fn test_locals_are_expanded_on_stop(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+    update_debugger_settings(cx, |settings| settings.expand_locals_depth = 1);
+
+    let variables = many_expandable_locals();
+    let item_count = variables[&1].len();
+    let (mut cx, _session, running_state) =
+        start_stopped_session(executor, cx, dap::Capabilities::default(), variables).await;
+    let cx = &mut cx;
+
+    // The locals scope and its variables are expanded, up to the limit on eager expansions.
+    running_state.update(cx, |running_state, cx| {
+        running_state
+            .variable_list()
+            .update(cx, |variable_list, _| {
+                let mut expected_entries = vec!["v Locals".to_string()];
+                for ix in 0..item_count {
+                    if ix < MAX_EAGERLY_EXPANDED_VARIABLES {
+                        expected_entries.push(format!("    v item{ix}"));
+                        expected_entries.push("        > value".to_string());
+                    } else {
+                        expected_entries.push(format!("    > item{ix}"));
+                    }
+                }
+                variable_list
+                    .assert_visual_entries(expected_entries.iter().map(String::as_str).collect());
+            });
+    });
+}
+
+#[gpui::test]
+async fn test_expanding_locals_on_stop_can_be_turned_off(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+    update_debugger_settings(cx, |settings| {
+        settings.expand_locals_on_stop = false;
+        settings.expand_locals_depth = 1;
+    });
+
+    let (mut cx, session, running_state) = start_stopped_session(
+        executor,
+        cx,
+        dap::Capabilities::default(),
+        many_expandable_locals(),
+    )
+    .await;
+    let cx = &mut cx;
+
+    running_state.update(cx, |running_state, cx| {
+        running_state
+            .variable_list()
+            .update(cx, |variable_list, _| {
+                variable_list.assert_visual_entries(vec!["> Locals"]);
+            });
+    });
+
+    // Nothing inside the scope was asked for, even after stopping again.
+    let requested_references = record_variables_requests(&session, many_expandable_locals(), cx);
+    step(&session, cx).await;
+    let mut requested_references = requested_references.lock().unwrap().clone();
+    requested_references.dedup();
+    assert_eq!(requested_references, [1]);
+}
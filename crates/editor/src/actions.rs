@@ -416,12 +416,15 @@ actions!(
         Tab,
         Backtab,
         ToggleBreakpoint,
+        ToggleColumnBreakpoint,
         ToggleCase,
         DisableBreakpoint,
         EnableBreakpoint,
         EditLogBreakpoint,
         DebuggerRunToCursor,
+        DebuggerSetNextStatement,
         DebuggerEvaluateSelectedText,
+        DebuggerHoverSelectedText,
         ToggleAutoSignatureHelp,
         ToggleGitBlameInline,
         OpenGitBlameCommit,
@@ -72,6 +72,22 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn column_breakpoint<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::ColumnBreakpoint(id),
+            position,
+            text: text.into(),
+        }
+    }
+
+    pub fn repl_result<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::ReplResult(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -295,7 +311,10 @@ impl<'a> Iterator for InlayChunks<'a> {
                         })
                     }
                     InlayId::Hint(_) => self.highlight_styles.inlay_hint,
-                    InlayId::DebuggerValue(_) => self.highlight_styles.inlay_hint,
+                    InlayId::DebuggerValue(_) | InlayId::ReplResult(_) => {
+                        self.highlight_styles.inlay_hint
+                    }
+                    InlayId::ColumnBreakpoint(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -193,7 +193,7 @@ use std::{
 };
 pub use sum_tree::Bias;
 use sum_tree::TreeMap;
-use text::{BufferId, FromAnchor, OffsetUtf16, Rope};
+use text::{BufferId, FromAnchor, OffsetUtf16, Rope, Unclipped};
 use theme::{
     ActiveTheme, PlayerColor, StatusColors, SyntaxTheme, ThemeColors, ThemeSettings,
     observe_buffer_font_size_adjustment,
@@ -278,6 +278,8 @@ pub enum InlayId {
     InlineCompletion(usize),
     Hint(usize),
     DebuggerValue(usize),
+    ReplResult(usize),
+    ColumnBreakpoint(usize),
 }
 
 impl InlayId {
@@ -286,11 +288,14 @@ impl InlayId {
             Self::InlineCompletion(id) => *id,
             Self::Hint(id) => *id,
             Self::DebuggerValue(id) => *id,
+            Self::ReplResult(id) => *id,
+            Self::ColumnBreakpoint(id) => *id,
         }
     }
 }
 
 pub enum ActiveDebugLine {}
+enum ColumnBreakpointMarker {}
 pub enum DebugStackFrameLine {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
@@ -1046,6 +1051,7 @@ pub struct Editor {
     hide_mouse_mode: HideMouseMode,
     pub change_list: ChangeList,
     inline_value_cache: InlineValueCache,
+    column_breakpoint_inlays: Vec<Inlay>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -1827,6 +1833,7 @@ impl Editor {
             },
             inline_diagnostics_enabled: mode.is_full(),
             inline_value_cache: InlineValueCache::new(inlay_hint_settings.show_value_hints),
+            column_breakpoint_inlays: Vec::new(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
 
             gutter_hovered: false,
@@ -1908,9 +1915,11 @@ impl Editor {
         };
         if let Some(breakpoints) = this.breakpoint_store.as_ref() {
             this._subscriptions
-                .push(cx.observe(breakpoints, |_, _, cx| {
+                .push(cx.observe(breakpoints, |editor, _, cx| {
+                    editor.refresh_column_breakpoint_inlays(cx);
                     cx.notify();
                 }));
+            this.refresh_column_breakpoint_inlays(cx);
         }
         this.tasks_update_task = Some(this.refresh_runnables(window, cx));
         this._subscriptions.extend(project_subscriptions);
@@ -2375,6 +2384,10 @@ impl Editor {
         self.completion_provider = provider;
     }
 
+    pub fn take_completion_provider(&mut self) -> Option<Box<dyn CompletionProvider>> {
+        self.completion_provider.take()
+    }
+
     pub fn semantics_provider(&self) -> Option<Rc<dyn SemanticsProvider>> {
         self.semantics_provider.clone()
     }
@@ -4773,6 +4786,11 @@ impl Editor {
         }
     }
 
+    /// Allocates an id for an inlay inserted with [`Editor::splice_inlays`].
+    pub fn next_inlay_id(&mut self) -> usize {
+        post_inc(&mut self.next_inlay_id)
+    }
+
     pub fn splice_inlays(
         &self,
         to_remove: &[InlayId],
@@ -7051,7 +7069,7 @@ impl Editor {
                 buffer_snapshot,
                 cx,
             );
-            for (breakpoint, state) in breakpoints {
+            for (breakpoint, state) in breakpoints.filter(|(breakpoint, _)| !breakpoint.at_column) {
                 let multi_buffer_anchor =
                     Anchor::in_buffer(excerpt_id, buffer_snapshot.remote_id(), breakpoint.position);
                 let position = multi_buffer_anchor
@@ -7120,6 +7138,8 @@ impl Editor {
 
         let run_to_cursor = command_palette_hooks::CommandPaletteFilter::try_global(cx)
             .map_or(false, |filter| !filter.is_hidden(&DebuggerRunToCursor));
+        let set_next_statement = command_palette_hooks::CommandPaletteFilter::try_global(cx)
+            .map_or(false, |filter| !filter.is_hidden(&DebuggerSetNextStatement));
 
         let toggle_state_msg = breakpoint.as_ref().map_or(None, |bp| match bp.1.state {
             BreakpointState::Enabled => Some("Disable"),
@@ -7145,6 +7165,26 @@ impl Editor {
 
                         window.dispatch_action(Box::new(DebuggerRunToCursor), cx);
                     })
+                    .item(
+                        ui::ContextMenuEntry::new("Set next statement")
+                            .disabled(!set_next_statement)
+                            .handler({
+                                let weak_editor = weak_editor.clone();
+                                move |window, cx| {
+                                    weak_editor
+                                        .update(cx, |editor, cx| {
+                                            editor.change_selections(None, window, cx, |s| {
+                                                s.select_ranges([
+                                                    Point::new(row, 0)..Point::new(row, 0)
+                                                ])
+                                            });
+                                        })
+                                        .ok();
+
+                                    window.dispatch_action(Box::new(DebuggerSetNextStatement), cx);
+                                }
+                            }),
+                    )
                     .separator()
                 })
                 .when_some(toggle_state_msg, |this, msg| {
@@ -9665,7 +9705,7 @@ impl Editor {
                         &buffer_snapshot,
                         cx,
                     )
-                    .next()
+                    .find(|(bp, _)| !bp.at_column)
                     .and_then(|(bp, _)| {
                         let breakpoint_row = buffer_snapshot
                             .summary_for_anchor::<text::PointUtf16>(&bp.position)
@@ -9841,6 +9881,7 @@ impl Editor {
                 BreakpointWithPosition {
                     position: breakpoint_position.text_anchor,
                     bp: breakpoint,
+                    at_column: false,
                 },
                 edit_action,
                 cx,
@@ -9850,6 +9891,185 @@ impl Editor {
         cx.notify();
     }
 
+    /// Toggles a breakpoint at the column of each cursor. While a debug session whose adapter
+    /// reports breakpoint locations is running, the breakpoint snaps to the nearest location the
+    /// adapter can stop at.
+    pub fn toggle_column_breakpoint(
+        &mut self,
+        _: &crate::actions::ToggleColumnBreakpoint,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(breakpoint_store) = self.breakpoint_store.clone() else {
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let multi_buffer = self.buffer.read(cx);
+        let cursors = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .filter_map(|selection| {
+                let head = selection.head();
+                let buffer = multi_buffer.buffer(head.buffer_id?)?;
+                let position = buffer
+                    .read(cx)
+                    .summary_for_anchor::<text::PointUtf16>(&head.text_anchor);
+                Some((buffer, position))
+            })
+            .collect::<Vec<_>>();
+
+        let session = project
+            .read(cx)
+            .active_debug_session(cx)
+            .map(|(session, _)| session)
+            .or_else(|| {
+                project
+                    .read(cx)
+                    .dap_store()
+                    .read(cx)
+                    .sessions()
+                    .find(|session| !session.read(cx).is_terminated())
+                    .cloned()
+            })
+            .filter(|session| {
+                session
+                    .read(cx)
+                    .capabilities()
+                    .supports_breakpoint_locations_request
+                    .unwrap_or_default()
+            });
+
+        for (buffer, position) in cursors {
+            let Some(path) = BreakpointStore::abs_path_from_buffer(&buffer, cx) else {
+                continue;
+            };
+            let locations = session.as_ref().map(|session| {
+                session.update(cx, |session, cx| {
+                    session.breakpoint_locations(path.clone(), position.row, cx)
+                })
+            });
+            let breakpoint_store = breakpoint_store.clone();
+            cx.spawn(async move |_, cx| {
+                let columns = match locations {
+                    Some(locations) => locations.await.log_err().unwrap_or_default(),
+                    None => Vec::new(),
+                };
+                let column = columns
+                    .iter()
+                    .copied()
+                    .find(|column| *column >= position.column)
+                    .or_else(|| columns.last().copied())
+                    .unwrap_or(position.column);
+
+                breakpoint_store.update(cx, |breakpoint_store, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let point = snapshot.clip_point_utf16(
+                        Unclipped(text::PointUtf16::new(position.row, column)),
+                        Bias::Left,
+                    );
+                    let breakpoint = breakpoint_store
+                        .breakpoint_at_position(&path, point.row, Some(point.column), cx)
+                        .map(|(_, breakpoint)| breakpoint)
+                        .unwrap_or_else(|| BreakpointWithPosition {
+                            position: snapshot.anchor_after(point),
+                            bp: Breakpoint::new_standard(),
+                            at_column: true,
+                        });
+                    breakpoint_store.toggle_breakpoint(
+                        buffer,
+                        breakpoint,
+                        BreakpointEditAction::Toggle,
+                        cx,
+                    );
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    /// Column breakpoints are drawn as markers within their line, since the gutter only has room
+    /// for one breakpoint per line.
+    fn refresh_column_breakpoint_inlays(&mut self, cx: &mut Context<Self>) {
+        let (Some(breakpoint_store), Some(project)) =
+            (self.breakpoint_store.clone(), self.project.clone())
+        else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut markers = Vec::new();
+        for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+            let Some(buffer) = project
+                .read(cx)
+                .buffer_for_id(buffer_snapshot.remote_id(), cx)
+            else {
+                continue;
+            };
+            markers.extend(
+                breakpoint_store
+                    .read(cx)
+                    .breakpoints(&buffer, Some(excerpt_range.context), buffer_snapshot, cx)
+                    .filter(|(breakpoint, _)| breakpoint.at_column)
+                    .map(|(breakpoint, _)| {
+                        let marker = if breakpoint.bp.is_enabled() {
+                            "● "
+                        } else {
+                            "○ "
+                        };
+                        (
+                            Anchor::in_buffer(
+                                excerpt_id,
+                                buffer_snapshot.remote_id(),
+                                breakpoint.position,
+                            ),
+                            marker,
+                        )
+                    }),
+            );
+        }
+
+        let unchanged = markers.len() == self.column_breakpoint_inlays.len()
+            && markers.iter().zip(&self.column_breakpoint_inlays).all(
+                |((position, marker), inlay)| {
+                    *position == inlay.position && inlay.text.chars().eq(marker.chars())
+                },
+            );
+        if unchanged {
+            return;
+        }
+
+        let new_inlays = markers
+            .into_iter()
+            .map(|(position, marker)| {
+                Inlay::column_breakpoint(post_inc(&mut self.next_inlay_id), position, marker)
+            })
+            .collect::<Vec<_>>();
+        let highlights = new_inlays
+            .iter()
+            .map(|inlay| InlayHighlight {
+                inlay: inlay.id,
+                inlay_position: inlay.position,
+                range: 0..inlay.text.len() - 1,
+            })
+            .collect();
+        let old_inlays = mem::replace(&mut self.column_breakpoint_inlays, new_inlays.clone())
+            .into_iter()
+            .map(|inlay| inlay.id)
+            .collect::<Vec<_>>();
+
+        self.clear_highlights::<ColumnBreakpointMarker>(cx);
+        self.splice_inlays(&old_inlays, new_inlays, cx);
+        self.highlight_inlays::<ColumnBreakpointMarker>(
+            highlights,
+            HighlightStyle::color(cx.theme().colors().debugger_accent),
+            cx,
+        );
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn breakpoint_store(&self) -> Option<Entity<BreakpointStore>> {
         self.breakpoint_store.clone()
@@ -18273,6 +18493,7 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_column_breakpoint_inlays(cx);
             }
             multi_buffer::Event::ExcerptsRemoved {
                 ids,
@@ -19772,6 +19993,15 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Vec<project::Hover>>>;
 
+    /// The value of the expression at `range` in a stopped debug session, which is shown apart
+    /// from the language server's hover so that neither waits for the other.
+    fn debugger_hover(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Option<project::Hover>>>;
+
     fn inline_values(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -20214,6 +20444,15 @@ impl SemanticsProvider for Entity<Project> {
         Some(self.update(cx, |project, cx| project.hover(buffer, position, cx)))
     }
 
+    fn debugger_hover(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Option<project::Hover>>> {
+        Some(self.update(cx, |project, cx| project.debugger_hover(buffer, range, cx)))
+    }
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -19094,6 +19094,99 @@ async fn test_breakpoint_toggling(cx: &mut TestAppContext) {
     assert_breakpoint(&breakpoints, &abs_path, vec![]);
 }
 
+#[gpui::test]
+async fn test_column_breakpoint_toggling(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let sample_text = "First line\nSecond line\nThird line\nFourth line".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": sample_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    let project_path = editor.update(cx, |editor, cx| editor.project_path(cx).unwrap());
+    let abs_path: Arc<Path> = project.read_with(cx, |project, cx| {
+        project
+            .absolute_path(&project_path, cx)
+            .map(|path_buf| Arc::from(path_buf.to_owned()))
+            .unwrap()
+    });
+    let source_breakpoints = |editor: &Entity<Editor>, cx: &mut VisualTestContext| {
+        editor.update(cx, |editor, cx| {
+            let mut breakpoints = editor
+                .breakpoint_store()
+                .unwrap()
+                .read(cx)
+                .all_source_breakpoints(cx)
+                .remove(&abs_path)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|breakpoint| (breakpoint.row, breakpoint.column))
+                .collect::<Vec<_>>();
+            breakpoints.sort();
+            breakpoints
+        })
+    };
+
+    // A line breakpoint and a column breakpoint can share a line.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_breakpoint(&actions::ToggleBreakpoint, window, cx);
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 6)..Point::new(0, 6)])
+        });
+        editor.toggle_column_breakpoint(&actions::ToggleColumnBreakpoint, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        source_breakpoints(&editor, cx),
+        vec![(0, None), (0, Some(6))]
+    );
+
+    // Toggling the line breakpoint leaves the column breakpoint alone.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_breakpoint(&actions::ToggleBreakpoint, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(source_breakpoints(&editor, cx), vec![(0, Some(6))]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_column_breakpoint(&actions::ToggleColumnBreakpoint, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(source_breakpoints(&editor, cx), Vec::new());
+}
+
 #[gpui::test]
 async fn test_log_breakpoint_editing(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
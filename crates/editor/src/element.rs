@@ -429,6 +429,7 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_inline_diagnostics);
         register_action(editor, window, Editor::toggle_minimap);
         register_action(editor, window, hover_popover::hover);
+        register_action(editor, window, hover_popover::debugger_hover_selected_text);
         register_action(editor, window, Editor::reveal_in_finder);
         register_action(editor, window, Editor::copy_path);
         register_action(editor, window, Editor::copy_relative_path);
@@ -554,6 +555,7 @@ impl EditorElement {
         register_action(editor, window, Editor::open_selections_in_multibuffer);
         if cx.has_flag::<DebuggerFeatureFlag>() {
             register_action(editor, window, Editor::toggle_breakpoint);
+            register_action(editor, window, Editor::toggle_column_breakpoint);
             register_action(editor, window, Editor::edit_log_breakpoint);
             register_action(editor, window, Editor::enable_breakpoint);
             register_action(editor, window, Editor::disable_breakpoint);
@@ -1,6 +1,6 @@
 use crate::{
-    ActiveDiagnostic, Anchor, AnchorRangeExt, DisplayPoint, DisplayRow, Editor, EditorSettings,
-    EditorSnapshot, GlobalDiagnosticRenderer, Hover,
+    ActiveDiagnostic, Anchor, AnchorRangeExt, DebuggerHoverSelectedText, DisplayPoint, DisplayRow,
+    Editor, EditorSettings, EditorSnapshot, ExcerptId, GlobalDiagnosticRenderer, Hover,
     display_map::{InlayOffset, ToDisplayPoint, invisibles::is_invisible},
     hover_links::{InlayHighlight, RangeInEditor},
     scroll::{Autoscroll, ScrollAmount},
@@ -10,7 +10,7 @@ use gpui::{
     AnyElement, AsyncWindowContext, Context, Entity, Focusable as _, FontWeight, Hsla,
     InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, ScrollHandle, Size,
     Stateful, StatefulInteractiveElement, StyleRefinement, Styled, Subscription, Task,
-    TextStyleRefinement, Window, div, px,
+    TextStyleRefinement, WeakEntity, Window, div, px,
 };
 use itertools::Itertools;
 use language::{DiagnosticEntry, Language, LanguageRegistry};
@@ -19,7 +19,10 @@ use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use multi_buffer::{MultiOrSingleBufferOffsetRange, ToOffset, ToPoint};
 use project::{HoverBlock, HoverBlockKind, InlayHintLabelPart};
 use settings::Settings;
-use std::{borrow::Cow, cell::RefCell};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+};
 use std::{ops::Range, sync::Arc, time::Duration};
 use std::{path::PathBuf, rc::Rc};
 use theme::ThemeSettings;
@@ -40,6 +43,94 @@ pub fn hover(editor: &mut Editor, _: &Hover, window: &mut Window, cx: &mut Conte
     show_hover(editor, head, true, window, cx);
 }
 
+/// Bindable action which shows the value of the newest selection while debugging
+pub fn debugger_hover_selected_text(
+    editor: &mut Editor,
+    _: &DebuggerHoverSelectedText,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let selection = editor.selections.newest_anchor().clone();
+    let multi_buffer = editor.buffer().read(cx);
+    let Some((buffer, start)) = multi_buffer.text_anchor_for_position(selection.start, cx) else {
+        return;
+    };
+    let Some((end_buffer, end)) = multi_buffer.text_anchor_for_position(selection.end, cx) else {
+        return;
+    };
+    if end_buffer != buffer {
+        return;
+    }
+    let Some(request) = editor
+        .semantics_provider
+        .clone()
+        .and_then(|provider| provider.debugger_hover(&buffer, start..end, cx))
+    else {
+        return;
+    };
+
+    show_requested_hover(editor, selection.head(), request, window, cx);
+}
+
+/// Shows hover contents that come from somewhere other than a language server, such as a REPL
+/// kernel, in a popover at `anchor`.
+pub fn show_hover_contents(
+    editor: &mut Editor,
+    anchor: Anchor,
+    hover: project::Hover,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    show_requested_hover(editor, anchor, async move { Some(hover) }, window, cx);
+}
+
+/// Shows what `request` resolves to in a popover at `anchor`, in place of any other hovers.
+fn show_requested_hover(
+    editor: &mut Editor,
+    anchor: Anchor,
+    request: impl Future<Output = Option<project::Hover>> + 'static,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let Some((excerpt_id, _, _)) = editor.buffer().read(cx).excerpt_containing(anchor, cx) else {
+        return;
+    };
+    let Some(language_registry) = editor
+        .project
+        .as_ref()
+        .map(|project| project.read(cx).languages().clone())
+    else {
+        return;
+    };
+
+    let task = cx.spawn_in(window, async move |this, cx| {
+        async move {
+            let Some(hover_result) = request.await else {
+                return anyhow::Ok(());
+            };
+            let snapshot = this.update_in(cx, |this, window, cx| this.snapshot(window, cx))?;
+            let (_, popover) = info_popover_for_hover(
+                hover_result,
+                anchor,
+                excerpt_id,
+                &snapshot,
+                &language_registry,
+                true,
+                &this,
+                cx,
+            )
+            .await;
+            this.update(cx, |editor, cx| {
+                editor.hover_state.info_popovers = vec![popover];
+                cx.notify();
+            })
+        }
+        .log_err()
+        .await
+    });
+    editor.hover_state.info_task = Some(task);
+}
+
 /// The internal hover action dispatches between `show_hover` or `hide_hover`
 /// depending on whether a point to hover over is provided.
 pub fn hover_at(
@@ -304,6 +395,9 @@ fn show_hover(
             };
 
             let hover_request = cx.update(|_, cx| provider.hover(&buffer, buffer_position, cx))?;
+            let debugger_hover_request = cx.update(|_, cx| {
+                provider.debugger_hover(&buffer, buffer_position..buffer_position, cx)
+            })?;
 
             if let Some(delay) = delay {
                 delay.await;
@@ -423,112 +517,122 @@ fn show_hover(
                 None
             };
 
-            let hovers_response = if let Some(hover_request) = hover_request {
-                hover_request.await
-            } else {
-                Vec::new()
-            };
-            let snapshot = this.update_in(cx, |this, window, cx| this.snapshot(window, cx))?;
-            let mut hover_highlights = Vec::with_capacity(hovers_response.len());
-            let mut info_popovers = Vec::with_capacity(
-                hovers_response.len() + if invisible_char.is_some() { 1 } else { 0 },
-            );
-
-            if let Some((invisible, range)) = invisible_char {
-                let blocks = vec![HoverBlock {
-                    text: format!("Unicode character U+{:02X}", invisible as u32),
-                    kind: HoverBlockKind::PlainText,
-                }];
-                let parsed_content = parse_blocks(&blocks, &language_registry, None, cx).await;
-                let scroll_handle = ScrollHandle::new();
-                let subscription = this
-                    .update(cx, |_, cx| {
-                        if let Some(parsed_content) = &parsed_content {
-                            Some(cx.observe(parsed_content, |_, _, cx| cx.notify()))
-                        } else {
-                            None
-                        }
+            // The debugger's value is shown ahead of the language server's hover, as soon as
+            // either is known.
+            let debugger_popover_shown = Rc::new(Cell::new(false));
+            let show_debugger_popover = {
+                let this = this.clone();
+                let language_registry = language_registry.clone();
+                let debugger_popover_shown = debugger_popover_shown.clone();
+                let mut cx = cx.clone();
+                async move {
+                    let Some(hover_result) = (match debugger_hover_request {
+                        Some(debugger_hover_request) => debugger_hover_request.await,
+                        None => None,
+                    }) else {
+                        return anyhow::Ok(());
+                    };
+                    let snapshot =
+                        this.update_in(&mut cx, |this, window, cx| this.snapshot(window, cx))?;
+                    let (_, popover) = info_popover_for_hover(
+                        hover_result,
+                        anchor,
+                        excerpt_id,
+                        &snapshot,
+                        &language_registry,
+                        ignore_timeout,
+                        &this,
+                        &mut cx,
+                    )
+                    .await;
+                    this.update(&mut cx, |editor, cx| {
+                        editor.hover_state.info_popovers.insert(0, popover);
+                        debugger_popover_shown.set(true);
+                        cx.notify();
                     })
-                    .ok()
-                    .flatten();
-                info_popovers.push(InfoPopover {
-                    symbol_range: RangeInEditor::Text(range),
-                    parsed_content,
-                    scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
-                    scroll_handle,
-                    keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
-                    anchor: Some(anchor),
-                    _subscription: subscription,
-                })
-            }
+                }
+            };
 
-            for hover_result in hovers_response {
-                // Create symbol range of anchors for highlighting and filtering of future requests.
-                let range = hover_result
-                    .range
-                    .and_then(|range| {
-                        let start = snapshot
-                            .buffer_snapshot
-                            .anchor_in_excerpt(excerpt_id, range.start)?;
-                        let end = snapshot
-                            .buffer_snapshot
-                            .anchor_in_excerpt(excerpt_id, range.end)?;
-                        Some(start..end)
-                    })
-                    .or_else(|| {
-                        let snapshot = &snapshot.buffer_snapshot;
-                        match snapshot.syntax_ancestor(anchor..anchor)?.1 {
-                            MultiOrSingleBufferOffsetRange::Multi(range) => Some(
-                                snapshot.anchor_before(range.start)
-                                    ..snapshot.anchor_after(range.end),
-                            ),
-                            MultiOrSingleBufferOffsetRange::Single(_) => None,
-                        }
-                    })
-                    .unwrap_or_else(|| anchor..anchor);
+            let show_hovers = async {
+                let hovers_response = if let Some(hover_request) = hover_request {
+                    hover_request.await
+                } else {
+                    Vec::new()
+                };
+                let snapshot = this.update_in(cx, |this, window, cx| this.snapshot(window, cx))?;
+                let mut hover_highlights = Vec::with_capacity(hovers_response.len());
+                let mut info_popovers = Vec::with_capacity(
+                    hovers_response.len() + if invisible_char.is_some() { 1 } else { 0 },
+                );
 
-                let blocks = hover_result.contents;
-                let language = hover_result.language;
-                let parsed_content = parse_blocks(&blocks, &language_registry, language, cx).await;
-                let scroll_handle = ScrollHandle::new();
-                hover_highlights.push(range.clone());
-                let subscription = this
-                    .update(cx, |_, cx| {
-                        if let Some(parsed_content) = &parsed_content {
-                            Some(cx.observe(parsed_content, |_, _, cx| cx.notify()))
-                        } else {
-                            None
-                        }
+                if let Some((invisible, range)) = invisible_char {
+                    let blocks = vec![HoverBlock {
+                        text: format!("Unicode character U+{:02X}", invisible as u32),
+                        kind: HoverBlockKind::PlainText,
+                    }];
+                    let parsed_content = parse_blocks(&blocks, &language_registry, None, cx).await;
+                    let scroll_handle = ScrollHandle::new();
+                    let subscription = this
+                        .update(cx, |_, cx| {
+                            if let Some(parsed_content) = &parsed_content {
+                                Some(cx.observe(parsed_content, |_, _, cx| cx.notify()))
+                            } else {
+                                None
+                            }
+                        })
+                        .ok()
+                        .flatten();
+                    info_popovers.push(InfoPopover {
+                        symbol_range: RangeInEditor::Text(range),
+                        parsed_content,
+                        scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+                        scroll_handle,
+                        keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
+                        anchor: Some(anchor),
+                        _subscription: subscription,
                     })
-                    .ok()
-                    .flatten();
-                info_popovers.push(InfoPopover {
-                    symbol_range: RangeInEditor::Text(range),
-                    parsed_content,
-                    scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
-                    scroll_handle,
-                    keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
-                    anchor: Some(anchor),
-                    _subscription: subscription,
-                });
-            }
+                }
 
-            this.update_in(cx, |editor, window, cx| {
-                if hover_highlights.is_empty() {
-                    editor.clear_background_highlights::<HoverState>(cx);
-                } else {
-                    // Highlight the selected symbol using a background highlight
-                    editor.highlight_background::<HoverState>(
-                        &hover_highlights,
-                        |theme| theme.element_hover, // todo update theme
+                for hover_result in hovers_response {
+                    let (range, popover) = info_popover_for_hover(
+                        hover_result,
+                        anchor,
+                        excerpt_id,
+                        &snapshot,
+                        &language_registry,
+                        ignore_timeout,
+                        &this,
                         cx,
-                    );
+                    )
+                    .await;
+                    hover_highlights.push(range);
+                    info_popovers.push(popover);
                 }
 
-                editor.hover_state.info_popovers = info_popovers;
-                cx.notify();
-                window.refresh();
-            })?;
+                this.update_in(cx, |editor, window, cx| {
+                    if hover_highlights.is_empty() {
+                        editor.clear_background_highlights::<HoverState>(cx);
+                    } else {
+                        // Highlight the selected symbol using a background highlight
+                        editor.highlight_background::<HoverState>(
+                            &hover_highlights,
+                            |theme| theme.element_hover, // todo update theme
+                            cx,
+                        );
+                    }
+
+                    let debugger_popovers = usize::from(debugger_popover_shown.get());
+                    editor.hover_state.info_popovers.truncate(debugger_popovers);
+                    editor.hover_state.info_popovers.extend(info_popovers);
+                    cx.notify();
+                    window.refresh();
+                })
+            };
+
+            let (debugger_popover_result, hovers_result) =
+                futures::join!(show_debugger_popover, show_hovers);
+            debugger_popover_result?;
+            hovers_result?;
 
             anyhow::Ok(())
         }
@@ -540,6 +644,66 @@ fn show_hover(
     None
 }
 
+/// Builds the popover for a hover result, along with the range of the symbol it's about.
+async fn info_popover_for_hover(
+    hover_result: project::Hover,
+    anchor: Anchor,
+    excerpt_id: ExcerptId,
+    snapshot: &EditorSnapshot,
+    language_registry: &Arc<LanguageRegistry>,
+    keyboard_grace: bool,
+    editor: &WeakEntity<Editor>,
+    cx: &mut AsyncWindowContext,
+) -> (Range<Anchor>, InfoPopover) {
+    // Create symbol range of anchors for highlighting and filtering of future requests.
+    let range = hover_result
+        .range
+        .and_then(|range| {
+            let start = snapshot
+                .buffer_snapshot
+                .anchor_in_excerpt(excerpt_id, range.start)?;
+            let end = snapshot
+                .buffer_snapshot
+                .anchor_in_excerpt(excerpt_id, range.end)?;
+            Some(start..end)
+        })
+        .or_else(|| {
+            let snapshot = &snapshot.buffer_snapshot;
+            match snapshot.syntax_ancestor(anchor..anchor)?.1 {
+                MultiOrSingleBufferOffsetRange::Multi(range) => {
+                    Some(snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end))
+                }
+                MultiOrSingleBufferOffsetRange::Single(_) => None,
+            }
+        })
+        .unwrap_or_else(|| anchor..anchor);
+
+    let blocks = hover_result.contents;
+    let language = hover_result.language;
+    let parsed_content = parse_blocks(&blocks, language_registry, language, cx).await;
+    let scroll_handle = ScrollHandle::new();
+    let subscription = editor
+        .update(cx, |_, cx| {
+            if let Some(parsed_content) = &parsed_content {
+                Some(cx.observe(parsed_content, |_, _, cx| cx.notify()))
+            } else {
+                None
+            }
+        })
+        .ok()
+        .flatten();
+    let popover = InfoPopover {
+        symbol_range: RangeInEditor::Text(range.clone()),
+        parsed_content,
+        scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+        scroll_handle,
+        keyboard_grace: Rc::new(RefCell::new(keyboard_grace)),
+        anchor: Some(anchor),
+        _subscription: subscription,
+    };
+    (range, popover)
+}
+
 fn same_info_hover(editor: &Editor, snapshot: &EditorSnapshot, anchor: Anchor) -> bool {
     editor
         .hover_state
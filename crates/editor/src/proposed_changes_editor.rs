@@ -445,6 +445,15 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         self.0.hover(&buffer, position, cx)
     }
 
+    fn debugger_hover(
+        &self,
+        _: &Entity<Buffer>,
+        _: Range<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<Option<project::Hover>>> {
+        None
+    }
+
     fn inlay_hints(
         &self,
         buffer: Entity<Buffer>,
@@ -858,6 +858,9 @@ struct Shadow {
     corner_radii: Corners,
     content_mask: Bounds,
     color: Hsla,
+    offset: vec2<f32>,
+    spread_radius: f32,
+    inset: u32,
 }
 var<storage, read> b_shadows: array<Shadow>;
 
@@ -874,11 +877,14 @@ fn vs_shadow(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) ins
     let unit_vertex = vec2<f32>(f32(vertex_id & 1u), 0.5 * f32(vertex_id & 2u));
     var shadow = b_shadows[instance_id];
 
-    let margin = 3.0 * shadow.blur_radius;
-    // Set the bounds of the shadow and adjust its size based on the shadow's
-    // spread radius to achieve the spreading effect
-    shadow.bounds.origin -= vec2<f32>(margin);
-    shadow.bounds.size += 2.0 * vec2<f32>(margin);
+    // Inset shadows are clipped to their bounds, so only outer shadows need room for the blur.
+    if ((shadow.inset & 0xFFu) == 0u) {
+        let margin = 3.0 * shadow.blur_radius;
+        // Set the bounds of the shadow and adjust its size based on the shadow's
+        // spread radius to achieve the spreading effect
+        shadow.bounds.origin -= vec2<f32>(margin);
+        shadow.bounds.size += 2.0 * vec2<f32>(margin);
+    }
 
     var out = ShadowVarying();
     out.position = to_device_position(unit_vertex, shadow.bounds);
@@ -896,17 +902,40 @@ fn fs_shadow(input: ShadowVarying) -> @location(0) vec4<f32> {
     }
 
     let shadow = b_shadows[input.shadow_id];
-    let half_size = shadow.bounds.size / 2.0;
-    let center = shadow.bounds.origin + half_size;
-    let center_to_point = input.position.xy - center;
+    if ((shadow.inset & 0xFFu) == 0u) {
+        let alpha = blurred_quad_alpha(input.position.xy, shadow.bounds, shadow.corner_radii, shadow.blur_radius);
+        return blend_color(input.color, alpha);
+    }
+
+    // An inset shadow covers its bounds, except for the blurred hole left by moving and shrinking
+    // them. The bounds also clip the shadow, following their rounded corners.
+    let clip_alpha = saturate(0.5 - quad_sdf(input.position.xy, shadow.bounds, shadow.corner_radii));
+    var hole = shadow.bounds;
+    hole.origin += shadow.offset + vec2<f32>(shadow.spread_radius);
+    hole.size = max(hole.size - 2.0 * vec2<f32>(shadow.spread_radius), vec2<f32>(0.0));
+    var hole_radii = shadow.corner_radii;
+    hole_radii.top_left = max(hole_radii.top_left - shadow.spread_radius, 0.0);
+    hole_radii.top_right = max(hole_radii.top_right - shadow.spread_radius, 0.0);
+    hole_radii.bottom_right = max(hole_radii.bottom_right - shadow.spread_radius, 0.0);
+    hole_radii.bottom_left = max(hole_radii.bottom_left - shadow.spread_radius, 0.0);
+    let hole_alpha = blurred_quad_alpha(input.position.xy, hole, hole_radii, shadow.blur_radius);
+
+    return blend_color(input.color, clip_alpha * (1.0 - hole_alpha));
+}
 
-    let corner_radius = pick_corner_radius(center_to_point, shadow.corner_radii);
+// How much of a blurred rounded rectangle covers the given point.
+fn blurred_quad_alpha(point: vec2<f32>, bounds: Bounds, corner_radii: Corners, blur_radius: f32) -> f32 {
+    let half_size = bounds.size / 2.0;
+    let center = bounds.origin + half_size;
+    let center_to_point = point - center;
+
+    let corner_radius = pick_corner_radius(center_to_point, corner_radii);
 
     // The signal is only non-zero in a limited range, so don't waste samples
     let low = center_to_point.y - half_size.y;
     let high = center_to_point.y + half_size.y;
-    let start = clamp(-3.0 * shadow.blur_radius, low, high);
-    let end = clamp(3.0 * shadow.blur_radius, low, high);
+    let start = clamp(-3.0 * blur_radius, low, high);
+    let end = clamp(3.0 * blur_radius, low, high);
 
     // Accumulate samples (we can get away with surprisingly few samples)
     let step = (end - start) / 4.0;
@@ -914,12 +943,12 @@ fn fs_shadow(input: ShadowVarying) -> @location(0) vec4<f32> {
     var alpha = 0.0;
     for (var i = 0; i < 4; i += 1) {
         let blur = blur_along_x(center_to_point.x, center_to_point.y - y,
-            shadow.blur_radius, corner_radius, half_size);
-        alpha +=  blur * gaussian(y, shadow.blur_radius) * step;
+            blur_radius, corner_radius, half_size);
+        alpha +=  blur * gaussian(y, blur_radius) * step;
         y += step;
     }
 
-    return blend_color(input.color, alpha);
+    return alpha;
 }
 
 // --- path rasterization --- //
@@ -30,6 +30,8 @@ float gaussian(float x, float sigma);
 float2 erf(float2 x);
 float blur_along_x(float x, float y, float sigma, float corner,
                    float2 half_size);
+float blurred_quad_alpha(float2 position, Bounds_ScaledPixels bounds,
+                         Corners_ScaledPixels corner_radii, float blur_radius);
 float4 over(float4 below, float4 above);
 float radians(float degrees);
 float4 fill_color(Background background, float2 position, Bounds_ScaledPixels bounds,
@@ -458,14 +460,18 @@ vertex ShadowVertexOutput shadow_vertex(
   float2 unit_vertex = unit_vertices[unit_vertex_id];
   Shadow shadow = shadows[shadow_id];
 
-  float margin = 3. * shadow.blur_radius;
-  // Set the bounds of the shadow and adjust its size based on the shadow's
-  // spread radius to achieve the spreading effect
   Bounds_ScaledPixels bounds = shadow.bounds;
-  bounds.origin.x -= margin;
-  bounds.origin.y -= margin;
-  bounds.size.width += 2. * margin;
-  bounds.size.height += 2. * margin;
+  // Inset shadows are clipped to their bounds, so only outer shadows need room
+  // for the blur.
+  if (!shadow.inset) {
+    float margin = 3. * shadow.blur_radius;
+    // Set the bounds of the shadow and adjust its size based on the shadow's
+    // spread radius to achieve the spreading effect
+    bounds.origin.x -= margin;
+    bounds.origin.y -= margin;
+    bounds.size.width += 2. * margin;
+    bounds.size.height += 2. * margin;
+  }
 
   float4 device_position =
       to_device_position(unit_vertex, bounds, viewport_size);
@@ -484,51 +490,82 @@ fragment float4 shadow_fragment(ShadowFragmentInput input [[stage_in]],
                                 constant Shadow *shadows
                                 [[buffer(ShadowInputIndex_Shadows)]]) {
   Shadow shadow = shadows[input.shadow_id];
+  if (!shadow.inset) {
+    float alpha = blurred_quad_alpha(input.position.xy, shadow.bounds,
+                                     shadow.corner_radii, shadow.blur_radius);
+    return input.color * float4(1., 1., 1., alpha);
+  }
+
+  // An inset shadow covers its bounds, except for the blurred hole left by
+  // moving and shrinking them. The bounds also clip the shadow, following their
+  // rounded corners.
+  float clip_alpha = saturate(
+      0.5 - quad_sdf(input.position.xy, shadow.bounds, shadow.corner_radii));
+  Bounds_ScaledPixels hole = shadow.bounds;
+  hole.origin.x += shadow.offset.x + shadow.spread_radius;
+  hole.origin.y += shadow.offset.y + shadow.spread_radius;
+  hole.size.width = max(hole.size.width - 2. * shadow.spread_radius, 0.);
+  hole.size.height = max(hole.size.height - 2. * shadow.spread_radius, 0.);
+  Corners_ScaledPixels hole_radii = shadow.corner_radii;
+  hole_radii.top_left = max(hole_radii.top_left - shadow.spread_radius, 0.);
+  hole_radii.top_right = max(hole_radii.top_right - shadow.spread_radius, 0.);
+  hole_radii.bottom_right =
+      max(hole_radii.bottom_right - shadow.spread_radius, 0.);
+  hole_radii.bottom_left =
+      max(hole_radii.bottom_left - shadow.spread_radius, 0.);
+  float hole_alpha = blurred_quad_alpha(input.position.xy, hole, hole_radii,
+                                        shadow.blur_radius);
+
+  return input.color * float4(1., 1., 1., clip_alpha * (1. - hole_alpha));
+}
 
-  float2 origin = float2(shadow.bounds.origin.x, shadow.bounds.origin.y);
-  float2 size = float2(shadow.bounds.size.width, shadow.bounds.size.height);
+// How much of a blurred rounded rectangle covers the given point.
+float blurred_quad_alpha(float2 position, Bounds_ScaledPixels bounds,
+                         Corners_ScaledPixels corner_radii, float blur_radius) {
+  float2 origin = float2(bounds.origin.x, bounds.origin.y);
+  float2 size = float2(bounds.size.width, bounds.size.height);
   float2 half_size = size / 2.;
   float2 center = origin + half_size;
-  float2 point = input.position.xy - center;
+  float2 point = position - center;
   float corner_radius;
   if (point.x < 0.) {
     if (point.y < 0.) {
-      corner_radius = shadow.corner_radii.top_left;
+      corner_radius = corner_radii.top_left;
     } else {
-      corner_radius = shadow.corner_radii.bottom_left;
+      corner_radius = corner_radii.bottom_left;
     }
   } else {
     if (point.y < 0.) {
-      corner_radius = shadow.corner_radii.top_right;
+      corner_radius = corner_radii.top_right;
     } else {
-      corner_radius = shadow.corner_radii.bottom_right;
+      corner_radius = corner_radii.bottom_right;
     }
   }
 
   float alpha;
-  if (shadow.blur_radius == 0.) {
-    float distance = quad_sdf(input.position.xy, shadow.bounds, shadow.corner_radii);
+  if (blur_radius == 0.) {
+    float distance = quad_sdf(position, bounds, corner_radii);
     alpha = saturate(0.5 - distance);
   } else {
     // The signal is only non-zero in a limited range, so don't waste samples
     float low = point.y - half_size.y;
     float high = point.y + half_size.y;
-    float start = clamp(-3. * shadow.blur_radius, low, high);
-    float end = clamp(3. * shadow.blur_radius, low, high);
+    float start = clamp(-3. * blur_radius, low, high);
+    float end = clamp(3. * blur_radius, low, high);
 
     // Accumulate samples (we can get away with surprisingly few samples)
     float step = (end - start) / 4.;
     float y = start + step * 0.5;
     alpha = 0.;
     for (int i = 0; i < 4; i++) {
-      alpha += blur_along_x(point.x, point.y - y, shadow.blur_radius,
-                            corner_radius, half_size) *
-               gaussian(y, shadow.blur_radius) * step;
+      alpha += blur_along_x(point.x, point.y - y, blur_radius, corner_radius,
+                            half_size) *
+               gaussian(y, blur_radius) * step;
       y += step;
     }
   }
 
-  return input.color * float4(1., 1., 1., alpha);
+  return alpha;
 }
 
 struct UnderlineVertexOutput {
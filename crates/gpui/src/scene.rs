@@ -497,6 +497,12 @@ pub(crate) struct Shadow {
     pub corner_radii: Corners<ScaledPixels>,
     pub content_mask: ContentMask<ScaledPixels>,
     pub color: Hsla,
+    /// How far an inset shadow is moved from `bounds`.
+    pub offset: Point<ScaledPixels>,
+    /// How far an inset shadow spreads inwards from `bounds`.
+    pub spread_radius: ScaledPixels,
+    /// Whether the shadow is cast inside of `bounds`, which also clip it, rather than around them.
+    pub inset: bool,
 }
 
 impl From<Shadow> for Primitive {
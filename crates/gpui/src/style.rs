@@ -254,6 +254,9 @@ pub struct Style {
     /// Box shadow of the element
     pub box_shadow: SmallVec<[BoxShadow; 2]>,
 
+    /// Inset shadow of the element, drawn above its background
+    pub inner_shadow: SmallVec<[BoxShadow; 2]>,
+
     /// The text style of this element
     pub text: TextStyleRefinement,
 
@@ -289,7 +292,7 @@ pub enum Visibility {
 }
 
 /// The possible values of the box-shadow property
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct BoxShadow {
     /// What color should the shadow have?
     pub color: Hsla,
@@ -641,6 +644,8 @@ impl Style {
             ));
         }
 
+        window.paint_inner_shadows(bounds, corner_radii, &self.inner_shadow);
+
         continuation(window, cx);
 
         if self.is_border_visible() {
@@ -756,6 +761,7 @@ impl Default for Style {
             border_style: BorderStyle::default(),
             corner_radii: Corners::default(),
             box_shadow: Default::default(),
+            inner_shadow: Default::default(),
             text: TextStyleRefinement::default(),
             mouse_cursor: None,
             opacity: None,
@@ -2360,6 +2360,40 @@ impl Window {
                 content_mask: content_mask.scale(scale_factor),
                 corner_radii: corner_radii.scale(scale_factor),
                 color: shadow.color.opacity(opacity),
+                offset: Point::default(),
+                spread_radius: ScaledPixels::default(),
+                inset: false,
+            });
+        }
+    }
+
+    /// Paint one or more inset shadows into the scene for the next frame at the current z-index.
+    /// Inset shadows are cast inwards from the edges of the given bounds and are clipped to them,
+    /// following their rounded corners.
+    ///
+    /// This method should only be called as part of the paint phase of element drawing.
+    pub fn paint_inner_shadows(
+        &mut self,
+        bounds: Bounds<Pixels>,
+        corner_radii: Corners<Pixels>,
+        shadows: &[BoxShadow],
+    ) {
+        self.invalidator.debug_assert_paint();
+
+        let scale_factor = self.scale_factor();
+        let content_mask = self.content_mask();
+        let opacity = self.element_opacity();
+        for shadow in shadows {
+            self.next_frame.scene.insert_primitive(Shadow {
+                order: 0,
+                blur_radius: shadow.blur_radius.scale(scale_factor),
+                bounds: bounds.scale(scale_factor),
+                content_mask: content_mask.scale(scale_factor),
+                corner_radii: corner_radii.scale(scale_factor),
+                color: shadow.color.opacity(opacity),
+                offset: shadow.offset.scale(scale_factor),
+                spread_radius: shadow.spread_radius.scale(scale_factor),
+                inset: true,
             });
         }
     }
@@ -405,6 +405,13 @@ pub fn box_shadow_style_methods(input: TokenStream) -> TokenStream {
             self
         }
 
+        /// Sets the inset shadow of the element, drawn inside its bounds above the background.
+        /// [Docs](https://tailwindcss.com/docs/box-shadow#adding-an-inset-shadow)
+        #visibility fn inner_shadow(mut self, shadows: smallvec::SmallVec<[gpui::BoxShadow; 2]>) -> Self {
+            self.style().inner_shadow = Some(shadows);
+            self
+        }
+
         /// Sets the box shadow of the element.
         /// [Docs](https://tailwindcss.com/docs/box-shadow)
         #visibility fn shadow_sm(mut self) -> Self {
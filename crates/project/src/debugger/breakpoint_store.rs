@@ -14,10 +14,14 @@ use rpc::{
     proto::{self},
 };
 use std::{hash::Hash, ops::Range, path::Path, sync::Arc, u32};
-use text::{Point, PointUtf16};
+use text::{Bias, PointUtf16, Unclipped};
 use util::maybe;
 
-use crate::{Project, ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+use crate::{
+    Project, ProjectPath,
+    buffer_store::{BufferStore, BufferStoreEvent},
+    worktree_store::WorktreeStore,
+};
 
 use super::session::ThreadId;
 
@@ -31,6 +35,8 @@ mod breakpoints_in_file {
     pub struct BreakpointWithPosition {
         pub position: text::Anchor,
         pub bp: Breakpoint,
+        /// Whether the breakpoint stops at the column of `position`, rather than anywhere on its line.
+        pub at_column: bool,
     }
 
     /// A breakpoint with per-session data about it's state (as seen by the Debug Adapter).
@@ -137,6 +143,7 @@ struct RemoteBreakpointStore {
 struct LocalBreakpointStore {
     worktree_store: Entity<WorktreeStore>,
     buffer_store: Entity<BufferStore>,
+    _buffer_store_subscription: Arc<Subscription>,
 }
 
 #[derive(Clone)]
@@ -156,6 +163,10 @@ pub struct ActiveStackFrame {
 
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    /// Breakpoints whose file could not be opened yet (e.g. restored from the database
+    /// before their worktree got loaded). They are kept as plain rows and get anchored
+    /// once a buffer for their path is available.
+    unresolved_breakpoints: BTreeMap<Arc<Path>, Vec<SourceBreakpoint>>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
@@ -167,12 +178,20 @@ impl BreakpointStore {
         client.add_entity_request_handler(Self::handle_toggle_breakpoint);
         client.add_entity_message_handler(Self::handle_breakpoints_for_file);
     }
-    pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+    pub fn local(
+        worktree_store: Entity<WorktreeStore>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer_store_subscription =
+            Arc::new(cx.subscribe(&buffer_store, Self::on_buffer_store_event));
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            unresolved_breakpoints: BTreeMap::new(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
+                _buffer_store_subscription: buffer_store_subscription,
             }),
             downstream_client: None,
             active_stack_frame: Default::default(),
@@ -182,6 +201,7 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            unresolved_breakpoints: BTreeMap::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -191,6 +211,37 @@ impl BreakpointStore {
         }
     }
 
+    fn on_buffer_store_event(
+        &mut self,
+        _: Entity<BufferStore>,
+        event: &BufferStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if let BufferStoreEvent::BufferOpened { buffer, .. } = event {
+            self.resolve_breakpoints(buffer, cx);
+        }
+    }
+
+    /// Anchors the breakpoints that were kept by line number for the buffer's file, now that it's open.
+    fn resolve_breakpoints(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx) else {
+            return;
+        };
+        let Some(unresolved) = self.unresolved_breakpoints.remove(&abs_path) else {
+            return;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let breakpoint_set = self
+            .breakpoints
+            .entry(abs_path)
+            .or_insert_with(|| BreakpointsInFile::new(buffer.clone(), cx));
+        breakpoint_set
+            .breakpoints
+            .extend(Self::anchor_source_breakpoints(&snapshot, unresolved));
+        cx.notify();
+    }
+
     pub(crate) fn shared(&mut self, project_id: u64, downstream_client: AnyProtoClient) {
         self.downstream_client = Some((downstream_client.clone(), project_id));
     }
@@ -246,10 +297,12 @@ impl BreakpointStore {
                             (SessionId::from_proto(*session_id), state)
                         })
                         .collect();
+                    let at_column = breakpoint.at_column;
                     let breakpoint = Breakpoint::from_proto(breakpoint)?;
                     let bp = BreakpointWithPosition {
                         position,
                         bp: breakpoint,
+                        at_column,
                     };
 
                     Some(StatefulBreakpoint { bp, session_state })
@@ -289,6 +342,7 @@ impl BreakpointStore {
                 .context("Anchor not present in RPC payload")?,
         )
         .context("Anchor deserialization failed")?;
+        let at_column = breakpoint.at_column;
         let breakpoint =
             Breakpoint::from_proto(breakpoint).context("Could not deserialize breakpoint")?;
 
@@ -298,6 +352,7 @@ impl BreakpointStore {
                 BreakpointWithPosition {
                     position,
                     bp: breakpoint,
+                    at_column,
                 },
                 BreakpointEditAction::Toggle,
                 cx,
@@ -319,6 +374,7 @@ impl BreakpointStore {
                             breakpoint.bp.bp.to_proto(
                                 &path,
                                 &breakpoint.position(),
+                                breakpoint.bp.at_column,
                                 &breakpoint.session_state,
                             )
                         })
@@ -385,6 +441,43 @@ impl BreakpointStore {
         });
     }
 
+    /// What the session's debug adapter reported about each breakpoint of `path`, keyed by its row
+    /// and, for a column breakpoint, its column.
+    pub fn breakpoint_session_states(
+        &self,
+        path: &Path,
+        session_id: SessionId,
+        cx: &App,
+    ) -> HashMap<(u32, Option<u32>), BreakpointSessionState> {
+        let Some(breakpoints) = self.breakpoints.get(path) else {
+            return HashMap::default();
+        };
+        let snapshot = breakpoints.buffer.read(cx).snapshot();
+        breakpoints
+            .breakpoints
+            .iter()
+            .filter_map(|breakpoint| {
+                let state = breakpoint.session_state.get(&session_id).copied()?;
+                let position = snapshot.summary_for_anchor::<PointUtf16>(&breakpoint.position());
+                let column = breakpoint.bp.at_column.then_some(position.column);
+                Some(((position.row, column), state))
+            })
+            .collect()
+    }
+
+    fn is_breakpoint_at(
+        breakpoint: &BreakpointWithPosition,
+        row: u32,
+        column: Option<u32>,
+        snapshot: &text::BufferSnapshot,
+    ) -> bool {
+        let position = snapshot.summary_for_anchor::<PointUtf16>(&breakpoint.position);
+        match column {
+            Some(column) => breakpoint.at_column && position == PointUtf16::new(row, column),
+            None => !breakpoint.at_column && position.row == row,
+        }
+    }
+
     pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
         worktree::File::from_dyn(buffer.read(cx).file())
             .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
@@ -402,6 +495,7 @@ impl BreakpointStore {
             return;
         };
 
+        self.resolve_breakpoints(&buffer, cx);
         let breakpoint_set = self
             .breakpoints
             .entry(abs_path.clone())
@@ -541,11 +635,12 @@ impl BreakpointStore {
             self.breakpoints.remove(&abs_path);
         }
         if let BreakpointStoreMode::Remote(remote) = &self.mode {
-            if let Some(breakpoint) =
-                breakpoint
-                    .bp
-                    .to_proto(&abs_path, &breakpoint.position, &HashMap::default())
-            {
+            if let Some(breakpoint) = breakpoint.bp.to_proto(
+                &abs_path,
+                &breakpoint.position,
+                breakpoint.at_column,
+                &HashMap::default(),
+            ) {
                 cx.background_spawn(remote.upstream_client.request(proto::ToggleBreakpoint {
                     project_id: remote._upstream_project_id,
                     path: abs_path.to_str().map(ToOwned::to_owned).unwrap(),
@@ -562,9 +657,12 @@ impl BreakpointStore {
                         .breakpoints
                         .iter()
                         .filter_map(|bp| {
-                            bp.bp
-                                .bp
-                                .to_proto(&abs_path, bp.position(), &bp.session_state)
+                            bp.bp.bp.to_proto(
+                                &abs_path,
+                                bp.position(),
+                                bp.bp.at_column,
+                                &bp.session_state,
+                            )
                         })
                         .collect()
                 })
@@ -594,12 +692,62 @@ impl BreakpointStore {
             self.breakpoints.insert(new_path.clone(), breakpoints);
 
             cx.notify();
+        } else if let Some(mut breakpoints) = self.unresolved_breakpoints.remove(&old_path) {
+            for breakpoint in &mut breakpoints {
+                breakpoint.path = new_path.clone();
+            }
+            self.unresolved_breakpoints.insert(new_path, breakpoints);
+
+            cx.notify();
+        }
+    }
+
+    /// Enables or disables every breakpoint, keeping their positions and conditions.
+    pub fn set_all_breakpoint_states(&mut self, state: BreakpointState, cx: &mut Context<Self>) {
+        let breakpoints_to_invert = self
+            .breakpoints
+            .values()
+            .flat_map(|breakpoint_set| {
+                breakpoint_set
+                    .breakpoints
+                    .iter()
+                    .filter(|breakpoint| breakpoint.bp.bp.state != state)
+                    .map(|breakpoint| (breakpoint_set.buffer.clone(), breakpoint.bp.clone()))
+            })
+            .collect::<Vec<_>>();
+        for (buffer, breakpoint) in breakpoints_to_invert {
+            self.toggle_breakpoint(buffer, breakpoint, BreakpointEditAction::InvertState, cx);
+        }
+
+        let mut updated_paths = Vec::new();
+        for (path, breakpoints) in &mut self.unresolved_breakpoints {
+            let mut updated = false;
+            for breakpoint in breakpoints.iter_mut().filter(|bp| bp.state != state) {
+                breakpoint.state = state;
+                updated = true;
+            }
+            if updated {
+                updated_paths.push(path.clone());
+            }
+        }
+        for path in updated_paths {
+            cx.emit(BreakpointStoreEvent::BreakpointsUpdated(
+                path,
+                BreakpointUpdatedReason::Toggled,
+            ));
         }
+        cx.notify();
     }
 
     pub fn clear_breakpoints(&mut self, cx: &mut Context<Self>) {
-        let breakpoint_paths = self.breakpoints.keys().cloned().collect();
+        let breakpoint_paths = self
+            .breakpoints
+            .keys()
+            .chain(self.unresolved_breakpoints.keys())
+            .cloned()
+            .collect();
         self.breakpoints.clear();
+        self.unresolved_breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
     }
 
@@ -683,6 +831,17 @@ impl BreakpointStore {
         path: &Path,
         row: u32,
         cx: &App,
+    ) -> Option<(Entity<Buffer>, BreakpointWithPosition)> {
+        self.breakpoint_at_position(path, row, None, cx)
+    }
+
+    /// The line breakpoint at `row` of `path` or, given a `column`, the column breakpoint there.
+    pub fn breakpoint_at_position(
+        &self,
+        path: &Path,
+        row: u32,
+        column: Option<u32>,
+        cx: &App,
     ) -> Option<(Entity<Buffer>, BreakpointWithPosition)> {
         self.breakpoints.get(path).and_then(|breakpoints| {
             let snapshot = breakpoints.buffer.read(cx).text_snapshot();
@@ -690,7 +849,7 @@ impl BreakpointStore {
             breakpoints
                 .breakpoints
                 .iter()
-                .find(|bp| bp.position().summary::<Point>(&snapshot).row == row)
+                .find(|bp| Self::is_breakpoint_at(&bp.bp, row, column, &snapshot))
                 .map(|breakpoint| (breakpoints.buffer.clone(), breakpoint.bp.clone()))
         })
     }
@@ -714,10 +873,11 @@ impl BreakpointStore {
                 bp.breakpoints
                     .iter()
                     .map(|bp| {
-                        let position = snapshot.summary_for_anchor::<PointUtf16>(bp.position()).row;
+                        let position = snapshot.summary_for_anchor::<PointUtf16>(bp.position());
                         let bp = &bp.bp;
                         SourceBreakpoint {
-                            row: position,
+                            row: position.row,
+                            column: bp.at_column.then_some(position.column),
                             path: path.clone(),
                             state: bp.bp.state,
                             message: bp.bp.message.clone(),
@@ -727,9 +887,11 @@ impl BreakpointStore {
                     })
                     .collect()
             })
+            .or_else(|| self.unresolved_breakpoints.get(path).cloned())
             .unwrap_or_default()
     }
 
+    /// Breakpoints in files that aren't open yet have no anchors, so their paths map to no breakpoints.
     pub fn all_breakpoints(&self) -> BTreeMap<Arc<Path>, Vec<BreakpointWithPosition>> {
         self.breakpoints
             .iter()
@@ -739,6 +901,11 @@ impl BreakpointStore {
                     bp.breakpoints.iter().map(|bp| bp.bp.clone()).collect(),
                 )
             })
+            .chain(
+                self.unresolved_breakpoints
+                    .keys()
+                    .map(|path| (path.clone(), Vec::new())),
+            )
             .collect()
     }
     pub fn all_source_breakpoints(&self, cx: &App) -> BTreeMap<Arc<Path>, Vec<SourceBreakpoint>> {
@@ -751,12 +918,12 @@ impl BreakpointStore {
                     bp.breakpoints
                         .iter()
                         .map(|breakpoint| {
-                            let position = snapshot
-                                .summary_for_anchor::<PointUtf16>(&breakpoint.position())
-                                .row;
+                            let position =
+                                snapshot.summary_for_anchor::<PointUtf16>(&breakpoint.position());
                             let breakpoint = &breakpoint.bp;
                             SourceBreakpoint {
-                                row: position,
+                                row: position.row,
+                                column: breakpoint.at_column.then_some(position.column),
                                 path: path.clone(),
                                 message: breakpoint.bp.message.clone(),
                                 state: breakpoint.bp.state,
@@ -767,9 +934,39 @@ impl BreakpointStore {
                         .collect(),
                 )
             })
+            .chain(
+                self.unresolved_breakpoints
+                    .iter()
+                    .map(|(path, breakpoints)| (path.clone(), breakpoints.clone())),
+            )
             .collect()
     }
 
+    fn anchor_source_breakpoints(
+        snapshot: &BufferSnapshot,
+        breakpoints: Vec<SourceBreakpoint>,
+    ) -> impl Iterator<Item = StatefulBreakpoint> {
+        let max_point = snapshot.max_point_utf16();
+        breakpoints.into_iter().filter_map(move |bp| {
+            let point = PointUtf16::new(bp.row, bp.column.unwrap_or(0));
+            if point > max_point {
+                log::error!("skipping a deserialized breakpoint that's out of range");
+                return None;
+            }
+            Some(StatefulBreakpoint::new(BreakpointWithPosition {
+                position: snapshot
+                    .anchor_after(snapshot.clip_point_utf16(Unclipped(point), Bias::Left)),
+                bp: Breakpoint {
+                    message: bp.message,
+                    state: bp.state,
+                    condition: bp.condition,
+                    hit_condition: bp.hit_condition,
+                },
+                at_column: bp.column.is_some(),
+            }))
+        })
+    }
+
     pub fn with_serialized_breakpoints(
         &self,
         breakpoints: BTreeMap<Arc<Path>, Vec<SourceBreakpoint>>,
@@ -779,55 +976,46 @@ impl BreakpointStore {
             let mode = mode.clone();
             cx.spawn(async move |this, cx| {
                 let mut new_breakpoints = BTreeMap::default();
+                let mut unresolved_breakpoints = BTreeMap::default();
                 for (path, bps) in breakpoints {
                     if bps.is_empty() {
                         continue;
                     }
-                    let (worktree, relative_path) = mode
-                        .worktree_store
-                        .update(cx, |this, cx| {
-                            this.find_or_create_worktree(&path, false, cx)
-                        })?
-                        .await?;
-                    let buffer = mode
-                        .buffer_store
-                        .update(cx, |this, cx| {
-                            let path = ProjectPath {
-                                worktree_id: worktree.read(cx).id(),
-                                path: relative_path.into(),
-                            };
-                            this.open_buffer(path, cx)
-                        })?
-                        .await;
-                    let Ok(buffer) = buffer else {
-                        log::error!("Todo: Serialized breakpoints which do not have buffer (yet)");
-                        continue;
+                    let buffer = async {
+                        let (worktree, relative_path) = mode
+                            .worktree_store
+                            .update(cx, |this, cx| {
+                                this.find_or_create_worktree(&path, false, cx)
+                            })?
+                            .await?;
+                        mode.buffer_store
+                            .update(cx, |this, cx| {
+                                let path = ProjectPath {
+                                    worktree_id: worktree.read(cx).id(),
+                                    path: relative_path.into(),
+                                };
+                                this.open_buffer(path, cx)
+                            })?
+                            .await
+                    }
+                    .await;
+                    let buffer = match buffer {
+                        Ok(buffer) => buffer,
+                        Err(error) => {
+                            log::warn!(
+                                "Keeping breakpoints at {path:?} by line number, failed to open buffer: {error:#}"
+                            );
+                            unresolved_breakpoints.insert(path, bps);
+                            continue;
+                        }
                     };
                     let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot())?;
 
                     let mut breakpoints_for_file =
                         this.update(cx, |_, cx| BreakpointsInFile::new(buffer, cx))?;
-
-                    for bp in bps {
-                        let max_point = snapshot.max_point_utf16();
-                        let point = PointUtf16::new(bp.row, 0);
-                        if point > max_point {
-                            log::error!("skipping a deserialized breakpoint that's out of range");
-                            continue;
-                        }
-                        let position = snapshot.anchor_after(point);
-                        breakpoints_for_file
-                            .breakpoints
-                            .push(StatefulBreakpoint::new(BreakpointWithPosition {
-                                position,
-                                bp: Breakpoint {
-                                    message: bp.message,
-                                    state: bp.state,
-                                    condition: bp.condition,
-                                    hit_condition: bp.hit_condition,
-                                },
-                            }))
-                    }
+                    breakpoints_for_file
+                        .breakpoints
+                        .extend(Self::anchor_source_breakpoints(&snapshot, bps));
                     new_breakpoints.insert(path, breakpoints_for_file);
                 }
                 this.update(cx, |this, cx| {
@@ -844,6 +1032,7 @@ impl BreakpointStore {
                     }
 
                     this.breakpoints = new_breakpoints;
+                    this.unresolved_breakpoints = unresolved_breakpoints;
 
                     cx.notify();
                 })?;
@@ -954,10 +1143,12 @@ impl Breakpoint {
         &self,
         _path: &Path,
         position: &text::Anchor,
+        at_column: bool,
         session_states: &HashMap<SessionId, BreakpointSessionState>,
     ) -> Option<client::proto::Breakpoint> {
         Some(client::proto::Breakpoint {
             position: Some(serialize_text_anchor(position)),
+            at_column,
             state: match self.state {
                 BreakpointState::Enabled => proto::BreakpointState::Enabled.into(),
                 BreakpointState::Disabled => proto::BreakpointState::Disabled.into(),
@@ -1010,6 +1201,8 @@ impl Breakpoint {
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SourceBreakpoint {
     pub row: u32,
+    /// The column to stop at, for a breakpoint set at a column rather than on the whole line.
+    pub column: Option<u32>,
     pub path: Arc<Path>,
     pub message: Option<Arc<str>>,
     pub condition: Option<Arc<str>>,
@@ -1021,7 +1214,7 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
     fn from(bp: SourceBreakpoint) -> Self {
         Self {
             line: bp.row as u64 + 1,
-            column: None,
+            column: bp.column.map(|column| column as u64 + 1),
             condition: bp
                 .condition
                 .map(|condition| String::from(condition.as_ref())),
@@ -1036,10 +1036,13 @@ impl DapCommand for RestartStackFrameCommand {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub(crate) struct ModulesCommand;
+pub(crate) struct ModulesCommand {
+    pub start_module: Option<u64>,
+    pub module_count: Option<u64>,
+}
 
 impl LocalDapCommand for ModulesCommand {
-    type Response = Vec<dap::Module>;
+    type Response = dap::ModulesResponse;
     type DapRequest = dap::requests::Modules;
 
     fn is_supported(capabilities: &Capabilities) -> bool {
@@ -1048,8 +1051,8 @@ impl LocalDapCommand for ModulesCommand {
 
     fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
         dap::ModulesArguments {
-            start_module: None,
-            module_count: None,
+            start_module: self.start_module,
+            module_count: self.module_count,
         }
     }
 
@@ -1057,7 +1060,7 @@ impl LocalDapCommand for ModulesCommand {
         &self,
         message: <Self::DapRequest as dap::requests::Request>::Response,
     ) -> Result<Self::Response> {
-        Ok(message.modules)
+        Ok(message)
     }
 }
 
@@ -1070,8 +1073,11 @@ impl DapCommand for ModulesCommand {
         SessionId::from_proto(request.client_id)
     }
 
-    fn from_proto(_request: &Self::ProtoRequest) -> Self {
-        Self {}
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            start_module: request.start_module,
+            module_count: request.module_count,
+        }
     }
 
     fn to_proto(
@@ -1082,6 +1088,8 @@ impl DapCommand for ModulesCommand {
         proto::DapModulesRequest {
             project_id: upstream_project_id,
             client_id: debug_client_id.to_proto(),
+            start_module: self.start_module,
+            module_count: self.module_count,
         }
     }
 
@@ -1091,19 +1099,24 @@ impl DapCommand for ModulesCommand {
     ) -> Self::ProtoResponse {
         proto::DapModulesResponse {
             modules: message
+                .modules
                 .into_iter()
                 .map(|module| module.to_proto())
                 .collect(),
             client_id: debug_client_id.to_proto(),
+            total_modules: message.total_modules,
         }
     }
 
     fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
-        Ok(message
-            .modules
-            .into_iter()
-            .filter_map(|module| dap::Module::from_proto(module).ok())
-            .collect())
+        Ok(dap::ModulesResponse {
+            modules: message
+                .modules
+                .into_iter()
+                .filter_map(|module| dap::Module::from_proto(module).ok())
+                .collect(),
+            total_modules: message.total_modules,
+        })
     }
 }
 
@@ -1185,7 +1198,7 @@ pub(crate) struct StackTraceCommand {
 }
 
 impl LocalDapCommand for StackTraceCommand {
-    type Response = Vec<dap::StackFrame>;
+    type Response = dap::StackTraceResponse;
     type DapRequest = dap::requests::StackTrace;
 
     fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
@@ -1201,7 +1214,7 @@ impl LocalDapCommand for StackTraceCommand {
         &self,
         message: <Self::DapRequest as dap::requests::Request>::Response,
     ) -> Result<Self::Response> {
-        Ok(message.stack_frames)
+        Ok(message)
     }
 }
 
@@ -1233,11 +1246,14 @@ impl DapCommand for StackTraceCommand {
     }
 
     fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
-        Ok(message
-            .frames
-            .into_iter()
-            .map(dap::StackFrame::from_proto)
-            .collect())
+        Ok(dap::StackTraceResponse {
+            stack_frames: message
+                .frames
+                .into_iter()
+                .map(dap::StackFrame::from_proto)
+                .collect(),
+            total_frames: message.total_frames,
+        })
     }
 
     fn response_to_proto(
@@ -1245,7 +1261,8 @@ impl DapCommand for StackTraceCommand {
         message: Self::Response,
     ) -> Self::ProtoResponse {
         proto::DapStackTraceResponse {
-            frames: message.to_proto(),
+            frames: message.stack_frames.to_proto(),
+            total_frames: message.total_frames,
         }
     }
 }
@@ -1704,6 +1721,198 @@ impl LocalDapCommand for SetExceptionBreakpoints {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct DataBreakpointInfoCommand {
+    pub(super) variables_reference: u64,
+    pub(super) name: String,
+    pub(super) frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for DataBreakpointInfoCommand {
+    type Response = dap::DataBreakpointInfoResponse;
+    type DapRequest = dap::requests::DataBreakpointInfo;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DataBreakpointInfoArguments {
+            variables_reference: Some(self.variables_reference),
+            name: self.name.clone(),
+            frame_id: self.frame_id,
+            bytes: None,
+            as_address: None,
+            mode: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct SetDataBreakpointsCommand {
+    pub(super) data_ids: Vec<String>,
+}
+
+impl LocalDapCommand for SetDataBreakpointsCommand {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetDataBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetDataBreakpointsArguments {
+            breakpoints: self
+                .data_ids
+                .iter()
+                .map(|data_id| dap::DataBreakpoint {
+                    data_id: data_id.clone(),
+                    access_type: None,
+                    condition: None,
+                    hit_condition: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct GotoTargetsCommand {
+    pub(super) source: dap::Source,
+    pub(super) line: u64,
+}
+
+impl LocalDapCommand for GotoTargetsCommand {
+    type Response = Vec<dap::GotoTarget>;
+    type DapRequest = dap::requests::GotoTargets;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_goto_targets_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::GotoTargetsArguments {
+            source: self.source.clone(),
+            line: self.line,
+            column: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.targets)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(super) struct GotoCommand {
+    pub(super) thread_id: u64,
+    pub(super) target_id: u64,
+}
+
+impl LocalDapCommand for GotoCommand {
+    type Response = ();
+    type DapRequest = dap::requests::Goto;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_goto_targets_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::GotoArguments {
+            thread_id: self.thread_id,
+            target_id: self.target_id,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        _message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct BreakpointLocationsCommand {
+    pub(super) source: dap::Source,
+    pub(super) line: u64,
+}
+
+impl LocalDapCommand for BreakpointLocationsCommand {
+    type Response = Vec<dap::BreakpointLocation>;
+    type DapRequest = dap::requests::BreakpointLocations;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_breakpoint_locations_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::BreakpointLocationsArguments {
+            source: self.source.clone(),
+            line: self.line,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SourceCommand {
+    pub(super) source: dap::Source,
+    pub(super) source_reference: u64,
+}
+
+impl LocalDapCommand for SourceCommand {
+    type Response = dap::SourceResponse;
+    type DapRequest = dap::requests::Source;
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SourceArguments {
+            source: Some(self.source.clone()),
+            source_reference: self.source_reference,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub(super) struct LocationsCommand {
     pub(super) reference: u64,
@@ -379,7 +379,8 @@ impl DapStore {
                 SessionStateEvent::Shutdown => {
                     this.shutdown_session(session_id, cx).detach_and_log_err(cx);
                 }
-                SessionStateEvent::Restart | SessionStateEvent::SpawnChildSession { .. } => {}
+                SessionStateEvent::Restart { .. } | SessionStateEvent::SpawnChildSession { .. } => {
+                }
                 SessionStateEvent::Running => {
                     cx.emit(DapStoreEvent::DebugClientStarted(session_id));
                 }
@@ -4,12 +4,13 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand,
-    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
-    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, BreakpointLocationsCommand, ConfigurationDone, ContinueCommand, DapCommand,
+    DataBreakpointInfoCommand, DisconnectCommand, EvaluateCommand, GotoCommand, GotoTargetsCommand,
+    Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand,
+    NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand, SourceCommand,
+    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
+    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
@@ -42,8 +43,9 @@ use std::u64;
 use std::{
     any::Any,
     collections::hash_map::Entry,
+    ffi::OsStr,
     hash::{Hash, Hasher},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use text::{PointUtf16, ToPointUtf16};
@@ -86,7 +88,10 @@ pub enum ThreadStatus {
     Running,
     Stopped,
     Stepping,
+    /// The adapter reported that this thread exited, while the rest of the program may still be
+    /// running.
     Exited,
+    /// The program being debugged finished, ending all of its threads.
     Ended,
 }
 
@@ -100,12 +105,26 @@ impl ThreadStatus {
             ThreadStatus::Ended => "Ended",
         }
     }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ThreadStatus::Running => "The thread is running",
+            ThreadStatus::Stopped => "The thread is paused and can be inspected",
+            ThreadStatus::Stepping => "The thread is stepping to the next location",
+            ThreadStatus::Exited => {
+                "The thread exited, but the rest of the program may still be running"
+            }
+            ThreadStatus::Ended => "The program finished, ending all of its threads",
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Thread {
     dap: dap::Thread,
     stack_frame_ids: IndexSet<StackFrameId>,
+    /// Where the next page of stack frames starts, while the adapter has more frames to send.
+    next_stack_frame_page: Option<u64>,
     _has_stopped: bool,
 }
 
@@ -114,6 +133,7 @@ impl From<dap::Thread> for Thread {
         Self {
             dap,
             stack_frame_ids: Default::default(),
+            next_stack_frame_page: None,
             _has_stopped: false,
         }
     }
@@ -134,12 +154,16 @@ pub struct LocalMode {
 }
 
 fn client_source(abs_path: &Path) -> dap::Source {
+    // Sources written out by `adapter_source_path` are only known to the adapter by reference.
+    let source_reference = adapter_source_reference(abs_path);
     dap::Source {
         name: abs_path
             .file_name()
             .map(|filename| filename.to_string_lossy().to_string()),
-        path: Some(abs_path.to_string_lossy().to_string()),
-        source_reference: None,
+        path: source_reference
+            .is_none()
+            .then(|| abs_path.to_string_lossy().to_string()),
+        source_reference,
         presentation_hint: None,
         origin: None,
         sources: None,
@@ -148,6 +172,32 @@ fn client_source(abs_path: &Path) -> dap::Source {
     }
 }
 
+fn adapter_sources_dir() -> PathBuf {
+    paths::temp_dir().join("debug_sources")
+}
+
+/// Where the contents of a source that only the debug adapter has are written to, so that it can
+/// be opened and have breakpoints set in it like any other file.
+pub fn adapter_source_path(session_id: SessionId, source_reference: u64, name: &str) -> PathBuf {
+    let file_name = Path::new(name)
+        .file_name()
+        .unwrap_or_else(|| OsStr::new("source"));
+    adapter_sources_dir()
+        .join(session_id.0.to_string())
+        .join(source_reference.to_string())
+        .join(file_name)
+}
+
+/// The source reference of a path returned by [`adapter_source_path`].
+fn adapter_source_reference(abs_path: &Path) -> Option<u64> {
+    let mut components = abs_path
+        .strip_prefix(adapter_sources_dir())
+        .ok()?
+        .components();
+    components.next()?;
+    components.next()?.as_os_str().to_str()?.parse().ok()
+}
+
 impl LocalMode {
     async fn new(
         session_id: SessionId,
@@ -219,7 +269,7 @@ impl LocalMode {
         reason: BreakpointUpdatedReason,
         breakpoint_store: &Entity<BreakpointStore>,
         cx: &mut App,
-    ) -> Task<()> {
+    ) -> Task<Vec<dap::Breakpoint>> {
         let breakpoints =
             breakpoint_store
                 .read_with(cx, |store, cx| {
@@ -248,10 +298,11 @@ impl LocalMode {
         let session_id = self.client.id();
         let breakpoint_store = breakpoint_store.downgrade();
         cx.spawn(async move |cx| match cx.background_spawn(task).await {
-            Ok(breakpoints) => {
+            Ok(response) => {
                 let breakpoints =
-                    breakpoints
-                        .into_iter()
+                    response
+                        .iter()
+                        .cloned()
                         .zip(raw_breakpoints)
                         .filter_map(|(dap_bp, zed_bp)| {
                             Some((
@@ -263,12 +314,17 @@ impl LocalMode {
                             ))
                         });
                 breakpoint_store
-                    .update(cx, |this, _| {
+                    .update(cx, |this, cx| {
                         this.mark_breakpoints_verified(session_id, &abs_path, breakpoints);
+                        cx.notify();
                     })
                     .ok();
+                response
+            }
+            Err(err) => {
+                log::warn!("Set breakpoints request failed for path: {}", err);
+                Vec::new()
             }
-            Err(err) => log::warn!("Set breakpoints request failed for path: {}", err),
         })
     }
 
@@ -350,8 +406,9 @@ impl LocalMode {
                         },
                     );
                     breakpoint_store
-                        .update(cx, |this, _| {
+                        .update(cx, |this, cx| {
                             this.mark_breakpoints_verified(session_id, &path, breakpoints);
+                            cx.notify();
                         })
                         .ok();
 
@@ -510,6 +567,11 @@ impl ThreadStates {
         self.known_thread_states.clear();
     }
 
+    fn end_all_threads(&mut self) {
+        self.global_state = Some(ThreadStatus::Ended);
+        self.known_thread_states.clear();
+    }
+
     fn continue_all_threads(&mut self) {
         self.global_state = Some(ThreadStatus::Running);
         self.known_thread_states.clear();
@@ -558,8 +620,42 @@ impl ThreadStates {
 }
 const MAX_TRACKED_OUTPUT_EVENTS: usize = 5000;
 
+/// How many modules to request at once, since native programs can load thousands of them.
+const MODULES_PAGE_SIZE: u64 = 200;
+
+/// How many stack frames to request at once, so stopping in deep recursion stays responsive.
+const STACK_FRAMES_PAGE_SIZE: u64 = 20;
+
+/// Whether the adapter has stack frames after the page it sent starting at `start_frame`.
+fn has_more_stack_frames_after(response: &dap::StackTraceResponse, start_frame: u64) -> bool {
+    let received = response.stack_frames.len() as u64;
+    // Adapters may not report how many frames there are, so a short page is taken to be the
+    // last one.
+    let has_more = match response.total_frames {
+        Some(total_frames) => start_frame + received < total_frames,
+        None => received == STACK_FRAMES_PAGE_SIZE,
+    };
+    has_more && received > 0
+}
+
 type IsEnabled = bool;
 
+/// A module's id in a form that can key a map.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ModuleKey {
+    Number(i64),
+    String(String),
+}
+
+impl From<&dap::ModuleId> for ModuleKey {
+    fn from(id: &dap::ModuleId) -> Self {
+        match id {
+            dap::ModuleId::Number(number) => Self::Number(i64::from(*number)),
+            dap::ModuleId::String(string) => Self::String(string.clone()),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Default, Debug, PartialEq, PartialOrd, Eq, Ord)]
 pub struct OutputToken(pub usize);
 /// Represents a current state of a single debug adapter and provides ways to mutate it.
@@ -572,19 +668,32 @@ pub struct Session {
     child_session_ids: HashSet<SessionId>,
     parent_session: Option<Entity<Session>>,
     modules: Vec<dap::Module>,
+    /// Where each module sits in `modules`.
+    module_indices: HashMap<ModuleKey, usize>,
+    /// Where the next page of modules starts, while the adapter has more modules to send.
+    next_module_page: Option<u64>,
     loaded_sources: Vec<dap::Source>,
+    /// Contents of sources that only the adapter has, by their source reference.
+    source_contents: HashMap<u64, Arc<str>>,
     output_token: OutputToken,
     output: Box<circular_buffer::CircularBuffer<MAX_TRACKED_OUTPUT_EVENTS, dap::OutputEvent>>,
     threads: IndexMap<ThreadId, Thread>,
     thread_states: ThreadStates,
+    last_stopped_event: Option<StoppedEvent>,
+    /// The exit code the adapter reported once the program finished.
+    exit_code: Option<u64>,
     variables: HashMap<VariableReference, Vec<dap::Variable>>,
     stack_frames: IndexMap<StackFrameId, StackFrame>,
     locations: HashMap<u64, dap::LocationsResponse>,
+    /// Values of expressions hovered in the editor, which stay valid until execution resumes.
+    hover_values: HashMap<(StackFrameId, String), SharedString>,
     is_session_terminated: bool,
     requests: HashMap<TypeId, HashMap<RequestSlot, Shared<Task<Option<()>>>>>,
     pub(crate) breakpoint_store: Entity<BreakpointStore>,
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
+    /// Descriptions of the data breakpoints set in this session, by their data id.
+    data_breakpoints: IndexMap<String, SharedString>,
     background_tasks: Vec<Task<()>>,
 }
 
@@ -665,14 +774,20 @@ pub enum SessionEvent {
     Modules,
     LoadedSources,
     Stopped(Option<ThreadId>),
+    /// The adapter reported that threads resumed running.
+    Continued,
     StackTrace,
+    /// Another page of a thread's stack frames was loaded.
+    MoreStackFrames(ThreadId),
     Variables,
     Threads,
     InvalidateInlineValue,
     CapabilitiesLoaded,
+    /// The adapter updated its capabilities after initialization.
+    CapabilitiesChanged,
     RunInTerminal {
         request: RunInTerminalRequestArguments,
-        sender: mpsc::Sender<Result<u32>>,
+        sender: mpsc::Sender<Result<dap::RunInTerminalResponse>>,
     },
     ConsoleOutput,
 }
@@ -681,7 +796,10 @@ pub enum SessionEvent {
 pub enum SessionStateEvent {
     Running,
     Shutdown,
-    Restart,
+    /// The program has to be restarted by relaunching the debug adapter, for the given reason.
+    Restart {
+        reason: SharedString,
+    },
     SpawnChildSession {
         request: StartDebuggingRequestArguments,
     },
@@ -736,18 +854,25 @@ impl Session {
                 variables: Default::default(),
                 stack_frames: Default::default(),
                 thread_states: ThreadStates::default(),
+                last_stopped_event: None,
+                exit_code: None,
                 output_token: OutputToken(0),
                 output: circular_buffer::CircularBuffer::boxed(),
                 requests: HashMap::default(),
                 modules: Vec::default(),
+                module_indices: HashMap::default(),
+                next_module_page: None,
                 loaded_sources: Vec::default(),
+                source_contents: HashMap::default(),
                 threads: IndexMap::default(),
                 background_tasks: Vec::default(),
                 locations: Default::default(),
+                hover_values: HashMap::default(),
                 is_session_terminated: false,
                 ignore_breakpoints: false,
                 breakpoint_store,
                 exception_breakpoints: Default::default(),
+                data_breakpoints: Default::default(),
                 label,
                 adapter,
             };
@@ -780,6 +905,12 @@ impl Session {
                     if let Events::Initialized(_) = *event {
                         if let Some(tx) = initialized_tx.take() {
                             tx.send(()).ok();
+                        } else {
+                            let Ok(_) = this.update(cx, |session, cx| {
+                                session.handle_reinitialized(cx);
+                            }) else {
+                                break;
+                            };
                         }
                     } else {
                         let Ok(_) = this.update(cx, |session, cx| {
@@ -873,6 +1004,14 @@ impl Session {
         self.adapter.clone()
     }
 
+    /// Whether the debuggee was launched or attached to, once the adapter is running.
+    pub fn request_kind(&self) -> Option<dap::StartDebuggingRequestArgumentsRequest> {
+        match &self.mode {
+            Mode::Running(local_mode) => Some(local_mode.binary.request_args.request.clone()),
+            Mode::Building => None,
+        }
+    }
+
     pub fn label(&self) -> SharedString {
         self.label.clone()
     }
@@ -975,7 +1114,7 @@ impl Session {
 
         let seq = request.seq;
 
-        let (tx, mut rx) = mpsc::channel::<Result<u32>>(1);
+        let (tx, mut rx) = mpsc::channel::<Result<dap::RunInTerminalResponse>>(1);
         cx.emit(SessionEvent::RunInTerminal {
             request: request_args,
             sender: tx,
@@ -990,14 +1129,7 @@ impl Session {
             })
             .await;
             let (success, body) = match result {
-                Ok(pid) => (
-                    true,
-                    serde_json::to_value(dap::RunInTerminalResponse {
-                        process_id: None,
-                        shell_process_id: Some(pid as u64),
-                    })
-                    .ok(),
-                ),
+                Ok(response) => (true, serde_json::to_value(response).ok()),
                 Err(error) => (
                     false,
                     serde_json::to_value(dap::ErrorResponse {
@@ -1077,41 +1209,165 @@ impl Session {
         }
     }
 
+    /// Continues `active_thread_id` until it reaches `breakpoint`, which is only sent to the
+    /// adapter and never added to the breakpoint store. It's removed again on the next stop.
+    /// Fails if the adapter doesn't accept the breakpoint, e.g. because the request errored.
     pub fn run_to_position(
         &mut self,
         breakpoint: SourceBreakpoint,
         active_thread_id: ThreadId,
         cx: &mut Context<Self>,
-    ) {
+    ) -> Task<Result<()>> {
         match &mut self.mode {
             Mode::Running(local_mode) => {
                 if !matches!(
                     self.thread_states.thread_state(active_thread_id),
                     Some(ThreadStatus::Stopped)
                 ) {
-                    return;
+                    return Task::ready(Ok(()));
                 };
                 let path = breakpoint.path.clone();
+                let row = breakpoint.row;
                 local_mode.tmp_breakpoint = Some(breakpoint);
                 let task = local_mode.send_breakpoints_from_path(
-                    path,
+                    path.clone(),
                     BreakpointUpdatedReason::Toggled,
                     &self.breakpoint_store,
                     cx,
                 );
 
                 cx.spawn(async move |this, cx| {
-                    task.await;
+                    // Adapters may only verify the temporary breakpoint once the code it's in has
+                    // been loaded, so it's enough for them to have accepted it.
+                    let is_placed = !task.await.is_empty();
                     this.update(cx, |this, cx| {
-                        this.continue_thread(active_thread_id, cx);
-                    })
+                        if is_placed {
+                            this.continue_thread(active_thread_id, cx);
+                            return Ok(());
+                        }
+
+                        let breakpoint_store = this.breakpoint_store.clone();
+                        if let Some(local) = this.as_local_mut() {
+                            local.tmp_breakpoint = None;
+                            local
+                                .send_breakpoints_from_path(
+                                    path.clone(),
+                                    BreakpointUpdatedReason::Toggled,
+                                    &breakpoint_store,
+                                    cx,
+                                )
+                                .detach();
+                        }
+                        Err(anyhow!(
+                            "Cannot run to line {} of {}: the debug adapter couldn't set a breakpoint there",
+                            row + 1,
+                            path.display()
+                        ))
+                    })?
                 })
-                .detach();
             }
-            Mode::Building => {}
+            Mode::Building => Task::ready(Err(anyhow!("cannot run to position, still building"))),
         }
     }
 
+    /// Moves `thread_id` to the start of `row` without executing the code in between, using the
+    /// first target the adapter offers for that line.
+    pub fn set_next_statement(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        thread_id: ThreadId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !self
+            .capabilities
+            .supports_goto_targets_request
+            .unwrap_or_default()
+        {
+            return Task::ready(Err(anyhow!(
+                "The debug adapter doesn't support setting the next statement"
+            )));
+        }
+        if self.thread_states.thread_status(thread_id) != ThreadStatus::Stopped {
+            return Task::ready(Err(anyhow!(
+                "The next statement can only be set while the thread is stopped"
+            )));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!("cannot set next statement, still building")));
+        };
+
+        let targets = local.request(GotoTargetsCommand {
+            source: client_source(&path),
+            line: row as u64 + 1,
+        });
+        cx.spawn(async move |this, cx| {
+            let target = targets.await?.into_iter().next().with_context(|| {
+                format!("Cannot jump to line {} of {}", row + 1, path.display())
+            })?;
+            let goto = this
+                .update(cx, |this, _| {
+                    this.as_local().map(|local| {
+                        local.request(GotoCommand {
+                            thread_id: thread_id.0,
+                            target_id: target.id,
+                        })
+                    })
+                })?
+                .context("The debug session is no longer running")?;
+            goto.await?;
+
+            // Adapters report the jump with a stopped event, but not all of them do.
+            this.update(cx, |this, cx| {
+                this.invalidate_stack_frames(thread_id);
+                this.variables.clear();
+                cx.emit(SessionEvent::Stopped(Some(thread_id)));
+                cx.notify();
+            })
+        })
+    }
+
+    /// The columns of `row` the adapter can stop at, for placing column breakpoints. Columns are
+    /// zero-based, like the row.
+    pub fn breakpoint_locations(
+        &self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<u32>>> {
+        if !self
+            .capabilities
+            .supports_breakpoint_locations_request
+            .unwrap_or_default()
+        {
+            return Task::ready(Err(anyhow!(
+                "The debug adapter doesn't report breakpoint locations"
+            )));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Breakpoint locations can only be fetched from debug sessions running on this machine"
+            )));
+        };
+
+        let line = row as u64 + 1;
+        let locations = local.request(BreakpointLocationsCommand {
+            source: client_source(&path),
+            line,
+        });
+        cx.background_spawn(async move {
+            let mut columns = locations
+                .await?
+                .into_iter()
+                .filter(|location| location.line == line)
+                .filter_map(|location| Some(location.column?.saturating_sub(1) as u32))
+                .collect::<Vec<_>>();
+            columns.sort_unstable();
+            columns.dedup();
+            Ok(columns)
+        })
+    }
+
     pub fn has_new_output(&self, last_update: OutputToken) -> bool {
         self.output_token.0.checked_sub(last_update.0).unwrap_or(0) != 0
     }
@@ -1190,25 +1446,19 @@ impl Session {
         if let Some(thread_id) = event.thread_id {
             self.thread_states.stop_thread(ThreadId(thread_id));
 
-            self.invalidate_state(
-                &StackTraceCommand {
-                    thread_id,
-                    start_frame: None,
-                    levels: None,
-                }
-                .into(),
-            );
+            self.invalidate_stack_frames(ThreadId(thread_id));
         }
 
         self.invalidate_generic();
         self.threads.clear();
         self.variables.clear();
-        cx.emit(SessionEvent::Stopped(
-            event
-                .thread_id
-                .map(Into::into)
-                .filter(|_| !event.preserve_focus_hint.unwrap_or(false)),
-        ));
+        self.hover_values.clear();
+        let focused_thread_id = event
+            .thread_id
+            .map(Into::into)
+            .filter(|_| !event.preserve_focus_hint.unwrap_or(false));
+        self.last_stopped_event = Some(event);
+        cx.emit(SessionEvent::Stopped(focused_thread_id));
         cx.emit(SessionEvent::InvalidateInlineValue);
         cx.notify();
     }
@@ -1223,20 +1473,42 @@ impl Session {
             }
             Events::Stopped(event) => self.handle_stopped_event(event, cx),
             Events::Continued(event) => {
-                if event.all_threads_continued.unwrap_or_default() {
+                let continued_threads = if event.all_threads_continued.unwrap_or_default() {
                     self.thread_states.continue_all_threads();
-                    self.breakpoint_store.update(cx, |store, cx| {
-                        store.remove_active_position(Some(self.session_id()), cx)
-                    });
+                    self.threads.keys().copied().collect::<Vec<_>>()
                 } else {
                     self.thread_states
                         .continue_thread(ThreadId(event.thread_id));
+                    vec![ThreadId(event.thread_id)]
+                };
+
+                // The program may resume without being asked to, so the frames of the threads
+                // that are running again mustn't be shown as if they were still stopped there.
+                for thread_id in continued_threads {
+                    if let Some(thread) = self.threads.get_mut(&thread_id) {
+                        thread.stack_frame_ids.clear();
+                        thread.next_stack_frame_page = None;
+                    }
+                    self.invalidate_stack_frames(thread_id);
                 }
+                if !self.thread_states.any_stopped_thread() {
+                    self.breakpoint_store.update(cx, |store, cx| {
+                        store.remove_active_position(Some(self.session_id()), cx)
+                    });
+                }
+
                 // todo(debugger): We should be able to get away with only invalidating generic if all threads were continued
                 self.invalidate_generic();
+                self.hover_values.clear();
+                cx.emit(SessionEvent::Continued);
+                cx.emit(SessionEvent::InvalidateInlineValue);
+                cx.notify();
             }
-            Events::Exited(_event) => {
+            Events::Exited(event) => {
+                self.exit_code = Some(event.exit_code);
+                self.thread_states.end_all_threads();
                 self.clear_active_debug_line(cx);
+                cx.notify();
             }
             Events::Terminated(_) => {
                 self.shutdown(cx).detach();
@@ -1270,26 +1542,21 @@ impl Session {
                 self.push_output(event, cx);
                 cx.notify();
             }
-            Events::Breakpoint(event) => self.breakpoint_store.update(cx, |store, _| {
+            Events::Breakpoint(event) => self.breakpoint_store.update(cx, |store, cx| {
                 store.update_session_breakpoint(self.session_id(), event.reason, event.breakpoint);
+                cx.notify();
             }),
             Events::Module(event) => {
                 match event.reason {
-                    dap::ModuleEventReason::New => {
-                        self.modules.push(event.module);
-                    }
+                    // The module may have been loaded in a page of modules already.
+                    dap::ModuleEventReason::New => self.insert_module(event.module),
                     dap::ModuleEventReason::Changed => {
-                        if let Some(module) = self
-                            .modules
-                            .iter_mut()
-                            .find(|other| event.module.id == other.id)
-                        {
-                            *module = event.module;
+                        let key = ModuleKey::from(&event.module.id);
+                        if let Some(&ix) = self.module_indices.get(&key) {
+                            self.modules[ix] = event.module;
                         }
                     }
-                    dap::ModuleEventReason::Removed => {
-                        self.modules.retain(|other| event.module.id != other.id);
-                    }
+                    dap::ModuleEventReason::Removed => self.remove_module(&event.module.id),
                 }
 
                 // todo(debugger): We should only send the invalidate command to downstream clients.
@@ -1300,6 +1567,7 @@ impl Session {
             }
             Events::Capabilities(event) => {
                 self.capabilities = self.capabilities.merge(event.capabilities);
+                cx.emit(SessionEvent::CapabilitiesChanged);
                 cx.notify();
             }
             Events::Memory(_) => {}
@@ -1445,12 +1713,39 @@ impl Session {
             });
     }
 
+    /// Drops every page of the thread's stack trace, so they're requested again.
+    fn invalidate_stack_frames(&mut self, thread_id: ThreadId) {
+        if let Some(request_map) = self.requests.get_mut(&TypeId::of::<StackTraceCommand>()) {
+            request_map.retain(|slot, _| {
+                (&*slot.0 as &dyn Any)
+                    .downcast_ref::<StackTraceCommand>()
+                    .is_none_or(|command| command.thread_id != thread_id.0)
+            });
+        }
+    }
+
     fn push_output(&mut self, event: OutputEvent, cx: &mut Context<Self>) {
         self.output.push_back(event);
         self.output_token.0 += 1;
         cx.emit(SessionEvent::ConsoleOutput);
     }
 
+    /// Writes a message from Zed, rather than from the adapter or program, to the console.
+    pub fn push_console_message(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
+        let event = dap::OutputEvent {
+            category: Some(OutputEventCategory::Console),
+            output: message.into(),
+            group: None,
+            variables_reference: None,
+            source: None,
+            line: None,
+            column: None,
+            data: None,
+            location_reference: None,
+        };
+        self.push_output(event, cx);
+    }
+
     pub fn any_stopped_thread(&self) -> bool {
         self.thread_states.any_stopped_thread()
     }
@@ -1459,6 +1754,22 @@ impl Session {
         self.thread_states.thread_status(thread_id)
     }
 
+    /// The exit code of the program being debugged, once it has finished.
+    pub fn exit_code(&self) -> Option<u64> {
+        self.exit_code
+    }
+
+    /// The event the adapter sent the last time the debuggee stopped, describing why it did.
+    pub fn last_stopped_event(&self) -> Option<&StoppedEvent> {
+        self.last_stopped_event.as_ref()
+    }
+
+    pub fn thread_name(&self, thread_id: ThreadId) -> Option<&str> {
+        self.threads
+            .get(&thread_id)
+            .map(|thread| thread.dap.name.as_str())
+    }
+
     pub fn threads(&mut self, cx: &mut Context<Self>) -> Vec<(dap::Thread, ThreadStatus)> {
         self.fetch(
             dap_command::ThreadsCommand,
@@ -1491,12 +1802,74 @@ impl Session {
     }
 
     pub fn modules(&mut self, cx: &mut Context<Self>) -> &[Module] {
+        self.fetch_modules_page(0, cx);
+        &self.modules
+    }
+
+    pub fn has_more_modules(&self) -> bool {
+        self.next_module_page.is_some()
+    }
+
+    /// Requests the page of modules after the ones loaded so far, if the adapter has more.
+    pub fn load_more_modules(&mut self, cx: &mut Context<Self>) {
+        if let Some(start_module) = self.next_module_page {
+            self.fetch_modules_page(start_module, cx);
+        }
+    }
+
+    /// Adds a module, or replaces the one with the same id.
+    fn insert_module(&mut self, module: dap::Module) {
+        match self.module_indices.entry(ModuleKey::from(&module.id)) {
+            Entry::Occupied(entry) => self.modules[*entry.get()] = module,
+            Entry::Vacant(entry) => {
+                entry.insert(self.modules.len());
+                self.modules.push(module);
+            }
+        }
+    }
+
+    fn remove_module(&mut self, id: &dap::ModuleId) {
+        let Some(removed_ix) = self.module_indices.remove(&ModuleKey::from(id)) else {
+            return;
+        };
+        self.modules.remove(removed_ix);
+        for ix in self.module_indices.values_mut() {
+            if *ix > removed_ix {
+                *ix -= 1;
+            }
+        }
+    }
+
+    fn fetch_modules_page(&mut self, start_module: u64, cx: &mut Context<Self>) {
         self.fetch(
-            dap_command::ModulesCommand,
-            |this, result, cx| {
+            dap_command::ModulesCommand {
+                start_module: Some(start_module),
+                module_count: Some(MODULES_PAGE_SIZE),
+            },
+            move |this, result, cx| {
                 let result = result.log_err()?;
 
-                this.modules = result.iter().cloned().collect();
+                // The first page comes from a full refetch, which replaces what was loaded before
+                // rather than piling on top of it.
+                if start_module == 0 {
+                    this.modules.clear();
+                    this.module_indices.clear();
+                }
+                // Module events may have reported some of these modules already.
+                for module in &result.modules {
+                    this.insert_module(module.clone());
+                }
+
+                let received = result.modules.len() as u64;
+                let next_start = start_module + received;
+                // Adapters that don't page return every module and may not report a total, so a
+                // short page is taken to be the last one.
+                let has_more = match result.total_modules {
+                    Some(total_modules) => next_start < total_modules,
+                    None => received == MODULES_PAGE_SIZE,
+                };
+                this.next_module_page = (has_more && received > 0).then_some(next_start);
+
                 cx.emit(SessionEvent::Modules);
                 cx.notify();
 
@@ -1504,8 +1877,6 @@ impl Session {
             },
             cx,
         );
-
-        &self.modules
     }
 
     pub fn ignore_breakpoints(&self) -> bool {
@@ -1571,6 +1942,66 @@ impl Session {
         }
     }
 
+    pub fn data_breakpoints(&self) -> impl Iterator<Item = (&String, &SharedString)> {
+        self.data_breakpoints.iter()
+    }
+
+    /// Asks the adapter whether the variable's memory can be watched and, if so, breaks whenever
+    /// its value changes.
+    pub fn add_data_breakpoint(
+        &mut self,
+        variables_reference: VariableReference,
+        name: String,
+        frame_id: Option<StackFrameId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Data breakpoints are only supported in local sessions"
+            )));
+        };
+
+        let info = local.request(DataBreakpointInfoCommand {
+            variables_reference,
+            name: name.clone(),
+            frame_id,
+        });
+        cx.spawn(async move |this, cx| {
+            let info = info.await?;
+            let data_id = info
+                .data_id
+                .with_context(|| format!("Cannot watch {name}: {}", info.description))?;
+            this.update(cx, |this, cx| {
+                this.data_breakpoints
+                    .insert(data_id, info.description.into());
+                cx.notify();
+                this.send_data_breakpoints(cx)
+            })?
+            .await
+        })
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if self.data_breakpoints.shift_remove(data_id).is_some() {
+            cx.notify();
+            self.send_data_breakpoints(cx).detach_and_log_err(cx);
+        }
+    }
+
+    /// The adapter replaces all data breakpoints with each request, so every one is sent.
+    fn send_data_breakpoints(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Data breakpoints are only supported in local sessions"
+            )));
+        };
+
+        let request = local.request(SetDataBreakpointsCommand {
+            data_ids: self.data_breakpoints.keys().cloned().collect(),
+        });
+        cx.background_spawn(async move { request.await.map(|_| ()) })
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -1596,11 +2027,46 @@ impl Session {
         res: Result<()>,
         cx: &mut Context<Self>,
     ) -> Option<()> {
-        if res.log_err().is_none() {
-            cx.emit(SessionStateEvent::Restart);
-            return None;
+        if let Err(error) = &res {
+            self.push_console_message(
+                "The debug adapter couldn't restart the program in place, relaunching it instead",
+                cx,
+            );
+            cx.emit(SessionStateEvent::Restart {
+                reason: format!("restarting the program in place failed: {error}").into(),
+            });
         }
-        Some(())
+        res.log_err()
+    }
+
+    /// Adapters that restart the program in place may ask to be configured again, having
+    /// forgotten the breakpoints set before the restart.
+    fn handle_reinitialized(&mut self, cx: &mut Context<Self>) {
+        self.send_exception_breakpoints(cx);
+        let data_breakpoints = if self.data_breakpoints.is_empty() {
+            Task::ready(Ok(()))
+        } else {
+            self.send_data_breakpoints(cx)
+        };
+        let Some(local) = self.as_local() else {
+            return;
+        };
+
+        let source_breakpoints =
+            local.send_source_breakpoints(self.ignore_breakpoints, &self.breakpoint_store, cx);
+        let configuration_done =
+            ConfigurationDone::is_supported(&self.capabilities).then(|| local.clone());
+        cx.spawn(async move |_, _| {
+            for (path, error) in source_breakpoints.await {
+                log::error!("failed to set breakpoints for {path:?} after restarting: {error}");
+            }
+            data_breakpoints.await.log_err();
+            if let Some(local) = configuration_done {
+                local.request(ConfigurationDone {}).await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
     }
 
     fn empty_response(&mut self, res: Result<()>, _cx: &mut Context<Self>) -> Option<()> {
@@ -1663,8 +2129,12 @@ impl Session {
         .detach();
     }
 
+    /// Restarts the program in place when the adapter supports it, which is much faster than
+    /// relaunching the adapter. Otherwise, or if restarting in place fails, the debug panel
+    /// relaunches the adapter with the same configuration.
     pub fn restart(&mut self, args: Option<Value>, cx: &mut Context<Self>) {
         if self.capabilities.supports_restart_request.unwrap_or(false) && !self.is_terminated() {
+            self.push_console_message("Restarting the program in place", cx);
             self.request(
                 RestartCommand {
                     raw: args.unwrap_or(Value::Null),
@@ -1674,7 +2144,14 @@ impl Session {
             )
             .detach();
         } else {
-            cx.emit(SessionStateEvent::Restart);
+            let reason = if self.is_terminated() {
+                "the session had already ended"
+            } else {
+                "the debug adapter can't restart the program in place"
+            };
+            cx.emit(SessionStateEvent::Restart {
+                reason: reason.into(),
+            });
         }
     }
 
@@ -1747,15 +2224,34 @@ impl Session {
     }
 
     pub fn continue_thread(&mut self, thread_id: ThreadId, cx: &mut Context<Self>) {
-        self.thread_states.continue_thread(thread_id);
+        let supports_single_thread_execution_requests = self
+            .capabilities
+            .supports_single_thread_execution_requests
+            .unwrap_or_default();
+
+        if supports_single_thread_execution_requests {
+            self.thread_states.continue_thread(thread_id);
+        } else {
+            self.thread_states.continue_all_threads();
+        }
+
         self.request(
             ContinueCommand {
                 args: ContinueArguments {
                     thread_id: thread_id.0,
-                    single_thread: Some(true),
+                    single_thread: supports_single_thread_execution_requests.then_some(true),
                 },
             },
-            Self::on_step_response::<ContinueCommand>(thread_id),
+            move |this, response, cx| {
+                let response =
+                    Self::on_step_response::<ContinueCommand>(thread_id)(this, response, cx)?;
+                // Per the spec, an omitted `allThreadsContinued` means every thread was resumed.
+                if response.all_threads_continued.unwrap_or(true) {
+                    this.thread_states.continue_all_threads();
+                    cx.notify();
+                }
+                Some(response)
+            },
             cx,
         )
         .detach();
@@ -1898,40 +2394,7 @@ impl Session {
         // This isn't the biggest concern right now because it hasn't caused any issues outside of tests
         // But it very well could cause a minor bug in the future that is hard to track down
         {
-            self.fetch(
-                super::dap_command::StackTraceCommand {
-                    thread_id: thread_id.0,
-                    start_frame: None,
-                    levels: None,
-                },
-                move |this, stack_frames, cx| {
-                    let stack_frames = stack_frames.log_err()?;
-
-                    let entry = this.threads.entry(thread_id).and_modify(|thread| {
-                        thread.stack_frame_ids =
-                            stack_frames.iter().map(|frame| frame.id).collect();
-                    });
-                    debug_assert!(
-                        matches!(entry, indexmap::map::Entry::Occupied(_)),
-                        "Sent request for thread_id that doesn't exist"
-                    );
-
-                    this.stack_frames.extend(
-                        stack_frames
-                            .iter()
-                            .cloned()
-                            .map(|frame| (frame.id, StackFrame::from(frame))),
-                    );
-
-                    this.invalidate_command_type::<ScopesCommand>();
-                    this.invalidate_command_type::<VariablesCommand>();
-
-                    cx.emit(SessionEvent::StackTrace);
-                    cx.notify();
-                    Some(stack_frames)
-                },
-                cx,
-            );
+            self.fetch_stack_frames_page(thread_id, 0, cx);
         }
 
         self.threads
@@ -1947,6 +2410,130 @@ impl Session {
             .unwrap_or_default()
     }
 
+    pub fn has_more_stack_frames(&self, thread_id: ThreadId) -> bool {
+        self.threads
+            .get(&thread_id)
+            .is_some_and(|thread| thread.next_stack_frame_page.is_some())
+    }
+
+    /// Requests the page of the thread's stack frames after the ones loaded so far, if the
+    /// adapter has more.
+    pub fn load_more_stack_frames(&mut self, thread_id: ThreadId, cx: &mut Context<Self>) {
+        if let Some(start_frame) = self
+            .threads
+            .get(&thread_id)
+            .and_then(|thread| thread.next_stack_frame_page)
+        {
+            self.fetch_stack_frames_page(thread_id, start_frame, cx);
+        }
+    }
+
+    /// Requests all of the thread's stack frames, page after page, leaving the ones loaded for
+    /// display as they are.
+    pub fn full_stack_trace(
+        &self,
+        thread_id: ThreadId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<dap::StackFrame>>> {
+        let levels = self
+            .capabilities
+            .supports_delayed_stack_trace_loading
+            .unwrap_or_default()
+            .then_some(STACK_FRAMES_PAGE_SIZE);
+
+        cx.spawn(async move |this, cx| {
+            let mut stack_frames = Vec::new();
+            loop {
+                let start_frame = stack_frames.len() as u64;
+                let response = this
+                    .update(cx, |this, cx| {
+                        this.request(
+                            StackTraceCommand {
+                                thread_id: thread_id.0,
+                                start_frame: levels.map(|_| start_frame),
+                                levels,
+                            },
+                            |_, response, _| response.log_err(),
+                            cx,
+                        )
+                    })?
+                    .await
+                    .context("requesting the stack trace")?;
+                let has_more = has_more_stack_frames_after(&response, start_frame);
+                stack_frames.extend(response.stack_frames);
+                if levels.is_none() || !has_more {
+                    return Ok(stack_frames);
+                }
+            }
+        })
+    }
+
+    fn fetch_stack_frames_page(
+        &mut self,
+        thread_id: ThreadId,
+        start_frame: u64,
+        cx: &mut Context<Self>,
+    ) {
+        let levels = self
+            .capabilities
+            .supports_delayed_stack_trace_loading
+            .unwrap_or_default()
+            .then_some(STACK_FRAMES_PAGE_SIZE);
+
+        self.fetch(
+            StackTraceCommand {
+                thread_id: thread_id.0,
+                start_frame: levels.map(|_| start_frame),
+                levels,
+            },
+            move |this, response, cx| {
+                let response = response.log_err()?;
+
+                let thread = this.threads.get_mut(&thread_id);
+                debug_assert!(
+                    thread.is_some(),
+                    "Sent request for thread_id that doesn't exist"
+                );
+                let thread = thread?;
+
+                let frame_ids = response.stack_frames.iter().map(|frame| frame.id);
+                if start_frame == 0 {
+                    thread.stack_frame_ids = frame_ids.collect();
+                } else if thread.stack_frame_ids.len() as u64 == start_frame {
+                    thread.stack_frame_ids.extend(frame_ids);
+                } else {
+                    // The thread ran again since this page was requested.
+                    return None;
+                }
+
+                let received = response.stack_frames.len() as u64;
+                let next_start = start_frame + received;
+                thread.next_stack_frame_page = (levels.is_some()
+                    && has_more_stack_frames_after(&response, start_frame))
+                .then_some(next_start);
+
+                this.stack_frames.extend(
+                    response
+                        .stack_frames
+                        .iter()
+                        .cloned()
+                        .map(|frame| (frame.id, StackFrame::from(frame))),
+                );
+
+                if start_frame == 0 {
+                    this.invalidate_command_type::<ScopesCommand>();
+                    this.invalidate_command_type::<VariablesCommand>();
+                    cx.emit(SessionEvent::StackTrace);
+                } else {
+                    cx.emit(SessionEvent::MoreStackFrames(thread_id));
+                }
+                cx.notify();
+                Some(response)
+            },
+            cx,
+        );
+    }
+
     pub fn scopes(&mut self, stack_frame_id: u64, cx: &mut Context<Self>) -> &[dap::Scope] {
         if self.requests.contains_key(&TypeId::of::<ThreadsCommand>())
             && self
@@ -2035,6 +2622,14 @@ impl Session {
             .unwrap_or_default()
     }
 
+    /// The children of `variables_reference` that have already been fetched, without requesting them.
+    pub fn fetched_variables(&self, variables_reference: VariableReference) -> &[dap::Variable] {
+        self.variables
+            .get(&variables_reference)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
     pub fn set_variable_value(
         &mut self,
         variables_reference: u64,
@@ -2127,6 +2722,99 @@ impl Session {
         })
     }
 
+    /// Evaluates `expression` in the clipboard context, in which adapters return a value in full
+    /// rather than the abbreviated form they show in the variables list. Adapters without that
+    /// context are asked in the repl one, where values are printed in full as well. Unlike
+    /// [`Self::evaluate`], nothing is written to the console.
+    pub fn evaluate_for_clipboard(
+        &self,
+        expression: String,
+        frame_id: Option<u64>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<String>> {
+        let context = if self
+            .capabilities
+            .supports_clipboard_context
+            .unwrap_or_default()
+        {
+            EvaluateArgumentsContext::Clipboard
+        } else {
+            EvaluateArgumentsContext::Repl
+        };
+        let request = self.mode.request_dap(EvaluateCommand {
+            expression,
+            context: Some(context),
+            frame_id,
+            source: None,
+        });
+        cx.background_spawn(async move { Ok(request.await?.result) })
+    }
+
+    /// Evaluates `expression` in the hover context, for showing its value when it's hovered in
+    /// the editor. Values are cached until execution resumes.
+    pub fn evaluate_for_hover(
+        &mut self,
+        expression: String,
+        frame_id: StackFrameId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<SharedString>> {
+        let key = (frame_id, expression);
+        if let Some(value) = self.hover_values.get(&key) {
+            return Task::ready(Ok(value.clone()));
+        }
+
+        let request = self.mode.request_dap(EvaluateCommand {
+            expression: key.1.clone(),
+            context: Some(EvaluateArgumentsContext::Hover),
+            frame_id: Some(frame_id),
+            source: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let value = SharedString::from(request.await?.result);
+            this.update(cx, |this, _| {
+                this.hover_values.insert(key, value.clone());
+            })?;
+            Ok(value)
+        })
+    }
+
+    /// Fetches the contents of a source that isn't on disk, such as generated or bundled code,
+    /// from the adapter. Contents are cached for the rest of the session.
+    pub fn source_content(
+        &mut self,
+        source: dap::Source,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Arc<str>>> {
+        let Some(source_reference) = source.source_reference.filter(|reference| *reference > 0)
+        else {
+            return Task::ready(Err(anyhow!(
+                "{} isn't provided by the debug adapter",
+                source.name.as_deref().unwrap_or("This source")
+            )));
+        };
+        if let Some(content) = self.source_contents.get(&source_reference) {
+            return Task::ready(Ok(content.clone()));
+        }
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Sources can only be fetched from debug sessions running on this machine"
+            )));
+        };
+
+        let request = local.request(SourceCommand {
+            source,
+            source_reference,
+        });
+        cx.spawn(async move |this, cx| {
+            let content = Arc::<str>::from(request.await?.content);
+            this.update(cx, |this, _| {
+                this.source_contents
+                    .insert(source_reference, content.clone());
+            })?;
+            Ok(content)
+        })
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
@@ -71,7 +71,7 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    Buffer, BufferEvent, Capability, CodeLabel, CursorShape, Language, LanguageName,
+    Buffer, BufferEvent, Capability, CharKind, CodeLabel, CursorShape, Language, LanguageName,
     LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
     Unclipped, language_settings::InlayHintKind, proto::split_operations,
 };
@@ -887,8 +887,8 @@ impl Project {
             cx.subscribe(&buffer_store, Self::on_buffer_store_event)
                 .detach();
 
-            let breakpoint_store =
-                cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let breakpoint_store = cx
+                .new(|cx| BreakpointStore::local(worktree_store.clone(), buffer_store.clone(), cx));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -3560,6 +3560,71 @@ impl Project {
         })
     }
 
+    /// Shows the value of `range` while a debug session is stopped in `buffer`. An empty range
+    /// stands for the identifier around it, while a selected expression is evaluated as is.
+    pub fn debugger_hover(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Hover>> {
+        let Some((session, active_stack_frame)) = self
+            .active_debug_session(cx)
+            .filter(|(session, _)| session.read(cx).any_stopped_thread())
+        else {
+            return Task::ready(None);
+        };
+        // Other buffers don't have their variables in the stopped frame's scope.
+        if BreakpointStore::abs_path_from_buffer(buffer, cx).as_ref()
+            != Some(&active_stack_frame.path)
+        {
+            return Task::ready(None);
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let mut range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+        if range.is_empty() {
+            let (word_range, kind) = snapshot.surrounding_word(range.start);
+            let is_identifier = kind == Some(CharKind::Word)
+                && !word_range.is_empty()
+                && match snapshot.syntax_ancestor(word_range.start..word_range.start) {
+                    Some(node) => node.kind().contains("identifier"),
+                    None => snapshot
+                        .chars_at(word_range.start)
+                        .next()
+                        .is_some_and(|c| c.is_alphabetic() || c == '_'),
+                };
+            if !is_identifier {
+                return Task::ready(None);
+            }
+            range = word_range;
+        }
+        let expression = snapshot.text_for_range(range.clone()).collect::<String>();
+        let language = snapshot.language_at(range.start).cloned();
+        let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+
+        let value = session.update(cx, |session, cx| {
+            session.evaluate_for_hover(expression.clone(), active_stack_frame.stack_frame_id, cx)
+        });
+        cx.background_spawn(async move {
+            // Expressions the adapter can't evaluate just don't get a hover.
+            let value = value.await.ok()?;
+            Some(Hover {
+                contents: vec![HoverBlock {
+                    text: format!("{expression} = {value}"),
+                    kind: HoverBlockKind::Code {
+                        language: language
+                            .as_ref()
+                            .map(|language| language.name().to_string())
+                            .unwrap_or_default(),
+                    },
+                }],
+                range: Some(range),
+                language,
+            })
+        })
+    }
+
     pub fn inline_values(
         &mut self,
         session: Entity<Session>,
@@ -8769,6 +8769,90 @@ fn git_status(repo: &git2::Repository) -> collections::HashMap<String, git2::Sta
         .collect()
 }
 
+#[gpui::test]
+async fn test_breakpoints_in_files_that_are_not_open(cx: &mut gpui::TestAppContext) {
+    use crate::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+    use collections::BTreeMap;
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    // A directory stands where the file will be, so that it can't be opened yet.
+    fs.insert_tree(path!("/project"), json!({ "main.rs": {} }))
+        .await;
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+
+    let main_path: Arc<Path> = Path::new(path!("/project/main.rs")).into();
+    let renamed_path: Arc<Path> = Path::new(path!("/project/renamed.rs")).into();
+    let breakpoints_at = |path: &Arc<Path>| {
+        BTreeMap::from_iter([(
+            path.clone(),
+            vec![SourceBreakpoint {
+                row: 1,
+                column: None,
+                path: path.clone(),
+                message: None,
+                condition: None,
+                hit_condition: None,
+                state: BreakpointState::Enabled,
+            }],
+        )])
+    };
+
+    breakpoint_store
+        .update(cx, |store, cx| {
+            store.with_serialized_breakpoints(breakpoints_at(&main_path), cx)
+        })
+        .await
+        .unwrap();
+    breakpoint_store.read_with(cx, |store, cx| {
+        assert_eq!(store.all_source_breakpoints(cx), breakpoints_at(&main_path));
+        assert_eq!(
+            store.all_breakpoints(),
+            BTreeMap::from_iter([(main_path.clone(), Vec::new())])
+        );
+    });
+
+    breakpoint_store.update(cx, |store, cx| {
+        store.on_file_rename(main_path.clone(), renamed_path.clone(), cx)
+    });
+    breakpoint_store.read_with(cx, |store, cx| {
+        assert_eq!(
+            store.all_source_breakpoints(cx),
+            breakpoints_at(&renamed_path)
+        );
+    });
+
+    fs.insert_file(&renamed_path, "fn main() {\n    let x = 1;\n}\n".into())
+        .await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/project/renamed.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    breakpoint_store.read_with(cx, |store, cx| {
+        let rows = store
+            .breakpoints(&buffer, None, &snapshot, cx)
+            .map(|(breakpoint, _)| {
+                snapshot
+                    .summary_for_anchor::<Point>(&breakpoint.position)
+                    .row
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [1]);
+        assert_eq!(store.all_breakpoints()[&renamed_path].len(), 1);
+        assert_eq!(
+            store.all_source_breakpoints(cx),
+            breakpoints_at(&renamed_path)
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_find_project_path_abs(
     background_executor: BackgroundExecutor,
@@ -53,9 +53,9 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
-    Color, ContextMenu, DecoratedIcon, Icon, IconDecoration, IconDecorationKind, IndentGuideColors,
-    IndentGuideLayout, KeyBinding, Label, LabelSize, ListItem, ListItemSpacing, Scrollbar,
-    ScrollbarState, Tooltip, prelude::*, v_flex,
+    Color, ContextMenu, DecoratedIcon, ElevationIndex, Icon, IconDecoration, IconDecorationKind,
+    IndentGuideColors, IndentGuideLayout, KeyBinding, Label, LabelSize, ListItem, ListItemSpacing,
+    Scrollbar, ScrollbarState, Tooltip, prelude::*, v_flex,
 };
 use util::{ResultExt, TakeUntilExt, TryFutureExt, maybe, paths::compare_paths};
 use workspace::{
@@ -4938,7 +4938,8 @@ impl Render for DraggedProjectEntryView {
                     .py_1()
                     .px_2()
                     .rounded_lg()
-                    .bg(cx.theme().colors().background)
+                    .bg(ElevationIndex::DraggedElement.bg(cx))
+                    .shadow(ElevationIndex::DraggedElement.shadow(cx))
                     .map(|this| {
                         if self.selections.len() > 1 && self.selections.contains(&self.selection) {
                             this.child(Label::new(format!("{} entries", self.selections.len())))
@@ -104,7 +104,7 @@ impl HeadlessProject {
         });
 
         let breakpoint_store =
-            cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            cx.new(|cx| BreakpointStore::local(worktree_store.clone(), buffer_store.clone(), cx));
 
         let dap_store = cx.new(|cx| {
             let mut dap_store = DapStore::new_local(
@@ -1,5 +1,5 @@
 use gpui::AnyElement;
-use ui::{Indicator, ListItem, prelude::*};
+use ui::{Indicator, ListItem, Tooltip, prelude::*};
 
 use crate::KernelSpecification;
 
@@ -7,6 +7,7 @@ use crate::KernelSpecification;
 pub struct KernelListItem {
     kernel_specification: KernelSpecification,
     status_color: Color,
+    tooltip: Option<SharedString>,
     buttons: Vec<AnyElement>,
     children: Vec<AnyElement>,
 }
@@ -16,6 +17,7 @@ impl KernelListItem {
         Self {
             kernel_specification,
             status_color: Color::Disabled,
+            tooltip: None,
             buttons: Vec::new(),
             children: Vec::new(),
         }
@@ -26,6 +28,11 @@ impl KernelListItem {
         self
     }
 
+    pub fn tooltip(mut self, tooltip: impl Into<SharedString>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
     pub fn button(mut self, button: impl IntoElement) -> Self {
         self.buttons.push(button.into_any_element());
         self
@@ -54,6 +61,9 @@ impl RenderOnce for KernelListItem {
                     .justify_center()
                     .child(Indicator::dot().color(self.status_color)),
             )
+            .when_some(self.tooltip, |item, tooltip| {
+                item.tooltip(Tooltip::text(tooltip))
+            })
             .children(self.children)
             .end_slot(h_flex().gap_2().children(self.buttons))
     }
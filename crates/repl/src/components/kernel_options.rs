@@ -1,5 +1,6 @@
 use crate::KERNEL_DOCS_URL;
 use crate::kernels::KernelSpecification;
+use crate::repl_sessions_ui::RefreshKernelspecs;
 use crate::repl_store::ReplStore;
 
 use gpui::AnyView;
@@ -15,7 +16,7 @@ use ui::ListItemSpacing;
 
 use gpui::SharedString;
 use gpui::Task;
-use ui::{ListItem, PopoverMenu, PopoverMenuHandle, PopoverTrigger, prelude::*};
+use ui::{ListItem, PopoverMenu, PopoverMenuHandle, PopoverTrigger, Tooltip, prelude::*};
 
 type OnSelect = Box<dyn Fn(KernelSpecification, &mut Window, &mut App)>;
 
@@ -38,6 +39,7 @@ pub struct KernelPickerDelegate {
     filtered_kernels: Vec<KernelSpecification>,
     selected_kernelspec: Option<KernelSpecification>,
     on_select: OnSelect,
+    worktree_id: WorktreeId,
 }
 
 // Helper function to truncate long paths
@@ -149,16 +151,29 @@ impl PickerDelegate for KernelPickerDelegate {
         let is_selected = self.selected_kernelspec.as_ref() == Some(kernelspec);
         let icon = kernelspec.icon(cx);
 
+        let interpreter_path = kernelspec.interpreter_path();
+        let version = interpreter_path.as_ref().and_then(|path| {
+            ReplStore::global(cx)
+                .read(cx)
+                .interpreter_version(path)
+                .cloned()
+        });
+        let interpreter_path = interpreter_path
+            .map(|path| truncate_path(&SharedString::from(path.to_string_lossy().to_string()), 42));
+
         let (name, kernel_type, path_or_url) = match kernelspec {
-            KernelSpecification::Jupyter(_) => (kernelspec.name(), "Jupyter", None),
-            KernelSpecification::PythonEnv(_) => (
+            KernelSpecification::Jupyter(_) => (kernelspec.name(), "Jupyter", interpreter_path),
+            KernelSpecification::PythonEnv(_) => {
+                (kernelspec.name(), "Python Env", interpreter_path)
+            }
+            KernelSpecification::Remote(_) => (
                 kernelspec.name(),
-                "Python Env",
+                "Remote",
                 Some(truncate_path(&kernelspec.path(), 42)),
             ),
-            KernelSpecification::Remote(_) => (
+            KernelSpecification::ExistingConnection(_) => (
                 kernelspec.name(),
-                "Remote",
+                "Existing",
                 Some(truncate_path(&kernelspec.path(), 42)),
             ),
         };
@@ -199,14 +214,32 @@ impl PickerDelegate for KernelPickerDelegate {
                                     h_flex()
                                         .gap_1()
                                         .child(
-                                            Label::new(kernelspec.language())
-                                                .size(LabelSize::Small)
-                                                .color(Color::Muted),
+                                            Label::new(
+                                                version.unwrap_or_else(|| kernelspec.language()),
+                                            )
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
                                         )
                                         .child(
                                             Label::new(kernel_type)
                                                 .size(LabelSize::Small)
                                                 .color(Color::Muted),
+                                        )
+                                        .child(
+                                            div()
+                                                .px_1()
+                                                .rounded_sm()
+                                                .border_1()
+                                                .border_color(cx.theme().colors().border_variant)
+                                                .child(
+                                                    Label::new(if kernelspec.is_remote() {
+                                                        "Remote"
+                                                    } else {
+                                                        "Native"
+                                                    })
+                                                    .size(LabelSize::XSmall)
+                                                    .color(Color::Muted),
+                                                ),
                                         ),
                                 ),
                         ),
@@ -233,6 +266,17 @@ impl PickerDelegate for KernelPickerDelegate {
                 .border_color(cx.theme().colors().border_variant)
                 .p_1()
                 .gap_4()
+                .child(
+                    Button::new("refresh-kernels", "Refresh")
+                        .icon(IconName::RotateCw)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .icon_position(IconPosition::Start)
+                        .tooltip(Tooltip::text("Rediscover kernels and environments"))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(Box::new(RefreshKernelspecs), cx)
+                        }),
+                )
                 .child(
                     Button::new("kernel-docs", "Kernel Docs")
                         .icon(IconName::ExternalLink)
@@ -252,23 +296,42 @@ where
     TT: Fn(&mut Window, &mut App) -> AnyView + 'static,
 {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
-        let store = ReplStore::global(cx).read(cx);
+        let store = ReplStore::global(cx);
+        let worktree_id = self.worktree_id;
 
         let all_kernels: Vec<KernelSpecification> = store
-            .kernel_specifications_for_worktree(self.worktree_id)
+            .read(cx)
+            .kernel_specifications_for_worktree(worktree_id)
             .cloned()
             .collect();
 
-        let selected_kernelspec = store.active_kernelspec(self.worktree_id, None, cx);
+        let selected_kernelspec = store.read(cx).active_kernelspec(worktree_id, None, cx);
 
         let delegate = KernelPickerDelegate {
             on_select: self.on_select,
             all_kernels: all_kernels.clone(),
             filtered_kernels: all_kernels,
             selected_kernelspec,
+            worktree_id,
         };
 
         let picker_view = cx.new(|cx| {
+            // Refreshing rediscovers kernels and interpreter versions arrive one at a time, so the
+            // list follows the store while it's open.
+            cx.observe_in(
+                &store,
+                window,
+                |picker: &mut Picker<KernelPickerDelegate>, store, window, cx| {
+                    picker.delegate.all_kernels = store
+                        .read(cx)
+                        .kernel_specifications_for_worktree(picker.delegate.worktree_id)
+                        .cloned()
+                        .collect();
+                    picker.refresh(window, cx);
+                },
+            )
+            .detach();
+
             let picker = Picker::uniform_list(delegate, window, cx)
                 .width(rems(30.))
                 .max_height(Some(rems(20.).into()));
@@ -276,7 +339,12 @@ where
         });
 
         PopoverMenu::new("kernel-switcher")
-            .menu(move |_window, _cx| Some(picker_view.clone()))
+            .menu(move |_window, cx| {
+                ReplStore::global(cx).update(cx, |store, cx| {
+                    store.query_interpreter_versions(worktree_id, cx)
+                });
+                Some(picker_view.clone())
+            })
             .trigger_with_tooltip(self.trigger, self.tooltip)
             .attach(gpui::Corner::BottomLeft)
             .when_some(self.handle, |menu, handle| menu.with_handle(handle))
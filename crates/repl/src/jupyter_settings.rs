@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use editor::EditorSettings;
+use editor::display_map::BlockStyle;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -9,6 +11,55 @@ use settings::{Settings, SettingsSources};
 #[derive(Debug, Default)]
 pub struct JupyterSettings {
     pub kernel_selections: HashMap<String, String>,
+    pub kernel_env: HashMap<String, HashMap<String, String>>,
+    pub mime_type_preference: Vec<String>,
+    pub output_density: OutputDensity,
+    pub output_block_style: OutputBlockStyle,
+    pub keep_outputs_on_edit: bool,
+    pub auto_start_kernel: bool,
+    pub pin_output_scroll: bool,
+    pub scroll_wide_output: bool,
+    pub inline_result_max_length: usize,
+    pub max_output_lines: u32,
+    pub diff_rerun_output: bool,
+    pub clear_outputs_on_run: bool,
+    pub keep_outputs_on_restart: Option<bool>,
+    pub autoplay_animations: bool,
+    pub startup_code: HashMap<String, String>,
+}
+
+/// How much space output blocks leave around their content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputDensity {
+    /// Half a line of padding above and below each output.
+    #[default]
+    Comfortable,
+    /// Minimal padding, for fitting more outputs on screen.
+    Compact,
+}
+
+/// How output blocks are laid out as the editor scrolls horizontally.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputBlockStyle {
+    /// Span the visible width of the editor and stay in view when it scrolls horizontally.
+    #[default]
+    Sticky,
+    /// Span the editor's full scrollable width and scroll horizontally with the code.
+    Flex,
+    /// Span the visible width of the editor and scroll horizontally with the code.
+    Fixed,
+}
+
+impl From<OutputBlockStyle> for BlockStyle {
+    fn from(style: OutputBlockStyle) -> Self {
+        match style {
+            OutputBlockStyle::Sticky => BlockStyle::Sticky,
+            OutputBlockStyle::Flex => BlockStyle::Flex,
+            OutputBlockStyle::Fixed => BlockStyle::Fixed,
+        }
+    }
 }
 
 impl JupyterSettings {
@@ -18,6 +69,33 @@ impl JupyterSettings {
         // This allows the editor to set up context for key bindings/actions.
         EditorSettings::jupyter_enabled(cx)
     }
+
+    /// Returns the environment variables configured for the named kernel, with
+    /// `${workspaceRoot}` replaced by `workspace_root`.
+    pub fn kernel_env(&self, kernel_name: &str, workspace_root: &Path) -> HashMap<String, String> {
+        let workspace_root = workspace_root.to_string_lossy();
+        self.kernel_env
+            .get(kernel_name)
+            .map(|env| {
+                env.iter()
+                    .map(|(key, value)| {
+                        (
+                            key.clone(),
+                            value.replace("${workspaceRoot}", &workspace_root),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the code to run when a kernel for the given language starts, if any.
+    pub fn startup_code(&self, language: &str) -> Option<&str> {
+        self.startup_code
+            .get(&language.to_lowercase())
+            .map(String::as_str)
+            .filter(|code| !code.trim().is_empty())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
@@ -26,12 +104,114 @@ pub struct JupyterSettingsContent {
     ///
     /// Default: `{}`
     pub kernel_selections: Option<HashMap<String, String>>,
+    /// Environment variables to set when launching each kernel, keyed by kernel name.
+    ///
+    /// `${workspaceRoot}` in a value is replaced with the root of the worktree the file belongs
+    /// to, or with the kernel's working directory for files outside of the project.
+    /// These take precedence over the kernelspec's `env` and over variables inherited
+    /// from Zed's environment.
+    ///
+    /// Default: `{}`
+    pub kernel_env: Option<HashMap<String, HashMap<String, String>>>,
+    /// Media types to prefer when an output includes several representations, most preferred
+    /// first. Types that aren't listed, or that Zed can't render, fall back to Zed's own ordering.
+    ///
+    /// For example, `["text/plain"]` shows plain text instead of tables and images.
+    ///
+    /// Default: `[]`
+    pub mime_type_preference: Option<Vec<String>>,
+    /// How much vertical space output blocks use around their content.
+    ///
+    /// Default: `comfortable`
+    pub output_density: Option<OutputDensity>,
+    /// How output blocks behave when the editor scrolls horizontally. `sticky` blocks stay in
+    /// view, while `flex` and `fixed` blocks scroll along with the code. Applies to outputs
+    /// created after it's changed.
+    ///
+    /// Default: `sticky`
+    pub output_block_style: Option<OutputBlockStyle>,
+    /// Whether outputs stay in place when the code that produced them is edited away.
+    /// When disabled, outputs are dimmed as soon as an edit invalidates them, and removed
+    /// shortly after if the code they belong to was deleted.
+    ///
+    /// Default: `false`
+    pub keep_outputs_on_edit: Option<bool>,
+    /// Whether to start a kernel as soon as an editor for a language with an available kernel
+    /// is focused, instead of on the first run. The kernel chosen is the same one running code
+    /// would use.
+    ///
+    /// Default: `false`
+    pub auto_start_kernel: Option<bool>,
+    /// Whether an output block that has been scrolled stays where it is as new output arrives.
+    /// When disabled, blocks scrolled to the bottom follow the newest output, like a terminal,
+    /// until they're scrolled back up.
+    ///
+    /// Default: `false`
+    pub pin_output_scroll: Option<bool>,
+    /// Whether text output wider than the output area scrolls horizontally instead of wrapping.
+    /// Each output can be switched between the two from its controls.
+    ///
+    /// Default: `false`
+    pub scroll_wide_output: Option<bool>,
+    /// The longest single-line text result that's shown at the end of the code line instead
+    /// of in a block below it. Set to 0 to always show results in blocks.
+    ///
+    /// Default: `40`
+    pub inline_result_max_length: Option<usize>,
+    /// How many lines tall an output block grows before its content scrolls within it, so
+    /// that a tall image or long log doesn't push the rest of the buffer out of view. Each
+    /// block can still be expanded to its full height. Set to 0 to never limit the height.
+    ///
+    /// Default: `32`
+    pub max_output_lines: Option<u32>,
+    /// Whether re-running code keeps the text of the output it replaces, so that the new
+    /// output can be diffed against it from the block's controls.
+    ///
+    /// Default: `false`
+    pub diff_rerun_output: Option<bool>,
+    /// Whether running code clears the outputs of every other cell that has finished, so that
+    /// only the latest output is shown. Cells that are still queued or running keep theirs.
+    ///
+    /// Default: `false`
+    pub clear_outputs_on_run: Option<bool>,
+    /// Whether restarting a kernel keeps the outputs it produced, dimmed to show they're out of
+    /// date, instead of clearing them. When unset, the first restart with outputs shown asks,
+    /// and saves the answer here.
+    ///
+    /// Default: unset
+    pub keep_outputs_on_restart: Option<bool>,
+    /// Whether animated images, such as GIFs, play as soon as they're shown. When disabled,
+    /// they show their first frame until played from their controls.
+    ///
+    /// Default: `true`
+    pub autoplay_animations: Option<bool>,
+    /// Code to run whenever a kernel starts, keyed by language name. It runs without showing
+    /// any output, before anything else is run on the kernel. If it fails, the error is shown
+    /// with the kernel's status.
+    ///
+    /// Default: `{}`
+    pub startup_code: Option<HashMap<String, String>>,
 }
 
 impl Default for JupyterSettingsContent {
     fn default() -> Self {
         JupyterSettingsContent {
             kernel_selections: Some(HashMap::new()),
+            kernel_env: Some(HashMap::new()),
+            mime_type_preference: Some(Vec::new()),
+            output_density: Some(OutputDensity::default()),
+            output_block_style: Some(OutputBlockStyle::default()),
+            keep_outputs_on_edit: Some(false),
+            auto_start_kernel: Some(false),
+            pin_output_scroll: Some(false),
+            scroll_wide_output: Some(false),
+            inline_result_max_length: Some(40),
+            max_output_lines: Some(32),
+            diff_rerun_output: Some(false),
+            clear_outputs_on_run: Some(false),
+            keep_outputs_on_restart: None,
+            autoplay_animations: Some(true),
+            startup_code: Some(HashMap::new()),
         }
     }
 }
@@ -48,7 +228,11 @@ impl Settings for JupyterSettings {
     where
         Self: Sized,
     {
-        let mut settings = JupyterSettings::default();
+        // The defaults only list `enabled`, so settings that default to `true` start out that way.
+        let mut settings = JupyterSettings {
+            autoplay_animations: true,
+            ..JupyterSettings::default()
+        };
 
         for value in sources.defaults_and_customizations() {
             if let Some(source) = &value.kernel_selections {
@@ -56,6 +240,56 @@ impl Settings for JupyterSettings {
                     settings.kernel_selections.insert(k.clone(), v.clone());
                 }
             }
+            if let Some(source) = &value.kernel_env {
+                for (k, v) in source {
+                    settings.kernel_env.insert(k.clone(), v.clone());
+                }
+            }
+            if let Some(source) = &value.mime_type_preference {
+                settings.mime_type_preference = source.clone();
+            }
+            if let Some(source) = value.output_density {
+                settings.output_density = source;
+            }
+            if let Some(source) = value.output_block_style {
+                settings.output_block_style = source;
+            }
+            if let Some(source) = value.keep_outputs_on_edit {
+                settings.keep_outputs_on_edit = source;
+            }
+            if let Some(source) = value.auto_start_kernel {
+                settings.auto_start_kernel = source;
+            }
+            if let Some(source) = value.pin_output_scroll {
+                settings.pin_output_scroll = source;
+            }
+            if let Some(source) = value.scroll_wide_output {
+                settings.scroll_wide_output = source;
+            }
+            if let Some(source) = value.inline_result_max_length {
+                settings.inline_result_max_length = source;
+            }
+            if let Some(source) = value.max_output_lines {
+                settings.max_output_lines = source;
+            }
+            if let Some(source) = value.diff_rerun_output {
+                settings.diff_rerun_output = source;
+            }
+            if let Some(source) = value.clear_outputs_on_run {
+                settings.clear_outputs_on_run = source;
+            }
+            if let Some(source) = value.keep_outputs_on_restart {
+                settings.keep_outputs_on_restart = Some(source);
+            }
+            if let Some(source) = value.autoplay_animations {
+                settings.autoplay_animations = source;
+            }
+            if let Some(source) = &value.startup_code {
+                // Languages are matched regardless of case, so they're stored lowercased.
+                for (k, v) in source {
+                    settings.startup_code.insert(k.to_lowercase(), v.clone());
+                }
+            }
         }
 
         Ok(settings)
@@ -63,3 +297,34 @@ impl Settings for JupyterSettings {
 
     fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_env_substitutes_workspace_root() {
+        let settings = JupyterSettings {
+            kernel_env: HashMap::from_iter([(
+                "python3".to_string(),
+                HashMap::from_iter([
+                    (
+                        "PYTHONPATH".to_string(),
+                        "${workspaceRoot}/src:${workspaceRoot}/lib".to_string(),
+                    ),
+                    ("MPLBACKEND".to_string(), "agg".to_string()),
+                ]),
+            )]),
+            ..JupyterSettings::default()
+        };
+
+        let env = settings.kernel_env("python3", Path::new("/projects/app"));
+        assert_eq!(env["PYTHONPATH"], "/projects/app/src:/projects/app/lib");
+        assert_eq!(env["MPLBACKEND"], "agg");
+        assert!(
+            settings
+                .kernel_env("ir", Path::new("/projects/app"))
+                .is_empty()
+        );
+    }
+}
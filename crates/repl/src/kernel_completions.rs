@@ -0,0 +1,196 @@
+//! Completions from the kernel for editors attached to a REPL session.
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::{CompletionProvider, Editor, ExcerptId};
+use futures::future::{self, Either};
+use gpui::{Context, Entity, Task, WeakEntity, Window};
+use language::{Bias, Buffer, CodeLabel, ToOffset as _, ToPoint as _};
+use project::{Completion, CompletionSource};
+use runtimelib::{CompleteRequest, JupyterMessage, JupyterMessageContent};
+use util::ResultExt as _;
+
+use crate::Session;
+use crate::kernels::KernelStatus;
+use crate::repl_editor::code_around_cursor;
+
+/// How long the editor's own completions are held back waiting for the kernel's matches.
+const KERNEL_COMPLETION_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// The provider the editor had before a session was attached. The session keeps a handle to it
+/// so that it can be given back to the editor once the session ends.
+pub(crate) type FallbackCompletionProvider = Rc<RefCell<Option<Box<dyn CompletionProvider>>>>;
+
+/// Adds the kernel's `complete_request` matches to the completions the editor would otherwise
+/// show, so that names that only exist at runtime (like dynamic attributes) can be completed.
+pub struct KernelCompletionProvider {
+    session: WeakEntity<Session>,
+    fallback: FallbackCompletionProvider,
+}
+
+impl KernelCompletionProvider {
+    pub fn new(session: WeakEntity<Session>, fallback: FallbackCompletionProvider) -> Self {
+        Self { session, fallback }
+    }
+
+    fn kernel_completions(
+        &self,
+        buffer: &Entity<Buffer>,
+        buffer_position: language::Anchor,
+        cx: &mut Context<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let Some(session) = self.session.upgrade() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        // A busy kernel only answers once the running cell finishes, which is too late to matter.
+        if !matches!(session.read(cx).kernel.status(), KernelStatus::Idle) {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let cursor = buffer_position.to_point(&snapshot);
+        let (cell, code, cursor_pos) = code_around_cursor(&snapshot, cursor, cx);
+        let cell_start = cell.start.to_offset(&snapshot);
+
+        let request: JupyterMessage = CompleteRequest {
+            code: code.clone(),
+            cursor_pos,
+        }
+        .into();
+        let reply = session.update(cx, |session, cx| session.send_request(request, cx));
+
+        cx.background_spawn(async move {
+            let JupyterMessageContent::CompleteReply(reply) = reply.await? else {
+                return Ok(Vec::new());
+            };
+
+            // The reply's positions are counted in code points within the code that was sent.
+            let offset_of = |code_point: usize| {
+                let byte_offset = code
+                    .char_indices()
+                    .nth(code_point)
+                    .map_or(code.len(), |(offset, _)| offset);
+                snapshot.clip_offset(cell_start + byte_offset, Bias::Left)
+            };
+            let replace_range = snapshot.anchor_before(offset_of(reply.cursor_start))
+                ..snapshot.anchor_after(offset_of(reply.cursor_end));
+
+            Ok(reply
+                .matches
+                .into_iter()
+                .map(|new_text| Completion {
+                    replace_range: replace_range.clone(),
+                    label: CodeLabel::plain(new_text.clone(), None),
+                    new_text,
+                    documentation: None,
+                    source: CompletionSource::Custom,
+                    icon_path: None,
+                    insert_text_mode: None,
+                    confirm: None,
+                })
+                .collect())
+        })
+    }
+}
+
+impl CompletionProvider for KernelCompletionProvider {
+    fn completions(
+        &self,
+        excerpt_id: ExcerptId,
+        buffer: &Entity<Buffer>,
+        buffer_position: language::Anchor,
+        trigger: editor::CompletionContext,
+        window: &mut Window,
+        cx: &mut Context<Editor>,
+    ) -> Task<Result<Option<Vec<Completion>>>> {
+        let fallback_completions = self.fallback.borrow().as_ref().map(|fallback| {
+            fallback.completions(excerpt_id, buffer, buffer_position, trigger, window, cx)
+        });
+        let kernel_completions = self.kernel_completions(buffer, buffer_position, cx);
+        let deadline = cx.background_executor().timer(KERNEL_COMPLETION_TIMEOUT);
+
+        cx.spawn(async move |_, _| {
+            let mut completions = match fallback_completions {
+                Some(task) => task.await.log_err().flatten().unwrap_or_default(),
+                None => Vec::new(),
+            };
+
+            // The kernel's matches are only merged in if they arrive quickly; a slow kernel
+            // shouldn't hold back the editor's own completions.
+            let kernel_completions = match future::select(kernel_completions, deadline).await {
+                Either::Left((kernel_completions, _)) => kernel_completions,
+                Either::Right(_) => return Ok(Some(completions)),
+            };
+            match kernel_completions {
+                Ok(kernel_completions) => {
+                    let existing = completions
+                        .iter()
+                        .map(|completion| completion.new_text.clone())
+                        .collect::<HashSet<_>>();
+                    completions.extend(
+                        kernel_completions
+                            .into_iter()
+                            .filter(|completion| !existing.contains(&completion.new_text)),
+                    );
+                }
+                Err(error) => log::debug!("kernel completions unavailable: {error:#}"),
+            }
+
+            Ok(Some(completions))
+        })
+    }
+
+    fn resolve_completions(
+        &self,
+        buffer: Entity<Buffer>,
+        completion_indices: Vec<usize>,
+        completions: Rc<RefCell<Box<[Completion]>>>,
+        cx: &mut Context<Editor>,
+    ) -> Task<Result<bool>> {
+        match self.fallback.borrow().as_ref() {
+            Some(fallback) => {
+                fallback.resolve_completions(buffer, completion_indices, completions, cx)
+            }
+            None => Task::ready(Ok(false)),
+        }
+    }
+
+    fn apply_additional_edits_for_completion(
+        &self,
+        buffer: Entity<Buffer>,
+        completions: Rc<RefCell<Box<[Completion]>>>,
+        completion_index: usize,
+        push_to_history: bool,
+        cx: &mut Context<Editor>,
+    ) -> Task<Result<Option<language::Transaction>>> {
+        match self.fallback.borrow().as_ref() {
+            Some(fallback) => fallback.apply_additional_edits_for_completion(
+                buffer,
+                completions,
+                completion_index,
+                push_to_history,
+                cx,
+            ),
+            None => Task::ready(Ok(None)),
+        }
+    }
+
+    fn is_completion_trigger(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: language::Anchor,
+        text: &str,
+        trigger_in_words: bool,
+        cx: &mut Context<Editor>,
+    ) -> bool {
+        if let Some(fallback) = self.fallback.borrow().as_ref() {
+            if fallback.is_completion_trigger(buffer, position, text, trigger_in_words, cx) {
+                return true;
+            }
+        }
+
+        text == "." || (trigger_in_words && text.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{Context as _, Result};
+use futures::{
+    FutureExt as _,
+    channel::mpsc,
+    future::{self, BoxFuture},
+};
+use gpui::{App, Entity, Task, Window};
+use jupyter_protocol::{
+    ExecutionState, JupyterMessage, KernelInfoReply, KernelInfoRequest,
+    connection_info::ConnectionInfo,
+};
+use project::Fs;
+use std::{fmt::Debug, path::PathBuf, sync::Arc};
+
+use crate::Session;
+
+use super::RunningKernel;
+use super::native_kernel::connect_to_kernel;
+
+/// A kernel that was started outside of Zed, reached through its connection file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExistingKernelSpecification {
+    pub name: String,
+    pub connection_path: PathBuf,
+    pub language: String,
+}
+
+impl ExistingKernelSpecification {
+    pub fn new(connection_path: PathBuf, language: String) -> Self {
+        let name = connection_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| connection_path.to_string_lossy().to_string());
+
+        Self {
+            name,
+            connection_path,
+            language,
+        }
+    }
+}
+
+pub struct ExistingRunningKernel {
+    _shell_task: Task<Result<()>>,
+    _control_task: Task<Result<()>>,
+    _routing_task: Task<Result<()>>,
+    /// Taken when disconnecting, so that Zed stops listening to a kernel that other clients
+    /// may keep using.
+    iopub_task: Option<Task<Result<()>>>,
+    reply_task: Option<Task<Result<()>>>,
+    pub connection_path: PathBuf,
+    pub working_directory: PathBuf,
+    pub request_tx: mpsc::Sender<JupyterMessage>,
+    pub execution_state: ExecutionState,
+    pub kernel_info: Option<KernelInfoReply>,
+}
+
+impl Debug for ExistingRunningKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExistingRunningKernel")
+            .field("connection_path", &self.connection_path)
+            .finish()
+    }
+}
+
+impl ExistingRunningKernel {
+    pub fn new(
+        kernel_specification: ExistingKernelSpecification,
+        working_directory: PathBuf,
+        fs: Arc<dyn Fs>,
+        session: Entity<Session>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Box<dyn RunningKernel>>> {
+        window.spawn(cx, async move |cx| {
+            let connection_path = kernel_specification.connection_path;
+            let content = fs.load(&connection_path).await.with_context(|| {
+                format!(
+                    "Failed to read connection file {}",
+                    connection_path.display()
+                )
+            })?;
+            let connection_info: ConnectionInfo =
+                serde_json::from_str(&content).with_context(|| {
+                    format!("Invalid connection file {}", connection_path.display())
+                })?;
+
+            let mut connection = connect_to_kernel(&connection_info, session, cx).await?;
+
+            // The kernel may be in the middle of running someone else's code. Shell requests are
+            // handled in order, so the reply to this only arrives once the kernel is free, and
+            // until then the kernel is treated as busy.
+            let message: JupyterMessage = KernelInfoRequest {}.into();
+            connection.request_tx.try_send(message).ok();
+
+            anyhow::Ok(Box::new(Self {
+                _shell_task: connection.shell_task,
+                _control_task: connection.control_task,
+                _routing_task: connection.routing_task,
+                iopub_task: Some(connection.iopub_task),
+                reply_task: Some(connection.reply_task),
+                connection_path,
+                working_directory,
+                request_tx: connection.request_tx,
+                execution_state: ExecutionState::Busy,
+                kernel_info: None,
+            }) as Box<dyn RunningKernel>)
+        })
+    }
+}
+
+impl RunningKernel for ExistingRunningKernel {
+    fn request_tx(&self) -> mpsc::Sender<JupyterMessage> {
+        self.request_tx.clone()
+    }
+
+    fn working_directory(&self) -> &PathBuf {
+        &self.working_directory
+    }
+
+    fn execution_state(&self) -> &ExecutionState {
+        &self.execution_state
+    }
+
+    fn set_execution_state(&mut self, state: ExecutionState) {
+        self.execution_state = state;
+    }
+
+    fn kernel_info(&self) -> Option<&KernelInfoReply> {
+        self.kernel_info.as_ref()
+    }
+
+    fn set_kernel_info(&mut self, info: KernelInfoReply) {
+        self.kernel_info = Some(info);
+    }
+
+    fn force_shutdown(&mut self) -> BoxFuture<'static, anyhow::Result<()>> {
+        // Zed didn't start this kernel, so shutting down only disconnects from it.
+        self.request_tx.close_channel();
+        self.iopub_task.take();
+        self.reply_task.take();
+        future::ready(Ok(())).boxed()
+    }
+
+    fn owns_process(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernels::Kernel;
+    use crate::session::tests::init_session_test;
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, VisualTestContext};
+    use std::env::temp_dir;
+
+    /// A stand-in for one of the connection's readers, which routes whatever the kernel sends to
+    /// the session for as long as it runs.
+    fn route_to_session(
+        session: &Entity<Session>,
+        mut messages: mpsc::UnboundedReceiver<JupyterMessage>,
+        cx: &mut VisualTestContext,
+    ) -> Task<Result<()>> {
+        let session = session.clone();
+        cx.update(|window, cx| {
+            window.spawn(cx, async move |cx| {
+                while let Some(message) = messages.next().await {
+                    session.update_in(cx, |session, window, cx| {
+                        session.route(&message, window, cx);
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+        })
+    }
+
+    #[gpui::test]
+    async fn test_disconnecting_releases_the_session(cx: &mut TestAppContext) {
+        let (session, editor, _kernel, cx) = init_session_test(cx).await;
+
+        let (iopub_tx, iopub_rx) = mpsc::unbounded();
+        let (reply_tx, reply_rx) = mpsc::unbounded();
+        let iopub_task = route_to_session(&session, iopub_rx, cx);
+        let reply_task = route_to_session(&session, reply_rx, cx);
+
+        let (request_tx, _request_rx) = mpsc::channel(16);
+        session.update(cx, |session, cx| {
+            session.kernel(
+                Kernel::RunningKernel(Box::new(ExistingRunningKernel {
+                    _shell_task: Task::ready(Ok(())),
+                    _control_task: Task::ready(Ok(())),
+                    _routing_task: Task::ready(Ok(())),
+                    iopub_task: Some(iopub_task),
+                    reply_task: Some(reply_task),
+                    connection_path: PathBuf::new(),
+                    working_directory: temp_dir(),
+                    request_tx,
+                    execution_state: ExecutionState::Idle,
+                    kernel_info: None,
+                })),
+                cx,
+            );
+        });
+
+        session
+            .update(cx, |session, cx| session.shutdown(cx))
+            .detach();
+        cx.run_until_parked();
+
+        assert!(
+            iopub_tx.is_closed(),
+            "Zed should stop reading the kernel's iopub"
+        );
+        assert!(
+            reply_tx.is_closed(),
+            "Zed should stop reading the kernel's replies"
+        );
+
+        let weak_session = session.downgrade();
+        drop((session, editor));
+        cx.run_until_parked();
+        assert!(
+            weak_session.upgrade().is_none(),
+            "the session should be released once disconnected"
+        );
+    }
+}
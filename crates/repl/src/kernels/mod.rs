@@ -1,12 +1,14 @@
+mod existing_kernel;
 mod native_kernel;
 use std::{fmt::Debug, future::Future, path::PathBuf, sync::Arc};
 
+pub use existing_kernel::*;
 use futures::{
     channel::mpsc::{self, Receiver},
-    future::Shared,
+    future::{BoxFuture, Shared},
     stream,
 };
-use gpui::{App, Entity, Task, Window};
+use gpui::{App, Entity, Task};
 use language::LanguageName;
 pub use native_kernel::*;
 
@@ -26,6 +28,7 @@ pub enum KernelSpecification {
     Remote(RemoteKernelSpecification),
     Jupyter(LocalKernelSpecification),
     PythonEnv(LocalKernelSpecification),
+    ExistingConnection(ExistingKernelSpecification),
 }
 
 impl KernelSpecification {
@@ -34,6 +37,7 @@ impl KernelSpecification {
             Self::Jupyter(spec) => spec.name.clone().into(),
             Self::PythonEnv(spec) => spec.name.clone().into(),
             Self::Remote(spec) => spec.name.clone().into(),
+            Self::ExistingConnection(spec) => spec.name.clone().into(),
         }
     }
 
@@ -42,6 +46,7 @@ impl KernelSpecification {
             Self::Jupyter(_) => "Jupyter".into(),
             Self::PythonEnv(_) => "Python Environment".into(),
             Self::Remote(_) => "Remote".into(),
+            Self::ExistingConnection(_) => "Existing Kernel".into(),
         }
     }
 
@@ -50,6 +55,7 @@ impl KernelSpecification {
             Self::Jupyter(spec) => spec.path.to_string_lossy().to_string(),
             Self::PythonEnv(spec) => spec.path.to_string_lossy().to_string(),
             Self::Remote(spec) => spec.url.to_string(),
+            Self::ExistingConnection(spec) => spec.connection_path.to_string_lossy().to_string(),
         })
     }
 
@@ -58,14 +64,30 @@ impl KernelSpecification {
             Self::Jupyter(spec) => spec.kernelspec.language.clone(),
             Self::PythonEnv(spec) => spec.kernelspec.language.clone(),
             Self::Remote(spec) => spec.kernelspec.language.clone(),
+            Self::ExistingConnection(spec) => spec.language.clone(),
         })
     }
 
+    /// The interpreter that runs the kernel, for kernels started on this machine.
+    pub fn interpreter_path(&self) -> Option<PathBuf> {
+        match self {
+            Self::Jupyter(spec) | Self::PythonEnv(spec) => {
+                spec.kernelspec.argv.first().map(PathBuf::from)
+            }
+            Self::Remote(_) | Self::ExistingConnection(_) => None,
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Remote(_))
+    }
+
     pub fn icon(&self, cx: &App) -> Icon {
         let lang_name = match self {
             Self::Jupyter(spec) => spec.kernelspec.language.clone(),
             Self::PythonEnv(spec) => spec.kernelspec.language.clone(),
             Self::Remote(spec) => spec.kernelspec.language.clone(),
+            Self::ExistingConnection(spec) => spec.language.clone(),
         };
 
         file_icons::FileIcons::get(cx)
@@ -146,6 +168,28 @@ pub fn python_env_kernel_specifications(
     }
 }
 
+/// Asks a Python interpreter for its version, such as `Python 3.12.4`.
+pub async fn python_version(interpreter_path: PathBuf) -> Option<String> {
+    let output = util::command::new_smol_command(&interpreter_path)
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Python 2 prints its version to stderr.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let version = if stdout.trim().is_empty() {
+        stderr.trim()
+    } else {
+        stdout.trim()
+    };
+    version.lines().next().map(ToString::to_string)
+}
+
 pub trait RunningKernel: Send + Debug {
     fn request_tx(&self) -> mpsc::Sender<JupyterMessage>;
     fn working_directory(&self) -> &PathBuf;
@@ -153,7 +197,24 @@ pub trait RunningKernel: Send + Debug {
     fn set_execution_state(&mut self, state: ExecutionState);
     fn kernel_info(&self) -> Option<&KernelInfoReply>;
     fn set_kernel_info(&mut self, info: KernelInfoReply);
-    fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>>;
+    fn force_shutdown(&mut self) -> BoxFuture<'static, anyhow::Result<()>>;
+    /// Whether Zed started the kernel. Kernels that Zed only connected to are left running when
+    /// their session ends.
+    fn owns_process(&self) -> bool {
+        true
+    }
+    /// The id of the kernel's process, when it runs on this machine as Zed's child.
+    fn process_id(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// How much of the machine a kernel's process is using.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KernelResourceUsage {
+    /// Measured against a single core, so this exceeds 100 when several are busy.
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -233,6 +294,38 @@ impl Kernel {
         }
     }
 
+    /// The versions of the kernel's language and implementation it reported, such as
+    /// "Python 3.11.6 / ipykernel 6.29.0", once it has replied to the kernel info request.
+    pub fn version_description(&self) -> Option<String> {
+        let Kernel::RunningKernel(running_kernel) = self else {
+            return None;
+        };
+        let info = running_kernel.kernel_info()?;
+        let join = |name: &str, version: &str| {
+            [name.trim(), version.trim()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let parts = [
+            join(&info.language_info.name, &info.language_info.version),
+            join(&info.implementation, &info.implementation_version),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+        (!parts.is_empty()).then(|| parts.join(" / "))
+    }
+
+    /// The id of the kernel's process, when it's running on this machine.
+    pub fn process_id(&self) -> Option<u32> {
+        match self {
+            Kernel::RunningKernel(running_kernel) => running_kernel.process_id(),
+            _ => None,
+        }
+    }
+
     pub fn is_shutting_down(&self) -> bool {
         match self {
             Kernel::Restarting | Kernel::ShuttingDown => true,
@@ -1,11 +1,12 @@
 use anyhow::{Context as _, Result};
 use futures::{
-    AsyncBufReadExt as _, SinkExt as _,
+    AsyncBufReadExt as _, FutureExt as _, SinkExt as _,
     channel::mpsc::{self},
+    future::{self, BoxFuture},
     io::BufReader,
     stream::{SelectAll, StreamExt},
 };
-use gpui::{App, AppContext as _, Entity, EntityId, Task, Window};
+use gpui::{App, AppContext as _, AsyncWindowContext, Entity, EntityId, Task, Window};
 use jupyter_protocol::{
     ExecutionState, JupyterKernelspec, JupyterMessage, JupyterMessageContent, KernelInfoReply,
     connection_info::{ConnectionInfo, Transport},
@@ -14,8 +15,10 @@ use project::Fs;
 use runtimelib::dirs;
 use smol::{net::TcpListener, process::Command};
 use std::{
+    collections::{HashMap, VecDeque},
     env,
     fmt::Debug,
+    io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
     sync::Arc,
@@ -43,7 +46,7 @@ impl Eq for LocalKernelSpecification {}
 
 impl LocalKernelSpecification {
     #[must_use]
-    fn command(&self, connection_path: &PathBuf) -> Result<Command> {
+    fn command(&self, connection_path: &PathBuf, env: &HashMap<String, String>) -> Result<Command> {
         let argv = &self.kernelspec.argv;
 
         anyhow::ensure!(!argv.is_empty(), "Empty argv in kernelspec {}", self.name);
@@ -67,11 +70,73 @@ impl LocalKernelSpecification {
         if let Some(env) = &self.kernelspec.env {
             cmd.envs(env);
         }
+        cmd.envs(env);
 
         Ok(cmd)
     }
+
+    fn program(&self) -> &str {
+        self.kernelspec
+            .argv
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Explains why the kernel process could not be spawned, along with the command that was tried.
+    fn spawn_error_message(&self, error: &io::Error) -> String {
+        let program = self.program();
+        let suggestion = match error.kind() {
+            io::ErrorKind::NotFound => format!(
+                " `{program}` was not found. Make sure it is installed and on your PATH, or update the kernelspec at {}.",
+                self.path.display()
+            ),
+            io::ErrorKind::PermissionDenied => {
+                format!(" `{program}` is not executable. Check its file permissions.")
+            }
+            _ => String::new(),
+        };
+
+        format!(
+            "Failed to start kernel: {error}.{suggestion} Command: `{}`",
+            self.kernelspec.argv.join(" ")
+        )
+    }
+
+    /// Explains why the kernel process exited early, using what it wrote to stderr to spot
+    /// common setup problems.
+    fn exit_error_message(&self, exit_reason: &str, stderr_tail: &[String]) -> String {
+        let mentions = |needle: &str| stderr_tail.iter().any(|line| line.contains(needle));
+
+        let suggestion =
+            if mentions("No module named ipykernel") || mentions("No module named 'ipykernel") {
+                format!(
+                    " ipykernel is not installed. Run `{} -m pip install ipykernel`.",
+                    self.program()
+                )
+            } else if mentions("Permission denied") {
+                " Check the permissions of the kernel and its working directory.".to_string()
+            } else {
+                String::new()
+            };
+
+        let last_line = stderr_tail
+            .iter()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| format!(" ({})", line.trim()))
+            .unwrap_or_default();
+
+        format!(
+            "Kernel {exit_reason}{last_line}.{suggestion} Command: `{}`",
+            self.kernelspec.argv.join(" ")
+        )
+    }
 }
 
+/// How many trailing lines of kernel stderr to keep for diagnosing startup failures.
+const STDERR_TAIL_LINES: usize = 20;
+
 // Find a set of open ports. This creates a listener with port set to 0. The listener will be closed at the end when it goes out of scope.
 // There's a race condition between closing the ports and usage by a kernel, but it's inherent to the Jupyter protocol.
 async fn peek_ports(ip: IpAddr) -> Result<[u16; 5]> {
@@ -86,11 +151,130 @@ async fn peek_ports(ip: IpAddr) -> Result<[u16; 5]> {
     Ok(ports)
 }
 
+/// The channels that carry messages between a [`Session`] and a kernel's ZeroMQ sockets.
+pub(super) struct KernelConnection {
+    pub request_tx: mpsc::Sender<JupyterMessage>,
+    pub shell_task: Task<Result<()>>,
+    pub control_task: Task<Result<()>>,
+    pub routing_task: Task<Result<()>>,
+    /// Routes the kernel's iopub messages to the session, until dropped.
+    pub iopub_task: Task<Result<()>>,
+    /// Routes the replies to the session's shell and control requests, until dropped.
+    pub reply_task: Task<Result<()>>,
+}
+
+/// Connects to the kernel described by `connection_info` and routes everything it sends to
+/// `session`.
+pub(super) async fn connect_to_kernel(
+    connection_info: &ConnectionInfo,
+    session: Entity<Session>,
+    cx: &mut AsyncWindowContext,
+) -> Result<KernelConnection> {
+    let session_id = Uuid::new_v4().to_string();
+
+    let mut iopub_socket =
+        runtimelib::create_client_iopub_connection(connection_info, "", &session_id).await?;
+    let mut shell_socket =
+        runtimelib::create_client_shell_connection(connection_info, &session_id).await?;
+    let mut control_socket =
+        runtimelib::create_client_control_connection(connection_info, &session_id).await?;
+
+    let (request_tx, mut request_rx) = futures::channel::mpsc::channel::<JupyterMessage>(100);
+
+    let (mut control_reply_tx, control_reply_rx) = futures::channel::mpsc::channel(100);
+    let (mut shell_reply_tx, shell_reply_rx) = futures::channel::mpsc::channel(100);
+
+    let mut messages_rx = SelectAll::new();
+    messages_rx.push(control_reply_rx);
+    messages_rx.push(shell_reply_rx);
+
+    // The kernel owning these tasks belongs to the session, so they only hold on to it weakly.
+    let reply_task = cx.spawn({
+        let session = session.downgrade();
+
+        async move |cx| {
+            while let Some(message) = messages_rx.next().await {
+                session.update_in(cx, |session, window, cx| {
+                    session.route(&message, window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    let iopub_task = cx.spawn({
+        let session = session.downgrade();
+
+        async move |cx| {
+            while let Ok(message) = iopub_socket.read().await {
+                session.update_in(cx, |session, window, cx| {
+                    session.route(&message, window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    let (mut control_request_tx, mut control_request_rx) = futures::channel::mpsc::channel(100);
+    let (mut shell_request_tx, mut shell_request_rx) = futures::channel::mpsc::channel(100);
+
+    let routing_task = cx.background_spawn({
+        async move {
+            while let Some(message) = request_rx.next().await {
+                match message.content {
+                    JupyterMessageContent::DebugRequest(_)
+                    | JupyterMessageContent::InterruptRequest(_)
+                    | JupyterMessageContent::ShutdownRequest(_) => {
+                        control_request_tx.send(message).await?;
+                    }
+                    _ => {
+                        shell_request_tx.send(message).await?;
+                    }
+                }
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    let shell_task = cx.background_spawn({
+        async move {
+            while let Some(message) = shell_request_rx.next().await {
+                shell_socket.send(message).await.ok();
+                let reply = shell_socket.read().await?;
+                shell_reply_tx.send(reply).await?;
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    let control_task = cx.background_spawn({
+        async move {
+            while let Some(message) = control_request_rx.next().await {
+                control_socket.send(message).await.ok();
+                let reply = control_socket.read().await?;
+                control_reply_tx.send(reply).await?;
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    Ok(KernelConnection {
+        request_tx,
+        shell_task,
+        control_task,
+        routing_task,
+        iopub_task,
+        reply_task,
+    })
+}
+
 pub struct NativeRunningKernel {
     pub process: smol::process::Child,
     _shell_task: Task<Result<()>>,
     _control_task: Task<Result<()>>,
     _routing_task: Task<Result<()>>,
+    _iopub_task: Task<Result<()>>,
+    _reply_task: Task<Result<()>>,
     connection_path: PathBuf,
     _process_status_task: Option<Task<()>>,
     pub working_directory: PathBuf,
@@ -112,6 +296,7 @@ impl NativeRunningKernel {
         kernel_specification: LocalKernelSpecification,
         entity_id: EntityId,
         working_directory: PathBuf,
+        env: HashMap<String, String>,
         fs: Arc<dyn Fs>,
         // todo: convert to weak view
         session: Entity<Session>,
@@ -119,6 +304,12 @@ impl NativeRunningKernel {
         cx: &mut App,
     ) -> Task<Result<Box<dyn RunningKernel>>> {
         window.spawn(cx, async move |cx| {
+            anyhow::ensure!(
+                fs.is_dir(&working_directory).await,
+                "Working directory {} does not exist",
+                working_directory.display()
+            );
+
             let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
             let ports = peek_ports(ip).await?;
 
@@ -143,7 +334,7 @@ impl NativeRunningKernel {
             let content = serde_json::to_string(&connection_info)?;
             fs.atomic_write(connection_path.clone(), content).await?;
 
-            let mut cmd = kernel_specification.command(&connection_path)?;
+            let mut cmd = kernel_specification.command(&connection_path, &env)?;
 
             let mut process = cmd
                 .current_dir(&working_directory)
@@ -152,118 +343,30 @@ impl NativeRunningKernel {
                 .stdin(std::process::Stdio::piped())
                 .kill_on_drop(true)
                 .spawn()
-                .context("failed to start the kernel process")?;
-
-            let session_id = Uuid::new_v4().to_string();
-
-            let mut iopub_socket =
-                runtimelib::create_client_iopub_connection(&connection_info, "", &session_id)
-                    .await?;
-            let mut shell_socket =
-                runtimelib::create_client_shell_connection(&connection_info, &session_id).await?;
-            let mut control_socket =
-                runtimelib::create_client_control_connection(&connection_info, &session_id).await?;
-
-            let (request_tx, mut request_rx) =
-                futures::channel::mpsc::channel::<JupyterMessage>(100);
-
-            let (mut control_reply_tx, control_reply_rx) = futures::channel::mpsc::channel(100);
-            let (mut shell_reply_tx, shell_reply_rx) = futures::channel::mpsc::channel(100);
-
-            let mut messages_rx = SelectAll::new();
-            messages_rx.push(control_reply_rx);
-            messages_rx.push(shell_reply_rx);
-
-            cx.spawn({
-                let session = session.clone();
-
-                async move |cx| {
-                    while let Some(message) = messages_rx.next().await {
-                        session
-                            .update_in(cx, |session, window, cx| {
-                                session.route(&message, window, cx);
-                            })
-                            .ok();
-                    }
-                    anyhow::Ok(())
-                }
-            })
-            .detach();
+                .map_err(|error| {
+                    anyhow::anyhow!(kernel_specification.spawn_error_message(&error))
+                })?;
 
-            // iopub task
-            cx.spawn({
-                let session = session.clone();
-
-                async move |cx| {
-                    while let Ok(message) = iopub_socket.read().await {
-                        session
-                            .update_in(cx, |session, window, cx| {
-                                session.route(&message, window, cx);
-                            })
-                            .ok();
-                    }
-                    anyhow::Ok(())
-                }
-            })
-            .detach();
-
-            let (mut control_request_tx, mut control_request_rx) =
-                futures::channel::mpsc::channel(100);
-            let (mut shell_request_tx, mut shell_request_rx) = futures::channel::mpsc::channel(100);
-
-            let routing_task = cx.background_spawn({
-                async move {
-                    while let Some(message) = request_rx.next().await {
-                        match message.content {
-                            JupyterMessageContent::DebugRequest(_)
-                            | JupyterMessageContent::InterruptRequest(_)
-                            | JupyterMessageContent::ShutdownRequest(_) => {
-                                control_request_tx.send(message).await?;
-                            }
-                            _ => {
-                                shell_request_tx.send(message).await?;
-                            }
-                        }
-                    }
-                    anyhow::Ok(())
-                }
-            });
-
-            let shell_task = cx.background_spawn({
-                async move {
-                    while let Some(message) = shell_request_rx.next().await {
-                        shell_socket.send(message).await.ok();
-                        let reply = shell_socket.read().await?;
-                        shell_reply_tx.send(reply).await?;
-                    }
-                    anyhow::Ok(())
-                }
-            });
-
-            let control_task = cx.background_spawn({
-                async move {
-                    while let Some(message) = control_request_rx.next().await {
-                        control_socket.send(message).await.ok();
-                        let reply = control_socket.read().await?;
-                        control_reply_tx.send(reply).await?;
-                    }
-                    anyhow::Ok(())
-                }
-            });
+            let connection = connect_to_kernel(&connection_info, session.clone(), cx).await?;
 
             let stderr = process.stderr.take();
 
-            cx.spawn(async move |_cx| {
-                if stderr.is_none() {
-                    return;
-                }
-                let reader = BufReader::new(stderr.unwrap());
+            let stderr_task = cx.spawn(async move |_cx| {
+                let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+                let Some(stderr) = stderr else {
+                    return tail;
+                };
+                let reader = BufReader::new(stderr);
                 let mut lines = reader.lines();
                 while let Some(Ok(line)) = lines.next().await {
                     log::error!("kernel: {}", line);
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
                 }
-            })
-            .detach();
+                tail
+            });
 
             let stdout = process.stdout.take();
 
@@ -282,20 +385,24 @@ impl NativeRunningKernel {
             let status = process.status();
 
             let process_status_task = cx.spawn(async move |cx| {
-                let error_message = match status.await {
+                let exit_reason = match status.await {
                     Ok(status) => {
                         if status.success() {
                             log::info!("kernel process exited successfully");
                             return;
                         }
 
-                        format!("kernel process exited with status: {:?}", status)
+                        format!("process exited with {status}")
                     }
                     Err(err) => {
-                        format!("kernel process exited with error: {:?}", err)
+                        format!("process exited with error: {err}")
                     }
                 };
 
+                let stderr_tail = Vec::from(stderr_task.await);
+                let error_message =
+                    kernel_specification.exit_error_message(&exit_reason, &stderr_tail);
+
                 log::error!("{}", error_message);
 
                 session
@@ -309,12 +416,14 @@ impl NativeRunningKernel {
 
             anyhow::Ok(Box::new(Self {
                 process,
-                request_tx,
+                request_tx: connection.request_tx,
                 working_directory,
                 _process_status_task: Some(process_status_task),
-                _shell_task: shell_task,
-                _control_task: control_task,
-                _routing_task: routing_task,
+                _shell_task: connection.shell_task,
+                _control_task: connection.control_task,
+                _routing_task: connection.routing_task,
+                _iopub_task: connection.iopub_task,
+                _reply_task: connection.reply_task,
                 connection_path,
                 execution_state: ExecutionState::Idle,
                 kernel_info: None,
@@ -348,10 +457,14 @@ impl RunningKernel for NativeRunningKernel {
         self.kernel_info = Some(info);
     }
 
-    fn force_shutdown(&mut self, _window: &mut Window, _cx: &mut App) -> Task<anyhow::Result<()>> {
+    fn force_shutdown(&mut self) -> BoxFuture<'static, anyhow::Result<()>> {
         self._process_status_task.take();
         self.request_tx.close_channel();
-        Task::ready(self.process.kill().context("killing the kernel process"))
+        future::ready(self.process.kill().context("killing the kernel process")).boxed()
+    }
+
+    fn process_id(&self) -> Option<u32> {
+        Some(self.process.id())
     }
 }
 
@@ -512,4 +625,40 @@ mod test {
             vec!["deno", "python"]
         );
     }
+
+    #[test]
+    fn test_kernel_startup_diagnostics() {
+        let spec = LocalKernelSpecification {
+            name: "python3".to_string(),
+            path: PathBuf::from("/jupyter/kernels/python3"),
+            kernelspec: serde_json::from_value(json!({
+                "display_name": "Python 3",
+                "language": "python",
+                "argv": ["/usr/bin/python3", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+            }))
+            .unwrap(),
+        };
+
+        let message = spec.spawn_error_message(&io::Error::from(io::ErrorKind::NotFound));
+        assert!(message.contains("`/usr/bin/python3` was not found"));
+        assert!(message.contains("/jupyter/kernels/python3"));
+        assert!(
+            message
+                .contains("Command: `/usr/bin/python3 -m ipykernel_launcher -f {connection_file}`")
+        );
+
+        let message = spec.exit_error_message(
+            "process exited with exit status: 1",
+            &[
+                "Traceback (most recent call last):".to_string(),
+                "ModuleNotFoundError: No module named 'ipykernel_launcher'".to_string(),
+                String::new(),
+            ],
+        );
+        assert!(message.contains("(ModuleNotFoundError: No module named 'ipykernel_launcher')"));
+        assert!(message.contains("Run `/usr/bin/python3 -m pip install ipykernel`"));
+
+        let message = spec.exit_error_message("process exited with exit status: 1", &[]);
+        assert!(!message.contains("pip install"));
+    }
 }
@@ -1,4 +1,4 @@
-use futures::{SinkExt as _, channel::mpsc};
+use futures::{FutureExt as _, SinkExt as _, channel::mpsc, future::BoxFuture};
 use gpui::{App, AppContext as _, Entity, Task, Window};
 use http_client::{AsyncBody, HttpClient, Request};
 use jupyter_protocol::{ExecutionState, JupyterKernelspec, JupyterMessage, KernelInfoReply};
@@ -267,14 +267,14 @@ impl RunningKernel for RemoteRunningKernel {
         self.kernel_info = Some(info);
     }
 
-    fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>> {
+    fn force_shutdown(&mut self) -> BoxFuture<'static, anyhow::Result<()>> {
         let url = self
             .remote_server
             .api_url(&format!("/kernels/{}", self.kernel_id));
         let token = self.remote_server.token.clone();
         let http_client = self.http_client.clone();
 
-        window.spawn(cx, async move |_| {
+        async move {
             let request = Request::builder()
                 .method("DELETE")
                 .uri(&url)
@@ -289,6 +289,7 @@ impl RunningKernel for RemoteRunningKernel {
                 response.status()
             );
             Ok(())
-        })
+        }
+        .boxed()
     }
 }
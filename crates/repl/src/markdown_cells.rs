@@ -0,0 +1,222 @@
+//! Renders the prose of Jupytext `[markdown]` cells in place of their commented source.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use collections::HashSet;
+use editor::{
+    Anchor, Editor, MultiBuffer, ToPoint as _,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+};
+use gpui::{Entity, Subscription, Task, WeakEntity, prelude::*};
+use language::Point;
+use ui::{Tooltip, prelude::*};
+
+use crate::JupyterSettings;
+use crate::outputs::MarkdownView;
+use crate::repl_editor::markdown_cells;
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+struct RenderedCell {
+    header: Anchor,
+    text: String,
+    block_id: CustomBlockId,
+}
+
+pub struct MarkdownCells {
+    editor: WeakEntity<Editor>,
+    rendered: Vec<RenderedCell>,
+    /// Headers of the cells that were toggled to show their source.
+    raw: Vec<Anchor>,
+    _refresh_task: Task<()>,
+    _buffer_subscription: Subscription,
+}
+
+impl MarkdownCells {
+    pub fn new(
+        editor: WeakEntity<Editor>,
+        buffer: &Entity<MultiBuffer>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer_subscription = cx.subscribe(buffer, |this, _, event, cx| {
+            if let multi_buffer::Event::Edited { .. } | multi_buffer::Event::LanguageChanged(_) =
+                event
+            {
+                this.schedule_refresh(cx);
+            }
+        });
+
+        // The editor may still be mid-update when this is created, so the first render is deferred.
+        let refresh_task = cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.refresh(cx)).ok();
+        });
+
+        Self {
+            editor,
+            rendered: Vec::new(),
+            raw: Vec::new(),
+            _refresh_task: refresh_task,
+            _buffer_subscription: buffer_subscription,
+        }
+    }
+
+    fn schedule_refresh(&mut self, cx: &mut Context<Self>) {
+        self._refresh_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.refresh(cx)).ok();
+        });
+    }
+
+    /// Toggles the markdown cell containing `position` between its rendered prose and its source.
+    pub fn toggle_at(&mut self, position: Point, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.read(cx).snapshot();
+        let Some(cell) = markdown_cells(&buffer)
+            .into_iter()
+            .find(|cell| cell.header_row <= position.row && position.row <= cell.body.end.row)
+        else {
+            return;
+        };
+
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let raw_count = self.raw.len();
+        self.raw.retain(|header| {
+            header.is_valid(&snapshot) && header.to_point(&snapshot).row != cell.header_row
+        });
+        if self.raw.len() == raw_count {
+            self.raw
+                .push(snapshot.anchor_before(Point::new(cell.header_row, 0)));
+        }
+        self.refresh(cx);
+    }
+
+    fn show_source(&mut self, header: Anchor, cx: &mut Context<Self>) {
+        self.raw.push(header);
+        self.refresh(cx);
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        let cells = if JupyterSettings::enabled(cx) {
+            editor
+                .read(cx)
+                .buffer()
+                .read(cx)
+                .as_singleton()
+                .map(|buffer| markdown_cells(&buffer.read(cx).snapshot()))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        self.raw.retain(|header| header.is_valid(&snapshot));
+        let raw_rows = self
+            .raw
+            .iter()
+            .map(|header| header.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        let cells = cells
+            .into_iter()
+            .filter(|cell| !raw_rows.contains(&cell.header_row))
+            .collect::<Vec<_>>();
+
+        // Cells whose prose didn't change keep their block, so that typing elsewhere in the
+        // buffer doesn't re-render every cell.
+        let mut blocks_to_remove = HashSet::default();
+        let mut kept = Vec::new();
+        for rendered in self.rendered.drain(..) {
+            let row = rendered.header.to_point(&snapshot).row;
+            if cells
+                .iter()
+                .any(|cell| cell.header_row == row && cell.text == rendered.text)
+            {
+                kept.push(rendered);
+            } else {
+                blocks_to_remove.insert(rendered.block_id);
+            }
+        }
+
+        let mut new_cells = Vec::new();
+        let mut blocks = Vec::new();
+        for cell in cells {
+            if kept
+                .iter()
+                .any(|rendered| rendered.header.to_point(&snapshot).row == cell.header_row)
+            {
+                continue;
+            }
+
+            let view = cx.new(|cx| MarkdownView::from(cell.text.clone(), cx));
+            let header = snapshot.anchor_before(Point::new(cell.header_row, 0));
+            let body =
+                snapshot.anchor_after(cell.body.start)..=snapshot.anchor_before(cell.body.end);
+            blocks.push(BlockProperties {
+                placement: BlockPlacement::Replace(body),
+                height: Some(cell.body.end.row - cell.body.start.row + 1),
+                style: BlockStyle::Flex,
+                render: Self::create_renderer(view, header, cx.weak_entity()),
+                priority: 0,
+                render_in_minimap: false,
+            });
+            new_cells.push((header, cell.text));
+        }
+
+        editor.update(cx, |editor, cx| {
+            if !blocks_to_remove.is_empty() {
+                editor.remove_blocks(blocks_to_remove, None, cx);
+            }
+            let block_ids = editor.insert_blocks(blocks, None, cx);
+            kept.extend(
+                new_cells
+                    .into_iter()
+                    .zip(block_ids)
+                    .map(|((header, text), block_id)| RenderedCell {
+                        header,
+                        text,
+                        block_id,
+                    }),
+            );
+        });
+        self.rendered = kept;
+    }
+
+    fn create_renderer(
+        view: Entity<MarkdownView>,
+        header: Anchor,
+        markdown_cells: WeakEntity<Self>,
+    ) -> RenderBlock {
+        Arc::new(move |cx: &mut BlockContext| {
+            let markdown_cells = markdown_cells.clone();
+            let gutter_width = cx.margins.gutter.full_width();
+            div()
+                .id(cx.block_id)
+                .block_mouse_down()
+                .w_full()
+                .pl(gutter_width)
+                .pr(cx.margins.right)
+                .cursor_pointer()
+                .tooltip(Tooltip::text("Click to edit the markdown source"))
+                .on_click(move |_, _, cx| {
+                    markdown_cells
+                        .update(cx, |markdown_cells, cx| {
+                            markdown_cells.show_source(header, cx)
+                        })
+                        .ok();
+                })
+                .child(view.clone())
+                .into_any_element()
+        })
+    }
+}
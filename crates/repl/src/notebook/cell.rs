@@ -107,6 +107,7 @@ fn convert_outputs(
                 evalue: error.evalue.clone(),
                 traceback: cx
                     .new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
+                traceback_expanded: true,
             }),
         })
         .collect()
@@ -639,10 +640,18 @@ impl Render for CodeCell {
                                             Output::Table { content, .. } => {
                                                 Some(content.clone().into_any_element())
                                             }
+                                            Output::Json { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Html { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Widget { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
                                             Output::ErrorOutput(error_view) => {
-                                                error_view.render(window, cx)
+                                                error_view.render(None, window, cx)
                                             }
-                                            Output::ClearOutputWaitMarker => None,
                                         };
 
                                         div()
@@ -16,6 +16,9 @@
 //! - Markdown
 //! - Images (PNG and JPEG)
 //! - Tables
+//! - HTML, shown as its text fallback and opened in the browser on request
+//! - JSON, including Vega and Vega-Lite chart specifications
+//! - Basic Jupyter widgets, such as sliders and buttons
 //! - Error messages
 //!
 //! ## Clipboard Support
@@ -33,22 +36,34 @@
 //! This module is designed to work with Jupyter message protocols,
 //! interpreting and displaying various types of Jupyter output.
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::Duration;
 
+use collections::HashMap;
 use editor::{Editor, MultiBuffer};
 use gpui::{
-    Animation, AnimationExt, AnyElement, ClipboardItem, Entity, Render, Transformation, WeakEntity,
-    percentage,
+    Animation, AnimationExt, AnyElement, ClickEvent, ClipboardItem, Entity, FocusHandle, Focusable,
+    Render, ScrollHandle, ScrollWheelEvent, Transformation, WeakEntity, percentage,
 };
 use language::Buffer;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
+use settings::Settings as _;
 use ui::{Context, IntoElement, Styled, Tooltip, Window, div, prelude::*, v_flex};
 
+mod dataframe;
+
+mod html;
+use html::HtmlView;
+
 mod image;
 use image::ImageView;
 
+mod json;
+use json::JsonView;
+
 mod markdown;
-use markdown::MarkdownView;
+pub(crate) use markdown::MarkdownView;
 
 mod table;
 use table::TableView;
@@ -56,23 +71,81 @@ use table::TableView;
 pub mod plain;
 use plain::TerminalOutput;
 
+mod widget;
+use widget::WidgetView;
+pub(crate) use widget::{WIDGET_COMM_TARGET, WidgetModel};
+
 pub(crate) mod user_error;
-use user_error::ErrorView;
+use user_error::{ErrorView, ToggleTracebackFn};
 use workspace::Workspace;
 
+use crate::{JupyterSettings, OpenOutputInBuffer};
+
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
+        MimeType::WidgetView(_) => 8,
+        MimeType::DataTable(_) => 7,
+        MimeType::Json(_) => 6,
+        MimeType::Png(_) | MimeType::Gif(_) => 5,
+        MimeType::Jpeg(_) | MimeType::Svg(_) => 4,
+        MimeType::Markdown(_) => 3,
+        // Charts can't be drawn yet, so prefer any static rendering included alongside the spec
+        MimeType::VegaLiteV2(_)
+        | MimeType::VegaLiteV3(_)
+        | MimeType::VegaLiteV4(_)
+        | MimeType::VegaLiteV5(_)
+        | MimeType::VegaV3(_)
+        | MimeType::VegaV4(_)
+        | MimeType::VegaV5(_) => 3,
+        // HTML isn't drawn inline, but its text fallback comes with a way to open it in a browser
+        MimeType::Html(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
         _ => 0,
     }
 }
 
+fn mime_type_name(mimetype: &MimeType) -> Option<&'static str> {
+    match mimetype {
+        MimeType::Plain(_) => Some("text/plain"),
+        MimeType::Html(_) => Some("text/html"),
+        MimeType::WidgetView(_) => Some("application/vnd.jupyter.widget-view+json"),
+        MimeType::Markdown(_) => Some("text/markdown"),
+        MimeType::Png(_) => Some("image/png"),
+        MimeType::Jpeg(_) => Some("image/jpeg"),
+        MimeType::Gif(_) => Some("image/gif"),
+        MimeType::Svg(_) => Some("image/svg+xml"),
+        MimeType::Json(_) => Some("application/json"),
+        MimeType::DataTable(_) => Some("application/vnd.dataresource+json"),
+        MimeType::VegaLiteV2(_) => Some("application/vnd.vegalite.v2+json"),
+        MimeType::VegaLiteV3(_) => Some("application/vnd.vegalite.v3+json"),
+        MimeType::VegaLiteV4(_) => Some("application/vnd.vegalite.v4+json"),
+        MimeType::VegaLiteV5(_) => Some("application/vnd.vegalite.v5+json"),
+        MimeType::VegaV3(_) => Some("application/vnd.vega.v3+json"),
+        MimeType::VegaV4(_) => Some("application/vnd.vega.v4+json"),
+        MimeType::VegaV5(_) => Some("application/vnd.vega.v5+json"),
+        _ => None,
+    }
+}
+
+/// Picks the representation to render from a bundle. Media types listed in `preference` win in
+/// the order given, and everything else falls back to [`rank_mime_type`]. Types Zed can't render
+/// are never picked, even when preferred.
+fn preferred_mime_type<'a>(data: &'a MimeBundle, preference: &[String]) -> Option<&'a MimeType> {
+    data.content
+        .iter()
+        .filter(|mimetype| rank_mime_type(mimetype) > 0)
+        .max_by_key(|mimetype| {
+            let preferred_position = mime_type_name(mimetype)
+                .and_then(|name| preference.iter().position(|preferred| preferred == name));
+            (
+                preferred_position.map(|position| preference.len() - position),
+                rank_mime_type(mimetype),
+            )
+        })
+}
+
 pub(crate) trait OutputContent {
     fn clipboard_content(&self, window: &Window, cx: &App) -> Option<ClipboardItem>;
     fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
@@ -126,13 +199,26 @@ pub enum Output {
         content: Entity<MarkdownView>,
         display_id: Option<String>,
     },
-    ClearOutputWaitMarker,
+    Json {
+        content: Entity<JsonView>,
+        display_id: Option<String>,
+    },
+    Html {
+        content: Entity<HtmlView>,
+        display_id: Option<String>,
+    },
+    Widget {
+        content: Entity<WidgetView>,
+        display_id: Option<String>,
+    },
 }
 
 impl Output {
     fn render_output_controls<V: OutputContent + 'static>(
         v: Entity<V>,
         workspace: WeakEntity<Workspace>,
+        ix: usize,
+        tab_stops: &TabStops,
         window: &mut Window,
         cx: &mut Context<ExecutionView>,
     ) -> Option<AnyElement> {
@@ -146,66 +232,121 @@ impl Output {
                 .when(v.has_clipboard_content(window, cx), |el| {
                     let v = v.clone();
                     el.child(
-                        IconButton::new(ElementId::Name("copy-output".into()), IconName::Copy)
-                            .style(ButtonStyle::Transparent)
-                            .tooltip(Tooltip::text("Copy Output"))
-                            .on_click(cx.listener(move |_, _, window, cx| {
+                        tab_stops.output_control(
+                            format!("copy-output-{ix}"),
+                            "Copy Output",
+                            IconButton::new(ElementId::Name("copy-output".into()), IconName::Copy)
+                                .style(ButtonStyle::Transparent)
+                                .tooltip(Tooltip::text("Copy Output")),
+                            cx.listener(move |_, _, window, cx| {
                                 let clipboard_content = v.clipboard_content(window, cx);
 
                                 if let Some(clipboard_content) = clipboard_content.as_ref() {
                                     cx.write_to_clipboard(clipboard_content.clone());
                                 }
-                            })),
+                            }),
+                            window,
+                            cx,
+                        ),
                     )
                 })
                 .when(v.has_buffer_content(window, cx), |el| {
                     let v = v.clone();
                     el.child(
-                        IconButton::new(
-                            ElementId::Name("open-in-buffer".into()),
-                            IconName::FileText,
-                        )
-                        .style(ButtonStyle::Transparent)
-                        .tooltip(Tooltip::text("Open in Buffer"))
-                        .on_click(cx.listener({
-                            let workspace = workspace.clone();
-
-                            move |_, _, window, cx| {
-                                let buffer_content =
-                                    v.update(cx, |item, cx| item.buffer_content(window, cx));
-
-                                if let Some(buffer_content) = buffer_content.as_ref() {
-                                    let buffer = buffer_content.clone();
-                                    let editor = Box::new(cx.new(|cx| {
-                                        let multibuffer = cx.new(|cx| {
-                                            let mut multi_buffer =
-                                                MultiBuffer::singleton(buffer.clone(), cx);
-
-                                            multi_buffer.set_title("REPL Output".to_string(), cx);
-                                            multi_buffer
-                                        });
-
-                                        Editor::for_multibuffer(multibuffer, None, window, cx)
-                                    }));
-                                    workspace
-                                        .update(cx, |workspace, cx| {
-                                            workspace.add_item_to_active_pane(
-                                                editor, None, true, window, cx,
-                                            );
-                                        })
-                                        .ok();
+                        tab_stops.output_control(
+                            format!("open-in-buffer-{ix}"),
+                            "Open in Buffer",
+                            IconButton::new(
+                                ElementId::Name("open-in-buffer".into()),
+                                IconName::FileText,
+                            )
+                            .style(ButtonStyle::Transparent)
+                            .tooltip(Tooltip::text("Open in Buffer")),
+                            cx.listener({
+                                let workspace = workspace.clone();
+
+                                move |_, _, window, cx| {
+                                    let buffer_content =
+                                        v.update(cx, |item, cx| item.buffer_content(window, cx));
+
+                                    if let Some(buffer_content) = buffer_content.as_ref() {
+                                        let buffer = buffer_content.clone();
+                                        let editor = Box::new(cx.new(|cx| {
+                                            let multibuffer = cx.new(|cx| {
+                                                let mut multi_buffer =
+                                                    MultiBuffer::singleton(buffer.clone(), cx);
+
+                                                multi_buffer
+                                                    .set_title("REPL Output".to_string(), cx);
+                                                multi_buffer
+                                            });
+
+                                            Editor::for_multibuffer(multibuffer, None, window, cx)
+                                        }));
+                                        workspace
+                                            .update(cx, |workspace, cx| {
+                                                workspace.add_item_to_active_pane(
+                                                    editor, None, true, window, cx,
+                                                );
+                                            })
+                                            .ok();
+                                    }
                                 }
-                            }
-                        })),
+                            }),
+                            window,
+                            cx,
+                        ),
                     )
                 })
                 .into_any_element(),
         )
     }
 
+    /// This output as Markdown, with text in fenced code blocks so that it keeps its layout.
+    pub fn to_markdown(&self, window: &Window, cx: &App) -> Option<String> {
+        let clipboard_text = |content: Option<ClipboardItem>| content.and_then(|item| item.text());
+        match self {
+            Self::Plain { content, .. } | Self::Stream { content } => {
+                let text = content.read(cx).full_text();
+                let text = text.trim_end();
+                (!text.is_empty()).then(|| fenced_code_block(text, ""))
+            }
+            Self::ErrorOutput(error_view) => {
+                let traceback = error_view.traceback.read(cx).full_text();
+                let text = if traceback.trim().is_empty() {
+                    format!("{}: {}", error_view.ename, error_view.evalue)
+                } else {
+                    traceback.trim_end().to_string()
+                };
+                Some(fenced_code_block(&text, "error"))
+            }
+            Self::Image { content, .. } => {
+                let image = content.read(cx);
+                let alt_text = image
+                    .alt_text()
+                    .map(|text| text.replace(['[', ']', '\n'], " "))
+                    .unwrap_or_else(|| "output".to_string());
+                Some(format!("![{alt_text}]({})", image.data_uri()))
+            }
+            Self::Markdown { content, .. } => clipboard_text(content.clipboard_content(window, cx)),
+            Self::Table { content, .. } => clipboard_text(content.clipboard_content(window, cx)),
+            Self::Json { content, .. } => clipboard_text(content.clipboard_content(window, cx))
+                .map(|text| fenced_code_block(&text, "json")),
+            Self::Html { content, .. } => clipboard_text(content.clipboard_content(window, cx))
+                .map(|text| fenced_code_block(&text, "html")),
+            Self::Widget { content, .. } => content
+                .read(cx)
+                .fallback_text(cx)
+                .map(|text| fenced_code_block(text.trim_end(), "")),
+            Self::Message(message) => Some(format!("_{message}_")),
+        }
+    }
+
     pub fn render(
         &self,
         workspace: WeakEntity<Workspace>,
+        ix: usize,
+        tab_stops: &TabStops,
         window: &mut Window,
         cx: &mut Context<ExecutionView>,
     ) -> impl IntoElement + use<> {
@@ -216,8 +357,24 @@ impl Output {
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
-            Self::ErrorOutput(error_view) => error_view.render(window, cx),
-            Self::ClearOutputWaitMarker => None,
+            Self::Json { content, .. } => Some(content.clone().into_any_element()),
+            Self::Html { content, .. } => Some(content.clone().into_any_element()),
+            Self::Widget { content, .. } => Some(content.clone().into_any_element()),
+            Self::ErrorOutput(error_view) => {
+                let traceback_id = error_view.traceback.entity_id();
+                let on_toggle_traceback: ToggleTracebackFn =
+                    Box::new(cx.listener(move |execution_view, _, _, cx| {
+                        for output in &mut execution_view.outputs {
+                            if let Output::ErrorOutput(error_view) = output {
+                                if error_view.traceback.entity_id() == traceback_id {
+                                    error_view.traceback_expanded = !error_view.traceback_expanded;
+                                }
+                            }
+                        }
+                        cx.notify();
+                    }));
+                error_view.render(Some(on_toggle_traceback), window, cx)
+            }
         };
 
         h_flex()
@@ -225,32 +382,186 @@ impl Output {
             .items_start()
             .child(div().flex_1().children(content))
             .children(match self {
-                Self::Plain { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
-                }
-                Self::Markdown { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
-                }
-                Self::Stream { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
-                }
-                Self::Image { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
-                }
+                Self::Plain { content, .. } | Self::Stream { content } => Some(
+                    h_flex()
+                        .pl_1()
+                        .child(Self::render_wrap_toggle(
+                            content.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .children(Self::render_output_controls(
+                            content.clone(),
+                            workspace.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .into_any_element(),
+                ),
+                Self::Markdown { content, .. } => Self::render_output_controls(
+                    content.clone(),
+                    workspace.clone(),
+                    ix,
+                    tab_stops,
+                    window,
+                    cx,
+                ),
+                Self::Image { content, .. } => Some(
+                    h_flex()
+                        .children(Self::render_output_controls(
+                            content.clone(),
+                            workspace.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .child(Self::render_copy_data_uri_button(
+                            content.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .into_any_element(),
+                ),
                 Self::ErrorOutput(err) => Self::render_output_controls(
                     err.traceback.clone(),
                     workspace.clone(),
+                    ix,
+                    tab_stops,
                     window,
                     cx,
                 ),
-                Self::Message(_) => None,
-                Self::Table { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
-                }
-                Self::ClearOutputWaitMarker => None,
+                Self::Message(_) | Self::Widget { .. } => None,
+                Self::Table { content, .. } => Self::render_output_controls(
+                    content.clone(),
+                    workspace.clone(),
+                    ix,
+                    tab_stops,
+                    window,
+                    cx,
+                ),
+                Self::Json { content, .. } => Self::render_output_controls(
+                    content.clone(),
+                    workspace.clone(),
+                    ix,
+                    tab_stops,
+                    window,
+                    cx,
+                ),
+                Self::Html { content, .. } => Some(
+                    h_flex()
+                        .children(Self::render_output_controls(
+                            content.clone(),
+                            workspace.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .child(Self::render_open_in_browser_button(
+                            content.clone(),
+                            workspace.clone(),
+                            ix,
+                            tab_stops,
+                            window,
+                            cx,
+                        ))
+                        .into_any_element(),
+                ),
             })
     }
 
+    fn render_wrap_toggle(
+        content: Entity<TerminalOutput>,
+        ix: usize,
+        tab_stops: &TabStops,
+        window: &mut Window,
+        cx: &mut Context<ExecutionView>,
+    ) -> impl IntoElement {
+        let wraps = content.read(cx).wraps();
+        let label = if wraps {
+            "Scroll Wide Lines"
+        } else {
+            "Wrap Lines"
+        };
+        tab_stops.output_control(
+            format!("toggle-output-wrap-{ix}"),
+            label,
+            IconButton::new(
+                ElementId::named_usize("toggle-output-wrap", ix),
+                IconName::ArrowRightLeft,
+            )
+            .style(ButtonStyle::Transparent)
+            .toggle_state(!wraps)
+            .tooltip(Tooltip::text(label)),
+            cx.listener(move |this, _, window, cx| this.set_wrap_choice(!wraps, window, cx)),
+            window,
+            cx,
+        )
+    }
+
+    fn render_copy_data_uri_button(
+        content: Entity<ImageView>,
+        ix: usize,
+        tab_stops: &TabStops,
+        window: &mut Window,
+        cx: &mut Context<ExecutionView>,
+    ) -> impl IntoElement {
+        tab_stops.output_control(
+            format!("copy-image-data-uri-{ix}"),
+            "Copy as Data URI",
+            IconButton::new(
+                ElementId::Name("copy-image-data-uri".into()),
+                IconName::Link,
+            )
+            .style(ButtonStyle::Transparent)
+            .tooltip(Tooltip::text("Copy as Data URI")),
+            cx.listener(move |_, _, _, cx| {
+                let data_uri = content.read(cx).data_uri();
+                cx.write_to_clipboard(ClipboardItem::new_string(data_uri));
+            }),
+            window,
+            cx,
+        )
+    }
+
+    fn render_open_in_browser_button(
+        content: Entity<HtmlView>,
+        workspace: WeakEntity<Workspace>,
+        ix: usize,
+        tab_stops: &TabStops,
+        window: &mut Window,
+        cx: &mut Context<ExecutionView>,
+    ) -> impl IntoElement {
+        tab_stops.output_control(
+            format!("open-in-browser-{ix}"),
+            "Open in Browser",
+            IconButton::new(
+                ElementId::Name("open-in-browser".into()),
+                IconName::ArrowUpRight,
+            )
+            .style(ButtonStyle::Transparent)
+            .tooltip(Tooltip::text("Open in Browser")),
+            cx.listener(move |_, _, _, cx| {
+                let Some(workspace) = workspace.upgrade() else {
+                    return;
+                };
+                let fs = workspace.read(cx).app_state().fs.clone();
+                content
+                    .update(cx, |content, cx| content.open_in_browser(fs, cx))
+                    .detach_and_log_err(cx);
+            }),
+            window,
+            cx,
+        )
+    }
+
     pub fn display_id(&self) -> Option<String> {
         match self {
             Output::Plain { display_id, .. } => display_id.clone(),
@@ -260,7 +571,9 @@ impl Output {
             Output::Message(_) => None,
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
-            Output::ClearOutputWaitMarker => None,
+            Output::Json { display_id, .. } => display_id.clone(),
+            Output::Html { display_id, .. } => display_id.clone(),
+            Output::Widget { display_id, .. } => display_id.clone(),
         }
     }
 
@@ -270,11 +583,46 @@ impl Output {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
-        match data.richest(rank_mime_type) {
+        let mimetype =
+            preferred_mime_type(data, &JupyterSettings::get_global(cx).mime_type_preference);
+        match mimetype {
             Some(MimeType::Plain(text)) => Output::Plain {
                 content: cx.new(|cx| TerminalOutput::from(text, window, cx)),
                 display_id,
             },
+            Some(MimeType::Html(html)) => {
+                let fallback = data.content.iter().find_map(|mimetype| match mimetype {
+                    MimeType::Plain(text) => Some(text),
+                    _ => None,
+                });
+                let fallback =
+                    fallback.map(|text| cx.new(|cx| TerminalOutput::from(text, window, cx)));
+                let scripts = data
+                    .content
+                    .iter()
+                    .filter_map(|mimetype| match mimetype {
+                        MimeType::Javascript(script) => Some(script.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                Output::Html {
+                    content: cx
+                        .new(|cx| HtmlView::new(html.clone(), scripts, fallback, window, cx)),
+                    display_id,
+                }
+            }
+            Some(MimeType::WidgetView(view)) => {
+                let fallback = data.content.iter().find_map(|mimetype| match mimetype {
+                    MimeType::Plain(text) => Some(text),
+                    _ => None,
+                });
+                let fallback =
+                    fallback.map(|text| cx.new(|cx| TerminalOutput::from(text, window, cx)));
+                Output::Widget {
+                    content: cx.new(|_| WidgetView::new(view, fallback)),
+                    display_id,
+                }
+            }
             Some(MimeType::Markdown(text)) => {
                 let content = cx.new(|cx| MarkdownView::from(text.clone(), cx));
                 Output::Markdown {
@@ -282,23 +630,221 @@ impl Output {
                     display_id,
                 }
             }
-            Some(MimeType::Png(data)) | Some(MimeType::Jpeg(data)) => match ImageView::from(data) {
-                Ok(view) => Output::Image {
-                    content: cx.new(|_| view),
-                    display_id,
-                },
+            Some(MimeType::Png(image_data))
+            | Some(MimeType::Jpeg(image_data))
+            | Some(MimeType::Gif(image_data)) => match ImageView::from(image_data) {
+                Ok(view) => {
+                    let alt_text = data.content.iter().find_map(|mimetype| match mimetype {
+                        MimeType::Plain(text) => Some(text.clone()),
+                        _ => None,
+                    });
+                    let autoplay = JupyterSettings::get_global(cx).autoplay_animations;
+                    Output::Image {
+                        content: cx.new(|_| view.with_alt_text(alt_text).with_autoplay(autoplay)),
+                        display_id,
+                    }
+                }
+                Err(error) => Output::Message(format!("Failed to load image: {}", error)),
+            },
+            Some(MimeType::Svg(svg)) => match ImageView::from_svg(svg, cx) {
+                Ok(view) => {
+                    let alt_text = data.content.iter().find_map(|mimetype| match mimetype {
+                        MimeType::Plain(text) => Some(text.clone()),
+                        _ => None,
+                    });
+                    Output::Image {
+                        content: cx.new(|_| view.with_alt_text(alt_text)),
+                        display_id,
+                    }
+                }
                 Err(error) => Output::Message(format!("Failed to load image: {}", error)),
             },
             Some(MimeType::DataTable(data)) => Output::Table {
                 content: cx.new(|cx| TableView::new(data, window, cx)),
                 display_id,
             },
+            Some(MimeType::Json(value)) => Output::Json {
+                content: cx.new(|_| JsonView::new(value.clone())),
+                display_id,
+            },
+            Some(
+                MimeType::VegaLiteV2(spec)
+                | MimeType::VegaLiteV3(spec)
+                | MimeType::VegaLiteV4(spec)
+                | MimeType::VegaLiteV5(spec),
+            ) => Output::Json {
+                content: cx.new(|_| {
+                    JsonView::new(spec.clone())
+                        .with_title("Vega-Lite chart (showing the specification)")
+                }),
+                display_id,
+            },
+            Some(MimeType::VegaV3(spec) | MimeType::VegaV4(spec) | MimeType::VegaV5(spec)) => {
+                Output::Json {
+                    content: cx.new(|_| {
+                        JsonView::new(spec.clone())
+                            .with_title("Vega chart (showing the specification)")
+                    }),
+                    display_id,
+                }
+            }
             // Any other media types are not supported
             _ => Output::Message("Unsupported media type".to_string()),
         }
     }
 }
 
+/// Wraps `text` in a fence longer than any run of backticks inside it, so it can't close early.
+pub fn fenced_code_block(text: &str, language: &str) -> String {
+    let longest_backtick_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_backtick_run.max(2) + 1);
+    format!("{fence}{language}\n{text}\n{fence}")
+}
+
+struct TabStop {
+    focus_handle: FocusHandle,
+    label: SharedString,
+}
+
+#[derive(Default)]
+struct TabStopsState {
+    focus_handles: RefCell<HashMap<SharedString, FocusHandle>>,
+    /// The block's own buttons in the gutter, which come before the output's.
+    block_controls: RefCell<Vec<TabStop>>,
+    output_controls: RefCell<Vec<TabStop>>,
+}
+
+/// The buttons of an output block that tab moves through and enter presses, in the order they're
+/// rendered. Tooltips only show on hover, so the focused button's label is shown in the block.
+#[derive(Clone, Default)]
+pub struct TabStops(Rc<TabStopsState>);
+
+impl TabStops {
+    pub fn clear_block_controls(&self) {
+        self.0.block_controls.borrow_mut().clear();
+    }
+
+    fn clear_output_controls(&self) {
+        self.0.output_controls.borrow_mut().clear();
+    }
+
+    /// Adds one of the block's gutter buttons, which are reached before the output's.
+    pub fn block_control(
+        &self,
+        id: impl Into<SharedString>,
+        label: impl Into<SharedString>,
+        button: impl Clickable + IntoElement,
+        on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+        window: &Window,
+        cx: &App,
+    ) -> AnyElement {
+        self.control(true, id, label, button, on_click, window, cx)
+    }
+
+    pub fn output_control(
+        &self,
+        id: impl Into<SharedString>,
+        label: impl Into<SharedString>,
+        button: impl Clickable + IntoElement,
+        on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+        window: &Window,
+        cx: &App,
+    ) -> AnyElement {
+        self.control(false, id, label, button, on_click, window, cx)
+    }
+
+    fn control(
+        &self,
+        in_gutter: bool,
+        id: impl Into<SharedString>,
+        label: impl Into<SharedString>,
+        button: impl Clickable + IntoElement,
+        on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+        window: &Window,
+        cx: &App,
+    ) -> AnyElement {
+        let focus_handle = self
+            .0
+            .focus_handles
+            .borrow_mut()
+            .entry(id.into())
+            .or_insert_with(|| cx.focus_handle())
+            .clone();
+        let controls = if in_gutter {
+            &self.0.block_controls
+        } else {
+            &self.0.output_controls
+        };
+        controls.borrow_mut().push(TabStop {
+            focus_handle: focus_handle.clone(),
+            label: label.into(),
+        });
+
+        let border_color = if focus_handle.is_focused(window) {
+            cx.theme().colors().border_focused
+        } else {
+            gpui::transparent_black()
+        };
+        let on_click = Rc::new(on_click);
+        div()
+            .track_focus(&focus_handle)
+            .on_action({
+                let on_click = on_click.clone();
+                move |_: &menu::Confirm, window, cx| on_click(&ClickEvent::default(), window, cx)
+            })
+            .rounded_sm()
+            .border_1()
+            .border_color(border_color)
+            .child(button.on_click(move |event, window, cx| on_click(event, window, cx)))
+            .into_any_element()
+    }
+
+    /// Focuses the next button, or the first one when none is focused, wrapping around.
+    pub fn focus_next(&self, window: &mut Window) {
+        self.focus_by(1, window);
+    }
+
+    /// Focuses the previous button, or the last one when none is focused, wrapping around.
+    pub fn focus_previous(&self, window: &mut Window) {
+        self.focus_by(-1, window);
+    }
+
+    fn focus_by(&self, step: isize, window: &mut Window) {
+        let focus_handles = self
+            .0
+            .block_controls
+            .borrow()
+            .iter()
+            .chain(self.0.output_controls.borrow().iter())
+            .map(|stop| stop.focus_handle.clone())
+            .collect::<Vec<_>>();
+        if focus_handles.is_empty() {
+            return;
+        }
+        let count = focus_handles.len() as isize;
+        let next_ix = match focus_handles
+            .iter()
+            .position(|focus_handle| focus_handle.is_focused(window))
+        {
+            Some(ix) => (ix as isize + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        window.focus(&focus_handles[next_ix as usize]);
+    }
+
+    /// The label of the focused button, if one is.
+    pub fn focused_label(&self, window: &Window) -> Option<SharedString> {
+        self.0
+            .block_controls
+            .borrow()
+            .iter()
+            .chain(self.0.output_controls.borrow().iter())
+            .find(|stop| stop.focus_handle.is_focused(window))
+            .map(|stop| stop.label.clone())
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub enum ExecutionStatus {
     #[default]
@@ -311,6 +857,7 @@ pub enum ExecutionStatus {
     Shutdown,
     KernelErrored(String),
     Restarting,
+    Cancelled,
 }
 
 /// An ExecutionView shows the outputs of an execution.
@@ -321,19 +868,278 @@ pub struct ExecutionView {
     workspace: WeakEntity<Workspace>,
     pub outputs: Vec<Output>,
     pub status: ExecutionStatus,
+    /// This execution's 1-based position among those waiting for the kernel, while queued.
+    pub queue_position: Option<usize>,
+    /// Set by `clear_output(wait=True)`: the outputs are replaced when the next one arrives, so
+    /// that redrawing them, as animations do, doesn't flicker.
+    clear_on_next_output: bool,
+    /// Whether the code that produced these outputs has since been edited.
+    pub stale: bool,
+    /// Whether the user pinned these outputs, which keeps them when their code is edited or run
+    /// again.
+    pub pinned: bool,
+    scroll_handle: ScrollHandle,
+    /// Whether new output scrolls the block to the bottom. Cleared when the user scrolls up, and
+    /// set again once they scroll back down to the end.
+    follow_tail: bool,
+    /// Lets this block grow past `max_output_lines`, when the user asked to see all of it.
+    show_full_height: bool,
+    /// The text output of the run this one replaced, kept when `diff_rerun_output` is enabled.
+    previous_output: Option<String>,
+    /// Whether the outputs are shown as a diff against `previous_output`.
+    show_diff: bool,
+    /// The lines of that diff, each marked as added, removed or unchanged. Worked out when the
+    /// diff is first shown, and again after the outputs change.
+    diff_rows: Option<Vec<(Option<bool>, String)>>,
+    /// Whether the user chose to wrap this block's text, which its later outputs and the run
+    /// that replaces it follow too.
+    wrap_choice: Option<bool>,
+    /// Lets keyboard users move into the block to copy or close it.
+    focus_handle: FocusHandle,
+    pub tab_stops: TabStops,
 }
 
 impl ExecutionView {
     pub fn new(
         status: ExecutionStatus,
         workspace: WeakEntity<Workspace>,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Self {
         Self {
             workspace,
             outputs: Default::default(),
             status,
+            queue_position: None,
+            clear_on_next_output: false,
+            stale: false,
+            pinned: false,
+            scroll_handle: ScrollHandle::new(),
+            follow_tail: !JupyterSettings::get_global(cx).pin_output_scroll,
+            show_full_height: false,
+            previous_output: None,
+            show_diff: false,
+            diff_rows: None,
+            wrap_choice: None,
+            focus_handle: cx.focus_handle(),
+            tab_stops: TabStops::default(),
+        }
+    }
+
+    /// What the status of this execution is, in words, for where only an icon is shown.
+    pub fn status_description(&self) -> String {
+        match &self.status {
+            ExecutionStatus::Unknown => "Unknown status".to_string(),
+            ExecutionStatus::ConnectingToKernel => "Connecting to kernel".to_string(),
+            ExecutionStatus::Queued => "Queued".to_string(),
+            ExecutionStatus::Executing => "Running".to_string(),
+            ExecutionStatus::Finished => {
+                if self
+                    .outputs
+                    .iter()
+                    .any(|output| matches!(output, Output::ErrorOutput(_)))
+                {
+                    "Finished with an error".to_string()
+                } else {
+                    "Finished".to_string()
+                }
+            }
+            ExecutionStatus::ShuttingDown => "Kernel shutting down".to_string(),
+            ExecutionStatus::Shutdown => "Kernel shut down".to_string(),
+            ExecutionStatus::KernelErrored(error) => format!("Kernel error: {error}"),
+            ExecutionStatus::Restarting => "Kernel restarting".to_string(),
+            ExecutionStatus::Cancelled => "Cancelled".to_string(),
+        }
+    }
+
+    fn copy(&mut self, _: &editor::actions::Copy, _: &mut Window, cx: &mut Context<Self>) {
+        let text = self.text_output(cx);
+        if !text.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    fn open_in_buffer(
+        &mut self,
+        action: &OpenOutputInBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text = self.outputs_text(action.raw, cx);
+        if text.is_empty() {
+            return;
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer(&text, Some(language::PLAIN_TEXT.clone()), cx)
+            });
+            let editor = cx.new(|cx| Editor::for_buffer(buffer, Some(project), window, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        });
+    }
+
+    fn toggle_full_height(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_full_height = !self.show_full_height;
+        cx.notify();
+    }
+
+    pub fn wrap_choice(&self) -> Option<bool> {
+        self.wrap_choice
+    }
+
+    /// Wraps or unwraps the text of every output in this block, and of those added later.
+    pub fn set_wrap_choice(&mut self, wrap: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.wrap_choice = Some(wrap);
+        for output in &self.outputs {
+            self.apply_wrap_choice(output, window, cx);
         }
+        cx.notify();
+    }
+
+    fn apply_wrap_choice(&self, output: &Output, window: &mut Window, cx: &mut App) {
+        let Some(wrap) = self.wrap_choice else {
+            return;
+        };
+        if let Output::Plain { content, .. } | Output::Stream { content } = output {
+            content.update(cx, |content, cx| content.set_wrap(wrap, window, cx));
+        }
+    }
+
+    pub fn set_previous_output(&mut self, text: String, cx: &mut Context<Self>) {
+        self.previous_output = Some(text);
+        self.diff_rows = None;
+        cx.notify();
+    }
+
+    fn toggle_diff(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_diff = !self.show_diff;
+        cx.notify();
+    }
+
+    /// The text of this execution's outputs, leaving out those that aren't text, like images.
+    pub fn text_output(&self, cx: &App) -> String {
+        self.outputs_text(false, cx)
+    }
+
+    /// The text of the outputs either as shown, or as the kernel sent it, escape sequences
+    /// included.
+    fn outputs_text(&self, raw: bool, cx: &App) -> String {
+        let text = |output: &Entity<TerminalOutput>| {
+            let output = output.read(cx);
+            if raw {
+                output.raw_text().to_string()
+            } else {
+                output.full_text()
+            }
+        };
+
+        self.outputs
+            .iter()
+            .filter_map(|output| match output {
+                Output::Plain { content, .. } | Output::Stream { content } => Some(text(content)),
+                Output::ErrorOutput(error_view) => {
+                    let traceback = text(&error_view.traceback);
+                    Some(if traceback.trim().is_empty() {
+                        format!("{}: {}", error_view.ename, error_view.evalue)
+                    } else {
+                        traceback
+                    })
+                }
+                _ => None,
+            })
+            .map(|text| text.trim_end().to_string())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_diff(&mut self, cx: &App) -> AnyElement {
+        if self.diff_rows.is_none() {
+            let previous_output = self.previous_output.as_deref().unwrap_or_default();
+            self.diff_rows = Some(diff_rows(previous_output, &self.text_output(cx)));
+        }
+        let rows = self.diff_rows.as_deref().unwrap_or_default();
+
+        let status_colors = cx.theme().status();
+        v_flex()
+            .w_full()
+            .py_1()
+            .children(rows.iter().map(|(added, line)| {
+                let (prefix, background) = match *added {
+                    Some(true) => ("+", Some(status_colors.created_background)),
+                    Some(false) => ("-", Some(status_colors.deleted_background)),
+                    None => (" ", None),
+                };
+                h_flex()
+                    .w_full()
+                    .px_1()
+                    .when_some(background, |el, background| el.bg(background))
+                    .child(Label::new(format!("{prefix} {line}")).buffer_font(cx))
+            }))
+            .into_any_element()
+    }
+
+    fn outputs_changed(&mut self, cx: &mut Context<Self>) {
+        if self.follow_tail {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    fn handle_scroll(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if JupyterSettings::get_global(cx).pin_output_scroll {
+            return;
+        }
+
+        let max_offset = (self.scroll_handle.padded_content_size().height
+            - self.scroll_handle.bounds().size.height)
+            .max(px(0.));
+        let delta = event.delta.pixel_delta(window.line_height()).y;
+        let offset = (self.scroll_handle.offset().y + delta).clamp(-max_offset, px(0.));
+        self.follow_tail = offset <= -max_offset + px(1.);
+    }
+
+    pub fn set_stale(&mut self, stale: bool, cx: &mut Context<Self>) {
+        if self.stale != stale {
+            self.stale = stale;
+            cx.notify();
+        }
+    }
+
+    pub fn set_pinned(&mut self, pinned: bool, cx: &mut Context<Self>) {
+        if self.pinned != pinned {
+            self.pinned = pinned;
+            if pinned {
+                self.stale = false;
+            }
+            cx.notify();
+        }
+    }
+
+    /// The text of a finished execution whose only output is a short, single-line text result,
+    /// which is small enough to show at the end of the code line rather than in a block.
+    pub fn inline_result(&self, max_length: usize, cx: &App) -> Option<String> {
+        if !matches!(self.status, ExecutionStatus::Finished) {
+            return None;
+        }
+        let [Output::Plain { content, .. }] = self.outputs.as_slice() else {
+            return None;
+        };
+        let text = content.read(cx).full_text();
+        let text = text.trim();
+        if text.is_empty() || text.contains('\n') || text.chars().count() > max_length {
+            return None;
+        }
+        Some(text.to_string())
     }
 
     /// Accept a Jupyter message belonging to this execution
@@ -343,6 +1149,20 @@ impl ExecutionView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.diff_rows = None;
+        if self.clear_on_next_output
+            && matches!(
+                message,
+                JupyterMessageContent::ExecuteResult(_)
+                    | JupyterMessageContent::DisplayData(_)
+                    | JupyterMessageContent::StreamContent(_)
+                    | JupyterMessageContent::ErrorOutput(_)
+            )
+        {
+            self.clear_on_next_output = false;
+            self.outputs.clear();
+        }
+
         let output: Output = match message {
             JupyterMessageContent::ExecuteResult(result) => Output::new(
                 &result.data,
@@ -361,6 +1181,9 @@ impl ExecutionView {
                 if let Some(new_terminal) = self.apply_terminal_text(&result.text, window, cx) {
                     new_terminal
                 } else {
+                    // The output block is sized from this view, so it must re-render as the
+                    // stream grows, not just the terminal inside it.
+                    self.outputs_changed(cx);
                     return;
                 }
             }
@@ -372,27 +1195,28 @@ impl ExecutionView {
                     ename: result.ename.clone(),
                     evalue: result.evalue.clone(),
                     traceback: terminal,
+                    traceback_expanded: false,
                 })
             }
             JupyterMessageContent::ExecuteReply(reply) => {
                 for payload in reply.payload.iter() {
                     if let runtimelib::Payload::Page { data, .. } = payload {
                         let output = Output::new(data, None, window, cx);
+                        self.apply_wrap_choice(&output, window, cx);
                         self.outputs.push(output);
                     }
                 }
-                cx.notify();
+                self.outputs_changed(cx);
                 return;
             }
             JupyterMessageContent::ClearOutput(options) => {
-                if !options.wait {
+                if options.wait {
+                    self.clear_on_next_output = true;
+                } else {
                     self.outputs.clear();
                     cx.notify();
-                    return;
                 }
-
-                // Create a marker to clear the output after we get in a new output
-                Output::ClearOutputWaitMarker
+                return;
             }
             JupyterMessageContent::Status(status) => {
                 match status.execution_state {
@@ -409,16 +1233,29 @@ impl ExecutionView {
             }
         };
 
-        // Check for a clear output marker as the previous output, so we can clear it out
-        if let Some(output) = self.outputs.last() {
-            if let Output::ClearOutputWaitMarker = output {
-                self.outputs.clear();
-            }
-        }
-
+        self.apply_wrap_choice(&output, window, cx);
         self.outputs.push(output);
 
-        cx.notify();
+        self.outputs_changed(cx);
+    }
+
+    /// Connects the widgets shown here to their models, for those whose models are known.
+    pub fn bind_widgets(
+        &mut self,
+        models: &HashMap<String, Entity<WidgetModel>>,
+        cx: &mut Context<Self>,
+    ) {
+        for output in &self.outputs {
+            if let Output::Widget { content, .. } = output {
+                let view = content.read(cx);
+                if view.is_bound() {
+                    continue;
+                }
+                if let Some(model) = models.get(view.model_id()).cloned() {
+                    content.update(cx, |view, cx| view.bind(model, cx));
+                }
+            }
+        }
     }
 
     pub fn update_display_data(
@@ -430,16 +1267,17 @@ impl ExecutionView {
     ) {
         let mut any = false;
 
-        self.outputs.iter_mut().for_each(|output| {
-            if let Some(other_display_id) = output.display_id().as_ref() {
-                if other_display_id == display_id {
-                    *output = Output::new(data, Some(display_id.to_owned()), window, cx);
-                    any = true;
-                }
+        for ix in 0..self.outputs.len() {
+            if self.outputs[ix].display_id().as_deref() == Some(display_id) {
+                let output = Output::new(data, Some(display_id.to_owned()), window, cx);
+                self.apply_wrap_choice(&output, window, cx);
+                self.outputs[ix] = output;
+                any = true;
             }
-        });
+        }
 
         if any {
+            self.diff_rows = None;
             cx.notify();
         }
     }
@@ -471,8 +1309,46 @@ impl ExecutionView {
     }
 }
 
+impl Focusable for ExecutionView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
 impl Render for ExecutionView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let border_color = if self.focus_handle.is_focused(window) {
+            cx.theme().colors().border_focused
+        } else {
+            gpui::transparent_black()
+        };
+
+        self.tab_stops.clear_output_controls();
+        let content = self.render_content(window, cx);
+        let focused_label = self.tab_stops.focused_label(window);
+
+        div()
+            .key_context("ReplOutput")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::open_in_buffer))
+            .w_full()
+            .border_1()
+            .border_color(border_color)
+            .child(content)
+            .when_some(focused_label, |el, label| {
+                el.child(
+                    Label::new(label)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+    }
+}
+
+impl ExecutionView {
+    fn render_content(&mut self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let tab_stops = self.tab_stops.clone();
         let status = match &self.status {
             ExecutionStatus::ConnectingToKernel => Label::new("Connecting to kernel...")
                 .color(Color::Muted)
@@ -491,8 +1367,10 @@ impl Render for ExecutionView {
                 )
                 .child(Label::new("Executing...").color(Color::Muted))
                 .into_any_element(),
-            ExecutionStatus::Finished => Icon::new(IconName::Check)
-                .size(IconSize::Small)
+            ExecutionStatus::Finished => div()
+                .id("execution-status")
+                .tooltip(Tooltip::text(self.status_description()))
+                .child(Icon::new(IconName::Check).size(IconSize::Small))
                 .into_any_element(),
             ExecutionStatus::Unknown => Label::new("Unknown status")
                 .color(Color::Muted)
@@ -506,7 +1384,13 @@ impl Render for ExecutionView {
             ExecutionStatus::Shutdown => Label::new("Kernel shutdown")
                 .color(Color::Muted)
                 .into_any_element(),
-            ExecutionStatus::Queued => Label::new("Queued...")
+            ExecutionStatus::Queued => Label::new(match self.queue_position {
+                Some(position) => format!("{} in queue", ordinal(position)),
+                None => "Queued...".to_string(),
+            })
+            .color(Color::Muted)
+            .into_any_element(),
+            ExecutionStatus::Cancelled => Label::new("Cancelled")
                 .color(Color::Muted)
                 .into_any_element(),
             ExecutionStatus::KernelErrored(error) => Label::new(format!("Kernel error: {}", error))
@@ -522,18 +1406,540 @@ impl Render for ExecutionView {
                 .into_any_element();
         }
 
-        div()
+        // The editor sizes the block to what's rendered here, so capping the content's height is
+        // what keeps the block itself from growing.
+        let max_output_lines = JupyterSettings::get_global(cx).max_output_lines;
+        let max_height =
+            (max_output_lines > 0).then(|| window.line_height() * max_output_lines as f32);
+        let too_tall = max_height
+            .is_some_and(|max_height| self.scroll_handle.padded_content_size().height > max_height);
+        let has_previous_output =
+            self.previous_output.is_some() && matches!(self.status, ExecutionStatus::Finished);
+        let diff = (has_previous_output && self.show_diff).then(|| self.render_diff(cx));
+
+        v_flex()
             .w_full()
-            .children(
-                self.outputs
-                    .iter()
-                    .map(|output| output.render(self.workspace.clone(), window, cx)),
+            .when(self.pinned, |el| {
+                el.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Pin)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new("Pinned, so it no longer follows edits to its code")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .id("execution-view-outputs")
+                    .w_full()
+                    .when_some(
+                        max_height.filter(|_| !self.show_full_height),
+                        |el, max_height| el.max_h(max_height),
+                    )
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .on_scroll_wheel(cx.listener(Self::handle_scroll))
+                    .when(self.stale, |el| el.opacity(0.5))
+                    .map(|el| match diff {
+                        Some(diff) => el.child(diff),
+                        None => el.children(self.outputs.iter().enumerate().map(|(ix, output)| {
+                            output.render(self.workspace.clone(), ix, &tab_stops, window, cx)
+                        })),
+                    })
+                    .children(match self.status {
+                        ExecutionStatus::Executing => vec![status],
+                        ExecutionStatus::Queued => vec![status],
+                        _ => vec![],
+                    }),
             )
-            .children(match self.status {
-                ExecutionStatus::Executing => vec![status],
-                ExecutionStatus::Queued => vec![status],
-                _ => vec![],
+            .when(too_tall || has_previous_output, |el| {
+                el.child(
+                    h_flex()
+                        .gap_1()
+                        .when(has_previous_output, |el| {
+                            let label = if self.show_diff {
+                                "Hide Diff"
+                            } else {
+                                "Diff with Previous Run"
+                            };
+                            el.child(
+                                tab_stops.output_control(
+                                    "toggle-output-diff",
+                                    label,
+                                    Button::new("toggle-output-diff", label)
+                                        .style(ButtonStyle::Subtle)
+                                        .label_size(LabelSize::Small)
+                                        .icon(IconName::Diff)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Muted)
+                                        .icon_position(IconPosition::Start),
+                                    cx.listener(Self::toggle_diff),
+                                    window,
+                                    cx,
+                                ),
+                            )
+                        })
+                        .when(too_tall, |el| {
+                            el.child(tab_stops.output_control(
+                                "open-output-in-buffer",
+                                "Open the text as it's shown",
+                                Button::new("open-output-in-buffer", "Open in Buffer")
+                                    .style(ButtonStyle::Subtle)
+                                    .label_size(LabelSize::Small)
+                                    .icon(IconName::FileText)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon_color(Color::Muted)
+                                    .icon_position(IconPosition::Start)
+                                    .tooltip(Tooltip::text("Open the text as it's shown")),
+                                cx.listener(|this, _, window, cx| {
+                                    this.open_in_buffer(
+                                        &OpenOutputInBuffer { raw: false },
+                                        window,
+                                        cx,
+                                    )
+                                }),
+                                window,
+                                cx,
+                            ))
+                            .child(tab_stops.output_control(
+                                "open-raw-output-in-buffer",
+                                "Open the text with the escape sequences the kernel sent",
+                                Button::new("open-raw-output-in-buffer", "Open Raw")
+                                    .style(ButtonStyle::Subtle)
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::text(
+                                        "Open the text with the escape sequences the kernel sent",
+                                    )),
+                                cx.listener(|this, _, window, cx| {
+                                    this.open_in_buffer(
+                                        &OpenOutputInBuffer { raw: true },
+                                        window,
+                                        cx,
+                                    )
+                                }),
+                                window,
+                                cx,
+                            ))
+                        })
+                        .when(too_tall, |el| {
+                            let (label, icon) = if self.show_full_height {
+                                ("Limit Height", IconName::ChevronUp)
+                            } else {
+                                ("Show Full Height", IconName::ChevronDown)
+                            };
+                            el.child(
+                                tab_stops.output_control(
+                                    "toggle-full-height",
+                                    label,
+                                    Button::new("toggle-full-height", label)
+                                        .style(ButtonStyle::Subtle)
+                                        .label_size(LabelSize::Small)
+                                        .icon(icon)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Muted)
+                                        .icon_position(IconPosition::Start),
+                                    cx.listener(Self::toggle_full_height),
+                                    window,
+                                    cx,
+                                ),
+                            )
+                        }),
+                )
             })
             .into_any_element()
     }
 }
+
+/// The lines of `new` interleaved with those removed from `old`, each marked as added (`true`),
+/// removed (`false`) or unchanged (`None`).
+fn diff_rows(old: &str, new: &str) -> Vec<(Option<bool>, String)> {
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+
+    let mut rows = Vec::new();
+    let mut push_lines = |lines: Option<&[&str]>, added: Option<bool>| {
+        rows.extend(
+            lines
+                .into_iter()
+                .flatten()
+                .map(|line| (added, line.to_string())),
+        );
+    };
+    let mut new_row = 0;
+    for (old_range, new_range) in language::line_diff(old, new) {
+        push_lines(new_lines.get(new_row..new_range.start as usize), None);
+        push_lines(
+            old_lines.get(old_range.start as usize..old_range.end as usize),
+            Some(false),
+        );
+        push_lines(
+            new_lines.get(new_range.start as usize..new_range.end as usize),
+            Some(true),
+        );
+        new_row = new_range.end as usize;
+    }
+    push_lines(new_lines.get(new_row..), None);
+    rows
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::VisualTestContext;
+    use gpui::{TestAppContext, VisualContext as _};
+    use jupyter_protocol::{ClearOutput, Stdio, StreamContent};
+    use settings::SettingsStore;
+    use std::{cell::Cell, rc::Rc};
+
+    fn init_test(cx: &mut TestAppContext) -> (Entity<ExecutionView>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let cx = cx.add_empty_window();
+
+        let execution_view = cx.new_window_entity(|_, cx| {
+            ExecutionView::new(ExecutionStatus::Executing, WeakEntity::new_invalid(), cx)
+        });
+        (execution_view, cx)
+    }
+
+    fn push_message(
+        execution_view: &Entity<ExecutionView>,
+        message: JupyterMessageContent,
+        cx: &mut VisualTestContext,
+    ) {
+        cx.update(|window, cx| {
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.push_message(&message, window, cx);
+            });
+        });
+    }
+
+    fn stream(text: &str) -> JupyterMessageContent {
+        JupyterMessageContent::StreamContent(StreamContent {
+            name: Stdio::Stdout,
+            text: text.to_string(),
+        })
+    }
+
+    fn stream_text(execution_view: &Entity<ExecutionView>, cx: &mut VisualTestContext) -> String {
+        cx.update(|window, cx| {
+            let execution_view = execution_view.read(cx);
+            assert_eq!(execution_view.outputs.len(), 1);
+            let Some(Output::Stream { content }) = execution_view.outputs.first() else {
+                panic!("expected a single stream output");
+            };
+            content
+                .read(cx)
+                .clipboard_content(window, cx)
+                .and_then(|item| item.text())
+                .unwrap_or_default()
+        })
+    }
+
+    #[gpui::test]
+    fn test_stream_output_updates_incrementally(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        let notifications = Rc::new(Cell::new(0));
+        cx.update(|_, cx| {
+            let notifications = notifications.clone();
+            cx.observe(&execution_view, move |_, _| {
+                notifications.set(notifications.get() + 1)
+            })
+            .detach();
+        });
+
+        for (ix, line) in ["step 1", "step 2", "step 3"].into_iter().enumerate() {
+            push_message(&execution_view, stream(&format!("{line}\n")), cx);
+
+            assert_eq!(notifications.get(), ix + 1);
+            let output_text = stream_text(&execution_view, cx);
+            assert!(
+                output_text.contains(line),
+                "{line:?} missing from {output_text:?}"
+            );
+        }
+    }
+
+    #[gpui::test]
+    fn test_rerun_diff_is_kept_until_the_outputs_change(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+        let diff_rows = |execution_view: &Entity<ExecutionView>, cx: &mut VisualTestContext| {
+            cx.update(|_, cx| {
+                execution_view.update(cx, |execution_view, cx| {
+                    execution_view.render_diff(cx);
+                    execution_view.diff_rows.clone().unwrap_or_default()
+                })
+            })
+        };
+
+        cx.update(|_, cx| {
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.set_previous_output("a\nb".to_string(), cx);
+            });
+        });
+        push_message(&execution_view, stream("a\nc\n"), cx);
+        assert_eq!(
+            diff_rows(&execution_view, cx),
+            [
+                (None, "a".to_string()),
+                (Some(false), "b".to_string()),
+                (Some(true), "c".to_string()),
+            ]
+        );
+
+        push_message(&execution_view, stream("d\n"), cx);
+        execution_view.read_with(cx, |execution_view, _| {
+            assert!(execution_view.diff_rows.is_none());
+        });
+        assert_eq!(
+            diff_rows(&execution_view, cx).last(),
+            Some(&(Some(true), "d".to_string()))
+        );
+    }
+
+    #[gpui::test]
+    fn test_later_outputs_follow_wrap_choice(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        cx.update(|window, cx| {
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.set_wrap_choice(false, window, cx);
+            });
+        });
+        push_message(&execution_view, stream("hello\n"), cx);
+
+        execution_view.read_with(cx, |execution_view, cx| {
+            let [Output::Stream { content }] = execution_view.outputs.as_slice() else {
+                panic!("expected a single stream output");
+            };
+            assert!(!content.read(cx).wraps());
+        });
+    }
+
+    #[gpui::test]
+    fn test_clear_output_waits_for_next_output(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        push_message(&execution_view, stream("frame 1\n"), cx);
+        push_message(
+            &execution_view,
+            JupyterMessageContent::ClearOutput(ClearOutput { wait: true }),
+            cx,
+        );
+        assert!(stream_text(&execution_view, cx).contains("frame 1"));
+
+        push_message(&execution_view, stream("frame 2\n"), cx);
+        let output_text = stream_text(&execution_view, cx);
+        assert!(output_text.contains("frame 2"));
+        assert!(!output_text.contains("frame 1"));
+
+        push_message(
+            &execution_view,
+            JupyterMessageContent::ClearOutput(ClearOutput { wait: false }),
+            cx,
+        );
+        cx.update(|_, cx| assert!(execution_view.read(cx).outputs.is_empty()));
+    }
+
+    #[gpui::test]
+    fn test_svg_image_data_uri_keeps_source(cx: &mut TestAppContext) {
+        use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+        let (_, cx) = init_test(cx);
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="2"></svg>"#;
+
+        cx.update(|_, cx| {
+            let image = ImageView::from_svg(svg, cx).unwrap();
+            assert_eq!(
+                image.data_uri(),
+                format!("data:image/svg+xml;base64,{}", STANDARD.encode(svg))
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_raw_output_keeps_escape_sequences(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        push_message(&execution_view, stream("\x1b[31mred\x1b[0m\n"), cx);
+
+        cx.update(|_, cx| {
+            let execution_view = execution_view.read(cx);
+            assert_eq!(execution_view.outputs_text(false, cx), "red");
+            assert_eq!(execution_view.outputs_text(true, cx), "\x1b[31mred\x1b[0m");
+        });
+    }
+
+    #[gpui::test]
+    fn test_raw_output_drops_lines_past_the_scrollback(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        let text = (0..12_000)
+            .map(|ix| format!("line {ix}\n"))
+            .collect::<String>();
+        push_message(&execution_view, stream(&text), cx);
+
+        cx.update(|_, cx| {
+            let raw_text = execution_view.read(cx).outputs_text(true, cx);
+            assert!(raw_text.lines().count() < 12_000);
+            assert!(!raw_text.starts_with("line 0\n"));
+            assert!(raw_text.ends_with("line 11999"));
+        });
+    }
+
+    #[test]
+    fn test_gif_frames_are_kept() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD};
+        use image::{Frame, RgbaImage, codecs::gif::GifEncoder};
+
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut bytes);
+            encoder
+                .encode_frames([
+                    Frame::new(RgbaImage::from_pixel(3, 2, [255, 0, 0, 255].into())),
+                    Frame::new(RgbaImage::from_pixel(3, 2, [0, 0, 255, 255].into())),
+                ])
+                .unwrap();
+        }
+
+        let image = ImageView::from(&STANDARD.encode(&bytes)).unwrap();
+        assert!(image.is_animated());
+    }
+
+    #[gpui::test]
+    fn test_text_output_skips_empty_outputs(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        cx.update(|_, cx| assert_eq!(execution_view.read(cx).text_output(cx), ""));
+
+        push_message(&execution_view, stream("row 1\nrow 2\n"), cx);
+        cx.update(|_, cx| {
+            let text = execution_view.read(cx).text_output(cx);
+            assert!(text.contains("row 1") && text.contains("row 2"), "{text:?}");
+            assert!(!text.ends_with('\n'), "{text:?}");
+        });
+    }
+
+    #[gpui::test]
+    fn test_widget_outputs_bind_to_their_models(cx: &mut TestAppContext) {
+        let (execution_view, cx) = init_test(cx);
+
+        let bundle: MimeBundle = serde_json::from_value(serde_json::json!({
+            "application/vnd.jupyter.widget-view+json": {
+                "model_id": "slider-comm",
+                "version_major": 2,
+                "version_minor": 0,
+            },
+            "text/plain": "IntSlider(value=3)",
+        }))
+        .unwrap();
+        cx.update(|window, cx| {
+            let output = Output::new(&bundle, None, window, cx);
+            execution_view.update(cx, |execution_view, _| execution_view.outputs.push(output));
+        });
+
+        let widget = cx.update(|_, cx| match execution_view.read(cx).outputs.first() {
+            Some(Output::Widget { content, .. }) => content.clone(),
+            _ => panic!("expected a widget output"),
+        });
+        cx.update(|_, cx| {
+            assert!(!widget.read(cx).is_bound());
+            assert_eq!(
+                widget
+                    .read(cx)
+                    .fallback_text(cx)
+                    .as_deref()
+                    .map(str::trim_end),
+                Some("IntSlider(value=3)")
+            );
+        });
+
+        cx.update(|_, cx| {
+            let model = cx.new(|_| {
+                WidgetModel::new(
+                    "slider-comm".to_string(),
+                    serde_json::Map::default(),
+                    WeakEntity::new_invalid(),
+                )
+            });
+            let mut models = HashMap::default();
+            models.insert("slider-comm".to_string(), model);
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.bind_widgets(&models, cx)
+            });
+            assert!(widget.read(cx).is_bound());
+        });
+    }
+
+    #[test]
+    fn test_parse_dataframe_html() {
+        let html = indoc::indoc! {r#"
+            <div>
+            <style scoped>
+                .dataframe tbody tr th { vertical-align: top; }
+            </style>
+            <table border="1" class="dataframe">
+              <thead>
+                <tr style="text-align: right;">
+                  <th></th>
+                  <th></th>
+                  <th>name</th>
+                  <th>score</th>
+                </tr>
+              </thead>
+              <tbody>
+                <tr>
+                  <th rowspan="2">a</th>
+                  <th>0</th>
+                  <td>Alice</td>
+                  <td>1,024.5</td>
+                </tr>
+                <tr>
+                  <th>1</th>
+                  <td>Bob</td>
+                  <td>NaN</td>
+                </tr>
+              </tbody>
+            </table>
+            </div>
+        "#};
+
+        let table = dataframe::DataFrameTable::parse(html).unwrap();
+        assert_eq!(table.header_rows, vec![vec!["", "", "name", "score"]]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["a", "0", "Alice", "1,024.5"],
+                vec!["", "1", "Bob", "NaN"]
+            ]
+        );
+        assert_eq!(table.index_columns, 2);
+        assert!(!table.is_numeric_column(2));
+        assert!(table.is_numeric_column(3));
+
+        assert!(dataframe::DataFrameTable::parse("<table><tr><td>1</td></tr></table>").is_none());
+    }
+}
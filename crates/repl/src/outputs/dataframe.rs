@@ -0,0 +1,392 @@
+//! Pandas renders DataFrames as HTML tables with the `dataframe` class. HTML can't be drawn inside
+//! the editor, so those tables are parsed and drawn as a grid instead.
+
+use gpui::{AnyElement, FontWeight, TextRun};
+use html5ever::tendril::TendrilSink as _;
+use html5ever::{ParseOpts, parse_document};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{ElevationIndex, prelude::*};
+
+/// How many rows are shown at first, and how many more each "Show more" click reveals.
+const ROW_PAGE_SIZE: usize = 50;
+
+/// How many rows fit before the rows scroll beneath the header.
+const MAX_VISIBLE_ROWS: f32 = 15.;
+
+/// The HTML spec caps `colspan` at 1000, which also keeps a bogus value from allocating a huge row.
+const MAX_COLSPAN: usize = 1000;
+
+const CELL_X_PADDING: Pixels = px(16.);
+const CELL_Y_PADDING_MULTIPLE: f32 = 0.5;
+
+/// The cells of a DataFrame's HTML table, with spanning cells spread over every row and column
+/// they cover.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataFrameTable {
+    pub header_rows: Vec<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+    /// How many leading columns hold the row labels, which pandas writes as `<th>` cells.
+    pub index_columns: usize,
+}
+
+#[derive(Clone)]
+struct GridCell {
+    text: String,
+    is_header: bool,
+}
+
+impl DataFrameTable {
+    /// Parses the first table with the `dataframe` class in the given HTML, if there is one.
+    pub fn parse(html: &str) -> Option<Self> {
+        if !html.contains("dataframe") {
+            return None;
+        }
+
+        let dom = parse_document(RcDom::default(), ParseOpts::default()).one(html);
+        let table = find_dataframe_table(&dom.document)?;
+
+        let mut header_rows = Vec::new();
+        let mut body_rows = Vec::new();
+        for section in table.children.borrow().iter() {
+            match element_name(section).as_deref() {
+                Some("thead") => header_rows.extend(child_elements(section, "tr")),
+                Some("tbody") | Some("tfoot") => body_rows.extend(child_elements(section, "tr")),
+                _ => {}
+            }
+        }
+
+        let header_rows = layout_rows(&header_rows);
+        let body_rows = layout_rows(&body_rows);
+        let index_columns = body_rows
+            .first()
+            .map(|row| row.iter().take_while(|cell| cell.is_header).count())
+            .unwrap_or_default();
+
+        let column_count = header_rows
+            .iter()
+            .chain(body_rows.iter())
+            .map(|row| row.len())
+            .max()
+            .unwrap_or_default();
+        let into_texts = |rows: Vec<Vec<GridCell>>| {
+            rows.into_iter()
+                .map(|row| {
+                    let mut texts = row.into_iter().map(|cell| cell.text).collect::<Vec<_>>();
+                    texts.resize(column_count, String::new());
+                    texts
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Some(Self {
+            header_rows: into_texts(header_rows),
+            rows: into_texts(body_rows),
+            index_columns,
+        })
+    }
+
+    /// Whether every value in the column is a number, ignoring the placeholders pandas shows for
+    /// missing and elided values.
+    pub fn is_numeric_column(&self, column: usize) -> bool {
+        if column < self.index_columns {
+            return false;
+        }
+
+        let mut has_number = false;
+        for row in &self.rows {
+            let Some(text) = row.get(column) else {
+                continue;
+            };
+            if matches!(text.as_str(), "" | "..." | "<NA>" | "None" | "NaT") {
+                continue;
+            }
+            if text.replace(',', "").parse::<f64>().is_err() {
+                return false;
+            }
+            has_number = true;
+        }
+        has_number
+    }
+}
+
+fn element_name(node: &Handle) -> Option<String> {
+    match &node.data {
+        NodeData::Element { name, .. } => Some(name.local.to_string()),
+        _ => None,
+    }
+}
+
+fn attribute(node: &Handle, attribute: &str) -> Option<String> {
+    match &node.data {
+        NodeData::Element { attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .find(|attr| &*attr.name.local == attribute)
+            .map(|attr| attr.value.to_string()),
+        _ => None,
+    }
+}
+
+fn child_elements(node: &Handle, name: &str) -> Vec<Handle> {
+    node.children
+        .borrow()
+        .iter()
+        .filter(|child| element_name(child).as_deref() == Some(name))
+        .cloned()
+        .collect()
+}
+
+fn find_dataframe_table(node: &Handle) -> Option<Handle> {
+    if element_name(node).as_deref() == Some("table")
+        && attribute(node, "class")
+            .is_some_and(|class| class.split_whitespace().any(|class| class == "dataframe"))
+    {
+        return Some(node.clone());
+    }
+
+    node.children.borrow().iter().find_map(find_dataframe_table)
+}
+
+fn text_content(node: &Handle, text: &mut String) {
+    if let NodeData::Text { contents } = &node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        text_content(child, text);
+    }
+}
+
+/// Lays the rows' cells out on a grid. Cells spanning several columns are repeated in each of
+/// them, while the rows beneath a cell spanning several rows are left blank, the way pandas
+/// shows repeated labels of a multi-level index.
+fn layout_rows(rows: &[Handle]) -> Vec<Vec<GridCell>> {
+    // By column, whether the cell spanning down over it is a header and how many more rows it covers.
+    let mut row_spans: Vec<Option<(bool, usize)>> = Vec::new();
+
+    rows.iter()
+        .map(|row| {
+            let children = row.children.borrow();
+            let mut cells = children.iter().filter_map(|cell| {
+                let is_header = match element_name(cell).as_deref() {
+                    Some("th") => true,
+                    Some("td") => false,
+                    _ => return None,
+                };
+                let span = |name| {
+                    attribute(cell, name)
+                        .and_then(|span| span.trim().parse::<usize>().ok())
+                        .unwrap_or(1)
+                        .max(1)
+                };
+                let mut text = String::new();
+                text_content(cell, &mut text);
+                Some((
+                    GridCell {
+                        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                        is_header,
+                    },
+                    span("colspan").min(MAX_COLSPAN),
+                    span("rowspan"),
+                ))
+            });
+
+            let mut grid_row = Vec::new();
+            loop {
+                let column = grid_row.len();
+                if let Some(Some((is_header, remaining_rows))) = row_spans.get(column).copied() {
+                    grid_row.push(GridCell {
+                        text: String::new(),
+                        is_header,
+                    });
+                    row_spans[column] =
+                        (remaining_rows > 1).then_some((is_header, remaining_rows - 1));
+                    continue;
+                }
+
+                let Some((cell, colspan, rowspan)) = cells.next() else {
+                    break;
+                };
+                for _ in 0..colspan {
+                    let column = grid_row.len();
+                    if rowspan > 1 {
+                        if row_spans.len() <= column {
+                            row_spans.resize(column + 1, None);
+                        }
+                        row_spans[column] = Some((cell.is_header, rowspan - 1));
+                    }
+                    grid_row.push(cell.clone());
+                }
+            }
+            grid_row
+        })
+        .collect()
+}
+
+/// Draws a DataFrame's table with its header kept in view while the rows scroll.
+pub struct DataFrameView {
+    table: DataFrameTable,
+    numeric_columns: Vec<bool>,
+    widths: Vec<Pixels>,
+    visible_rows: usize,
+}
+
+impl DataFrameView {
+    pub fn new(table: DataFrameTable, window: &mut Window, cx: &mut App) -> Self {
+        let text_style = window.text_style();
+        let font_size = ThemeSettings::get_global(cx).buffer_font_size(cx);
+        let mut run = TextRun {
+            len: 0,
+            font: ThemeSettings::get_global(cx).buffer_font.clone(),
+            color: text_style.color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+
+        let column_count = table
+            .header_rows
+            .first()
+            .or(table.rows.first())
+            .map_or(0, Vec::len);
+        let widths = (0..column_count)
+            .map(|column| {
+                table
+                    .header_rows
+                    .iter()
+                    .chain(table.rows.iter())
+                    .filter_map(|row| row.get(column))
+                    .map(|text| {
+                        run.len = text.len();
+                        window
+                            .text_system()
+                            .layout_line(text, font_size, &[run.clone()])
+                            .width
+                    })
+                    .fold(px(0.), Pixels::max)
+            })
+            .collect();
+        let numeric_columns = (0..column_count)
+            .map(|column| table.is_numeric_column(column))
+            .collect();
+
+        Self {
+            table,
+            numeric_columns,
+            widths,
+            visible_rows: ROW_PAGE_SIZE,
+        }
+    }
+
+    fn render_row(
+        &self,
+        cells: &[String],
+        is_header: bool,
+        is_striped: bool,
+        line_height: Pixels,
+        cx: &App,
+    ) -> AnyElement {
+        h_flex()
+            .when(is_striped, |row| {
+                row.bg(ElevationIndex::EditorSurface.darker_bg(cx))
+            })
+            .children(
+                cells
+                    .iter()
+                    .zip(&self.widths)
+                    .enumerate()
+                    .map(|(column, (text, width))| {
+                        let is_label = is_header || column < self.table.index_columns;
+                        let is_numeric = !is_header
+                            && self.numeric_columns.get(column).copied().unwrap_or(false);
+                        h_flex()
+                            .flex_none()
+                            .w(*width + CELL_X_PADDING)
+                            .px_2()
+                            .py(line_height * (CELL_Y_PADDING_MULTIPLE / 2.))
+                            .when(is_numeric, |cell| cell.justify_end())
+                            .when(is_label, |cell| cell.font_weight(FontWeight::SEMIBOLD))
+                            .child(text.clone())
+                    }),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for DataFrameView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let line_height = window.line_height();
+        let total_rows = self.table.rows.len();
+        let shown_rows = self.visible_rows.min(total_rows);
+        let table_width = self
+            .widths
+            .iter()
+            .fold(px(0.), |total, width| total + *width + CELL_X_PADDING);
+
+        let header = self
+            .table
+            .header_rows
+            .iter()
+            .map(|row| self.render_row(row, true, false, line_height, cx))
+            .collect::<Vec<_>>();
+        let rows = self
+            .table
+            .rows
+            .iter()
+            .take(shown_rows)
+            .enumerate()
+            .map(|(ix, row)| self.render_row(row, false, ix % 2 == 1, line_height, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .gap_1()
+            .child(
+                div().id("dataframe").w_full().overflow_x_scroll().child(
+                    v_flex()
+                        .w(table_width)
+                        .child(
+                            v_flex()
+                                .border_b_1()
+                                .border_color(cx.theme().colors().border)
+                                .children(header),
+                        )
+                        .child(
+                            v_flex()
+                                .id("dataframe-rows")
+                                .max_h(
+                                    line_height * (1. + CELL_Y_PADDING_MULTIPLE) * MAX_VISIBLE_ROWS,
+                                )
+                                .overflow_y_scroll()
+                                .children(rows),
+                        ),
+                ),
+            )
+            .when(shown_rows < total_rows, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(format!("Showing {shown_rows} of {total_rows} rows"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Button::new(
+                                "show-more-rows",
+                                format!(
+                                    "Show {} More",
+                                    (total_rows - shown_rows).min(ROW_PAGE_SIZE)
+                                ),
+                            )
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.visible_rows += ROW_PAGE_SIZE;
+                                cx.notify();
+                            })),
+                        ),
+                )
+            })
+    }
+}
@@ -0,0 +1,110 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Context, Entity, Task, Window, div, prelude::*};
+use project::Fs;
+use ui::{Color, Label, LabelCommon as _};
+use uuid::Uuid;
+
+use crate::outputs::OutputContent;
+use crate::outputs::dataframe::{DataFrameTable, DataFrameView};
+use crate::outputs::plain::TerminalOutput;
+
+/// HTML can't be drawn inside the editor, so this shows the text representation the kernel sent
+/// alongside it and keeps the markup around to be opened in a browser. Pandas DataFrames are the
+/// exception, since their tables are simple enough to draw as a grid.
+pub struct HtmlView {
+    html: String,
+    scripts: Vec<String>,
+    fallback: Option<Entity<TerminalOutput>>,
+    dataframe: Option<Entity<DataFrameView>>,
+    /// The pages written by [`HtmlView::open_in_browser`], removed once the output is dropped
+    /// along with its session.
+    written_files: Vec<PathBuf>,
+}
+
+impl HtmlView {
+    pub fn new(
+        html: String,
+        scripts: Vec<String>,
+        fallback: Option<Entity<TerminalOutput>>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let dataframe = DataFrameTable::parse(&html)
+            .map(|table| cx.new(|cx| DataFrameView::new(table, window, cx)));
+        Self {
+            html,
+            scripts,
+            fallback,
+            dataframe,
+            written_files: Vec::new(),
+        }
+    }
+
+    /// A standalone page for the output. The markup is written out as is, including any `<script>`
+    /// tags, and `application/javascript` outputs from the same bundle are appended so that
+    /// interactive widgets run once the page loads.
+    pub fn document(&self) -> String {
+        let mut document = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>REPL Output</title>\n</head>\n<body>\n",
+        );
+        document.push_str(&self.html);
+        for script in &self.scripts {
+            document.push_str("\n<script>\n");
+            document.push_str(script);
+            document.push_str("\n</script>");
+        }
+        document.push_str("\n</body>\n</html>\n");
+        document
+    }
+
+    pub fn open_in_browser(&mut self, fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let document = self.document();
+        let path = std::env::temp_dir()
+            .join("zed-repl")
+            .join(format!("output-{}.html", Uuid::new_v4()));
+
+        cx.spawn(async move |this, cx| {
+            if let Some(dir) = path.parent() {
+                fs.create_dir(dir).await?;
+            }
+            fs.atomic_write(path.clone(), document).await?;
+            this.update(cx, |this, cx| {
+                cx.open_with_system(&path);
+                this.written_files.push(path);
+            })?;
+            anyhow::Ok(())
+        })
+    }
+}
+
+impl Drop for HtmlView {
+    fn drop(&mut self) {
+        for path in &self.written_files {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
+
+impl OutputContent for HtmlView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.html.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
+
+impl Render for HtmlView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        if let Some(dataframe) = &self.dataframe {
+            return div().child(dataframe.clone());
+        }
+        match &self.fallback {
+            Some(fallback) => div().child(fallback.clone()),
+            None => div().child(Label::new("HTML output").color(Color::Muted)),
+        }
+    }
+}
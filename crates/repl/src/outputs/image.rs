@@ -1,20 +1,28 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use base64::{
     Engine as _, alphabet,
-    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig, general_purpose::STANDARD},
 };
 use gpui::{App, ClipboardItem, Image, ImageFormat, Pixels, RenderImage, Window, img};
-use std::sync::Arc;
-use ui::{IntoElement, Styled, div, prelude::*};
+use image::AnimationDecoder as _;
+use std::{io::Cursor, sync::Arc};
+use ui::{IntoElement, Styled, Tooltip, div, prelude::*};
 
 use crate::outputs::OutputContent;
 
 /// ImageView renders an image inline in an editor, adapting to the line height to fit the image.
+///
+/// Animated GIFs play while they're drawn, so they stop using CPU once scrolled out of view or
+/// collapsed.
 pub struct ImageView {
     clipboard_image: Arc<Image>,
     height: u32,
     width: u32,
     image: Arc<RenderImage>,
+    /// A description of the image, taken from the plain text the kernel sent alongside it.
+    alt_text: Option<SharedString>,
+    /// Whether an animated image is playing, rather than showing its first frame.
+    playing: bool,
 }
 
 pub const STANDARD_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
@@ -32,17 +40,27 @@ impl ImageView {
 
         let format = image::guess_format(&bytes)?;
 
-        let mut data = image::load_from_memory_with_format(&bytes, format)?.into_rgba8();
+        let mut frames = if format == image::ImageFormat::Gif {
+            image::codecs::gif::GifDecoder::new(Cursor::new(&bytes))?
+                .into_frames()
+                .collect_frames()?
+        } else {
+            let data = image::load_from_memory_with_format(&bytes, format)?.into_rgba8();
+            vec![image::Frame::new(data)]
+        };
 
         // Convert from RGBA to BGRA.
-        for pixel in data.chunks_exact_mut(4) {
-            pixel.swap(0, 2);
+        for frame in &mut frames {
+            for pixel in frame.buffer_mut().chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
         }
 
-        let height = data.height();
-        let width = data.width();
+        let first_frame = frames.first().context("image has no frames")?.buffer();
+        let height = first_frame.height();
+        let width = first_frame.width();
 
-        let gpui_image_data = RenderImage::new(vec![image::Frame::new(data)]);
+        let gpui_image_data = RenderImage::new(frames);
 
         let format = match format {
             image::ImageFormat::Png => ImageFormat::Png,
@@ -64,12 +82,65 @@ impl ImageView {
             height,
             width,
             image: Arc::new(gpui_image_data),
+            alt_text: None,
+            playing: true,
+        })
+    }
+
+    /// Rasterizes an SVG for display, keeping its source for copying.
+    pub fn from_svg(svg: &str, cx: &App) -> Result<Self> {
+        let clipboard_image =
+            Arc::new(Image::from_bytes(ImageFormat::Svg, svg.as_bytes().to_vec()));
+        let image = clipboard_image.to_image_data(cx.svg_renderer())?;
+        let size = image.size(0);
+
+        Ok(ImageView {
+            clipboard_image,
+            height: size.height.0.max(0) as u32,
+            width: size.width.0.max(0) as u32,
+            image,
+            alt_text: None,
+            playing: true,
         })
     }
+
+    pub fn with_alt_text(mut self, alt_text: Option<String>) -> Self {
+        self.alt_text = alt_text
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .map(SharedString::from);
+        self
+    }
+
+    pub fn alt_text(&self) -> Option<&str> {
+        self.alt_text.as_deref()
+    }
+
+    /// Sets whether an animated image starts playing as soon as it's shown.
+    pub fn with_autoplay(mut self, autoplay: bool) -> Self {
+        self.playing = autoplay;
+        self
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.image.frame_count() > 1
+    }
+
+    /// The image as a `data:` URI, which Markdown renderers can show without a separate file.
+    ///
+    /// This encodes the bytes the kernel sent rather than the decoded pixels, so nothing is lost
+    /// to re-encoding.
+    pub fn data_uri(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.clipboard_image.format.mime_type(),
+            STANDARD.encode(&self.clipboard_image.bytes)
+        )
+    }
 }
 
 impl Render for ImageView {
-    fn render(&mut self, window: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let line_height = window.line_height();
 
         let (height, width) = if self.height as f32 / line_height.0 == u8::MAX as f32 {
@@ -80,9 +151,52 @@ impl Render for ImageView {
             (self.height as f32, self.width as f32)
         };
 
-        let image = self.image.clone();
-
-        div().h(Pixels(height)).w(Pixels(width)).child(img(image))
+        let image = img(self.image.clone());
+        let is_animated = self.is_animated();
+        let playing = self.playing;
+
+        div()
+            .id("image-output")
+            .relative()
+            .h(Pixels(height))
+            .w(Pixels(width))
+            .when_some(self.alt_text.clone(), |el, alt_text| {
+                el.tooltip(Tooltip::text(alt_text))
+            })
+            // Images only advance their frames when they're given an id to keep their state in.
+            // Without one, the first frame is shown, so stopping rewinds the animation.
+            .map(|el| {
+                if is_animated && playing {
+                    el.child(image.id("image-output-animation"))
+                } else {
+                    el.child(image)
+                }
+            })
+            .when(is_animated, |el| {
+                el.child(
+                    div().absolute().top_1().right_1().child(
+                        IconButton::new(
+                            "toggle-animation",
+                            if playing {
+                                IconName::Stop
+                            } else {
+                                IconName::Play
+                            },
+                        )
+                        .icon_size(IconSize::Small)
+                        .style(ButtonStyle::Filled)
+                        .tooltip(Tooltip::text(if playing {
+                            "Stop Animation"
+                        } else {
+                            "Play Animation"
+                        }))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.playing = !this.playing;
+                            cx.notify();
+                        })),
+                    ),
+                )
+            })
     }
 }
 
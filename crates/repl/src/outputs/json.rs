@@ -0,0 +1,185 @@
+//! # JSON Output for REPL
+//!
+//! Renders `application/json` display data as a tree whose objects and arrays can be
+//! expanded and collapsed, similar to the inspector found in browser devtools.
+
+use collections::HashSet;
+use gpui::{AnyElement, ClipboardItem, Entity};
+use language::Buffer;
+use serde_json::Value;
+use ui::{Disclosure, prelude::*};
+
+use crate::outputs::OutputContent;
+
+pub struct JsonView {
+    title: Option<SharedString>,
+    value: Value,
+    raw_text: String,
+    /// JSON pointers of the objects and arrays that are expanded. The root starts expanded.
+    expanded_paths: HashSet<String>,
+}
+
+impl JsonView {
+    pub fn new(value: Value) -> Self {
+        // Some kernels send JSON that was already serialized to a string.
+        let value = match value {
+            Value::String(text) => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+            value => value,
+        };
+        let raw_text = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+        let mut expanded_paths = HashSet::default();
+        expanded_paths.insert(String::new());
+
+        Self {
+            title: None,
+            value,
+            raw_text,
+            expanded_paths,
+        }
+    }
+
+    /// Shows a caption above the tree, for JSON that represents something else, like a chart.
+    pub fn with_title(mut self, title: impl Into<SharedString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    fn toggle(&mut self, path: &str, cx: &mut Context<Self>) {
+        if !self.expanded_paths.remove(path) {
+            self.expanded_paths.insert(path.to_string());
+        }
+        cx.notify();
+    }
+
+    fn render_node(
+        &self,
+        key: Option<String>,
+        value: &Value,
+        path: String,
+        depth: usize,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let key_label = key.map(|key| {
+            Label::new(format!("{key}:"))
+                .buffer_font(cx)
+                .color(Color::Muted)
+        });
+
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(ix, v)| (ix.to_string(), v))
+                .collect(),
+            _ => {
+                rows.push(
+                    h_flex()
+                        .pl(px(16. * depth as f32 + 16.))
+                        .gap_1()
+                        .children(key_label)
+                        .child(render_scalar(value, cx))
+                        .into_any_element(),
+                );
+                return;
+            }
+        };
+
+        let is_expanded = self.expanded_paths.contains(&path);
+        let summary = match value {
+            Value::Object(_) if is_expanded => "{".to_string(),
+            Value::Array(_) if is_expanded => "[".to_string(),
+            Value::Object(map) => format!("{{…}} {} keys", map.len()),
+            _ => format!("[…] {} items", children.len()),
+        };
+
+        rows.push(
+            h_flex()
+                .pl(px(16. * depth as f32))
+                .gap_1()
+                .child(
+                    Disclosure::new(ElementId::Name(format!("json-{path}").into()), is_expanded)
+                        .on_click(cx.listener({
+                            let path = path.clone();
+                            move |this, _, _, cx| this.toggle(&path, cx)
+                        })),
+                )
+                .children(key_label)
+                .child(Label::new(summary).buffer_font(cx).color(Color::Muted))
+                .into_any_element(),
+        );
+
+        if !is_expanded {
+            return;
+        }
+
+        for (child_key, child) in children {
+            let child_path = format!("{path}/{}", child_key.replace('~', "~0").replace('/', "~1"));
+            self.render_node(Some(child_key), child, child_path, depth + 1, rows, cx);
+        }
+
+        rows.push(
+            h_flex()
+                .pl(px(16. * depth as f32 + 16.))
+                .child(
+                    Label::new(if value.is_object() { "}" } else { "]" })
+                        .buffer_font(cx)
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
+        );
+    }
+}
+
+fn render_scalar(value: &Value, cx: &App) -> Label {
+    let (text, color) = match value {
+        Value::String(text) => (format!("{text:?}"), Color::Success),
+        Value::Number(number) => (number.to_string(), Color::Info),
+        Value::Bool(flag) => (flag.to_string(), Color::Accent),
+        Value::Null => ("null".to_string(), Color::Muted),
+        _ => (value.to_string(), Color::Default),
+    };
+    Label::new(text).buffer_font(cx).color(color)
+}
+
+impl Render for JsonView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        self.render_node(None, &self.value, String::new(), 0, &mut rows, cx);
+
+        v_flex()
+            .w_full()
+            .children(self.title.clone().map(|title| {
+                Label::new(title)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .italic()
+            }))
+            .children(rows)
+    }
+}
+
+impl OutputContent for JsonView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.raw_text.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.raw_text.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
@@ -22,13 +22,16 @@ use alacritty_terminal::{
     term::Config,
     vte::ansi::Processor,
 };
-use gpui::{Bounds, ClipboardItem, Entity, FontStyle, TextStyle, WhiteSpace, canvas, size};
+use gpui::{
+    Bounds, ClipboardItem, Entity, FontStyle, ScrollHandle, TextStyle, WhiteSpace, canvas, size,
+};
 use language::Buffer;
 use settings::Settings as _;
 use terminal_view::terminal_element::TerminalElement;
 use theme::ThemeSettings;
-use ui::{IntoElement, prelude::*};
+use ui::{IntoElement, Scrollbar, ScrollbarState, prelude::*};
 
+use crate::JupyterSettings;
 use crate::outputs::OutputContent;
 
 /// The `TerminalOutput` struct handles the parsing and rendering of text input,
@@ -46,14 +49,26 @@ use crate::outputs::OutputContent;
 ///
 pub struct TerminalOutput {
     full_buffer: Option<Entity<Buffer>>,
+    /// The text as the kernel sent it, escape sequences included. Only its last
+    /// `max_raw_lines` lines are kept, as the terminal only keeps so much scrollback.
+    raw_text: String,
+    raw_line_count: usize,
+    max_raw_lines: usize,
     /// ANSI escape sequence processor for parsing input text.
     parser: Processor,
     /// Alacritty terminal instance that manages the terminal state and content.
     handler: alacritty_terminal::Term<VoidListener>,
+    /// Whether long lines wrap, rather than scrolling horizontally.
+    wrap: bool,
+    scroll_handle: ScrollHandle,
+    scrollbar_state: ScrollbarState,
 }
 
 const DEFAULT_NUM_LINES: usize = 32;
 const DEFAULT_NUM_COLUMNS: usize = 128;
+/// The terminal still has to wrap somewhere when scrolling horizontally, so it's made wide enough
+/// that practically nothing does.
+const UNWRAPPED_NUM_COLUMNS: usize = 1024;
 
 /// Returns the default text style for the terminal output.
 pub fn text_style(window: &mut Window, cx: &mut App) -> TextStyle {
@@ -85,8 +100,12 @@ pub fn text_style(window: &mut Window, cx: &mut App) -> TextStyle {
     text_style
 }
 
-/// Returns the default terminal size for the terminal output.
-pub fn terminal_size(window: &mut Window, cx: &mut App) -> terminal::TerminalBounds {
+/// Returns the terminal size for output `columns` wide.
+pub fn terminal_size(
+    columns: usize,
+    window: &mut Window,
+    cx: &mut App,
+) -> terminal::TerminalBounds {
     let text_style = text_style(window, cx);
     let text_system = window.text_system();
 
@@ -101,7 +120,6 @@ pub fn terminal_size(window: &mut Window, cx: &mut App) -> terminal::TerminalBou
         .width;
 
     let num_lines = DEFAULT_NUM_LINES;
-    let columns = DEFAULT_NUM_COLUMNS;
 
     // Reversed math from terminal::TerminalSize to get pixel width according to terminal width
     let width = columns as f32 * cell_width;
@@ -124,17 +142,51 @@ impl TerminalOutput {
     /// and sets up the necessary components for handling terminal events and rendering.
     ///
     pub fn new(window: &mut Window, cx: &mut App) -> Self {
+        let wrap = !JupyterSettings::get_global(cx).scroll_wide_output;
+        let config = Config::default();
+        let max_raw_lines = config.scrolling_history + DEFAULT_NUM_LINES;
         let term = alacritty_terminal::Term::new(
-            Config::default(),
-            &terminal_size(window, cx),
+            config,
+            &terminal_size(Self::columns(wrap), window, cx),
             VoidListener,
         );
+        let scroll_handle = ScrollHandle::new();
 
         Self {
             parser: Processor::new(),
             handler: term,
             full_buffer: None,
+            raw_text: String::new(),
+            raw_line_count: 0,
+            max_raw_lines,
+            wrap,
+            scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+            scroll_handle,
+        }
+    }
+
+    fn columns(wrap: bool) -> usize {
+        if wrap {
+            DEFAULT_NUM_COLUMNS
+        } else {
+            UNWRAPPED_NUM_COLUMNS
+        }
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    /// Switches between wrapping long lines and scrolling horizontally to see them, reflowing
+    /// what has been output so far.
+    pub fn set_wrap(&mut self, wrap: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if self.wrap == wrap {
+            return;
         }
+        self.wrap = wrap;
+        self.handler
+            .resize(terminal_size(Self::columns(wrap), window, cx));
+        cx.notify();
     }
 
     /// Creates a new `TerminalOutput` instance with initial content.
@@ -182,6 +234,7 @@ impl TerminalOutput {
     ///
     /// * `text` - A string slice containing the text to be appended.
     pub fn append_text(&mut self, text: &str, cx: &mut App) {
+        self.append_raw_text(text);
         for byte in text.as_bytes() {
             if *byte == b'\n' {
                 // Dirty (?) hack to move the cursor down
@@ -200,7 +253,26 @@ impl TerminalOutput {
         }
     }
 
-    fn full_text(&self) -> String {
+    /// Keeps the raw text to the lines the terminal can still show, dropping the oldest ones.
+    fn append_raw_text(&mut self, text: &str) {
+        self.raw_text.push_str(text);
+        self.raw_line_count += text.matches('\n').count();
+        if self.raw_line_count <= self.max_raw_lines {
+            return;
+        }
+
+        let excess = self.raw_line_count - self.max_raw_lines;
+        if let Some((ix, _)) = self.raw_text.match_indices('\n').nth(excess - 1) {
+            self.raw_text.drain(..=ix);
+            self.raw_line_count = self.max_raw_lines;
+        }
+    }
+
+    pub(crate) fn raw_text(&self) -> &str {
+        &self.raw_text
+    }
+
+    pub(crate) fn full_text(&self) -> String {
         let mut full_text = String::new();
 
         // Get the total number of lines, including history
@@ -272,8 +344,9 @@ impl Render for TerminalOutput {
             .advance(font_id, font_pixels, 'w')
             .map(|advance| advance.width)
             .unwrap_or(Pixels(0.0));
+        let num_columns = cells.iter().map(|c| c.point.column).max().unwrap_or(0) + 1;
 
-        canvas(
+        let grid = canvas(
             // prepaint
             move |_bounds, _, _| {},
             // paint
@@ -306,7 +379,29 @@ impl Render for TerminalOutput {
             },
         )
         // We must set the height explicitly for the editor block to size itself correctly
-        .h(height)
+        .h(height);
+
+        if self.wrap {
+            return grid.into_any_element();
+        }
+
+        // Scrolling within the output keeps wide lines from widening the editor.
+        v_flex()
+            .w_full()
+            .child(
+                div()
+                    .id("terminal-output-scroll")
+                    .w_full()
+                    .overflow_x_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .on_scroll_wheel(cx.listener(|_, _, _, cx| cx.notify()))
+                    .child(grid.w(num_columns as f32 * cell_width)),
+            )
+            .children(
+                Scrollbar::horizontal(self.scrollbar_state.clone())
+                    .map(|scrollbar| div().w_full().h(px(12.)).child(scrollbar)),
+            )
+            .into_any_element()
     }
 }
 
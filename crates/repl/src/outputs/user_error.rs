@@ -1,20 +1,34 @@
-use gpui::{AnyElement, App, Entity, FontWeight, Window};
-use ui::{Label, h_flex, prelude::*, v_flex};
+use gpui::{AnyElement, App, ClickEvent, Entity, FontWeight, Window};
+use ui::{Disclosure, Label, h_flex, prelude::*, v_flex};
 
 use crate::outputs::plain::TerminalOutput;
 
+pub type ToggleTracebackFn = Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>;
+
 /// Userspace error from the kernel
 pub struct ErrorView {
     pub ename: String,
     pub evalue: String,
     pub traceback: Entity<TerminalOutput>,
+    pub traceback_expanded: bool,
 }
 
 impl ErrorView {
-    pub fn render(&self, window: &mut Window, cx: &mut App) -> Option<AnyElement> {
+    /// Renders a one-line summary of the error above its traceback.
+    ///
+    /// With `on_toggle_traceback`, the traceback is hidden behind a disclosure that follows
+    /// `traceback_expanded`. Without it, the traceback is always shown.
+    pub fn render(
+        &self,
+        on_toggle_traceback: Option<ToggleTracebackFn>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
         let theme = cx.theme();
 
         let padding = window.line_height() / 2.;
+        let show_traceback = on_toggle_traceback.is_none() || self.traceback_expanded;
+        let summary = self.evalue.lines().next().unwrap_or_default().to_string();
 
         Some(
             v_flex()
@@ -22,27 +36,40 @@ impl ErrorView {
                 .child(
                     h_flex()
                         .font_buffer(cx)
+                        .gap_1()
+                        .children(on_toggle_traceback.map(|on_toggle| {
+                            Disclosure::new(
+                                ElementId::Name(
+                                    format!("traceback-{}", self.traceback.entity_id()).into(),
+                                ),
+                                self.traceback_expanded,
+                            )
+                            .on_click(on_toggle)
+                        }))
                         .child(
                             Label::new(format!("{}: ", self.ename.clone()))
-                                // .size(LabelSize::Large)
                                 .color(Color::Error)
                                 .weight(FontWeight::BOLD),
                         )
                         .child(
-                            Label::new(self.evalue.clone())
-                                // .size(LabelSize::Large)
-                                .weight(FontWeight::BOLD),
+                            Label::new(summary)
+                                .color(Color::Error)
+                                .weight(FontWeight::BOLD)
+                                .single_line()
+                                .truncate(),
                         ),
                 )
-                .child(
-                    div()
-                        .w_full()
-                        .px(padding)
-                        .py(padding)
-                        .border_l_1()
-                        .border_color(theme.status().error_border)
-                        .child(self.traceback.clone()),
-                )
+                .when(show_traceback, |this| {
+                    this.child(
+                        div()
+                            .w_full()
+                            .px(padding)
+                            .py(padding)
+                            .border_l_1()
+                            .border_color(theme.status().error_border)
+                            .child(self.traceback.clone()),
+                    )
+                })
                 .into_any_element(),
         )
     }
@@ -0,0 +1,302 @@
+//! # Jupyter Widgets
+//!
+//! Kernels create ipywidgets by opening a `jupyter.widget` comm holding the widget's model state,
+//! then display it with an `application/vnd.jupyter.widget-view+json` output that refers to the
+//! model by its comm id. The session keeps the models up to date as the kernel sends updates, and
+//! sends changes made here back to the kernel.
+//!
+//! Only a few basic models are drawn:
+//!
+//! - `IntSliderModel` and `FloatSliderModel`, as a bar with buttons to step the value
+//! - `IntProgressModel` and `FloatProgressModel`, as a progress bar
+//! - `ButtonModel`, as a button that sends clicks to the kernel
+//! - `LabelModel`, as text
+//!
+//! Other widgets, including containers such as `HBox`, are shown as their text fallback.
+
+use gpui::{ClickEvent, ClipboardItem, Entity, Subscription, WeakEntity};
+use serde_json::{Map, Value, json};
+use ui::{NumericStepper, ProgressBar, prelude::*};
+
+use crate::Session;
+use crate::outputs::OutputContent;
+use crate::outputs::plain::TerminalOutput;
+
+/// The comm target ipywidgets opens a comm on for each widget model.
+pub const WIDGET_COMM_TARGET: &str = "jupyter.widget";
+
+/// The state of a widget, shared by every output that displays it.
+pub struct WidgetModel {
+    comm_id: String,
+    state: Map<String, Value>,
+    session: WeakEntity<Session>,
+}
+
+impl WidgetModel {
+    pub fn new(comm_id: String, state: Map<String, Value>, session: WeakEntity<Session>) -> Self {
+        Self {
+            comm_id,
+            state,
+            session,
+        }
+    }
+
+    pub fn model_name(&self) -> &str {
+        self.state
+            .get("_model_name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Merges the state sent by the kernel into the model.
+    pub fn apply_state(&mut self, state: &Map<String, Value>, cx: &mut Context<Self>) {
+        for (key, value) in state {
+            self.state.insert(key.clone(), value.clone());
+        }
+        cx.notify();
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.state
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|description| !description.is_empty())
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.state.get(key).and_then(Value::as_f64)
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.state
+            .get("disabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    fn is_int(&self) -> bool {
+        self.model_name().starts_with("Int")
+    }
+
+    fn format_number(&self, value: f64) -> String {
+        if self.is_int() {
+            format!("{}", value.round() as i64)
+        } else {
+            format!("{value:.2}")
+        }
+    }
+
+    /// Moves a slider's value by `steps` of its step size, keeping it within its bounds.
+    fn step_value(&mut self, steps: f64, cx: &mut Context<Self>) {
+        let (Some(value), Some(min), Some(max)) =
+            (self.number("value"), self.number("min"), self.number("max"))
+        else {
+            return;
+        };
+        let step = self.number("step").unwrap_or(1.);
+        let new_value = (value + step * steps).clamp(min, max);
+        let new_value = if self.is_int() {
+            json!(new_value.round() as i64)
+        } else {
+            json!(new_value)
+        };
+
+        self.state.insert("value".into(), new_value.clone());
+        cx.notify();
+        self.send(
+            json!({
+                "method": "update",
+                "state": { "value": new_value },
+                "buffer_paths": [],
+            }),
+            cx,
+        );
+    }
+
+    fn click(&self, cx: &mut Context<Self>) {
+        self.send(
+            json!({
+                "method": "custom",
+                "content": { "event": "click" },
+            }),
+            cx,
+        );
+    }
+
+    fn send(&self, data: Value, cx: &mut Context<Self>) {
+        let Value::Object(data) = data else {
+            return;
+        };
+        let comm_id = self.comm_id.clone();
+        self.session
+            .update(cx, |session, cx| {
+                session.send_comm_message(comm_id, data, cx);
+            })
+            .ok();
+    }
+}
+
+/// An output displaying a widget, which is drawn from its model once the session has it.
+pub struct WidgetView {
+    model_id: String,
+    model: Option<Entity<WidgetModel>>,
+    fallback: Option<Entity<TerminalOutput>>,
+    _model_subscription: Option<Subscription>,
+}
+
+impl WidgetView {
+    pub fn new(view: &Value, fallback: Option<Entity<TerminalOutput>>) -> Self {
+        let model_id = view
+            .get("model_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            model_id,
+            model: None,
+            fallback,
+            _model_subscription: None,
+        }
+    }
+
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.model.is_some()
+    }
+
+    pub fn bind(&mut self, model: Entity<WidgetModel>, cx: &mut Context<Self>) {
+        self._model_subscription = Some(cx.observe(&model, |_, _, cx| cx.notify()));
+        self.model = Some(model);
+        cx.notify();
+    }
+
+    pub fn fallback_text(&self, cx: &App) -> Option<String> {
+        self.fallback
+            .as_ref()
+            .map(|fallback| fallback.read(cx).full_text())
+    }
+
+    fn render_slider(model: &Entity<WidgetModel>, cx: &App) -> AnyElement {
+        let widget = model.read(cx);
+        let value = widget.number("value").unwrap_or_default();
+        let min = widget.number("min").unwrap_or_default();
+        let max = widget.number("max").unwrap_or(100.);
+        let disabled = widget.is_disabled();
+
+        let step_by = |steps: f64| {
+            let model = model.clone();
+            move |_: &ClickEvent, _: &mut Window, cx: &mut App| {
+                if !disabled {
+                    model.update(cx, |model, cx| model.step_value(steps, cx));
+                }
+            }
+        };
+
+        h_flex()
+            .gap_2()
+            .child(div().w(rems(12.)).child(ProgressBar::new(
+                "widget-slider",
+                (value - min) as f32,
+                (max - min) as f32,
+                cx,
+            )))
+            .child(NumericStepper::new(
+                "widget-slider-stepper",
+                widget.format_number(value),
+                step_by(-1.),
+                step_by(1.),
+            ))
+            .into_any_element()
+    }
+
+    fn render_progress(widget: &WidgetModel, cx: &App) -> AnyElement {
+        let value = widget.number("value").unwrap_or_default();
+        let min = widget.number("min").unwrap_or_default();
+        let max = widget.number("max").unwrap_or(100.);
+
+        h_flex()
+            .gap_2()
+            .child(div().w(rems(12.)).child(ProgressBar::new(
+                "widget-progress",
+                (value - min) as f32,
+                (max - min) as f32,
+                cx,
+            )))
+            .child(
+                Label::new(widget.format_number(value))
+                    .buffer_font(cx)
+                    .size(LabelSize::Small),
+            )
+            .into_any_element()
+    }
+
+    fn render_button(model: &Entity<WidgetModel>, cx: &App) -> AnyElement {
+        let widget = model.read(cx);
+        let label = widget.description().unwrap_or("Button").to_string();
+        let model = model.clone();
+
+        Button::new("widget-button", label)
+            .style(ButtonStyle::Filled)
+            .disabled(widget.is_disabled())
+            .on_click(move |_, _, cx| model.update(cx, |model, cx| model.click(cx)))
+            .into_any_element()
+    }
+
+    fn render_fallback(&self, message: String) -> AnyElement {
+        match &self.fallback {
+            Some(fallback) => fallback.clone().into_any_element(),
+            None => Label::new(message).color(Color::Muted).into_any_element(),
+        }
+    }
+}
+
+impl Render for WidgetView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(model) = self.model.as_ref() else {
+            return self.render_fallback("Widget state isn't available".to_string());
+        };
+
+        let widget = model.read(cx);
+        let description = match widget.model_name() {
+            "ButtonModel" => None,
+            _ => widget.description().map(str::to_string),
+        };
+        let content = match widget.model_name() {
+            "IntSliderModel" | "FloatSliderModel" => Self::render_slider(model, cx),
+            "IntProgressModel" | "FloatProgressModel" => Self::render_progress(widget, cx),
+            "ButtonModel" => Self::render_button(model, cx),
+            "LabelModel" => Label::new(
+                widget
+                    .state
+                    .get("value")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            )
+            .into_any_element(),
+            model_name => self.render_fallback(format!("Unsupported widget: {model_name}")),
+        };
+
+        h_flex()
+            .gap_2()
+            .when_some(description, |this, description| {
+                this.child(Label::new(description).color(Color::Muted))
+            })
+            .child(content)
+            .into_any_element()
+    }
+}
+
+impl OutputContent for WidgetView {
+    fn clipboard_content(&self, _window: &Window, cx: &App) -> Option<ClipboardItem> {
+        self.fallback_text(cx).map(ClipboardItem::new_string)
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        self.fallback.is_some()
+    }
+}
@@ -1,10 +1,13 @@
 pub mod components;
 mod jupyter_settings;
+mod kernel_completions;
 pub mod kernels;
+mod markdown_cells;
 pub mod notebook;
 mod outputs;
 mod repl_editor;
 mod repl_sessions_ui;
+mod repl_status_item;
 mod repl_store;
 mod session;
 
@@ -16,14 +19,21 @@ use project::Fs;
 pub use runtimelib::ExecutionState;
 use settings::Settings as _;
 
-pub use crate::jupyter_settings::JupyterSettings;
+pub use crate::jupyter_settings::{JupyterSettings, OutputBlockStyle, OutputDensity};
 pub use crate::kernels::{Kernel, KernelSpecification, KernelStatus};
 pub use crate::repl_editor::*;
 pub use crate::repl_sessions_ui::{
-    ClearOutputs, Interrupt, ReplSessionsPage, Restart, Run, Sessions, Shutdown,
+    ClearOutputs, ClearOutputsAbove, ClearOutputsBelow, CloseOutput, ConnectToKernel,
+    CopyTranscript, DuplicateSession, FocusOutput, Inspect, Interrupt, NextOutput,
+    OpenOutputInBuffer, PreviousOutput, ReplSessionsPage, Restart, RestartErroredKernels,
+    RevealKernelProcess, Run, RunPreviousCell, Sessions, SetWorkingDirectory, Shutdown,
+    ShutdownAllKernels, ToggleMarkdownCell, UnpinAllOutputs,
 };
+pub use crate::repl_status_item::ReplStatusItem;
 use crate::repl_store::ReplStore;
 pub use crate::session::Session;
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::{outputs::ExecutionStatus, session::BlockOutput};
 
 pub const KERNEL_DOCS_URL: &str = "https://zed.dev/docs/repl#changing-kernels";
 
@@ -1,18 +1,30 @@
 //! REPL operations on an [`Editor`].
 
 use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context as _, Result};
-use editor::Editor;
-use gpui::{App, Entity, WeakEntity, Window, prelude::*};
-use language::{BufferSnapshot, Language, LanguageName, Point};
+use editor::scroll::Autoscroll;
+use editor::{Anchor, AnchorRangeExt as _, Editor};
+use gpui::{
+    App, ClipboardItem, Entity, Focusable as _, PathPromptOptions, WeakEntity, Window, prelude::*,
+};
+use language::{Bias, BufferSnapshot, Language, LanguageName, Point};
 use project::{ProjectItem as _, WorktreeId};
+use util::ResultExt as _;
+use workspace::Toast;
+use workspace::notifications::NotificationId;
 
+use crate::kernels::ExistingKernelSpecification;
 use crate::repl_store::ReplStore;
 use crate::session::SessionEvent;
 use crate::{
-    ClearOutputs, Interrupt, JupyterSettings, KernelSpecification, Restart, Session, Shutdown,
+    ClearOutputs, ClearOutputsAbove, ClearOutputsBelow, CopyTranscript, DuplicateSession,
+    FocusOutput, Inspect, Interrupt, JupyterSettings, KernelSpecification, NextOutput,
+    PreviousOutput, Restart, RevealKernelProcess, Session, SetWorkingDirectory, Shutdown,
+    UnpinAllOutputs,
 };
 
 pub fn assign_kernelspec(
@@ -33,43 +45,152 @@ pub fn assign_kernelspec(
         store.set_active_kernelspec(worktree_id, kernel_specification.clone(), cx);
     });
 
-    let fs = store.read(cx).fs().clone();
-
     if let Some(session) = store.read(cx).get_session(weak_editor.entity_id()).cloned() {
         // Drop previous session, start new one
         session.update(cx, |session, cx| {
             session.clear_outputs(cx);
-            session.shutdown(window, cx);
+            session.shutdown(cx).detach();
             cx.notify();
         });
     }
 
-    let session =
-        cx.new(|cx| Session::new(weak_editor.clone(), fs, kernel_specification, window, cx));
+    start_session(weak_editor, kernel_specification, None, window, cx);
 
-    weak_editor
-        .update(cx, |_editor, cx| {
-            cx.notify();
+    Ok(())
+}
 
-            cx.subscribe(&session, {
-                let store = store.clone();
-                move |_this, _session, event, cx| match event {
-                    SessionEvent::Shutdown(shutdown_event) => {
-                        store.update(cx, |store, _cx| {
-                            store.remove_session(shutdown_event.entity_id());
-                        });
-                    }
-                }
-            })
-            .detach();
+/// Starts another kernel like the editor's, with the same kernel and working directory, and
+/// sends the editor's actions to it. The previous session keeps running with its outputs shown.
+pub fn duplicate_session(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    let (kernel_specification, working_directory) = {
+        let session = session.read(cx);
+        (
+            session.kernel_specification.clone(),
+            session.working_directory(cx),
+        )
+    };
+
+    store.update(cx, |store, cx| {
+        store.move_session_to_background(entity_id, cx)
+    });
+    start_session(
+        editor,
+        kernel_specification,
+        Some(working_directory),
+        window,
+        cx,
+    );
+}
+
+/// Creates a session for the editor, which starts its kernel, and registers it with the store.
+fn start_session(
+    weak_editor: WeakEntity<Editor>,
+    kernel_specification: KernelSpecification,
+    working_directory: Option<PathBuf>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<Session> {
+    let store = ReplStore::global(cx);
+    let fs = store.read(cx).fs().clone();
+
+    let session = cx.new(|cx| {
+        Session::new(
+            weak_editor.clone(),
+            fs,
+            kernel_specification,
+            working_directory,
+            window,
+            cx,
+        )
+    });
+
+    weak_editor.update(cx, |_editor, cx| cx.notify()).ok();
+
+    store.update(cx, |store, cx| {
+        // The store subscribes rather than the editor, so that sessions whose editor was closed
+        // are still removed once their kernel shuts down.
+        cx.subscribe(&session, |store, session, event, cx| {
+            if let SessionEvent::Shutdown(_) = event {
+                store.remove_session(&session, cx);
+            }
+        })
+        .detach();
+        store.insert_session(weak_editor.entity_id(), session.clone(), cx);
+    });
+
+    session
+}
+
+/// How long an editor has to stay focused before its kernel is started automatically, so that
+/// flipping through tabs doesn't start a kernel for each of them.
+const AUTO_START_DELAY: Duration = Duration::from_millis(750);
+
+/// Starts a kernel for a focused editor that doesn't have one yet, when `auto_start_kernel` is
+/// enabled and a kernel is available for the editor's language.
+pub fn auto_start_kernel(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    if !store.read(cx).is_enabled()
+        || !JupyterSettings::get_global(cx).auto_start_kernel
+        || store.read(cx).get_session(editor.entity_id()).is_some()
+    {
+        return;
+    }
+
+    let task = window.spawn(cx, async move |cx| {
+        cx.background_executor().timer(AUTO_START_DELAY).await;
+        cx.update(|window, cx| {
+            let Some(strong_editor) = editor.upgrade() else {
+                return;
+            };
+            if !strong_editor.focus_handle(cx).contains_focused(window, cx) {
+                return;
+            }
+            if let SessionSupport::Inactive(kernel_specification) = session(editor.clone(), cx) {
+                start_session(editor, kernel_specification, None, window, cx);
+            }
         })
         .ok();
+    });
+    store.update(cx, |store, _cx| store.set_auto_start_task(task));
+}
+
+/// Prompts for a Jupyter connection file and attaches the editor to the kernel it describes,
+/// instead of starting a new one.
+pub fn connect_to_existing_kernel(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let Some(language) = editor.upgrade().and_then(|editor| {
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        buffer.read(cx).language().cloned()
+    }) else {
+        return;
+    };
+    let language = language.code_fence_block_name().to_string();
 
-    store.update(cx, |store, _cx| {
-        store.insert_session(weak_editor.entity_id(), session.clone());
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
     });
 
-    Ok(())
+    window
+        .spawn(cx, async move |cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return anyhow::Ok(());
+            };
+
+            let kernel_specification = KernelSpecification::ExistingConnection(
+                ExistingKernelSpecification::new(path, language),
+            );
+            cx.update(|window, cx| assign_kernelspec(kernel_specification, editor, window, cx))??;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
 }
 
 pub fn run(
@@ -109,37 +230,11 @@ pub fn run(
             .active_kernelspec(project_path.worktree_id, Some(language.clone()), cx)
             .with_context(|| format!("No kernel found for language: {}", language.name()))?;
 
-        let fs = store.read(cx).fs().clone();
-
         let session = if let Some(session) = store.read(cx).get_session(editor.entity_id()).cloned()
         {
             session
         } else {
-            let weak_editor = editor.downgrade();
-            let session =
-                cx.new(|cx| Session::new(weak_editor, fs, kernel_specification, window, cx));
-
-            editor.update(cx, |_editor, cx| {
-                cx.notify();
-
-                cx.subscribe(&session, {
-                    let store = store.clone();
-                    move |_this, _session, event, cx| match event {
-                        SessionEvent::Shutdown(shutdown_event) => {
-                            store.update(cx, |store, _cx| {
-                                store.remove_session(shutdown_event.entity_id());
-                            });
-                        }
-                    }
-                })
-                .detach();
-            });
-
-            store.update(cx, |store, _cx| {
-                store.insert_session(editor.entity_id(), session.clone());
-            });
-
-            session
+            start_session(editor.downgrade(), kernel_specification, None, window, cx)
         };
 
         let selected_text;
@@ -170,6 +265,62 @@ pub fn run(
     anyhow::Ok(())
 }
 
+/// Runs the code that was last run in this editor's session again, wherever the cursor is.
+pub fn run_previous_cell(
+    editor: WeakEntity<Editor>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Result<()> {
+    let store = ReplStore::global(cx);
+    if !store.read(cx).is_enabled() {
+        return Ok(());
+    }
+
+    let editor = editor.upgrade().context("editor was dropped")?;
+    let Some(session) = store.read(cx).get_session(editor.entity_id()).cloned() else {
+        return Ok(());
+    };
+    let (last_range, last_start) = session
+        .read(cx)
+        .last_execution()
+        .cloned()
+        .context("no code has been run in this editor yet")?;
+
+    let multibuffer = editor.read(cx).buffer().clone();
+    let Some(buffer) = multibuffer.read(cx).as_singleton() else {
+        return Ok(());
+    };
+
+    let (code, anchor_range) = {
+        let snapshot = multibuffer.read(cx).snapshot(cx);
+        let is_intact = last_range.start.is_valid(&snapshot)
+            && last_range.end.is_valid(&snapshot)
+            && last_range.to_offset(&snapshot).len() > 0;
+
+        let range = if is_intact {
+            last_range.to_point(&snapshot)
+        } else {
+            // The cell was edited away, so find the one that now starts where it used to.
+            let start = snapshot.clip_point(last_start, Bias::Left);
+            runnable_ranges(&buffer.read(cx).snapshot(), start..start, cx)
+                .0
+                .into_iter()
+                .next()
+                .context("no code to run where the previous cell was")?
+        };
+
+        let code = snapshot.text_for_range(range.clone()).collect::<String>();
+        let anchor_range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+        (code, anchor_range)
+    };
+
+    session.update(cx, |session, cx| {
+        session.execute(code, anchor_range, None, false, window, cx);
+    });
+
+    Ok(())
+}
+
 pub enum SessionSupport {
     ActiveSession(Entity<Session>),
     Inactive(KernelSpecification),
@@ -237,6 +388,194 @@ pub fn clear_outputs(editor: WeakEntity<Editor>, cx: &mut App) {
     });
 }
 
+/// Unpins the outputs in the editor, so they're removed again when their code is edited or run.
+pub fn unpin_all_outputs(editor: WeakEntity<Editor>, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    session.update(cx, |session, cx| session.unpin_all_outputs(cx));
+}
+
+/// Copies the cells run in the editor's session and their outputs to the clipboard as Markdown.
+pub fn copy_transcript(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    if let Some(transcript) = session.read(cx).export_markdown(window, cx) {
+        cx.write_to_clipboard(ClipboardItem::new_string(transcript));
+    }
+}
+
+/// Copies the id of the kernel's process to the clipboard and opens the system's process
+/// monitor, where it can be looked up by that id.
+pub fn reveal_kernel_process(editor: WeakEntity<Editor>, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(workspace) = editor
+        .upgrade()
+        .and_then(|editor| editor.read(cx).workspace())
+    else {
+        return;
+    };
+
+    let message = match session.read(cx).kernel.process_id() {
+        Some(process_id) => {
+            cx.write_to_clipboard(ClipboardItem::new_string(process_id.to_string()));
+            open_process_monitor();
+            format!("Copied the kernel's process id ({process_id}) to the clipboard")
+        }
+        None => "The kernel isn't running as a process on this machine".to_string(),
+    };
+    workspace.update(cx, |workspace, cx| {
+        workspace.show_toast(
+            Toast::new(NotificationId::unique::<RevealKernelProcess>(), message).autohide(),
+            cx,
+        );
+    });
+}
+
+/// Neither monitor can be opened filtered to a process, so users search for the copied id.
+fn open_process_monitor() {
+    let mut command = match std::env::consts::OS {
+        "macos" => {
+            let mut command = util::command::new_smol_command("open");
+            command.args(["-a", "Activity Monitor"]);
+            command
+        }
+        "windows" => util::command::new_smol_command("taskmgr"),
+        _ => return,
+    };
+    command.spawn().log_err();
+}
+
+/// Moves focus into the output block of the cell at the cursor, so it can be used from the
+/// keyboard.
+pub fn focus_output(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+
+    let cursor = editor.read(cx).selections.newest_anchor().head();
+    if let Some(execution_view) = session.read(cx).output_at(cursor, cx) {
+        window.focus(&execution_view.focus_handle(cx));
+    }
+}
+
+/// Clears the outputs of the cells above the cursor, or below it when `above` is false.
+pub fn clear_outputs_around_cursor(editor: WeakEntity<Editor>, above: bool, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+
+    let cursor = editor.read(cx).selections.newest_anchor().head();
+    let range = if above {
+        Anchor::min()..cursor
+    } else {
+        cursor..Anchor::max()
+    };
+
+    session.update(cx, |session, cx| {
+        session.clear_outputs_in_range(range, cx);
+    });
+}
+
+/// Moves the cursor to the start of the next cell that has an output, or the previous one when
+/// `forward` is false. Outputs hidden inside a fold are skipped.
+pub fn go_to_adjacent_output(
+    editor: WeakEntity<Editor>,
+    forward: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+
+    let code_ranges = session.read(cx).output_code_ranges();
+
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        let buffer = &snapshot.buffer_snapshot;
+        let cursor = editor.selections.newest_anchor().head();
+
+        // Output blocks sit below the end of their code, so that is what a fold would hide.
+        let mut starts = code_ranges
+            .into_iter()
+            .filter(|range| !snapshot.intersects_fold(range.end))
+            .map(|range| range.start)
+            .collect::<Vec<_>>();
+        starts.sort_by(|a, b| a.cmp(b, buffer));
+
+        let target = if forward {
+            starts
+                .into_iter()
+                .find(|start| start.cmp(&cursor, buffer).is_gt())
+        } else {
+            starts
+                .into_iter()
+                .rev()
+                .find(|start| start.cmp(&cursor, buffer).is_lt())
+        };
+        let Some(target) = target else {
+            return;
+        };
+
+        editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+            selections.select_anchor_ranges([target..target]);
+        });
+    });
+}
+
+/// Asks the kernel about the symbol at the cursor, sending the surrounding cell as context.
+pub fn inspect(editor: WeakEntity<Editor>, detail_level: usize, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    let anchor = editor.read(cx).selections.newest_anchor().head();
+    let cursor = editor
+        .update(cx, |editor, cx| editor.selections.newest::<Point>(cx))
+        .head();
+    let snapshot = buffer.read(cx).snapshot();
+
+    let (_, code, cursor_pos) = code_around_cursor(&snapshot, cursor, cx);
+
+    session.update(cx, |session, cx| {
+        session.inspect(code, cursor_pos, detail_level, anchor, window, cx);
+    });
+}
+
 pub fn interrupt(editor: WeakEntity<Editor>, cx: &mut App) {
     let store = ReplStore::global(cx);
     let entity_id = editor.entity_id();
@@ -250,7 +589,7 @@ pub fn interrupt(editor: WeakEntity<Editor>, cx: &mut App) {
     });
 }
 
-pub fn shutdown(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+pub fn shutdown(editor: WeakEntity<Editor>, cx: &mut App) {
     let store = ReplStore::global(cx);
     let entity_id = editor.entity_id();
     let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
@@ -258,7 +597,7 @@ pub fn shutdown(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
     };
 
     session.update(cx, |session, cx| {
-        session.shutdown(window, cx);
+        session.shutdown(cx).detach();
         cx.notify();
     });
 }
@@ -284,6 +623,36 @@ pub fn restart(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
     });
 }
 
+pub fn set_working_directory(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+    });
+
+    window
+        .spawn(cx, async move |cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return anyhow::Ok(());
+            };
+
+            cx.update(|window, cx| {
+                session.update(cx, |session, cx| {
+                    session.set_working_directory(path, window, cx);
+                });
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
 pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEntity<Editor>) {
     editor
         .register_action({
@@ -298,6 +667,123 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEnti
         })
         .detach();
 
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &UnpinAllOutputs, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::unpin_all_outputs(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ClearOutputsAbove, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::clear_outputs_around_cursor(editor_handle.clone(), true, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ClearOutputsBelow, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::clear_outputs_around_cursor(editor_handle.clone(), false, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &NextOutput, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::go_to_adjacent_output(editor_handle.clone(), true, window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &PreviousOutput, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::go_to_adjacent_output(editor_handle.clone(), false, window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |action: &Inspect, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::inspect(editor_handle.clone(), action.detail_level, window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &CopyTranscript, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::copy_transcript(editor_handle.clone(), window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &FocusOutput, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::focus_output(editor_handle.clone(), window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &RevealKernelProcess, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::reveal_kernel_process(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
@@ -314,12 +800,12 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEnti
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
-            move |_: &Shutdown, window, cx| {
+            move |_: &Shutdown, _, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
                 }
 
-                crate::shutdown(editor_handle.clone(), window, cx);
+                crate::shutdown(editor_handle.clone(), cx);
             }
         })
         .detach();
@@ -336,6 +822,32 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEnti
             }
         })
         .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &DuplicateSession, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::duplicate_session(editor_handle.clone(), window, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &SetWorkingDirectory, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::set_working_directory(editor_handle.clone(), window, cx);
+            }
+        })
+        .detach();
 }
 
 fn cell_range(buffer: &BufferSnapshot, start_row: u32, end_row: u32) -> Range<Point> {
@@ -412,6 +924,134 @@ fn jupytext_cells(
     (snippets, None)
 }
 
+/// A Jupytext cell marked `[markdown]`, whose body is prose written in line comments.
+pub(crate) struct MarkdownCell {
+    pub header_row: u32,
+    pub body: Range<Point>,
+    /// The body with its comment prefixes removed.
+    pub text: String,
+}
+
+pub(crate) fn markdown_cells(buffer: &BufferSnapshot) -> Vec<MarkdownCell> {
+    let Some(language) = buffer.language() else {
+        return Vec::new();
+    };
+
+    let comment_prefixes = language
+        .default_scope()
+        .line_comment_prefixes()
+        .iter()
+        .map(|prefix| prefix.trim_end().to_string())
+        .filter(|prefix| !prefix.is_empty())
+        .collect::<Vec<_>>();
+    if comment_prefixes.is_empty() {
+        return Vec::new();
+    }
+
+    // Returns whether the line is a markdown cell header, or `None` when it isn't a header at all.
+    let header_kind = |line: &str| {
+        comment_prefixes.iter().find_map(|prefix| {
+            let marker = line.strip_prefix(prefix.as_str())?.trim_start();
+            let kind = marker.strip_prefix("%%")?.trim_start();
+            Some(kind.starts_with("[markdown]") || kind.starts_with("[md]"))
+        })
+    };
+
+    let mut cells = Vec::new();
+    let mut markdown_header_row = None;
+    let max_row = buffer.max_point().row;
+    for row in 0..=max_row {
+        let line = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        let Some(is_markdown) = header_kind(&line) else {
+            continue;
+        };
+
+        if let Some(header_row) = markdown_header_row.take() {
+            cells.extend(markdown_cell(
+                buffer,
+                header_row,
+                row - 1,
+                &comment_prefixes,
+            ));
+        }
+        if is_markdown {
+            markdown_header_row = Some(row);
+        }
+    }
+    if let Some(header_row) = markdown_header_row {
+        cells.extend(markdown_cell(
+            buffer,
+            header_row,
+            max_row,
+            &comment_prefixes,
+        ));
+    }
+
+    cells
+}
+
+fn markdown_cell(
+    buffer: &BufferSnapshot,
+    header_row: u32,
+    end_row: u32,
+    comment_prefixes: &[String],
+) -> Option<MarkdownCell> {
+    if end_row <= header_row {
+        return None;
+    }
+
+    let body = cell_range(buffer, header_row + 1, end_row);
+    if body.start.row == body.end.row && buffer.is_line_blank(body.start.row) {
+        return None;
+    }
+
+    let text = buffer
+        .text_for_range(body.clone())
+        .collect::<String>()
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            comment_prefixes
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix.as_str()))
+                .map_or(line, |prose| prose.strip_prefix(' ').unwrap_or(prose))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(MarkdownCell {
+        header_row,
+        body,
+        text,
+    })
+}
+
+/// Returns the cell containing `cursor` (or its line, when it isn't in a cell), the cell's code,
+/// and the cursor's position within that code, counted in Unicode code points as Jupyter expects.
+pub(crate) fn code_around_cursor(
+    buffer: &BufferSnapshot,
+    cursor: Point,
+    cx: &mut App,
+) -> (Range<Point>, String, usize) {
+    let (cells, _) = runnable_ranges(buffer, cursor..cursor, cx);
+    let cell = cells
+        .into_iter()
+        .find(|cell| cell.start <= cursor && cursor <= cell.end)
+        .unwrap_or_else(|| {
+            Point::new(cursor.row, 0)..Point::new(cursor.row, buffer.line_len(cursor.row))
+        });
+
+    let code = buffer.text_for_range(cell.clone()).collect::<String>();
+    let cursor_pos = buffer
+        .text_for_range(cell.start..cursor)
+        .map(|chunk| chunk.chars().count())
+        .sum();
+
+    (cell, code, cursor_pos)
+}
+
 fn runnable_ranges(
     buffer: &BufferSnapshot,
     range: Range<Point>,
@@ -632,6 +1272,59 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_jupytext_markdown_cells(cx: &mut App) {
+        let test_language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["# ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+
+        let buffer = cx.new(|cx| {
+            Buffer::local(
+                indoc! { r#"
+                    # %% [markdown]
+                    # # Arithmetic
+                    #
+                    # Some *sums*:
+
+                    # %%
+                    print(1 + 1)
+                    # %% [md]
+                    #     indented code
+                    # %% [markdown]
+
+                "# },
+                cx,
+            )
+            .with_language(test_language, cx)
+        });
+        let snapshot = buffer.read(cx).snapshot();
+
+        let cells = markdown_cells(&snapshot)
+            .into_iter()
+            .map(|cell| (cell.header_row, cell.body, cell.text))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            vec![
+                (
+                    0,
+                    Point::new(1, 0)..Point::new(3, 14),
+                    "# Arithmetic\n\nSome *sums*:".to_string()
+                ),
+                (
+                    7,
+                    Point::new(8, 0)..Point::new(8, 19),
+                    "    indented code".to_string()
+                ),
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_markdown_code_blocks(cx: &mut App) {
         use crate::kernels::LocalKernelSpecification;
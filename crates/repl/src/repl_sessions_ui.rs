@@ -1,35 +1,79 @@
 use editor::Editor;
 use gpui::{
-    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, actions,
-    prelude::*,
+    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, Subscription,
+    actions, impl_actions, prelude::*,
 };
+use language::Point;
 use project::ProjectItem as _;
+use schemars::JsonSchema;
+use serde::Deserialize;
 use ui::{ButtonLike, ElevationIndex, KeyBinding, prelude::*};
 use util::ResultExt as _;
 use workspace::WorkspaceId;
 use workspace::item::ItemEvent;
-use workspace::{Workspace, item::Item};
+use workspace::notifications::NotificationId;
+use workspace::{Toast, Workspace, item::Item};
 
 use crate::jupyter_settings::JupyterSettings;
+use crate::markdown_cells::MarkdownCells;
 use crate::repl_store::ReplStore;
+use crate::{Kernel, KernelStatus};
 
 actions!(
     repl,
     [
         Run,
         RunInPlace,
+        RunPreviousCell,
         ClearOutputs,
+        ClearOutputsAbove,
+        ClearOutputsBelow,
+        CloseOutput,
+        ConnectToKernel,
+        CopyTranscript,
+        DuplicateSession,
+        FocusOutput,
+        NextOutput,
+        PreviousOutput,
         Sessions,
         Interrupt,
         Shutdown,
+        ShutdownAllKernels,
         Restart,
-        RefreshKernelspecs
+        RestartErroredKernels,
+        RevealKernelProcess,
+        SetWorkingDirectory,
+        RefreshKernelspecs,
+        ToggleMarkdownCell,
+        UnpinAllOutputs
     ]
 );
 
+/// Asks the kernel to describe the symbol at the cursor.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Inspect {
+    /// 0 for a summary such as the signature and docstring, 1 to also include details like the
+    /// source code.
+    #[serde(default)]
+    pub detail_level: usize,
+}
+
+/// Opens the text of the focused output in a new buffer, where it can be searched and saved.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OpenOutputInBuffer {
+    /// Whether to keep the escape sequences the kernel sent, such as for colors, rather than
+    /// opening the text as it's shown.
+    #[serde(default)]
+    pub raw: bool,
+}
+
+impl_actions!(repl, [Inspect, OpenOutputInBuffer]);
+
 pub fn init(cx: &mut App) {
     cx.observe_new(
-        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, _window, cx: &mut Context<Workspace>| {
             workspace.register_action(|workspace, _: &Sessions, window, cx| {
                 let existing = workspace
                     .active_pane()
@@ -51,10 +95,61 @@ pub fn init(cx: &mut App) {
                 }
             });
 
-            workspace.register_action(|_workspace, _: &RefreshKernelspecs, _, cx| {
+            workspace.register_action(|workspace, _: &RestartErroredKernels, window, cx| {
+                let errored_sessions = ReplStore::global(cx)
+                    .read(cx)
+                    .sessions()
+                    .filter(|session| matches!(session.read(cx).kernel, Kernel::ErroredLaunch(_)))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                for session in &errored_sessions {
+                    session.update(cx, |session, cx| session.restart(window, cx));
+                }
+
+                let message = match errored_sessions.len() {
+                    0 => "No kernels failed to start".to_string(),
+                    1 => "Restarted 1 kernel that failed to start".to_string(),
+                    count => format!("Restarted {count} kernels that failed to start"),
+                };
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<RestartErroredKernels>(), message)
+                        .autohide(),
+                    cx,
+                );
+            });
+
+            workspace.register_action(|_, _: &ShutdownAllKernels, window, cx| {
+                shutdown_all_kernels(window, cx);
+            });
+
+            // Sessions belong to editors, which may outlive their workspace for a moment, so
+            // the kernels whose editors were left without a workspace are shut down with it.
+            cx.on_release(|_, cx| {
+                ReplStore::global(cx).update(cx, |store, cx| store.shutdown_orphaned_sessions(cx));
+            })
+            .detach();
+
+            workspace.register_action(|workspace, _: &RefreshKernelspecs, _, cx| {
+                let project = workspace.project().clone();
+                let worktree_ids = if project.read(cx).is_local() {
+                    project
+                        .read(cx)
+                        .worktrees(cx)
+                        .map(|worktree| worktree.read(cx).id())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+
                 let store = ReplStore::global(cx);
                 store.update(cx, |store, cx| {
                     store.refresh_kernelspecs(cx).detach();
+                    for worktree_id in worktree_ids {
+                        store
+                            .refresh_python_kernelspecs(worktree_id, &project, cx)
+                            .detach_and_log_err(cx);
+                    }
                 });
             });
         },
@@ -111,6 +206,15 @@ pub fn init(cx: &mut App) {
                     }
                 }
 
+                let focus_handle = editor.focus_handle(cx);
+                cx.on_focus(&focus_handle, window, |_editor, window, cx| {
+                    crate::auto_start_kernel(cx.weak_entity(), window, cx);
+                })
+                .detach();
+                if focus_handle.is_focused(window) {
+                    crate::auto_start_kernel(editor_handle.clone(), window, cx);
+                }
+
                 editor
                     .register_action({
                         let editor_handle = editor_handle.clone();
@@ -136,12 +240,107 @@ pub fn init(cx: &mut App) {
                         }
                     })
                     .detach();
+
+                editor
+                    .register_action({
+                        let editor_handle = editor_handle.clone();
+                        move |_: &RunPreviousCell, window, cx| {
+                            if !JupyterSettings::enabled(cx) {
+                                return;
+                            }
+
+                            crate::run_previous_cell(editor_handle.clone(), window, cx).log_err();
+                        }
+                    })
+                    .detach();
+
+                editor
+                    .register_action({
+                        let editor_handle = editor_handle.clone();
+                        move |_: &ConnectToKernel, window, cx| {
+                            if !JupyterSettings::enabled(cx) {
+                                return;
+                            }
+
+                            crate::connect_to_existing_kernel(editor_handle.clone(), window, cx);
+                        }
+                    })
+                    .detach();
+
+                let markdown_cells =
+                    cx.new(|cx| MarkdownCells::new(editor_handle.clone(), editor.buffer(), cx));
+                editor
+                    .register_action({
+                        let editor_handle = editor_handle.clone();
+                        move |_: &ToggleMarkdownCell, _window, cx| {
+                            let Some(editor) = editor_handle.upgrade() else {
+                                return;
+                            };
+                            let cursor = editor.update(cx, |editor, cx| {
+                                editor.selections.newest::<Point>(cx).head()
+                            });
+                            markdown_cells.update(cx, |markdown_cells, cx| {
+                                markdown_cells.toggle_at(cursor, cx)
+                            });
+                        }
+                    })
+                    .detach();
             });
         },
     )
     .detach();
 }
 
+/// Shuts down every session's kernel, first asking for confirmation if any of them are still
+/// running code.
+pub(crate) fn shutdown_all_kernels(window: &mut Window, cx: &mut App) {
+    let sessions = ReplStore::global(cx)
+        .read(cx)
+        .sessions()
+        .filter(|session| {
+            !matches!(
+                session.read(cx).kernel,
+                Kernel::ShuttingDown | Kernel::Shutdown
+            )
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let busy_count = sessions
+        .iter()
+        .filter(|session| matches!(session.read(cx).kernel.status(), KernelStatus::Busy))
+        .count();
+
+    let confirmation = (busy_count > 0).then(|| {
+        let detail = match busy_count {
+            1 => "1 kernel is still running code.".to_string(),
+            count => format!("{count} kernels are still running code."),
+        };
+        window.prompt(
+            PromptLevel::Warning,
+            "Shut down all kernels?",
+            Some(&detail),
+            &["Shut Down", "Cancel"],
+            cx,
+        )
+    });
+
+    window
+        .spawn(cx, async move |cx| {
+            if let Some(confirmation) = confirmation {
+                if confirmation.await != Ok(0) {
+                    return;
+                }
+            }
+            cx.update(|_, cx| {
+                for session in sessions {
+                    session.update(cx, |session, cx| session.shutdown(cx).detach());
+                }
+            })
+            .log_err();
+        })
+        .detach();
+}
+
 pub struct ReplSessionsPage {
     focus_handle: FocusHandle,
     _subscriptions: Vec<Subscription>,
@@ -0,0 +1,115 @@
+use collections::HashMap;
+use gpui::{Entity, EntityId, Subscription};
+use ui::{Indicator, Tooltip, prelude::*};
+use workspace::{ItemHandle, StatusItemView};
+
+use crate::repl_store::ReplStore;
+use crate::session::SessionEvent;
+use crate::{KernelStatus, Sessions};
+
+/// Shows how many kernels are running in the status bar, so that ones left running in the
+/// background don't go unnoticed.
+pub struct ReplStatusItem {
+    store: Entity<ReplStore>,
+    session_subscriptions: HashMap<EntityId, Subscription>,
+    _store_subscription: Subscription,
+}
+
+impl ReplStatusItem {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let store = ReplStore::global(cx);
+        let store_subscription = cx.observe(&store, |this, _, cx| {
+            this.subscribe_to_sessions(cx);
+            cx.notify();
+        });
+
+        let mut this = Self {
+            store,
+            session_subscriptions: HashMap::default(),
+            _store_subscription: store_subscription,
+        };
+        this.subscribe_to_sessions(cx);
+        this
+    }
+
+    fn subscribe_to_sessions(&mut self, cx: &mut Context<Self>) {
+        let sessions = self.store.read(cx).sessions().cloned().collect::<Vec<_>>();
+        self.session_subscriptions
+            .retain(|entity_id, _| sessions.iter().any(|s| s.entity_id() == *entity_id));
+
+        for session in sessions {
+            self.session_subscriptions
+                .entry(session.entity_id())
+                .or_insert_with(|| {
+                    cx.subscribe(&session, |_, _, event: &SessionEvent, cx| {
+                        if let SessionEvent::KernelStatusChanged(_) = event {
+                            cx.notify();
+                        }
+                    })
+                });
+        }
+    }
+}
+
+impl Render for ReplStatusItem {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let statuses = self
+            .store
+            .read(cx)
+            .sessions()
+            .map(|session| session.read(cx).kernel.status())
+            .filter(|status| {
+                !matches!(
+                    status,
+                    KernelStatus::Shutdown | KernelStatus::Error | KernelStatus::ShuttingDown
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if statuses.is_empty() {
+            return div().into_any_element();
+        }
+
+        let busy_count = statuses
+            .iter()
+            .filter(|status| !matches!(status, KernelStatus::Idle))
+            .count();
+        let kernel_count = statuses.len();
+        let label = if kernel_count == 1 {
+            "1 kernel".to_string()
+        } else {
+            format!("{kernel_count} kernels")
+        };
+        let tooltip = if busy_count == 0 {
+            "All kernels are idle".to_string()
+        } else {
+            format!("{busy_count} of {kernel_count} busy")
+        };
+        let indicator_color = if busy_count == 0 {
+            Color::Success
+        } else {
+            Color::Warning
+        };
+
+        ButtonLike::new("repl-status")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Indicator::dot().color(indicator_color))
+                    .child(Label::new(label).size(LabelSize::Small)),
+            )
+            .tooltip(move |window, cx| Tooltip::for_action(tooltip.clone(), &Sessions, window, cx))
+            .on_click(|_, window, cx| window.dispatch_action(Box::new(Sessions), cx))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for ReplStatusItem {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+}
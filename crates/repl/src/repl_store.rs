@@ -1,19 +1,25 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context as _, Result};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use command_palette_hooks::CommandPaletteFilter;
-use gpui::{App, Context, Entity, EntityId, Global, Subscription, Task, prelude::*};
+use gpui::{App, Context, Entity, EntityId, Global, SharedString, Subscription, Task, prelude::*};
 use jupyter_websocket_client::RemoteServer;
 use language::Language;
 use project::{Fs, Project, WorktreeId};
 use settings::{Settings, SettingsStore};
 
 use crate::kernels::{
-    list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
+    Kernel, list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
+    python_version,
 };
 use crate::{JupyterSettings, KernelSpecification, Session};
 
+/// How long kernels get to reply to a shutdown request when Zed quits.
+const QUIT_GRACE_PERIOD: Duration = Duration::from_millis(50);
+
 struct GlobalReplStore(Entity<ReplStore>);
 
 impl Global for GlobalReplStore {}
@@ -22,9 +28,17 @@ pub struct ReplStore {
     fs: Arc<dyn Fs>,
     enabled: bool,
     sessions: HashMap<EntityId, Entity<Session>>,
+    /// Sessions that were duplicated, which keep running and showing their outputs in their
+    /// editor until shut down while the editor's actions go to the duplicate.
+    background_sessions: Vec<Entity<Session>>,
     kernel_specifications: Vec<KernelSpecification>,
     selected_kernel_for_worktree: HashMap<WorktreeId, KernelSpecification>,
     kernel_specifications_for_worktree: HashMap<WorktreeId, Vec<KernelSpecification>>,
+    /// Versions of the interpreters behind Python kernels, queried the first time they're shown
+    /// since starting each interpreter is too slow to do for every kernel on discovery.
+    interpreter_versions: HashMap<PathBuf, Option<SharedString>>,
+    /// The pending automatic kernel start, replaced whenever another editor is focused.
+    auto_start_task: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -46,18 +60,24 @@ impl ReplStore {
     }
 
     pub fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
-        let subscriptions = vec![cx.observe_global::<SettingsStore>(move |this, cx| {
-            this.set_enabled(JupyterSettings::enabled(cx), cx);
-        })];
+        let subscriptions = vec![
+            cx.observe_global::<SettingsStore>(move |this, cx| {
+                this.set_enabled(JupyterSettings::enabled(cx), cx);
+            }),
+            cx.on_app_quit(Self::shutdown_kernels_on_quit),
+        ];
 
         let this = Self {
             fs,
             enabled: JupyterSettings::enabled(cx),
             sessions: HashMap::default(),
+            background_sessions: Vec::new(),
             kernel_specifications: Vec::new(),
             _subscriptions: subscriptions,
             kernel_specifications_for_worktree: HashMap::default(),
             selected_kernel_for_worktree: HashMap::default(),
+            interpreter_versions: HashMap::default(),
+            auto_start_task: None,
         };
         this.on_enabled_changed(cx);
         this
@@ -82,12 +102,47 @@ impl ReplStore {
             .chain(self.kernel_specifications.iter())
     }
 
+    pub fn interpreter_version(&self, interpreter_path: &Path) -> Option<&SharedString> {
+        self.interpreter_versions.get(interpreter_path)?.as_ref()
+    }
+
+    /// Starts querying the versions of the worktree's Python kernels that haven't been queried yet.
+    pub fn query_interpreter_versions(&mut self, worktree_id: WorktreeId, cx: &mut Context<Self>) {
+        let interpreter_paths = self
+            .kernel_specifications_for_worktree(worktree_id)
+            .filter(|kernel| kernel.language().eq_ignore_ascii_case("python"))
+            .filter_map(|kernel| kernel.interpreter_path())
+            .collect::<Vec<_>>();
+
+        for interpreter_path in interpreter_paths {
+            if self.interpreter_versions.contains_key(&interpreter_path) {
+                continue;
+            }
+            self.interpreter_versions
+                .insert(interpreter_path.clone(), None);
+
+            let version = cx.background_spawn(python_version(interpreter_path.clone()));
+            cx.spawn(async move |this, cx| {
+                let version = version.await;
+                this.update(cx, |this, cx| {
+                    this.interpreter_versions
+                        .insert(interpreter_path, version.map(SharedString::from));
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
     pub fn pure_jupyter_kernel_specifications(&self) -> impl Iterator<Item = &KernelSpecification> {
         self.kernel_specifications.iter()
     }
 
     pub fn sessions(&self) -> impl Iterator<Item = &Entity<Session>> {
-        self.sessions.values()
+        self.sessions
+            .values()
+            .chain(self.background_sessions.iter())
     }
 
     fn set_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
@@ -160,6 +215,8 @@ impl ReplStore {
     }
 
     pub fn refresh_kernelspecs(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        // Environments may have been upgraded in place, so versions are queried again.
+        self.interpreter_versions.clear();
         let local_kernel_specifications = local_kernel_specifications(self.fs.clone());
 
         let remote_kernel_specifications = self.get_remote_kernel_specifications(cx);
@@ -264,20 +321,99 @@ impl ReplStore {
                     remote_spec.kernelspec.language.to_lowercase()
                         == language_at_cursor.code_fence_block_name().to_lowercase()
                 }
+                KernelSpecification::ExistingConnection(existing_spec) => {
+                    existing_spec.language.to_lowercase()
+                        == language_at_cursor.code_fence_block_name().to_lowercase()
+                }
             })
             .cloned()
     }
 
+    pub fn set_auto_start_task(&mut self, task: Task<()>) {
+        self.auto_start_task = Some(task);
+    }
+
     pub fn get_session(&self, entity_id: EntityId) -> Option<&Entity<Session>> {
         self.sessions.get(&entity_id)
     }
 
-    pub fn insert_session(&mut self, entity_id: EntityId, session: Entity<Session>) {
+    pub fn insert_session(
+        &mut self,
+        entity_id: EntityId,
+        session: Entity<Session>,
+        cx: &mut Context<Self>,
+    ) {
+        // Each live session gets a color of its own, reusing those of sessions that have ended.
+        let replaced_session = self.sessions.get(&entity_id);
+        let used_color_indices = self
+            .sessions()
+            .filter(|session| Some(*session) != replaced_session)
+            .map(|session| session.read(cx).color_index())
+            .collect::<HashSet<_>>();
+        let color_index = (0..)
+            .find(|color_index| !used_color_indices.contains(color_index))
+            .unwrap_or_default();
+        session.update(cx, |session, cx| session.set_color_index(color_index, cx));
+
         self.sessions.insert(entity_id, session);
+        cx.notify();
     }
 
-    pub fn remove_session(&mut self, entity_id: EntityId) {
-        self.sessions.remove(&entity_id);
+    /// Keeps the editor's session running in the background, so that another session can be
+    /// inserted for the editor.
+    pub fn move_session_to_background(&mut self, entity_id: EntityId, cx: &mut Context<Self>) {
+        if let Some(session) = self.sessions.remove(&entity_id) {
+            // The session no longer owns its editor, so the editor's own completions come back
+            // before another session is attached to it.
+            session.update(cx, |session, cx| session.restore_completion_provider(cx));
+            self.background_sessions.push(session);
+            cx.notify();
+        }
+    }
+
+    pub fn remove_session(&mut self, session: &Entity<Session>, cx: &mut Context<Self>) {
+        self.sessions.retain(|_, existing| existing != session);
+        self.background_sessions
+            .retain(|existing| existing != session);
+        cx.notify();
+    }
+
+    /// Shuts down the kernels of sessions whose editor is gone or no longer in a workspace.
+    pub(crate) fn shutdown_orphaned_sessions(&mut self, cx: &mut Context<Self>) {
+        let orphaned_sessions = self
+            .sessions()
+            .filter(|session| {
+                let session = session.read(cx);
+                !matches!(session.kernel, Kernel::ShuttingDown | Kernel::Shutdown)
+                    && session
+                        .editor()
+                        .upgrade()
+                        .and_then(|editor| editor.read(cx).workspace())
+                        .is_none()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for session in orphaned_sessions {
+            session.update(cx, |session, cx| session.shutdown(cx).detach());
+        }
+    }
+
+    // The processes Zed started would otherwise outlive it. gpui only waits `SHUTDOWN_TIMEOUT`
+    // for quit observers, so kernels get less time to reply than when shut down from the UI.
+    fn shutdown_kernels_on_quit(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let sessions = self.sessions().cloned().collect::<Vec<_>>();
+        let shutdowns = sessions
+            .into_iter()
+            .map(|session| {
+                session.update(cx, |session, cx| {
+                    session.shutdown_kernel(QUIT_GRACE_PERIOD, cx)
+                })
+            })
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            futures::future::join_all(shutdowns).await;
+        })
     }
 
     #[cfg(test)]
@@ -290,3 +426,92 @@ impl ReplStore {
         cx.notify();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::tests::{FakeKernelHandle, init_session_test};
+    use gpui::{TestAppContext, VisualTestContext};
+    use jupyter_protocol::{ExecutionState, JupyterMessage, JupyterMessageContent};
+    use project::FakeFs;
+    use std::sync::atomic::Ordering;
+
+    /// Starts a session and registers it with a global store, as opening a REPL would.
+    async fn init_store_test(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<ReplStore>,
+        Entity<Session>,
+        FakeKernelHandle,
+        &mut VisualTestContext,
+    ) {
+        let (session, editor, kernel, cx) = init_session_test(cx).await;
+        let fs = FakeFs::new(cx.executor());
+        let store = cx.update(|_, cx| {
+            command_palette_hooks::init(cx);
+            let store = cx.new(|cx| ReplStore::new(fs, cx));
+            cx.set_global(GlobalReplStore(store.clone()));
+            store
+        });
+        store.update(cx, |store, cx| {
+            store.insert_session(editor.entity_id(), session.clone(), cx)
+        });
+        (store, session, kernel, cx)
+    }
+
+    fn sent_shutdown_request(kernel: &mut FakeKernelHandle) -> Option<JupyterMessage> {
+        std::iter::from_fn(|| kernel.request_rx.try_next().ok().flatten())
+            .find(|message| matches!(message.content, JupyterMessageContent::ShutdownRequest(_)))
+    }
+
+    #[gpui::test]
+    async fn test_shutdown_all_kernels_confirms_when_busy(cx: &mut TestAppContext) {
+        let (_store, session, mut kernel, cx) = init_store_test(cx).await;
+        session.update(cx, |session, _| {
+            session.kernel.set_execution_state(&ExecutionState::Busy)
+        });
+
+        cx.update(|window, cx| crate::repl_sessions_ui::shutdown_all_kernels(window, cx));
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        assert!(sent_shutdown_request(&mut kernel).is_none());
+
+        cx.simulate_prompt_answer("Shut Down");
+        cx.run_until_parked();
+        assert!(sent_shutdown_request(&mut kernel).is_some());
+
+        cx.executor()
+            .advance_clock(crate::session::SHUTDOWN_GRACE_PERIOD);
+        cx.run_until_parked();
+        assert!(kernel.killed.load(Ordering::SeqCst));
+        session.read_with(cx, |session, _| {
+            assert!(matches!(session.kernel, Kernel::Shutdown));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_shutdown_all_kernels_without_busy_kernels(cx: &mut TestAppContext) {
+        let (_store, _session, mut kernel, cx) = init_store_test(cx).await;
+
+        cx.update(|window, cx| crate::repl_sessions_ui::shutdown_all_kernels(window, cx));
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(sent_shutdown_request(&mut kernel).is_some());
+    }
+
+    #[gpui::test]
+    async fn test_kernels_are_shut_down_on_quit(cx: &mut TestAppContext) {
+        let (store, session, mut kernel, cx) = init_store_test(cx).await;
+
+        let shutdown = store.update(cx, |store, cx| store.shutdown_kernels_on_quit(cx));
+        assert!(sent_shutdown_request(&mut kernel).is_some());
+        session.read_with(cx, |session, _| {
+            assert!(matches!(session.kernel, Kernel::ShuttingDown));
+        });
+
+        // The fake kernel never replies, so it's killed once the grace period runs out.
+        cx.executor().advance_clock(QUIT_GRACE_PERIOD);
+        shutdown.await;
+        assert!(kernel.killed.load(Ordering::SeqCst));
+    }
+}
@@ -1,32 +1,49 @@
 use crate::components::KernelListItem;
+use crate::kernel_completions::{FallbackCompletionProvider, KernelCompletionProvider};
 use crate::kernels::RemoteRunningKernel;
 use crate::setup_editor_session_actions;
 use crate::{
-    KernelStatus,
-    kernels::{Kernel, KernelSpecification, NativeRunningKernel},
-    outputs::{ExecutionStatus, ExecutionView},
+    CloseOutput, JupyterSettings, KernelStatus, OutputDensity,
+    kernels::{
+        ExistingRunningKernel, Kernel, KernelResourceUsage, KernelSpecification,
+        NativeRunningKernel,
+    },
+    outputs::{
+        ExecutionStatus, ExecutionView, Output, WIDGET_COMM_TARGET, WidgetModel, fenced_code_block,
+    },
 };
-use anyhow::Context as _;
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
 use editor::{
-    Anchor, AnchorRangeExt as _, Editor, MultiBuffer, ToPoint,
+    Anchor, AnchorRangeExt as _, Editor, InlayId, MultiBuffer, MultiBufferSnapshot, ToPoint,
     display_map::{
-        BlockContext, BlockId, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId,
+        BlockContext, BlockId, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, Inlay,
         RenderBlock,
     },
+    hover_popover,
     scroll::Autoscroll,
 };
-use futures::FutureExt as _;
+use futures::{FutureExt as _, channel::oneshot, future::Either};
 use gpui::{
-    Context, Entity, EventEmitter, Render, Subscription, Task, WeakEntity, Window, div, prelude::*,
+    Animation, AnimationExt as _, Context, Entity, EventEmitter, Focusable, PromptLevel, Render,
+    Subscription, Task, Transformation, WeakEntity, Window, div, percentage, prelude::*,
 };
 use language::Point;
-use project::Fs;
+use multi_buffer::MultiBufferRow;
+use project::{Fs, HoverBlock, HoverBlockKind};
 use runtimelib::{
-    ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    CommId, CommMsg, ExecuteRequest, ExecutionState, InspectRequest, InterruptRequest,
+    JupyterMessage, JupyterMessageContent, MimeBundle, MimeType, ReplyStatus, ShutdownRequest,
 };
-use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
+use settings::Settings as _;
+use std::{
+    env::temp_dir,
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 use theme::ActiveTheme;
 use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::ResultExt as _;
@@ -36,32 +53,114 @@ pub struct Session {
     editor: WeakEntity<Editor>,
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
+    /// Executions waiting for the kernel to start, which haven't been sent to it yet.
+    queued_executions: HashSet<String>,
+    /// Executions that haven't started running yet, in the order they were submitted.
+    execution_queue: Vec<String>,
     pub kernel_specification: KernelSpecification,
+    /// Overrides the directory the kernel is launched in, when set by the user.
+    working_directory: Option<PathBuf>,
+    /// Requests sent with [`Session::send_request`] that are waiting for the kernel's reply.
+    pending_replies: HashMap<String, oneshot::Sender<JupyterMessageContent>>,
+    /// Executions started by [`Session::execute_silent`], which have no block in the editor.
+    silent_executions: HashMap<String, SilentExecution>,
+    /// The models of the widgets the kernel has opened comms for, keyed by comm id.
+    widget_models: HashMap<String, Entity<WidgetModel>>,
+    /// The code range last sent with [`Session::execute`], along with where it started in case
+    /// edits invalidate its anchors.
+    last_execution: Option<(Range<Anchor>, Point)>,
+    _stale_blocks_task: Option<Task<()>>,
+    /// The kernel process's latest CPU and memory use, for kernels running on this machine.
+    resource_usage: Option<KernelResourceUsage>,
+    _resource_usage_task: Option<Task<()>>,
+    /// When the kernel began starting, while it's still starting.
+    starting_since: Option<Instant>,
+    _starting_timer_task: Option<Task<()>>,
+    /// Why the startup code configured for the kernel's language failed, if it did.
+    startup_error: Option<SharedString>,
+    /// Picks the accent color marking this session's outputs, so that outputs from kernels
+    /// attached to different editors can be told apart.
+    color_index: u32,
+    /// The completion provider the editor had before this session replaced it, which is given
+    /// back once the session ends.
+    completion_fallback: FallbackCompletionProvider,
     _buffer_subscription: Subscription,
+    _editor_subscription: Subscription,
+}
+
+/// How long to wait for the kernel to answer a request before giving up on it.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long outputs invalidated by an edit are shown dimmed before they may be removed.
+const STALE_BLOCK_GRACE_PERIOD: Duration = Duration::from_millis(1500);
+
+/// How long a kernel asked to shut down may take to reply before its process is killed.
+pub(crate) const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// How often the kernel process's resource usage is sampled.
+const RESOURCE_USAGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a kernel may take to start before a hint explains why it might be slow.
+const SLOW_STARTUP_THRESHOLD: Duration = Duration::from_secs(30);
+
+struct SilentExecution {
+    execution_view: Entity<ExecutionView>,
+    outputs_tx: oneshot::Sender<Vec<Output>>,
 }
 
 struct EditorBlock {
     code_range: Range<Anchor>,
+    /// Where the block was when it was pinned, which it stays below instead of its code.
+    pin_anchor: Option<Anchor>,
     invalidation_anchor: Anchor,
     block_id: CustomBlockId,
+    style: BlockStyle,
+    /// Kept so that the block can be shown again after its result was shown inline.
+    render: RenderBlock,
+    /// Set while the block is removed and its result is shown at the end of the code line.
+    inline_result: Option<InlayId>,
     execution_view: Entity<ExecutionView>,
+    /// When the cell was sent to run, which orders the cells in exported transcripts.
+    submitted_at: Instant,
+    /// When the kernel reported it began running this cell.
+    started_at: Option<Instant>,
+    /// Set when the output was kept through a kernel restart, which leaves it out of date.
+    outlived_kernel: bool,
 }
 
 type CloseBlockFn =
     Arc<dyn for<'a> Fn(CustomBlockId, &'a mut Window, &mut App) + Send + Sync + 'static>;
 
+type CancelBlockFn = Arc<dyn for<'a> Fn(&'a mut Window, &mut App) + Send + Sync + 'static>;
+
+type TogglePinFn = Arc<dyn for<'a> Fn(&'a mut Window, &mut App) + Send + Sync + 'static>;
+
 impl EditorBlock {
     fn new(
         editor: WeakEntity<Editor>,
         code_range: Range<Anchor>,
         status: ExecutionStatus,
+        color_index: u32,
         on_close: CloseBlockFn,
+        on_cancel: CancelBlockFn,
+        on_toggle_pin: Option<TogglePinFn>,
         cx: &mut Context<Session>,
     ) -> anyhow::Result<Self> {
         let editor = editor.upgrade().context("editor is not open")?;
         let workspace = editor.read(cx).workspace().context("workspace dropped")?;
 
         let execution_view = cx.new(|cx| ExecutionView::new(status, workspace.downgrade(), cx));
+        let style = JupyterSettings::get_global(cx).output_block_style.into();
+
+        let render = Self::create_output_area_renderer(
+            execution_view.clone(),
+            editor.downgrade(),
+            color_index,
+            style,
+            on_close.clone(),
+            on_cancel,
+            on_toggle_pin,
+        );
 
         let (block_id, invalidation_anchor) = editor.update(cx, |editor, cx| {
             let buffer = editor.buffer().clone();
@@ -82,28 +181,76 @@ impl EditorBlock {
             }
 
             let invalidation_anchor = buffer.read(cx).read(cx).anchor_before(next_row_start);
-            let block = BlockProperties {
-                placement: BlockPlacement::Below(code_range.end),
-                // Take up at least one height for status, allow the editor to determine the real height based on the content from render
-                height: Some(1),
-                style: BlockStyle::Sticky,
-                render: Self::create_output_area_renderer(execution_view.clone(), on_close.clone()),
-                priority: 0,
-                render_in_minimap: false,
-            };
-
+            let block = Self::block_properties(code_range.end, style, render.clone());
             let block_id = editor.insert_blocks([block], None, cx)[0];
             (block_id, invalidation_anchor)
         });
 
         anyhow::Ok(Self {
             code_range,
+            pin_anchor: None,
             invalidation_anchor,
             block_id,
+            style,
+            render,
+            inline_result: None,
             execution_view,
+            submitted_at: Instant::now(),
+            started_at: None,
+            outlived_kernel: false,
         })
     }
 
+    /// The anchor the block is shown below.
+    fn position(&self) -> Anchor {
+        self.pin_anchor.unwrap_or(self.code_range.end)
+    }
+
+    /// Pins the block to the end of the line it's shown below, so that it stays there when its
+    /// code is edited or moved, or unpins it to follow its code again.
+    fn set_pinned(&mut self, pinned: bool, editor: &Entity<Editor>, cx: &mut App) {
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.set_pinned(pinned, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            self.pin_anchor = pinned.then(|| {
+                let row = self.code_range.end.to_point(&snapshot).row;
+                snapshot.anchor_before(Point::new(row, snapshot.line_len(MultiBufferRow(row))))
+            });
+            // A result shown inline has no block to move.
+            if self.inline_result.is_some() {
+                return;
+            }
+            editor.remove_blocks(HashSet::from_iter([self.block_id]), None, cx);
+            let properties =
+                Self::block_properties(self.position(), self.style, self.render.clone());
+            if let Some(block_id) = editor
+                .insert_blocks([properties], None, cx)
+                .into_iter()
+                .next()
+            {
+                self.block_id = block_id;
+            }
+        });
+    }
+
+    fn block_properties(
+        position: Anchor,
+        style: BlockStyle,
+        render: RenderBlock,
+    ) -> BlockProperties<Anchor> {
+        BlockProperties {
+            placement: BlockPlacement::Below(position),
+            // Take up at least one height for status, allow the editor to determine the real height based on the content from render
+            height: Some(1),
+            style,
+            render,
+            priority: 0,
+            render_in_minimap: false,
+        }
+    }
+
     fn handle_message(
         &mut self,
         message: &JupyterMessage,
@@ -117,7 +264,12 @@ impl EditorBlock {
 
     fn create_output_area_renderer(
         execution_view: Entity<ExecutionView>,
+        editor: WeakEntity<Editor>,
+        color_index: u32,
+        style: BlockStyle,
         on_close: CloseBlockFn,
+        on_cancel: CancelBlockFn,
+        on_toggle_pin: Option<TogglePinFn>,
     ) -> RenderBlock {
         Arc::new(move |cx: &mut BlockContext| {
             let execution_view = execution_view.clone();
@@ -128,17 +280,70 @@ impl EditorBlock {
 
             let block_id = cx.block_id;
             let on_close = on_close.clone();
+            let on_cancel = on_cancel.clone();
+            let close_output = {
+                let on_close = on_close.clone();
+                let editor = editor.clone();
+                move |_: &CloseOutput, window: &mut Window, cx: &mut App| {
+                    if let BlockId::Custom(block_id) = block_id {
+                        (on_close)(block_id, window, cx)
+                    }
+                    if let Some(editor) = editor.upgrade() {
+                        window.focus(&editor.focus_handle(cx));
+                    }
+                }
+            };
+            let return_to_editor = {
+                let editor = editor.clone();
+                move |_: &menu::Cancel, window: &mut Window, cx: &mut App| {
+                    if let Some(editor) = editor.upgrade() {
+                        window.focus(&editor.focus_handle(cx));
+                    }
+                }
+            };
+            let status = execution_view.read(cx.app).status.clone();
+            let is_active = matches!(
+                status,
+                ExecutionStatus::ConnectingToKernel
+                    | ExecutionStatus::Queued
+                    | ExecutionStatus::Executing
+            );
+            // Only executions that haven't been sent to the kernel, or the one it's running, can
+            // be cancelled.
+            let can_cancel = matches!(
+                status,
+                ExecutionStatus::ConnectingToKernel | ExecutionStatus::Executing
+            );
+            let pinned = execution_view.read(cx.app).pinned;
+            let tab_stops = execution_view.read(cx.app).tab_stops.clone();
+            tab_stops.clear_block_controls();
+            // A running output can't be pinned yet, so the pin takes the cancel button's place.
+            let on_toggle_pin = on_toggle_pin.clone().filter(|_| !is_active);
 
             let rem_size = cx.window.rem_size();
 
             let text_line_height = text_style.line_height_in_pixels(rem_size);
+            let compact =
+                JupyterSettings::get_global(cx.app).output_density == OutputDensity::Compact;
+            let vertical_padding = if compact {
+                text_line_height / 4.
+            } else {
+                text_line_height / 2.
+            };
+            // Each button keeps a full line of height in both densities so it stays easy to hit.
+            let button_count = if can_cancel || on_toggle_pin.is_some() {
+                2.
+            } else {
+                1.
+            };
+            let gutter_height = vertical_padding * 2. + text_line_height * button_count;
 
             let close_button = h_flex()
                 .flex_none()
                 .items_center()
                 .justify_center()
                 .absolute()
-                .top(text_line_height / 2.)
+                .top(vertical_padding)
                 .right(
                     // 2px is a magic number to nudge the button just a bit closer to
                     // the line number start
@@ -147,41 +352,145 @@ impl EditorBlock {
                 .w(text_line_height)
                 .h(text_line_height)
                 .child(
-                    IconButton::new("close_output_area", IconName::Close)
-                        .icon_size(IconSize::Small)
-                        .icon_color(Color::Muted)
-                        .size(ButtonSize::Compact)
-                        .shape(IconButtonShape::Square)
-                        .tooltip(Tooltip::text("Close output area"))
-                        .on_click(move |_, window, cx| {
+                    tab_stops.block_control(
+                        "close_output_area",
+                        "Close output area",
+                        IconButton::new("close_output_area", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .size(ButtonSize::Compact)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(Tooltip::text("Close output area")),
+                        move |_, window, cx| {
                             if let BlockId::Custom(block_id) = block_id {
                                 (on_close)(block_id, window, cx)
                             }
-                        }),
+                        },
+                        cx.window,
+                        cx.app,
+                    ),
                 );
 
+            let cancel_button = can_cancel.then(|| {
+                h_flex()
+                    .flex_none()
+                    .items_center()
+                    .justify_center()
+                    .absolute()
+                    .top(vertical_padding + text_line_height)
+                    .right(gutter.full_width() / 2.0 - text_line_height / 2.0 - px(2.))
+                    .w(text_line_height)
+                    .h(text_line_height)
+                    .child(
+                        tab_stops.block_control(
+                            "cancel_execution",
+                            "Cancel execution",
+                            IconButton::new("cancel_execution", IconName::Stop)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .size(ButtonSize::Compact)
+                                .shape(IconButtonShape::Square)
+                                .tooltip(Tooltip::text("Cancel execution")),
+                            move |_, window, cx| (on_cancel)(window, cx),
+                            cx.window,
+                            cx.app,
+                        ),
+                    )
+            });
+
+            let pin_button = on_toggle_pin.map(|on_toggle_pin| {
+                let label = if pinned { "Unpin output" } else { "Pin output" };
+                h_flex()
+                    .flex_none()
+                    .items_center()
+                    .justify_center()
+                    .absolute()
+                    .top(vertical_padding + text_line_height)
+                    .right(gutter.full_width() / 2.0 - text_line_height / 2.0 - px(2.))
+                    .w(text_line_height)
+                    .h(text_line_height)
+                    .child(
+                        tab_stops.block_control(
+                            "toggle_pin_output",
+                            label,
+                            IconButton::new("toggle_pin_output", IconName::Pin)
+                                .icon_size(IconSize::Small)
+                                .icon_color(if pinned { Color::Accent } else { Color::Muted })
+                                .size(ButtonSize::Compact)
+                                .shape(IconButtonShape::Square)
+                                .toggle_state(pinned)
+                                .tooltip(Tooltip::text(label)),
+                            move |_, window, cx| (on_toggle_pin)(window, cx),
+                            cx.window,
+                            cx.app,
+                        ),
+                    )
+            });
+
+            // The stripe ties the output to the code that produced it, which a pinned output no
+            // longer follows.
+            let accent_color = if pinned {
+                cx.theme().colors().border_variant
+            } else {
+                cx.theme()
+                    .players()
+                    .color_for_participant(color_index)
+                    .cursor
+                    .opacity(0.6)
+            };
+
+            // Keyboard users reach these through the focusable `ExecutionView` inside the block,
+            // and tab through its buttons from there.
             div()
                 .id(cx.block_id)
+                .key_context("ReplOutput")
+                .on_action(return_to_editor)
+                .on_action(close_output)
+                .on_action({
+                    let tab_stops = tab_stops.clone();
+                    move |_: &menu::SelectNext, window, _| tab_stops.focus_next(window)
+                })
+                .on_action(move |_: &menu::SelectPrevious, window, _| {
+                    tab_stops.focus_previous(window)
+                })
                 .block_mouse_down()
+                .relative()
                 .flex()
                 .items_start()
                 .min_h(text_line_height)
-                .w_full()
+                .map(|this| match style {
+                    // Fixed blocks are laid out at their content's width, so they're given the
+                    // editor's width instead. The em is left over because the editor widens its
+                    // scrollable area by one past the widest fixed block.
+                    BlockStyle::Fixed => this.w(gutter.width + cx.max_width - cx.em_width),
+                    BlockStyle::Flex | BlockStyle::Sticky => this.w_full(),
+                })
                 .border_y_1()
                 .border_color(cx.theme().colors().border)
                 .bg(cx.theme().colors().background)
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .bottom_0()
+                        .left_0()
+                        .w(px(2.))
+                        .bg(accent_color),
+                )
                 .child(
                     div()
                         .relative()
                         .w(gutter.full_width())
-                        .h(text_line_height * 2)
-                        .child(close_button),
+                        .h(gutter_height)
+                        .child(close_button)
+                        .children(cancel_button)
+                        .children(pin_button),
                 )
                 .child(
                     div()
                         .flex_1()
                         .size_full()
-                        .py(text_line_height / 2.)
+                        .py(vertical_padding)
                         .mr(editor_margins.right)
                         .pr_2()
                         .child(execution_view),
@@ -196,22 +505,36 @@ impl Session {
         editor: WeakEntity<Editor>,
         fs: Arc<dyn Fs>,
         kernel_specification: KernelSpecification,
+        working_directory: Option<PathBuf>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let subscription = match editor.upgrade() {
+        let (buffer_subscription, editor_subscription) = match editor.upgrade() {
             Some(editor) => {
                 let buffer = editor.read(cx).buffer().clone();
-                cx.subscribe(&buffer, Self::on_buffer_event)
+                (
+                    cx.subscribe(&buffer, Self::on_buffer_event),
+                    cx.observe_release(&editor, |session, _, cx| {
+                        session.editor_released(cx);
+                    }),
+                )
             }
-            None => Subscription::new(|| {}),
+            None => (Subscription::new(|| {}), Subscription::new(|| {})),
         };
 
         let editor_handle = editor.clone();
+        let session_handle = cx.weak_entity();
+        let completion_fallback = FallbackCompletionProvider::default();
 
         editor
             .update(cx, |editor, _cx| {
                 setup_editor_session_actions(editor, editor_handle);
+
+                *completion_fallback.borrow_mut() = editor.take_completion_provider();
+                editor.set_completion_provider(Some(Box::new(KernelCompletionProvider::new(
+                    session_handle,
+                    completion_fallback.clone(),
+                ))));
             })
             .ok();
 
@@ -220,8 +543,24 @@ impl Session {
             editor,
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
+            queued_executions: HashSet::default(),
+            execution_queue: Vec::new(),
             kernel_specification,
-            _buffer_subscription: subscription,
+            working_directory,
+            pending_replies: HashMap::default(),
+            silent_executions: HashMap::default(),
+            widget_models: HashMap::default(),
+            last_execution: None,
+            _stale_blocks_task: None,
+            resource_usage: None,
+            _resource_usage_task: None,
+            starting_since: None,
+            _starting_timer_task: None,
+            startup_error: None,
+            color_index: 0,
+            completion_fallback,
+            _buffer_subscription: buffer_subscription,
+            _editor_subscription: editor_subscription,
         };
 
         session.start_kernel(window, cx);
@@ -230,12 +569,15 @@ impl Session {
 
     fn start_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let kernel_language = self.kernel_specification.language();
-        let entity_id = self.editor.entity_id();
-        let working_directory = self
-            .editor
-            .upgrade()
-            .and_then(|editor| editor.read(cx).working_directory(cx))
-            .unwrap_or_else(temp_dir);
+        // Keyed by session rather than editor, since duplicated sessions share their editor and
+        // each kernel needs a connection file of its own.
+        let entity_id = cx.entity_id();
+        let working_directory = self.working_directory(cx);
+        let workspace_root = self
+            .worktree_root(cx)
+            .unwrap_or_else(|| working_directory.clone());
+        let env = JupyterSettings::get_global(cx)
+            .kernel_env(&self.kernel_specification.name(), &workspace_root);
 
         telemetry::event!(
             "Kernel Status Changed",
@@ -252,6 +594,7 @@ impl Session {
                 kernel_specification,
                 entity_id,
                 working_directory,
+                env,
                 self.fs.clone(),
                 session_view,
                 window,
@@ -264,6 +607,16 @@ impl Session {
                 window,
                 cx,
             ),
+            KernelSpecification::ExistingConnection(existing_kernel_specification) => {
+                ExistingRunningKernel::new(
+                    existing_kernel_specification,
+                    working_directory,
+                    self.fs.clone(),
+                    session_view,
+                    window,
+                    cx,
+                )
+            }
         };
 
         let pending_kernel = cx
@@ -274,6 +627,7 @@ impl Session {
                     Ok(kernel) => {
                         this.update(cx, |session, cx| {
                             session.kernel(Kernel::RunningKernel(kernel), cx);
+                            session.run_startup_code(cx);
                         })
                         .ok();
                     }
@@ -291,14 +645,123 @@ impl Session {
         cx.notify();
     }
 
+    /// With its editor gone there's nowhere to show the kernel's output, so the session stops
+    /// tracking outputs and shuts the kernel down rather than leaving it running unseen.
+    fn editor_released(&mut self, cx: &mut Context<Self>) {
+        self.clear_outputs(cx);
+        self.last_execution = None;
+
+        if !matches!(self.kernel, Kernel::ShuttingDown | Kernel::Shutdown) {
+            self.shutdown(cx).detach();
+        }
+    }
+
+    /// Runs the startup code configured for the kernel's language. It's sent before any
+    /// executions that were queued while the kernel started, so they can rely on it.
+    fn run_startup_code(&mut self, cx: &mut Context<Self>) {
+        self.startup_error = None;
+        let Some(code) = JupyterSettings::get_global(cx)
+            .startup_code(&self.kernel_specification.language())
+            .map(ToOwned::to_owned)
+        else {
+            return;
+        };
+
+        let execution = self.execute_silent(code, cx);
+        cx.spawn(async move |this, cx| {
+            let error = match execution.await {
+                Ok(outputs) => outputs.iter().find_map(|output| match output {
+                    Output::ErrorOutput(error) => {
+                        Some(format!("{}: {}", error.ename, error.evalue))
+                    }
+                    _ => None,
+                }),
+                Err(error) => Some(error.to_string()),
+            };
+            this.update(cx, |session, cx| {
+                session.startup_error = error.map(SharedString::from);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub fn startup_error(&self) -> Option<&SharedString> {
+        self.startup_error.as_ref()
+    }
+
+    pub fn editor(&self) -> &WeakEntity<Editor> {
+        &self.editor
+    }
+
+    pub fn color_index(&self) -> u32 {
+        self.color_index
+    }
+
+    pub(crate) fn set_color_index(&mut self, color_index: u32, cx: &mut Context<Self>) {
+        self.color_index = color_index;
+        cx.notify();
+    }
+
+    /// Returns the directory the kernel is launched in.
+    ///
+    /// Unless overridden, this is the directory of the editor's file. Unsaved buffers use the
+    /// first worktree's root, and the OS temp directory is only used as a last resort.
+    pub fn working_directory(&self, cx: &App) -> PathBuf {
+        if let Some(working_directory) = &self.working_directory {
+            return working_directory.clone();
+        }
+
+        let Some(editor) = self.editor.upgrade() else {
+            return temp_dir();
+        };
+        let editor = editor.read(cx);
+
+        editor
+            .working_directory(cx)
+            .or_else(|| {
+                let project = editor.project.as_ref()?.read(cx);
+                let worktree = project.visible_worktrees(cx).next()?;
+                Some(worktree.read(cx).abs_path().to_path_buf())
+            })
+            .unwrap_or_else(temp_dir)
+    }
+
+    /// The root of the worktree containing the editor's file, or of the project's first worktree.
+    fn worktree_root(&self, cx: &App) -> Option<PathBuf> {
+        let editor = self.editor.upgrade()?;
+        let editor = editor.read(cx);
+        let project = editor.project.as_ref()?.read(cx);
+        let worktree = editor
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).project_path(cx))
+            .and_then(|project_path| project.worktree_for_id(project_path.worktree_id, cx))
+            .or_else(|| project.visible_worktrees(cx).next())?;
+        Some(worktree.read(cx).abs_path().to_path_buf())
+    }
+
+    /// Overrides the kernel's working directory and restarts it there.
+    pub fn set_working_directory(
+        &mut self,
+        working_directory: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.working_directory = Some(working_directory);
+        self.restart(window, cx);
+    }
+
     pub fn kernel_errored(&mut self, error_message: String, cx: &mut Context<Self>) {
         self.kernel(Kernel::ErroredLaunch(error_message.clone()), cx);
 
         self.blocks.values().for_each(|block| {
             block.execution_view.update(cx, |execution_view, cx| {
                 match execution_view.status {
-                    ExecutionStatus::Finished => {
-                        // Do nothing when the output was good
+                    ExecutionStatus::Finished | ExecutionStatus::Cancelled => {
+                        // Do nothing when the output was good or never requested
                     }
                     _ => {
                         // All other cases, set the status to errored
@@ -319,25 +782,186 @@ impl Session {
     ) {
         if let multi_buffer::Event::Edited { .. } = event {
             let snapshot = buffer.read(cx).snapshot(cx);
+            self.update_stale_blocks(&snapshot, cx);
+        }
+    }
 
-            let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
+    /// Marks the outputs whose code was edited as stale. Pinned outputs are left alone, since
+    /// they no longer follow their code.
+    fn update_stale_blocks(&mut self, snapshot: &MultiBufferSnapshot, cx: &mut Context<Self>) {
+        let keep_outputs = JupyterSettings::get_global(cx).keep_outputs_on_edit;
 
-            self.blocks.retain(|_id, block| {
-                if block.invalidation_anchor.is_valid(&snapshot) {
-                    true
-                } else {
-                    blocks_to_remove.insert(block.block_id);
-                    false
+        let mut became_stale = false;
+        for block in self.blocks.values() {
+            block.execution_view.update(cx, |execution_view, cx| {
+                if execution_view.pinned {
+                    return;
                 }
+                let stale = block.outlived_kernel
+                    || !keep_outputs && !block.invalidation_anchor.is_valid(snapshot);
+                became_stale |= stale && !execution_view.stale;
+                execution_view.set_stale(stale, cx);
             });
+        }
 
-            if !blocks_to_remove.is_empty() {
-                self.editor
-                    .update(cx, |editor, cx| {
-                        editor.remove_blocks(blocks_to_remove, None, cx);
-                    })
+        // Removing outputs mid-keystroke makes them seem to vanish, so they're only dimmed at
+        // first, and the ones whose code didn't survive the edit are removed once it settles.
+        if became_stale {
+            self._stale_blocks_task = Some(cx.spawn(async move |this, cx| {
+                cx.background_executor()
+                    .timer(STALE_BLOCK_GRACE_PERIOD)
+                    .await;
+                this.update(cx, |this, cx| this.remove_destroyed_blocks(cx))
                     .ok();
-                cx.notify();
+            }));
+        }
+    }
+
+    /// Pins or unpins the output of the given execution. A pinned output stays below the line it
+    /// was shown below when it was pinned, even when its code is edited, deleted or run again.
+    pub fn toggle_pin(&mut self, message_id: &str, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let Some(block) = self.blocks.get_mut(message_id) else {
+            return;
+        };
+        let pinned = !block.execution_view.read(cx).pinned;
+        block.set_pinned(pinned, &editor, cx);
+        if !pinned {
+            self.refresh_stale_blocks(cx);
+        }
+        cx.notify();
+    }
+
+    pub fn unpin_all_outputs(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let mut unpinned_any = false;
+        for block in self.blocks.values_mut() {
+            if block.execution_view.read(cx).pinned {
+                block.set_pinned(false, &editor, cx);
+                unpinned_any = true;
+            }
+        }
+        if unpinned_any {
+            self.refresh_stale_blocks(cx);
+            cx.notify();
+        }
+    }
+
+    /// Catches unpinned outputs up on the edits made to their code while they were pinned.
+    fn refresh_stale_blocks(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        self.update_stale_blocks(&snapshot, cx);
+    }
+
+    /// Removes the stale blocks whose code was deleted, leaving those whose code was only edited.
+    fn remove_destroyed_blocks(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+
+        let destroyed = self
+            .blocks
+            .iter()
+            .filter(|(_, block)| {
+                block.execution_view.read(cx).stale
+                    && (!block.code_range.start.is_valid(&snapshot)
+                        || !block.code_range.end.is_valid(&snapshot)
+                        || block.code_range.to_offset(&snapshot).is_empty())
+            })
+            .map(|(message_id, _)| message_id.clone())
+            .collect::<Vec<_>>();
+
+        if !destroyed.is_empty() {
+            let blocks = destroyed
+                .iter()
+                .filter_map(|message_id| self.blocks.remove(message_id))
+                .collect();
+            self.remove_blocks(blocks, cx);
+            cx.notify();
+        }
+    }
+
+    /// Removes `blocks` from the editor, along with the results of those shown inline.
+    fn remove_blocks(&self, blocks: Vec<EditorBlock>, cx: &mut Context<Self>) {
+        let mut block_ids = HashSet::default();
+        let mut inlay_ids = Vec::new();
+        for block in blocks {
+            match block.inline_result {
+                Some(inlay_id) => inlay_ids.push(inlay_id),
+                None => {
+                    block_ids.insert(block.block_id);
+                }
+            }
+        }
+
+        self.editor
+            .update(cx, |editor, cx| {
+                if !block_ids.is_empty() {
+                    editor.remove_blocks(block_ids, None, cx);
+                }
+                if !inlay_ids.is_empty() {
+                    editor.splice_inlays(&inlay_ids, Vec::new(), cx);
+                }
+            })
+            .ok();
+    }
+
+    /// Moves a finished execution's result between its block and the end of its code line,
+    /// depending on whether it's short enough to show inline.
+    fn update_inline_result(&mut self, message_id: &str, cx: &mut Context<Self>) {
+        let Some(block) = self.blocks.get_mut(message_id) else {
+            return;
+        };
+        let max_length = JupyterSettings::get_global(cx).inline_result_max_length;
+        let inline_text = block.execution_view.read(cx).inline_result(max_length, cx);
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        match inline_text {
+            Some(text) => editor.update(cx, |editor, cx| {
+                let to_remove = match block.inline_result {
+                    Some(inlay_id) => vec![inlay_id],
+                    None => {
+                        editor.remove_blocks(HashSet::from_iter([block.block_id]), None, cx);
+                        Vec::new()
+                    }
+                };
+                let inlay = Inlay::repl_result(
+                    editor.next_inlay_id(),
+                    block.code_range.end,
+                    format!("  ⇒ {text}"),
+                );
+                block.inline_result = Some(inlay.id);
+                editor.splice_inlays(&to_remove, vec![inlay], cx);
+            }),
+            None => {
+                let Some(inlay_id) = block.inline_result.take() else {
+                    return;
+                };
+                editor.update(cx, |editor, cx| {
+                    editor.splice_inlays(&[inlay_id], Vec::new(), cx);
+                    let properties = EditorBlock::block_properties(
+                        block.position(),
+                        block.style,
+                        block.render.clone(),
+                    );
+                    if let Some(block_id) = editor
+                        .insert_blocks([properties], None, cx)
+                        .into_iter()
+                        .next()
+                    {
+                        block.block_id = block_id;
+                    }
+                });
             }
         }
     }
@@ -350,17 +974,243 @@ impl Session {
         anyhow::Ok(())
     }
 
-    pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
-        let blocks_to_remove: HashSet<CustomBlockId> =
-            self.blocks.values().map(|block| block.block_id).collect();
+    /// Sends a request on the shell channel and resolves with the kernel's reply to it.
+    pub fn send_request(
+        &mut self,
+        message: JupyterMessage,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<JupyterMessageContent>> {
+        let Kernel::RunningKernel(kernel) = &mut self.kernel else {
+            return Task::ready(Err(anyhow!("kernel is not running")));
+        };
 
-        self.editor
-            .update(cx, |editor, cx| {
-                editor.remove_blocks(blocks_to_remove, None, cx);
+        let message_id = message.header.msg_id.clone();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.pending_replies.insert(message_id.clone(), reply_tx);
+        kernel.request_tx().try_send(message).ok();
+
+        let timeout = cx.background_executor().timer(REPLY_TIMEOUT);
+        cx.spawn(async move |this, cx| {
+            let reply = match futures::future::select(reply_rx, timeout).await {
+                Either::Left((reply, _)) => reply.context("kernel disconnected before replying"),
+                Either::Right(_) => Err(anyhow!("kernel did not reply in time")),
+            };
+            this.update(cx, |session, _| {
+                session.pending_replies.remove(&message_id);
             })
             .ok();
+            reply
+        })
+    }
+
+    /// Asks the kernel about the symbol at `cursor_pos` in `code`, and shows what it says in a
+    /// popover at `anchor`. `detail_level` 0 asks for a summary such as the signature and
+    /// docstring, and 1 also asks for details such as the source.
+    pub fn inspect(
+        &mut self,
+        code: String,
+        cursor_pos: usize,
+        detail_level: usize,
+        anchor: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let request: JupyterMessage = InspectRequest {
+            code,
+            cursor_pos,
+            detail_level: Some(detail_level),
+        }
+        .into();
+        let reply = self.send_request(request, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let contents = match reply.await {
+                Ok(JupyterMessageContent::InspectReply(reply)) if reply.found => {
+                    inspection_contents(&reply.data)
+                }
+                Ok(_) => None,
+                Err(error) => {
+                    log::debug!("inspect request failed: {error:#}");
+                    None
+                }
+            };
+            let contents = contents.unwrap_or_else(|| HoverBlock {
+                text: "No information available".to_string(),
+                kind: HoverBlockKind::PlainText,
+            });
+            let hover = project::Hover {
+                contents: vec![contents],
+                range: None,
+                language: None,
+            };
+
+            this.update_in(cx, |session, window, cx| {
+                session
+                    .editor
+                    .update(cx, |editor, cx| {
+                        hover_popover::show_hover_contents(editor, anchor, hover, window, cx);
+                    })
+                    .ok();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Removes the outputs of the cells that are done running, leaving those that are still
+    /// queued or running in place, as well as pinned ones.
+    pub fn clear_finished_outputs(&mut self, cx: &mut Context<Self>) {
+        let message_ids = self
+            .blocks
+            .iter()
+            .filter(|(message_id, block)| {
+                let execution_view = block.execution_view.read(cx);
+                !self.execution_queue.contains(message_id)
+                    && !execution_view.pinned
+                    && !matches!(
+                        execution_view.status,
+                        ExecutionStatus::ConnectingToKernel
+                            | ExecutionStatus::Queued
+                            | ExecutionStatus::Executing
+                    )
+            })
+            .map(|(message_id, _)| message_id.clone())
+            .collect::<Vec<_>>();
+        let blocks = message_ids
+            .iter()
+            .filter_map(|message_id| self.blocks.remove(message_id))
+            .collect::<Vec<_>>();
+        if !blocks.is_empty() {
+            self.remove_blocks(blocks, cx);
+            cx.notify();
+        }
+    }
+
+    pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
+        let blocks = self.blocks.drain().map(|(_, block)| block).collect();
+        self.remove_blocks(blocks, cx);
+        // Silent executions that were never sent would otherwise wait forever.
+        for message_id in self.queued_executions.drain() {
+            self.silent_executions.remove(&message_id);
+        }
+        self.execution_queue.clear();
+    }
+
+    /// The cells run in this session and their outputs as Markdown, in the order they were run.
+    pub fn export_markdown(&self, window: &Window, cx: &App) -> Option<String> {
+        let editor = self.editor.upgrade()?;
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let language = self.kernel_specification.language().to_lowercase();
+
+        let mut blocks = self.blocks.values().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.submitted_at);
+
+        let sections = blocks
+            .into_iter()
+            .map(|block| {
+                let code = snapshot
+                    .text_for_range(block.code_range.clone())
+                    .collect::<String>();
+                let mut section = vec![fenced_code_block(code.trim_end(), &language)];
+                section.extend(
+                    block
+                        .execution_view
+                        .read(cx)
+                        .outputs
+                        .iter()
+                        .filter_map(|output| output.to_markdown(window, cx)),
+                );
+                section.join("\n\n")
+            })
+            .collect::<Vec<_>>();
+
+        (!sections.is_empty()).then(|| sections.join("\n\n") + "\n")
+    }
+
+    /// The output block of the cell containing `position`, if it's shown as a block.
+    pub fn output_at(&self, position: Anchor, cx: &App) -> Option<Entity<ExecutionView>> {
+        let editor = self.editor.upgrade()?;
+        let buffer = editor.read(cx).buffer().read(cx).snapshot(cx);
+        self.blocks
+            .values()
+            .filter(|block| block.inline_result.is_none())
+            .find(|block| {
+                block.code_range.start.cmp(&position, &buffer).is_le()
+                    && block.code_range.end.cmp(&position, &buffer).is_ge()
+            })
+            .map(|block| block.execution_view.clone())
+    }
+
+    /// The code ranges of the cells that currently have an output block.
+    pub fn output_code_ranges(&self) -> Vec<Range<Anchor>> {
+        self.blocks
+            .values()
+            .map(|block| block.code_range.clone())
+            .collect()
+    }
 
-        self.blocks.clear();
+    /// Removes the outputs of the cells whose code lies entirely within `range`.
+    pub fn clear_outputs_in_range(&mut self, range: Range<Anchor>, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let buffer = editor.read(cx).buffer().read(cx).snapshot(cx);
+
+        let message_ids = self
+            .blocks
+            .iter()
+            .filter(|(_, block)| {
+                block.code_range.start.cmp(&range.start, &buffer).is_ge()
+                    && block.code_range.end.cmp(&range.end, &buffer).is_le()
+            })
+            .map(|(message_id, _)| message_id.clone())
+            .collect::<Vec<_>>();
+        if message_ids.is_empty() {
+            return;
+        }
+
+        let mut blocks_to_remove = Vec::new();
+        for message_id in message_ids {
+            if let Some(block) = self.blocks.remove(&message_id) {
+                blocks_to_remove.push(block);
+            }
+            self.queued_executions.remove(&message_id);
+            self.dequeue_execution(&message_id, cx);
+        }
+
+        self.remove_blocks(blocks_to_remove, cx);
+
+        // Check the outputs that are left against their invalidation anchors again, so that the
+        // pending removal of stale outputs only outlives the ones it was waiting on if needed.
+        self.refresh_stale_blocks(cx);
+        if !self
+            .blocks
+            .values()
+            .any(|block| block.execution_view.read(cx).stale)
+        {
+            self._stale_blocks_task = None;
+        }
+        cx.notify();
+    }
+
+    fn dequeue_execution(&mut self, parent_message_id: &str, cx: &mut Context<Self>) {
+        let queue_len = self.execution_queue.len();
+        self.execution_queue.retain(|id| id != parent_message_id);
+        if self.execution_queue.len() == queue_len {
+            return;
+        }
+
+        for (ix, id) in self.execution_queue.iter().enumerate() {
+            if let Some(block) = self.blocks.get(id) {
+                block.execution_view.update(cx, |execution_view, cx| {
+                    execution_view.queue_position = Some(ix + 1);
+                    cx.notify();
+                });
+            }
+        }
+    }
+
+    pub fn last_execution(&self) -> Option<&(Range<Anchor>, Point)> {
+        self.last_execution.as_ref()
     }
 
     pub fn execute(
@@ -387,24 +1237,41 @@ impl Session {
 
         let message: JupyterMessage = execute_request.into();
 
-        let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
-
         let buffer = editor.read(cx).buffer().read(cx).snapshot(cx);
-
-        self.blocks.retain(|_key, block| {
-            if anchor_range.overlaps(&block.code_range, &buffer) {
-                blocks_to_remove.insert(block.block_id);
-                false
-            } else {
-                true
-            }
+        self.last_execution = Some((anchor_range.clone(), anchor_range.start.to_point(&buffer)));
+
+        let overlapping = self
+            .blocks
+            .iter()
+            .filter(|(_, block)| {
+                !block.execution_view.read(cx).pinned
+                    && anchor_range.overlaps(&block.code_range, &buffer)
+            })
+            .map(|(message_id, _)| message_id.clone())
+            .collect::<Vec<_>>();
+        let blocks_to_remove = overlapping
+            .iter()
+            .filter_map(|message_id| self.blocks.remove(message_id))
+            .collect::<Vec<_>>();
+        // A range can overlap several cells, but only the run of the same code is the one being
+        // re-run: the block whose code starts and ends closest to the range.
+        let anchor_offsets = anchor_range.to_offset(&buffer);
+        let rerun_block = blocks_to_remove.iter().min_by_key(|block| {
+            let code_offsets = block.code_range.to_offset(&buffer);
+            (
+                code_offsets.start.abs_diff(anchor_offsets.start),
+                code_offsets.end.abs_diff(anchor_offsets.end),
+            )
         });
-
-        self.editor
-            .update(cx, |editor, cx| {
-                editor.remove_blocks(blocks_to_remove, None, cx);
-            })
-            .ok();
+        let previous_output = rerun_block
+            .filter(|_| JupyterSettings::get_global(cx).diff_rerun_output)
+            .map(|block| block.execution_view.read(cx).text_output(cx))
+            .filter(|text| !text.is_empty());
+        let wrap_choice = rerun_block.and_then(|block| block.execution_view.read(cx).wrap_choice());
+        self.remove_blocks(blocks_to_remove, cx);
+        if JupyterSettings::get_global(cx).clear_outputs_on_run {
+            self.clear_finished_outputs(cx);
+        }
 
         let status = match &self.kernel {
             Kernel::Restarting => ExecutionStatus::Restarting,
@@ -419,6 +1286,30 @@ impl Session {
         let session_view = cx.entity().downgrade();
         let weak_editor = self.editor.clone();
 
+        let on_cancel: CancelBlockFn = Arc::new({
+            let session_view = session_view.clone();
+            let parent_message_id = parent_message_id.clone();
+            move |_: &mut Window, cx: &mut App| {
+                if let Some(session) = session_view.upgrade() {
+                    session.update(cx, |session, cx| {
+                        session.cancel_execution(&parent_message_id, cx);
+                    });
+                }
+            }
+        });
+
+        let on_toggle_pin: TogglePinFn = Arc::new({
+            let session_view = session_view.clone();
+            let parent_message_id = parent_message_id.clone();
+            move |_: &mut Window, cx: &mut App| {
+                if let Some(session) = session_view.upgrade() {
+                    session.update(cx, |session, cx| {
+                        session.toggle_pin(&parent_message_id, cx);
+                    });
+                }
+            }
+        });
+
         let on_close: CloseBlockFn = Arc::new(
             move |block_id: CustomBlockId, _: &mut Window, cx: &mut App| {
                 if let Some(session) = session_view.upgrade() {
@@ -438,21 +1329,116 @@ impl Session {
             },
         );
 
-        let Ok(editor_block) =
-            EditorBlock::new(self.editor.clone(), anchor_range, status, on_close, cx)
-        else {
+        let Ok(editor_block) = EditorBlock::new(
+            self.editor.clone(),
+            anchor_range,
+            status,
+            self.color_index,
+            on_close,
+            on_cancel,
+            Some(on_toggle_pin),
+            cx,
+        ) else {
             return;
         };
 
+        if let Some(previous_output) = previous_output {
+            editor_block
+                .execution_view
+                .update(cx, |execution_view, cx| {
+                    execution_view.set_previous_output(previous_output, cx);
+                });
+        }
+        if let Some(wrap) = wrap_choice {
+            editor_block
+                .execution_view
+                .update(cx, |execution_view, cx| {
+                    execution_view.set_wrap_choice(wrap, window, cx);
+                });
+        }
+
         let new_cursor_pos = if let Some(next_cursor) = next_cell {
             next_cursor
         } else {
             editor_block.invalidation_anchor
         };
 
+        if matches!(
+            self.kernel,
+            Kernel::RunningKernel(_) | Kernel::StartingKernel(_)
+        ) {
+            self.execution_queue.push(message.header.msg_id.clone());
+            let queue_position = self.execution_queue.len();
+            editor_block.execution_view.update(cx, |execution_view, _| {
+                execution_view.queue_position = Some(queue_position);
+            });
+        }
+
         self.blocks
             .insert(message.header.msg_id.clone(), editor_block);
 
+        self.send_or_queue(message, cx);
+
+        if move_down {
+            editor.update(cx, move |editor, cx| {
+                editor.change_selections(
+                    Some(Autoscroll::top_relative(8)),
+                    window,
+                    cx,
+                    |selections| {
+                        selections.select_ranges([new_cursor_pos..new_cursor_pos]);
+                    },
+                );
+            });
+        }
+    }
+
+    /// Runs `code` without creating a block in the editor, resolving with its outputs once the
+    /// kernel is idle again. It's queued and cancelled like executions started from the editor,
+    /// and an interrupt stops it too.
+    pub fn execute_silent(
+        &mut self,
+        code: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Output>>> {
+        match &self.kernel {
+            Kernel::RunningKernel(_) | Kernel::StartingKernel(_) => {}
+            Kernel::ErroredLaunch(error) => {
+                return Task::ready(Err(anyhow!("kernel failed to start: {error}")));
+            }
+            _ => return Task::ready(Err(anyhow!("kernel is not running"))),
+        }
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            // Keep tooling's code out of the user's `In`/`Out` history.
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        let execution_view =
+            cx.new(|cx| ExecutionView::new(ExecutionStatus::Queued, WeakEntity::new_invalid(), cx));
+        let (outputs_tx, outputs_rx) = oneshot::channel();
+        self.silent_executions.insert(
+            message.header.msg_id.clone(),
+            SilentExecution {
+                execution_view,
+                outputs_tx,
+            },
+        );
+        self.execution_queue.push(message.header.msg_id.clone());
+        self.send_or_queue(message, cx);
+
+        cx.spawn(async move |_, _| {
+            outputs_rx
+                .await
+                .map_err(|_| anyhow!("execution was cancelled"))
+        })
+    }
+
+    /// Sends an execution to the kernel, or holds it until the kernel has started.
+    fn send_or_queue(&mut self, message: JupyterMessage, cx: &mut Context<Self>) {
         match &self.kernel {
             Kernel::RunningKernel(_) => {
                 self.send(message, cx).ok();
@@ -461,11 +1447,14 @@ impl Session {
                 // Queue up the execution as a task to run after the kernel starts
                 let task = task.clone();
                 let message = message.clone();
+                self.queued_executions.insert(message.header.msg_id.clone());
 
                 cx.spawn(async move |this, cx| {
                     task.await;
                     this.update(cx, |session, cx| {
-                        session.send(message, cx).ok();
+                        if session.queued_executions.remove(&message.header.msg_id) {
+                            session.send(message, cx).ok();
+                        }
                     })
                     .ok();
                 })
@@ -473,19 +1462,6 @@ impl Session {
             }
             _ => {}
         }
-
-        if move_down {
-            editor.update(cx, move |editor, cx| {
-                editor.change_selections(
-                    Some(Autoscroll::top_relative(8)),
-                    window,
-                    cx,
-                    |selections| {
-                        selections.select_ranges([new_cursor_pos..new_cursor_pos]);
-                    },
-                );
-            });
-        }
     }
 
     pub fn route(&mut self, message: &JupyterMessage, window: &mut Window, cx: &mut Context<Self>) {
@@ -494,23 +1470,110 @@ impl Session {
             None => return,
         };
 
+        if message.header.msg_type.ends_with("_reply") {
+            if let Some(reply_tx) = self.pending_replies.remove(parent_message_id) {
+                reply_tx.send(message.content.clone()).ok();
+                return;
+            }
+        }
+
+        // A kernel that finished starting after its editor closed would otherwise keep running.
+        if self.editor.upgrade().is_none() {
+            self.editor_released(cx);
+            return;
+        }
+
         match &message.content {
             JupyterMessageContent::Status(status) => {
                 self.kernel.set_execution_state(&status.execution_state);
 
+                let kernel_status = KernelStatus::from(&self.kernel);
                 telemetry::event!(
                     "Kernel Status Changed",
                     kernel_language = self.kernel_specification.language(),
-                    kernel_status = KernelStatus::from(&self.kernel).to_string(),
+                    kernel_status = kernel_status.to_string(),
                     repl_session_id = cx.entity_id().to_string(),
                 );
+                cx.emit(SessionEvent::KernelStatusChanged(kernel_status));
+
+                if matches!(status.execution_state, ExecutionState::Busy)
+                    && (self.blocks.contains_key(parent_message_id)
+                        || self.silent_executions.contains_key(parent_message_id))
+                {
+                    if let Some(block) = self.blocks.get_mut(parent_message_id) {
+                        block.started_at.get_or_insert_with(Instant::now);
+                    }
+                    self.dequeue_execution(parent_message_id, cx);
+                    cx.emit(SessionEvent::ExecutionStarted {
+                        parent_message_id: parent_message_id.clone(),
+                    });
+                }
 
                 cx.notify();
             }
+            JupyterMessageContent::ExecuteReply(reply) => {
+                self.dequeue_execution(parent_message_id, cx);
+                if let Some(block) = self.blocks.get(parent_message_id) {
+                    let duration_ms = block
+                        .started_at
+                        .map(|started_at| started_at.elapsed().as_millis() as u64);
+                    telemetry::event!(
+                        "Kernel Execution Completed",
+                        kernel_language = self.kernel_specification.language(),
+                        success = matches!(reply.status, ReplyStatus::Ok),
+                        duration_ms,
+                        output_count = block.execution_view.read(cx).outputs.len(),
+                        repl_session_id = cx.entity_id().to_string(),
+                    );
+                    cx.emit(SessionEvent::ExecutionFinished {
+                        parent_message_id: parent_message_id.clone(),
+                        success: matches!(reply.status, ReplyStatus::Ok),
+                    });
+                }
+            }
             JupyterMessageContent::KernelInfoReply(reply) => {
                 self.kernel.set_kernel_info(reply);
                 cx.notify();
             }
+            JupyterMessageContent::CommOpen(open) => {
+                if open.target_name == WIDGET_COMM_TARGET {
+                    let state = open
+                        .data
+                        .get("state")
+                        .and_then(|state| state.as_object())
+                        .cloned()
+                        .unwrap_or_default();
+                    let session = cx.entity().downgrade();
+                    let model =
+                        cx.new(|_| WidgetModel::new(open.comm_id.0.clone(), state, session));
+                    self.widget_models.insert(open.comm_id.0.clone(), model);
+                    self.bind_widgets(cx);
+                }
+                return;
+            }
+            JupyterMessageContent::CommMsg(message) => {
+                let is_update = matches!(
+                    message
+                        .data
+                        .get("method")
+                        .and_then(|method| method.as_str()),
+                    Some("update" | "echo_update")
+                );
+                let state = message
+                    .data
+                    .get("state")
+                    .and_then(|state| state.as_object());
+                if let (true, Some(state), Some(model)) =
+                    (is_update, state, self.widget_models.get(&message.comm_id.0))
+                {
+                    model.update(cx, |model, cx| model.apply_state(state, cx));
+                }
+                return;
+            }
+            JupyterMessageContent::CommClose(close) => {
+                self.widget_models.remove(&close.comm_id.0);
+                return;
+            }
             JupyterMessageContent::UpdateDisplayData(update) => {
                 let display_id = if let Some(display_id) = update.transient.display_id.clone() {
                     display_id
@@ -523,6 +1586,11 @@ impl Session {
                         execution_view.update_display_data(&update.data, &display_id, window, cx);
                     });
                 });
+                self.bind_widgets(cx);
+                let message_ids = self.blocks.keys().cloned().collect::<Vec<_>>();
+                for message_id in message_ids {
+                    self.update_inline_result(&message_id, cx);
+                }
                 return;
             }
             _ => {}
@@ -530,6 +1598,54 @@ impl Session {
 
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
+            if matches!(
+                message.content,
+                JupyterMessageContent::DisplayData(_) | JupyterMessageContent::ExecuteResult(_)
+            ) {
+                self.bind_widgets(cx);
+            }
+            self.update_inline_result(parent_message_id, cx);
+        } else if let Some(execution) = self.silent_executions.get(parent_message_id) {
+            execution.execution_view.update(cx, |execution_view, cx| {
+                execution_view.push_message(&message.content, window, cx);
+            });
+
+            if let JupyterMessageContent::Status(status) = &message.content {
+                if matches!(status.execution_state, ExecutionState::Idle) {
+                    if let Some(execution) = self.silent_executions.remove(parent_message_id) {
+                        let outputs = execution.execution_view.update(cx, |execution_view, _| {
+                            std::mem::take(&mut execution_view.outputs)
+                        });
+                        execution.outputs_tx.send(outputs).ok();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends a message to the kernel over the comm with the given id.
+    pub(crate) fn send_comm_message(
+        &mut self,
+        comm_id: String,
+        data: serde_json::Map<String, serde_json::Value>,
+        cx: &mut Context<Self>,
+    ) {
+        let message = CommMsg {
+            comm_id: CommId(comm_id),
+            data,
+        };
+        self.send(message.into(), cx).log_err();
+    }
+
+    /// Connects the widgets shown in the editor to the models the kernel has opened for them.
+    fn bind_widgets(&self, cx: &mut Context<Self>) {
+        if self.widget_models.is_empty() {
+            return;
+        }
+        for block in self.blocks.values() {
+            block.execution_view.update(cx, |execution_view, cx| {
+                execution_view.bind_widgets(&self.widget_models, cx);
+            });
         }
     }
 
@@ -545,59 +1661,252 @@ impl Session {
         }
     }
 
+    /// Cancels a single execution.
+    ///
+    /// Executions that haven't been sent to the kernel yet are dropped, and a running execution
+    /// is stopped by interrupting the kernel. One the kernel has but hasn't started can't be
+    /// withdrawn, and interrupting would stop whichever one is running, so it's left alone.
+    pub fn cancel_execution(&mut self, parent_message_id: &str, cx: &mut Context<Self>) {
+        let Some(execution_view) = self
+            .blocks
+            .get(parent_message_id)
+            .map(|block| block.execution_view.clone())
+            .or_else(|| {
+                self.silent_executions
+                    .get(parent_message_id)
+                    .map(|execution| execution.execution_view.clone())
+            })
+        else {
+            return;
+        };
+
+        if !self.queued_executions.remove(parent_message_id) {
+            if matches!(execution_view.read(cx).status, ExecutionStatus::Executing) {
+                self.interrupt(cx);
+            }
+            return;
+        }
+
+        execution_view.update(cx, |execution_view, cx| {
+            execution_view.status = ExecutionStatus::Cancelled;
+            cx.notify();
+        });
+        // Dropping the sender fails the task returned by `execute_silent`.
+        self.silent_executions.remove(parent_message_id);
+        self.dequeue_execution(parent_message_id, cx);
+    }
+
     pub fn kernel(&mut self, kernel: Kernel, cx: &mut Context<Self>) {
         if let Kernel::Shutdown = kernel {
+            self.restore_completion_provider(cx);
             cx.emit(SessionEvent::Shutdown(self.editor.clone()));
         }
 
-        let kernel_status = KernelStatus::from(&kernel).to_string();
+        let kernel_status = KernelStatus::from(&kernel);
         let kernel_language = self.kernel_specification.language();
 
         telemetry::event!(
             "Kernel Status Changed",
             kernel_language,
-            kernel_status,
+            kernel_status = kernel_status.to_string(),
             repl_session_id = cx.entity_id().to_string(),
         );
+        cx.emit(SessionEvent::KernelStatusChanged(kernel_status));
 
-        self.kernel = kernel;
-    }
-
-    pub fn shutdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let kernel = std::mem::replace(&mut self.kernel, Kernel::ShuttingDown);
+        if matches!(kernel, Kernel::Shutdown | Kernel::ErroredLaunch(_)) {
+            self.silent_executions.clear();
+            self.pending_replies.clear();
+        }
+        // Widget models live in the kernel, so a new kernel starts without any.
+        if !matches!(kernel, Kernel::RunningKernel(_)) {
+            self.widget_models.clear();
+        }
 
-        match kernel {
-            Kernel::RunningKernel(mut kernel) => {
-                let mut request_tx = kernel.request_tx().clone();
+        self.resource_usage = None;
+        self._resource_usage_task = match &kernel {
+            Kernel::RunningKernel(kernel) => kernel
+                .process_id()
+                .map(|process_id| Self::sample_resource_usage(process_id, cx)),
+            _ => None,
+        };
 
-                let forced = kernel.force_shutdown(window, cx);
+        if let Kernel::StartingKernel(_) = kernel {
+            if self.starting_since.is_none() {
+                self.starting_since = Some(Instant::now());
+                self._starting_timer_task = Some(Self::tick_starting_timer(cx));
+            }
+        } else {
+            self.starting_since = None;
+            self._starting_timer_task = None;
+        }
 
-                cx.spawn(async move |this, cx| {
-                    let message: JupyterMessage = ShutdownRequest { restart: false }.into();
-                    request_tx.try_send(message).ok();
+        self.kernel = kernel;
+    }
 
-                    forced.await.log_err();
+    /// Re-renders the session every second so the time spent starting the kernel stays current.
+    fn tick_starting_timer(cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        })
+    }
 
-                    // Give the kernel a bit of time to clean up
-                    cx.background_executor().timer(Duration::from_secs(3)).await;
+    fn sample_resource_usage(process_id: u32, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let pid = Pid::from_u32(process_id);
+            let refresh_kind = ProcessRefreshKind::new().with_cpu().with_memory();
+            let mut system = System::new();
+
+            loop {
+                cx.background_executor()
+                    .timer(RESOURCE_USAGE_INTERVAL)
+                    .await;
+                let (refreshed_system, usage) = cx
+                    .background_spawn(async move {
+                        // CPU use is measured since the previous refresh, so the same `System`
+                        // is carried from one sample to the next.
+                        system.refresh_processes_specifics(
+                            ProcessesToUpdate::Some(&[pid]),
+                            refresh_kind,
+                        );
+                        let usage = system.process(pid).map(|process| KernelResourceUsage {
+                            cpu_percent: process.cpu_usage(),
+                            memory_bytes: process.memory(),
+                        });
+                        (system, usage)
+                    })
+                    .await;
+                system = refreshed_system;
 
-                    this.update(cx, |session, cx| {
-                        session.clear_outputs(cx);
-                        session.kernel(Kernel::Shutdown, cx);
+                let updated = this.update(cx, |session, cx| {
+                    if session.resource_usage != usage {
+                        session.resource_usage = usage;
                         cx.notify();
-                    })
-                    .ok();
-                })
-                .detach();
-            }
-            _ => {
-                self.kernel(Kernel::Shutdown, cx);
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
             }
+        })
+    }
+
+    /// Gives the editor back the completion provider it had before this session was attached.
+    pub(crate) fn restore_completion_provider(&mut self, cx: &mut Context<Self>) {
+        let Some(fallback) = self.completion_fallback.borrow_mut().take() else {
+            return;
+        };
+        self.editor
+            .update(cx, |editor, _| {
+                editor.set_completion_provider(Some(fallback))
+            })
+            .ok();
+    }
+
+    pub fn shutdown(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        self.restore_completion_provider(cx);
+        if !matches!(self.kernel, Kernel::RunningKernel(_)) {
+            self.kernel(Kernel::Shutdown, cx);
+            cx.notify();
+            return Task::ready(());
         }
+
+        let shutdown = self.shutdown_kernel(SHUTDOWN_GRACE_PERIOD, cx);
+        cx.spawn(async move |this, cx| {
+            shutdown.await;
+            this.update(cx, |session, cx| {
+                session.clear_outputs(cx);
+                session.kernel(Kernel::Shutdown, cx);
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    /// Asks a running kernel to shut down, and returns a task that kills it once it replies or
+    /// the grace period runs out. Kernels that Zed didn't start are disconnected from instead.
+    /// The task runs on the background executor, so it still makes progress while Zed quits.
+    pub(crate) fn shutdown_kernel(
+        &mut self,
+        grace_period: Duration,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let kernel = std::mem::replace(&mut self.kernel, Kernel::ShuttingDown);
+        let Kernel::RunningKernel(mut kernel) = kernel else {
+            self.kernel = kernel;
+            return Task::ready(());
+        };
         cx.notify();
+
+        if !kernel.owns_process() {
+            // Someone else started the kernel, so it's only disconnected from.
+            let disconnect = kernel.force_shutdown();
+            return cx.background_spawn(async move {
+                disconnect.await.log_err();
+            });
+        }
+
+        let message: JupyterMessage = ShutdownRequest { restart: false }.into();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.pending_replies
+            .insert(message.header.msg_id.clone(), reply_tx);
+        kernel.request_tx().try_send(message).ok();
+
+        let grace_period = cx.background_executor().timer(grace_period);
+        cx.background_spawn(async move {
+            // The kernel is kept alive so that its reply can still be routed here. Kernels that
+            // reply have finished cleaning up, so they needn't wait out the grace period.
+            futures::future::select(reply_rx, grace_period).await;
+            kernel.force_shutdown().await.log_err();
+        })
     }
 
+    /// Restarts the kernel. If there are outputs, the first restart asks whether to keep them,
+    /// and the answer is saved to the `keep_outputs_on_restart` setting.
     pub fn restart(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.blocks.is_empty() {
+            self.restart_kernel(false, window, cx);
+            return;
+        }
+        if let Some(keep_outputs) = JupyterSettings::get_global(cx).keep_outputs_on_restart {
+            self.restart_kernel(keep_outputs, window, cx);
+            return;
+        }
+
+        let answer = window.prompt(
+            PromptLevel::Info,
+            "Keep outputs when restarting the kernel?",
+            Some(
+                "Kept outputs are dimmed, since the new kernel didn't produce them. \
+                This choice is remembered in the `jupyter.keep_outputs_on_restart` setting.",
+            ),
+            &["Keep Outputs", "Clear Outputs", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let keep_outputs = match answer.await {
+                Ok(0) => true,
+                Ok(1) => false,
+                _ => return,
+            };
+            this.update_in(cx, |session, window, cx| {
+                settings::update_settings_file::<JupyterSettings>(
+                    session.fs.clone(),
+                    cx,
+                    move |settings, _| settings.keep_outputs_on_restart = Some(keep_outputs),
+                );
+                session.restart_kernel(keep_outputs, window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn restart_kernel(&mut self, keep_outputs: bool, window: &mut Window, cx: &mut Context<Self>) {
         let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
 
         match kernel {
@@ -605,15 +1914,19 @@ impl Session {
                 // Do nothing if already restarting
             }
             Kernel::RunningKernel(mut kernel) => {
-                let mut request_tx = kernel.request_tx().clone();
+                // A kernel that Zed didn't start is only disconnected from, so it's left running
+                // rather than asked to shut down.
+                let mut request_tx = kernel.owns_process().then(|| kernel.request_tx());
 
-                let forced = kernel.force_shutdown(window, cx);
+                let forced = kernel.force_shutdown();
 
                 cx.spawn_in(window, async move |this, cx| {
                     // Send shutdown request with restart flag
                     log::debug!("restarting kernel");
-                    let message: JupyterMessage = ShutdownRequest { restart: true }.into();
-                    request_tx.try_send(message).ok();
+                    if let Some(request_tx) = request_tx.as_mut() {
+                        let message: JupyterMessage = ShutdownRequest { restart: true }.into();
+                        request_tx.try_send(message).ok();
+                    }
 
                     // Wait for kernel to shutdown
                     cx.background_executor().timer(Duration::from_secs(1)).await;
@@ -623,8 +1936,7 @@ impl Session {
 
                     // Start a new kernel
                     this.update_in(cx, |session, window, cx| {
-                        // TODO: Differentiate between restart and restart+clear-outputs
-                        session.clear_outputs(cx);
+                        session.discard_outputs_from_previous_kernel(keep_outputs, cx);
                         session.start_kernel(window, cx);
                     })
                     .ok();
@@ -632,16 +1944,83 @@ impl Session {
                 .detach();
             }
             _ => {
-                self.clear_outputs(cx);
+                self.discard_outputs_from_previous_kernel(keep_outputs, cx);
                 self.start_kernel(window, cx);
             }
         }
         cx.notify();
     }
+
+    /// Clears the outputs of the kernel being restarted, or marks them stale when they're kept.
+    /// Pinned outputs aren't marked, since they're kept deliberately.
+    fn discard_outputs_from_previous_kernel(&mut self, keep_outputs: bool, cx: &mut Context<Self>) {
+        if !keep_outputs {
+            self.clear_outputs(cx);
+            return;
+        }
+        for block in self.blocks.values_mut() {
+            if block.execution_view.read(cx).pinned {
+                continue;
+            }
+            block.outlived_kernel = true;
+            block.execution_view.update(cx, |execution_view, cx| {
+                execution_view.set_stale(true, cx);
+            });
+        }
+        cx.notify();
+    }
+}
+
+/// What a block has shown so far, for tests to assert on.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Debug, Clone)]
+pub struct BlockOutput {
+    /// The id of the execute request that produced the block.
+    pub message_id: String,
+    pub text: String,
+    pub status: ExecutionStatus,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Session {
+    /// The output of every block, in the order their code was run.
+    pub fn block_outputs(&self, cx: &App) -> Vec<BlockOutput> {
+        let mut blocks = self.blocks.iter().collect::<Vec<_>>();
+        blocks.sort_by_key(|(_, block)| block.submitted_at);
+        blocks
+            .into_iter()
+            .map(|(message_id, block)| Self::block_output_for(message_id, block, cx))
+            .collect()
+    }
+
+    pub fn block_output(&self, message_id: &str, cx: &App) -> Option<BlockOutput> {
+        self.blocks
+            .get(message_id)
+            .map(|block| Self::block_output_for(message_id, block, cx))
+    }
+
+    fn block_output_for(message_id: &str, block: &EditorBlock, cx: &App) -> BlockOutput {
+        let execution_view = block.execution_view.read(cx);
+        BlockOutput {
+            message_id: message_id.to_string(),
+            text: execution_view.text_output(cx),
+            status: execution_view.status.clone(),
+        }
+    }
 }
 
 pub enum SessionEvent {
     Shutdown(WeakEntity<Editor>),
+    /// The kernel began running the code sent in the execute request with this message id.
+    ExecutionStarted {
+        parent_message_id: String,
+    },
+    /// The kernel replied to the execute request with this message id.
+    ExecutionFinished {
+        parent_message_id: String,
+        success: bool,
+    },
+    KernelStatusChanged(KernelStatus),
 }
 
 impl EventEmitter<SessionEvent> for Session {}
@@ -662,7 +2041,15 @@ impl Render for Session {
                         })),
                 ),
             ),
-            Kernel::StartingKernel(_) => (Some("Starting".into()), None),
+            Kernel::StartingKernel(_) => (
+                Some(match self.starting_since {
+                    Some(starting_since) => {
+                        format!("Starting… {}s", starting_since.elapsed().as_secs())
+                    }
+                    None => "Starting".into(),
+                }),
+                None,
+            ),
             Kernel::ErroredLaunch(err) => (Some(format!("Error: {err}")), None),
             Kernel::ShuttingDown => (Some("Shutting Down".into()), None),
             Kernel::Shutdown => (Some("Shutdown".into()), None),
@@ -670,8 +2057,12 @@ impl Render for Session {
         };
 
         KernelListItem::new(self.kernel_specification.clone())
+            .when_some(self.kernel.version_description(), |item, versions| {
+                item.tooltip(versions)
+            })
             .status_color(match &self.kernel {
                 Kernel::RunningKernel(kernel) => match kernel.execution_state() {
+                    ExecutionState::Idle if self.startup_error.is_some() => Color::Warning,
                     ExecutionState::Idle => Color::Success,
                     ExecutionState::Busy => Color::Modified,
                 },
@@ -681,16 +2072,706 @@ impl Render for Session {
                 Kernel::Shutdown => Color::Disabled,
                 Kernel::Restarting => Color::Modified,
             })
+            .when(self.starting_since.is_some(), |item| {
+                item.child(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "kernel-starting",
+                            Animation::new(Duration::from_secs(3)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                )
+            })
             .child(Label::new(self.kernel_specification.name()))
             .children(status_text.map(|status_text| Label::new(format!("({status_text})"))))
+            .when(
+                self.starting_since
+                    .is_some_and(|starting_since| starting_since.elapsed() >= SLOW_STARTUP_THRESHOLD),
+                |item| {
+                    item.child(
+                        Label::new(
+                            "Large environments can take a while to load. If the kernel never starts, check the Zed log for errors.",
+                        )
+                        .color(Color::Muted),
+                    )
+                },
+            )
+            .children(self.startup_error.clone().map(|error| {
+                Label::new(format!("Startup code failed: {error}")).color(Color::Warning)
+            }))
+            .children(self.resource_usage.map(|usage| {
+                Label::new(format!(
+                    "{:.0}% CPU, {:.0} MB",
+                    usage.cpu_percent,
+                    usage.memory_bytes as f64 / (1024. * 1024.)
+                ))
+                .color(Color::Muted)
+            }))
             .button(
                 Button::new("shutdown", "Shutdown")
                     .style(ButtonStyle::Subtle)
                     .disabled(self.kernel.is_shutting_down())
-                    .on_click(cx.listener(move |session, _, window, cx| {
-                        session.shutdown(window, cx);
+                    .on_click(cx.listener(move |session, _, _, cx| {
+                        session.shutdown(cx).detach();
                     })),
             )
             .buttons(interrupt_button)
     }
 }
+
+/// What an inspection found, preferring Markdown. Plain text is shown as it's laid out, and
+/// without the escape sequences that kernels such as IPython color it with.
+fn inspection_contents(data: &MimeBundle) -> Option<HoverBlock> {
+    let markdown = data.content.iter().find_map(|mimetype| match mimetype {
+        MimeType::Markdown(text) => Some(text),
+        _ => None,
+    });
+    if let Some(text) = markdown.filter(|text| !text.trim().is_empty()) {
+        return Some(HoverBlock {
+            text: text.clone(),
+            kind: HoverBlockKind::Markdown,
+        });
+    }
+
+    data.content
+        .iter()
+        .find_map(|mimetype| match mimetype {
+            MimeType::Plain(text) => Some(strip_ansi_escapes(text)),
+            _ => None,
+        })
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| HoverBlock {
+            text,
+            kind: HoverBlockKind::Code {
+                language: "text".to_string(),
+            },
+        })
+}
+
+fn strip_ansi_escapes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\x1b' {
+            stripped.push(char);
+            continue;
+        }
+        // Control sequences are `ESC [`, then parameters, then a final byte from `@` to `~`.
+        if chars.next() == Some('[') {
+            for char in chars.by_ref() {
+                if ('@'..='~').contains(&char) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::kernels::{LocalKernelSpecification, RunningKernel};
+    use editor::{EditorMode, MultiBuffer};
+    use futures::{channel::mpsc, future::BoxFuture};
+    use gpui::{TestAppContext, VisualTestContext};
+    use jupyter_protocol::JupyterKernelspec;
+    use project::FakeFs;
+    use runtimelib::{ErrorOutput, KernelInfoReply, Status, Stdio, StreamContent};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Debug)]
+    struct FakeKernel {
+        request_tx: mpsc::Sender<JupyterMessage>,
+        working_directory: PathBuf,
+        execution_state: ExecutionState,
+        killed: Arc<AtomicBool>,
+        owns_process: bool,
+    }
+
+    impl RunningKernel for FakeKernel {
+        fn request_tx(&self) -> mpsc::Sender<JupyterMessage> {
+            self.request_tx.clone()
+        }
+
+        fn working_directory(&self) -> &PathBuf {
+            &self.working_directory
+        }
+
+        fn execution_state(&self) -> &ExecutionState {
+            &self.execution_state
+        }
+
+        fn set_execution_state(&mut self, state: ExecutionState) {
+            self.execution_state = state;
+        }
+
+        fn kernel_info(&self) -> Option<&KernelInfoReply> {
+            None
+        }
+
+        fn set_kernel_info(&mut self, _info: KernelInfoReply) {}
+
+        fn force_shutdown(&mut self) -> BoxFuture<'static, anyhow::Result<()>> {
+            self.killed.store(true, Ordering::SeqCst);
+            futures::future::ready(Ok(())).boxed()
+        }
+
+        fn owns_process(&self) -> bool {
+            self.owns_process
+        }
+    }
+
+    /// What a test can observe of the fake kernel that `init_session_test` installs.
+    pub(crate) struct FakeKernelHandle {
+        pub(crate) request_rx: mpsc::Receiver<JupyterMessage>,
+        pub(crate) killed: Arc<AtomicBool>,
+    }
+
+    /// Starts a session for an editor in a workspace, and swaps its kernel for a fake one.
+    pub(crate) async fn init_session_test(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Session>,
+        Entity<Editor>,
+        FakeKernelHandle,
+        &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let store = settings::SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            project::Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            JupyterSettings::register(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = project::Project::test(fs.clone(), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| workspace::Workspace::test_new(project, window, cx));
+        // Output blocks are only shown for editors that belong to a workspace.
+        let editor = workspace.update_in(cx, |workspace, window, cx| {
+            let editor = cx.new(|cx| {
+                Editor::new(
+                    EditorMode::full(),
+                    MultiBuffer::build_simple("print('hello')", cx),
+                    None,
+                    window,
+                    cx,
+                )
+            });
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, window, cx);
+            editor
+        });
+
+        // The working directory doesn't exist in the fake filesystem, so the real kernel fails to
+        // launch and is swapped for a fake one.
+        let kernel_specification = KernelSpecification::Jupyter(LocalKernelSpecification {
+            name: "python".into(),
+            kernelspec: JupyterKernelspec {
+                argv: vec![],
+                display_name: "Python".into(),
+                language: "python".into(),
+                interrupt_mode: None,
+                metadata: None,
+                env: None,
+            },
+            path: PathBuf::new(),
+        });
+        let session = cx.update(|window, cx| {
+            cx.new(|cx| {
+                Session::new(
+                    editor.downgrade(),
+                    fs,
+                    kernel_specification,
+                    None,
+                    window,
+                    cx,
+                )
+            })
+        });
+        cx.run_until_parked();
+
+        let killed = Arc::new(AtomicBool::new(false));
+        let (request_tx, request_rx) = mpsc::channel(16);
+        session.update(cx, |session, cx| {
+            session.kernel(
+                Kernel::RunningKernel(Box::new(FakeKernel {
+                    request_tx,
+                    working_directory: temp_dir(),
+                    execution_state: ExecutionState::Idle,
+                    killed: killed.clone(),
+                    owns_process: true,
+                })),
+                cx,
+            );
+        });
+
+        (session, editor, FakeKernelHandle { request_rx, killed }, cx)
+    }
+
+    #[gpui::test]
+    async fn test_kernel_shuts_down_when_editor_is_dropped(cx: &mut TestAppContext) {
+        let (session, editor, kernel, cx) = init_session_test(cx).await;
+
+        let workspace = cx
+            .update(|window, _| window.root::<workspace::Workspace>().flatten())
+            .unwrap();
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.close_active_item(
+                    &workspace::CloseActiveItem {
+                        save_intent: Some(workspace::SaveIntent::Skip),
+                        close_pinned: true,
+                    },
+                    window,
+                    cx,
+                )
+                .unwrap()
+                .detach();
+            });
+        });
+        drop(editor);
+        cx.run_until_parked();
+        session.read_with(cx, |session, _| {
+            assert!(matches!(session.kernel, Kernel::ShuttingDown));
+        });
+
+        // The fake kernel never replies to the shutdown request.
+        cx.executor().advance_clock(SHUTDOWN_GRACE_PERIOD);
+        cx.run_until_parked();
+
+        assert!(kernel.killed.load(Ordering::SeqCst));
+        session.read_with(cx, |session, _| {
+            assert!(matches!(session.kernel, Kernel::Shutdown));
+            assert!(session.blocks.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_shutdown_disconnects_from_external_kernels(cx: &mut TestAppContext) {
+        let (session, _editor, _kernel, cx) = init_session_test(cx).await;
+
+        // A kernel that the user started themselves, and that Zed only connected to.
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let (request_tx, mut request_rx) = mpsc::channel(16);
+        session.update(cx, |session, cx| {
+            session.kernel(
+                Kernel::RunningKernel(Box::new(FakeKernel {
+                    request_tx,
+                    working_directory: temp_dir(),
+                    execution_state: ExecutionState::Idle,
+                    killed: disconnected.clone(),
+                    owns_process: false,
+                })),
+                cx,
+            );
+        });
+
+        session
+            .update(cx, |session, cx| session.shutdown(cx))
+            .detach();
+        cx.run_until_parked();
+
+        assert!(disconnected.load(Ordering::SeqCst));
+        while let Ok(Some(message)) = request_rx.try_next() {
+            assert!(
+                !matches!(message.content, JupyterMessageContent::ShutdownRequest(_)),
+                "a kernel Zed didn't start shouldn't be asked to shut down"
+            );
+        }
+        session.read_with(cx, |session, _| {
+            assert!(matches!(session.kernel, Kernel::Shutdown));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_block_outputs(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+
+        cx.update(|window, cx| {
+            session.update(cx, |session, cx| {
+                let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                let range = snapshot.anchor_before(Point::new(0, 0))
+                    ..snapshot.anchor_after(Point::new(0, 14));
+                session.execute("print('hello')".into(), range, None, false, window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        let request = kernel
+            .request_rx
+            .try_next()
+            .ok()
+            .flatten()
+            .expect("the execute request should have been sent");
+        session.read_with(cx, |session, cx| {
+            let outputs = session.block_outputs(cx);
+            assert_eq!(outputs.len(), 1);
+            assert_eq!(outputs[0].message_id, request.header.msg_id);
+            assert!(matches!(outputs[0].status, ExecutionStatus::Queued));
+            assert!(outputs[0].text.is_empty());
+        });
+
+        for content in [
+            JupyterMessageContent::Status(Status {
+                execution_state: ExecutionState::Busy,
+            }),
+            JupyterMessageContent::StreamContent(StreamContent {
+                name: Stdio::Stdout,
+                text: "hello\n".into(),
+            }),
+            JupyterMessageContent::Status(Status {
+                execution_state: ExecutionState::Idle,
+            }),
+        ] {
+            let message = JupyterMessage::new(content, Some(&request));
+            cx.update(|window, cx| {
+                session.update(cx, |session, cx| session.route(&message, window, cx));
+            });
+        }
+
+        session.read_with(cx, |session, cx| {
+            let output = session
+                .block_output(&request.header.msg_id, cx)
+                .expect("the block should still be shown");
+            assert_eq!(output.text, "hello");
+            assert!(matches!(output.status, ExecutionStatus::Finished));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cancel_only_interrupts_the_running_execution(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+
+        for _ in 0..2 {
+            cx.update(|window, cx| {
+                session.update(cx, |session, cx| {
+                    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                    let range = snapshot.anchor_before(Point::new(0, 0))
+                        ..snapshot.anchor_after(Point::new(0, 14));
+                    session.execute("print('hello')".into(), range, None, false, window, cx);
+                });
+            });
+        }
+        cx.run_until_parked();
+
+        let mut requests = std::iter::from_fn(|| kernel.request_rx.try_next().ok().flatten())
+            .filter(|message| matches!(message.content, JupyterMessageContent::ExecuteRequest(_)));
+        let running = requests
+            .next()
+            .expect("the first execute request should have been sent");
+        let waiting = requests
+            .next()
+            .expect("the second execute request should have been sent");
+        drop(requests);
+
+        let message = JupyterMessage::new(
+            JupyterMessageContent::Status(Status {
+                execution_state: ExecutionState::Busy,
+            }),
+            Some(&running),
+        );
+        cx.update(|window, cx| {
+            session.update(cx, |session, cx| session.route(&message, window, cx));
+        });
+
+        // The kernel already has the waiting execution, so it can't be withdrawn.
+        session.update(cx, |session, cx| {
+            session.cancel_execution(&waiting.header.msg_id, cx)
+        });
+        cx.run_until_parked();
+        session.read_with(cx, |session, cx| {
+            let output = session.block_output(&waiting.header.msg_id, cx).unwrap();
+            assert!(matches!(output.status, ExecutionStatus::Queued));
+        });
+        assert!(kernel.request_rx.try_next().is_err());
+
+        session.update(cx, |session, cx| {
+            session.cancel_execution(&running.header.msg_id, cx)
+        });
+        cx.run_until_parked();
+        let interrupt = kernel.request_rx.try_next().ok().flatten();
+        assert!(matches!(
+            interrupt.map(|message| message.content),
+            Some(JupyterMessageContent::InterruptRequest(_))
+        ));
+    }
+
+    #[gpui::test]
+    async fn test_failing_startup_code_sets_startup_error(cx: &mut TestAppContext) {
+        let (session, _editor, mut kernel, cx) = init_session_test(cx).await;
+        cx.update(|_, cx| {
+            cx.update_global::<settings::SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<JupyterSettings>(cx, |settings| {
+                    settings.startup_code = Some(std::collections::HashMap::from_iter([(
+                        "Python".to_string(),
+                        "import missing_module".to_string(),
+                    )]));
+                });
+            });
+        });
+
+        session.update(cx, |session, cx| session.run_startup_code(cx));
+        cx.run_until_parked();
+
+        let request = kernel
+            .request_rx
+            .try_next()
+            .ok()
+            .flatten()
+            .expect("the startup code should have been sent");
+        match &request.content {
+            JupyterMessageContent::ExecuteRequest(request) => {
+                assert_eq!(request.code, "import missing_module");
+                assert!(!request.store_history);
+            }
+            content => panic!("expected an execute request, got {content:?}"),
+        }
+
+        for content in [
+            JupyterMessageContent::ErrorOutput(ErrorOutput {
+                ename: "ModuleNotFoundError".into(),
+                evalue: "No module named 'missing_module'".into(),
+                traceback: vec![],
+            }),
+            JupyterMessageContent::Status(Status {
+                execution_state: ExecutionState::Idle,
+            }),
+        ] {
+            let message = JupyterMessage::new(content, Some(&request));
+            cx.update(|window, cx| {
+                session.update(cx, |session, cx| session.route(&message, window, cx));
+            });
+        }
+        cx.run_until_parked();
+
+        session.read_with(cx, |session, _| {
+            assert_eq!(
+                session.startup_error().map(SharedString::as_ref),
+                Some("ModuleNotFoundError: No module named 'missing_module'")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_clear_outputs_in_range_keeps_the_others_current(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("a = 1\nb = 2", window, cx)
+        });
+
+        cx.update(|window, cx| {
+            session.update(cx, |session, cx| {
+                for row in 0..2 {
+                    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                    let range = snapshot.anchor_before(Point::new(row, 0))
+                        ..snapshot.anchor_after(Point::new(row, 5));
+                    let code = snapshot.text_for_range(range.clone()).collect::<String>();
+                    session.execute(code, range, None, false, window, cx);
+                }
+            });
+        });
+        cx.run_until_parked();
+        let message_ids = std::iter::from_fn(|| kernel.request_rx.try_next().ok().flatten())
+            .filter(|message| matches!(message.content, JupyterMessageContent::ExecuteRequest(_)))
+            .map(|message| message.header.msg_id)
+            .collect::<Vec<_>>();
+        let [first, second] = message_ids.as_slice() else {
+            panic!("expected two execute requests, got {message_ids:?}");
+        };
+
+        // Deleting the second cell leaves its output stale and waiting to be removed.
+        editor.update(cx, |editor, cx| {
+            editor.buffer().update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(1, 0)..Point::new(2, 0), "")], None, cx);
+            });
+        });
+        session.read_with(cx, |session, cx| {
+            assert!(session.blocks[second].execution_view.read(cx).stale);
+            assert!(session._stale_blocks_task.is_some());
+        });
+
+        session.update(cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let below = snapshot.anchor_before(Point::new(1, 0))..Anchor::max();
+            session.clear_outputs_in_range(below, cx);
+        });
+        session.read_with(cx, |session, cx| {
+            assert!(!session.blocks.contains_key(second));
+            assert!(!session.blocks[first].execution_view.read(cx).stale);
+            assert!(session._stale_blocks_task.is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restart_keeps_outputs(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+        cx.update(|_, cx| {
+            cx.update_global::<settings::SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<JupyterSettings>(cx, |settings| {
+                    settings.keep_outputs_on_restart = Some(true);
+                });
+            });
+        });
+
+        cx.update(|window, cx| {
+            session.update(cx, |session, cx| {
+                let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                let range = snapshot.anchor_before(Point::new(0, 0))
+                    ..snapshot.anchor_after(Point::new(0, 14));
+                session.execute("print('hello')".into(), range, None, false, window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        let request = kernel
+            .request_rx
+            .try_next()
+            .ok()
+            .flatten()
+            .expect("the execute request should have been sent");
+
+        session.update_in(cx, |session, window, cx| session.restart(window, cx));
+        // The old kernel is given a moment to shut down before the new one is started.
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+
+        session.read_with(cx, |session, cx| {
+            let block = session
+                .blocks
+                .get(&request.header.msg_id)
+                .expect("the output should be kept");
+            assert!(block.outlived_kernel);
+            assert!(block.execution_view.read(cx).stale);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restart_leaves_external_kernels_running(cx: &mut TestAppContext) {
+        let (session, _editor, _kernel, cx) = init_session_test(cx).await;
+
+        // A kernel that the user started themselves, and that Zed only connected to. Unlike a
+        // real connection, disconnecting from it leaves its channel open, so any shutdown
+        // request sent while restarting would arrive.
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let (request_tx, mut request_rx) = mpsc::channel(16);
+        session.update(cx, |session, cx| {
+            session.kernel(
+                Kernel::RunningKernel(Box::new(FakeKernel {
+                    request_tx,
+                    working_directory: temp_dir(),
+                    execution_state: ExecutionState::Idle,
+                    killed: disconnected.clone(),
+                    owns_process: false,
+                })),
+                cx,
+            );
+        });
+
+        session.update_in(cx, |session, window, cx| session.restart(window, cx));
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+
+        assert!(disconnected.load(Ordering::SeqCst));
+        while let Ok(Some(message)) = request_rx.try_next() {
+            assert!(
+                !matches!(message.content, JupyterMessageContent::ShutdownRequest(_)),
+                "a kernel Zed didn't start shouldn't be asked to shut down when restarting"
+            );
+        }
+    }
+
+    /// Runs the first line of the editor and returns the execute request's message id.
+    fn execute_first_line(
+        session: &Entity<Session>,
+        editor: &Entity<Editor>,
+        kernel: &mut FakeKernelHandle,
+        cx: &mut VisualTestContext,
+    ) -> String {
+        cx.update(|window, cx| {
+            session.update(cx, |session, cx| {
+                let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                let range = snapshot.anchor_before(Point::new(0, 0))
+                    ..snapshot.anchor_after(Point::new(0, 14));
+                session.execute("print('hello')".into(), range, None, false, window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        std::iter::from_fn(|| kernel.request_rx.try_next().ok().flatten())
+            .find(|message| matches!(message.content, JupyterMessageContent::ExecuteRequest(_)))
+            .expect("the execute request should have been sent")
+            .header
+            .msg_id
+    }
+
+    #[gpui::test]
+    async fn test_pinned_outputs_stay_in_place(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+        let message_id = execute_first_line(&session, &editor, &mut kernel, cx);
+
+        session.update(cx, |session, cx| session.toggle_pin(&message_id, cx));
+        editor.update(cx, |editor, cx| {
+            editor.buffer().update(cx, |buffer, cx| {
+                buffer.edit(
+                    [(Point::new(0, 14)..Point::new(0, 14), "\nprint('world')")],
+                    None,
+                    cx,
+                );
+            });
+        });
+
+        session.read_with(cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let block = &session.blocks[&message_id];
+            assert!(block.execution_view.read(cx).pinned);
+            assert_eq!(block.code_range.end.to_point(&snapshot).row, 1);
+            assert_eq!(block.position().to_point(&snapshot).row, 0);
+        });
+
+        session.update(cx, |session, cx| session.toggle_pin(&message_id, cx));
+        session.read_with(cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let block = &session.blocks[&message_id];
+            assert!(!block.execution_view.read(cx).pinned);
+            assert!(block.pin_anchor.is_none());
+            assert_eq!(block.position().to_point(&snapshot).row, 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rerun_keeps_pinned_outputs(cx: &mut TestAppContext) {
+        let (session, editor, mut kernel, cx) = init_session_test(cx).await;
+
+        let first = execute_first_line(&session, &editor, &mut kernel, cx);
+        let second = execute_first_line(&session, &editor, &mut kernel, cx);
+        session.read_with(cx, |session, _| {
+            assert!(!session.blocks.contains_key(&first));
+            assert!(session.blocks.contains_key(&second));
+        });
+
+        session.update(cx, |session, cx| session.toggle_pin(&second, cx));
+        let third = execute_first_line(&session, &editor, &mut kernel, cx);
+        session.read_with(cx, |session, _| {
+            assert!(session.blocks.contains_key(&second));
+            assert!(session.blocks.contains_key(&third));
+        });
+    }
+
+    #[test]
+    fn test_strip_ansi_escapes() {
+        assert_eq!(
+            strip_ansi_escapes(
+                "\x1b[0;31mSignature:\x1b[0m f(x)\n\x1b[1mDocstring:\x1b[0m Adds one."
+            ),
+            "Signature: f(x)\nDocstring: Adds one."
+        );
+        assert_eq!(strip_ansi_escapes("no escapes"), "no escapes");
+    }
+}
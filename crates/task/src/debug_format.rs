@@ -226,6 +226,10 @@ pub struct DebugScenario {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub build: Option<BuildTaskDefinition>,
     /// The main arguments to be sent to the debug adapter
+    ///
+    /// Every field is passed through to the adapter's launch or attach request unchanged, so
+    /// adapter-specific options such as debugpy's `justMyCode` can be set here. Adapters only
+    /// fill in fields that are missing; a value set here always takes precedence.
     #[serde(default, flatten)]
     pub config: serde_json::Value,
     /// Optional TCP connection information
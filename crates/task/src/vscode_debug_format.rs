@@ -1,3 +1,4 @@
+use anyhow::Context as _;
 use collections::HashMap;
 use gpui::SharedString;
 use serde::Deserialize;
@@ -42,6 +43,42 @@ struct VsCodeDebugTaskDefinition {
 impl VsCodeDebugTaskDefinition {
     fn try_to_zed(self, replacer: &EnvVariableReplacer) -> anyhow::Result<DebugScenario> {
         let label = replacer.replace(&self.name).into();
+
+        // Zed sends the configuration to the adapter as-is, so the attributes parsed above are
+        // put back alongside the adapter-specific ones.
+        let mut config = self.other_attributes;
+        let map = config
+            .as_object_mut()
+            .context("debug configuration isn't an object")?;
+        map.insert(
+            "request".into(),
+            match self.request {
+                Request::Launch => "launch",
+                Request::Attach => "attach",
+            }
+            .into(),
+        );
+        if let Some(program) = self.program {
+            map.insert("program".into(), replacer.replace(&program).into());
+        }
+        if !self.args.is_empty() {
+            let args = self
+                .args
+                .iter()
+                .map(|arg| replacer.replace(arg))
+                .collect::<Vec<_>>();
+            map.insert("args".into(), args.into());
+        }
+        if !self.env.is_empty() {
+            map.insert("env".into(), serde_json::to_value(self.env)?);
+        }
+        if let Some(cwd) = self.cwd {
+            map.insert("cwd".into(), replacer.replace(&cwd).into());
+        }
+        if let Some(stop_on_entry) = self.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
         // TODO based on grep.app results it seems that vscode supports whitespace-splitting this field (ugh)
         let definition = DebugScenario {
             label,
@@ -53,7 +90,7 @@ impl VsCodeDebugTaskDefinition {
                 host: None,
                 timeout: None,
             }),
-            config: self.other_attributes,
+            config,
         };
         Ok(definition)
     }
@@ -140,7 +177,15 @@ mod tests {
                 label: "Debug my JS app".into(),
                 adapter: "JavaScript".into(),
                 config: json!({
+                    "request": "launch",
+                    "program": "${ZED_WORKTREE_ROOT}/xyz.js",
                     "showDevDebugOutput": false,
+                    "stopOnEntry": true,
+                    "args": ["--foo", "${ZED_WORKTREE_ROOT}/thing"],
+                    "cwd": "${ZED_WORKTREE_ROOT}/${FOO}/sub",
+                    "env": {
+                        "X": "Y",
+                    },
                 }),
                 tcp_connection: Some(TcpArgumentsTemplate {
                     port: Some(17),
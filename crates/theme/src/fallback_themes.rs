@@ -3,8 +3,8 @@ use std::sync::Arc;
 use gpui::{FontStyle, FontWeight, HighlightStyle, Hsla, WindowBackgroundAppearance, hsla};
 
 use crate::{
-    AccentColors, Appearance, PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme,
-    SystemColors, Theme, ThemeColors, ThemeFamily, ThemeStyles, default_color_scales,
+    AccentColors, Appearance, ElevationShadows, PlayerColors, StatusColors, StatusColorsRefinement,
+    SyntaxTheme, SystemColors, Theme, ThemeColors, ThemeFamily, ThemeStyles, default_color_scales,
 };
 
 /// The default theme family for Zed.
@@ -270,6 +270,7 @@ pub(crate) fn zed_default_dark() -> Theme {
                 warning_border: yellow,
             },
             player: PlayerColors::dark(),
+            shadows: ElevationShadows::dark(),
             syntax: Arc::new(SyntaxTheme {
                 highlights: vec![
                     ("attribute".into(), purple.into()),
@@ -89,6 +89,10 @@ pub struct ThemeStyleContent {
     #[serde(default)]
     pub players: Vec<PlayerColorContent>,
 
+    /// The shadows drawn beneath elements at each elevation.
+    #[serde(default)]
+    pub shadows: ElevationShadowsContent,
+
     /// The styles for syntax nodes.
     #[serde(default)]
     pub syntax: IndexMap<String, HighlightStyleContent>,
@@ -1415,6 +1419,32 @@ impl StatusColorsContent {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct AccentContent(pub Option<String>);
 
+/// Overrides for the shadows at each elevation.
+///
+/// An elevation that is present replaces the default shadow entirely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default)]
+pub struct ElevationShadowsContent {
+    pub surface_inner: Option<Vec<BoxShadowContent>>,
+    pub editor_surface_inner: Option<Vec<BoxShadowContent>>,
+    pub elevated_surface: Option<Vec<BoxShadowContent>>,
+    pub modal_surface: Option<Vec<BoxShadowContent>>,
+    pub dragged_element: Option<Vec<BoxShadowContent>>,
+    /// The opacity, between 0 and 1, of the scrim that dims the window behind modals.
+    pub modal_scrim_opacity: Option<f32>,
+}
+
+/// A single shadow layer, with all lengths in pixels.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default)]
+pub struct BoxShadowContent {
+    pub color: Option<String>,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur_radius: f32,
+    pub spread_radius: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct PlayerColorContent {
     pub cursor: Option<String>,
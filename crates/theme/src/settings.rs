@@ -677,6 +677,7 @@ impl ThemeSettings {
                 .refine(&theme_overrides.status_colors_refinement());
             base_theme.styles.player.merge(&theme_overrides.players);
             base_theme.styles.accents.merge(&theme_overrides.accents);
+            base_theme.styles.shadows.merge(&theme_overrides.shadows);
             base_theme.styles.syntax =
                 SyntaxTheme::merge(base_theme.styles.syntax, theme_overrides.syntax_overrides());
 
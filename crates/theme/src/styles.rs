@@ -1,6 +1,7 @@
 mod accents;
 mod colors;
 mod players;
+mod shadows;
 mod status;
 mod syntax;
 mod system;
@@ -8,6 +9,7 @@ mod system;
 pub use accents::*;
 pub use colors::*;
 pub use players::*;
+pub use shadows::*;
 pub use status::*;
 pub use syntax::*;
 pub use system::*;
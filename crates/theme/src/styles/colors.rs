@@ -6,8 +6,8 @@ use std::sync::Arc;
 use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
 use crate::{
-    AccentColors, ActiveTheme, PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme,
-    SystemColors,
+    AccentColors, ActiveTheme, ElevationShadows, PlayerColors, StatusColors,
+    StatusColorsRefinement, SyntaxTheme, SystemColors,
 };
 
 #[derive(Refineable, Clone, Debug, PartialEq)]
@@ -538,6 +538,9 @@ pub struct ThemeStyles {
 
     pub player: PlayerColors,
 
+    /// The shadows drawn beneath elements at each elevation.
+    pub shadows: ElevationShadows,
+
     pub syntax: Arc<SyntaxTheme>,
 }
 
@@ -0,0 +1,113 @@
+use gpui::{BoxShadow, hsla, point, px};
+
+use crate::{BoxShadowContent, ElevationShadowsContent, try_parse_color};
+
+/// The shadows drawn beneath elements at each elevation.
+///
+/// Elevations that aren't listed here (the background, surfaces and editors) don't cast shadows,
+/// but surfaces and editors have an inset shadow for sunken wells like inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElevationShadows {
+    /// The inset shadow inside sunken containers on a surface.
+    pub surface_inner: Vec<BoxShadow>,
+    /// The inset shadow inside sunken, editable areas like inputs.
+    pub editor_surface_inner: Vec<BoxShadow>,
+    /// The shadow beneath popovers, menus and other elevated surfaces.
+    pub elevated_surface: Vec<BoxShadow>,
+    /// The shadow beneath modals, dialogs and alerts.
+    pub modal_surface: Vec<BoxShadow>,
+    /// The shadow beneath elements that are being dragged.
+    pub dragged_element: Vec<BoxShadow>,
+    /// The opacity of the scrim that dims the window behind modals.
+    pub modal_scrim_opacity: f32,
+}
+
+impl Default for ElevationShadows {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl ElevationShadows {
+    /// Returns the set of dark elevation shadows.
+    pub fn dark() -> Self {
+        Self::new(false)
+    }
+
+    /// Returns the set of light elevation shadows.
+    pub fn light() -> Self {
+        Self::new(true)
+    }
+
+    fn new(is_light: bool) -> Self {
+        Self {
+            surface_inner: vec![shadow(if is_light { 0.04 } else { 0.10 }, (0., 1.), 2.)],
+            editor_surface_inner: vec![shadow(if is_light { 0.06 } else { 0.14 }, (0., 1.), 2.)],
+            elevated_surface: vec![
+                shadow(0.12, (0., 2.), 3.),
+                shadow(if is_light { 0.03 } else { 0.06 }, (1., 1.), 0.),
+            ],
+            modal_surface: vec![
+                shadow(if is_light { 0.06 } else { 0.12 }, (0., 2.), 3.),
+                shadow(if is_light { 0.06 } else { 0.08 }, (0., 3.), 6.),
+                shadow(0.04, (0., 6.), 12.),
+                shadow(if is_light { 0.04 } else { 0.12 }, (1., 1.), 0.),
+            ],
+            dragged_element: vec![
+                shadow(if is_light { 0.08 } else { 0.16 }, (0., 4.), 8.),
+                shadow(if is_light { 0.08 } else { 0.16 }, (0., 12.), 24.),
+                shadow(if is_light { 0.06 } else { 0.14 }, (1., 1.), 0.),
+            ],
+            modal_scrim_opacity: 0.8,
+        }
+    }
+
+    /// Merges the given shadow overrides into this [`ElevationShadows`] instance.
+    ///
+    /// Each elevation is replaced as a whole, so a theme can remove a shadow by providing an empty list.
+    pub fn merge(&mut self, content: &ElevationShadowsContent) {
+        if let Some(shadows) = &content.surface_inner {
+            self.surface_inner = resolve_shadows(shadows);
+        }
+        if let Some(shadows) = &content.editor_surface_inner {
+            self.editor_surface_inner = resolve_shadows(shadows);
+        }
+        if let Some(shadows) = &content.elevated_surface {
+            self.elevated_surface = resolve_shadows(shadows);
+        }
+        if let Some(shadows) = &content.modal_surface {
+            self.modal_surface = resolve_shadows(shadows);
+        }
+        if let Some(shadows) = &content.dragged_element {
+            self.dragged_element = resolve_shadows(shadows);
+        }
+        if let Some(opacity) = content.modal_scrim_opacity {
+            self.modal_scrim_opacity = opacity.clamp(0., 1.);
+        }
+    }
+}
+
+fn resolve_shadows(shadows: &[BoxShadowContent]) -> Vec<BoxShadow> {
+    shadows
+        .iter()
+        .map(|shadow| BoxShadow {
+            color: shadow
+                .color
+                .as_ref()
+                .and_then(|color| try_parse_color(color).ok())
+                .unwrap_or(hsla(0., 0., 0., 0.)),
+            offset: point(px(shadow.offset_x), px(shadow.offset_y)),
+            blur_radius: px(shadow.blur_radius),
+            spread_radius: px(shadow.spread_radius),
+        })
+        .collect()
+}
+
+fn shadow(opacity: f32, (x, y): (f32, f32), blur_radius: f32) -> BoxShadow {
+    BoxShadow {
+        color: hsla(0., 0., 0., opacity),
+        offset: point(px(x), px(y)),
+        blur_radius: px(blur_radius),
+        spread_radius: px(0.),
+    }
+}
@@ -191,6 +191,12 @@ impl ThemeFamily {
         };
         refined_accent_colors.merge(&theme.style.accents);
 
+        let mut refined_shadows = match theme.appearance {
+            AppearanceContent::Light => ElevationShadows::light(),
+            AppearanceContent::Dark => ElevationShadows::dark(),
+        };
+        refined_shadows.merge(&theme.style.shadows);
+
         let syntax_highlights = theme
             .style
             .syntax
@@ -233,6 +239,7 @@ impl ThemeFamily {
                 colors: refined_theme_colors,
                 status: refined_status_colors,
                 player: refined_player_colors,
+                shadows: refined_shadows,
                 syntax: syntax_theme,
             },
         }
@@ -302,6 +309,12 @@ impl Theme {
         &self.styles.colors
     }
 
+    /// Returns the [`ElevationShadows`] for the theme.
+    #[inline(always)]
+    pub fn shadows(&self) -> &ElevationShadows {
+        &self.styles.shadows
+    }
+
     /// Returns the [`SyntaxTheme`] for the theme.
     #[inline(always)]
     pub fn syntax(&self) -> &Arc<SyntaxTheme> {
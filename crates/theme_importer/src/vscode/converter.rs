@@ -61,6 +61,7 @@ impl VsCodeThemeConverter {
                 colors: theme_colors,
                 status: status_colors,
                 players: Vec::new(),
+                shadows: Default::default(),
                 syntax: syntax_theme,
             },
         })
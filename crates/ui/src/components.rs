@@ -30,6 +30,7 @@ mod scrollbar;
 mod settings_container;
 mod settings_group;
 mod stack;
+mod surface;
 mod tab;
 mod tab_bar;
 mod table;
@@ -71,6 +72,7 @@ pub use scrollbar::*;
 pub use settings_container::*;
 pub use settings_group::*;
 pub use stack::*;
+pub use surface::*;
 pub use tab::*;
 pub use tab_bar::*;
 pub use table::*;
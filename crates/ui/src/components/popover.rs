@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use crate::v_flex;
+use crate::{ElevationIndex, Surface, v_flex};
 use gpui::{
     AnyElement, App, Element, IntoElement, ParentElement, Pixels, RenderOnce, Styled, Window, div,
 };
@@ -47,8 +47,9 @@ impl RenderOnce for Popover {
             .flex()
             .gap_1()
             .child(
-                v_flex()
-                    .elevation_2(cx)
+                Surface::new(ElevationIndex::ElevatedSurface)
+                    .v_flex()
+                    .rounded_lg()
                     .py(POPOVER_Y_PADDING / 2.)
                     .children(self.children),
             )
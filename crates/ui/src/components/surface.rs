@@ -0,0 +1,104 @@
+use crate::ElevationIndex;
+use crate::component_prelude::*;
+use crate::prelude::*;
+use gpui::{AnyElement, IntoElement, ParentElement, StyleRefinement, Styled};
+use smallvec::SmallVec;
+
+/// A container that applies the background, border and shadow of an [`ElevationIndex`].
+///
+/// Use it instead of composing `bg`, `border_color` and `shadow` by hand, so panels, popovers
+/// and modals at the same elevation always look alike.
+#[derive(IntoElement, Documented, RegisterComponent)]
+pub struct Surface {
+    base: Div,
+    elevation: ElevationIndex,
+    border: bool,
+    children: SmallVec<[AnyElement; 2]>,
+}
+
+impl Surface {
+    /// Creates a new [`Surface`] at the given elevation.
+    pub fn new(elevation: ElevationIndex) -> Self {
+        Self {
+            base: div(),
+            elevation,
+            border: true,
+            children: SmallVec::new(),
+        }
+    }
+
+    /// Removes the border from the [`Surface`].
+    pub fn borderless(mut self) -> Self {
+        self.border = false;
+        self
+    }
+}
+
+impl ParentElement for Surface {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.children.extend(elements)
+    }
+}
+
+impl Styled for Surface {
+    fn style(&mut self) -> &mut StyleRefinement {
+        self.base.style()
+    }
+}
+
+impl RenderOnce for Surface {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let elevation = self.elevation;
+
+        self.base
+            .bg(elevation.bg(cx))
+            .when(self.border, |this| {
+                this.border_1().border_color(elevation.border_color(cx))
+            })
+            .shadow(elevation.shadow(cx))
+            .children(self.children)
+    }
+}
+
+impl Component for Surface {
+    fn scope() -> ComponentScope {
+        ComponentScope::Layout
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(Surface::DOCS)
+    }
+
+    fn preview(_window: &mut Window, _cx: &mut App) -> Option<AnyElement> {
+        let example = |elevation: ElevationIndex| {
+            Surface::new(elevation)
+                .rounded_lg()
+                .p_4()
+                .child(Label::new(elevation.to_string()))
+        };
+
+        Some(
+            example_group(vec![
+                single_example(
+                    "Surface",
+                    example(ElevationIndex::Surface).into_any_element(),
+                ),
+                single_example(
+                    "Elevated Surface",
+                    example(ElevationIndex::ElevatedSurface).into_any_element(),
+                ),
+                single_example(
+                    "Modal Surface",
+                    example(ElevationIndex::ModalSurface).into_any_element(),
+                ),
+                single_example(
+                    "Without Border",
+                    example(ElevationIndex::ElevatedSurface)
+                        .borderless()
+                        .into_any_element(),
+                ),
+            ])
+            .into_any_element(),
+        )
+    }
+}
@@ -1,8 +1,16 @@
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
-use gpui::{App, BoxShadow, Hsla, hsla, point, px};
+use gpui::{
+    AnyElement, App, BoxShadow, Div, Hsla, IntoElement, ParentElement, Styled, Window, div, hsla,
+    point, px, transparent_black,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smallvec::{SmallVec, smallvec};
-use theme::{ActiveTheme, Appearance};
+use strum::EnumCount;
+use theme::ActiveTheme;
+
+use crate::{Label, LabelCommon, LabelSize, component_prelude::*, h_flex, v_flex};
 
 /// Today, elevation is primarily used to add shadows to elements, and set the correct background for elements like buttons.
 ///
@@ -11,7 +19,7 @@ use theme::{ActiveTheme, Appearance};
 /// z-axis and appear to be underneath elements with higher elevations.
 ///
 /// In the future, a more complete approach to elevation may be added.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount, RegisterComponent)]
 pub enum ElevationIndex {
     /// On the layer of the app background. This is under panels, panes, and
     /// other surfaces.
@@ -24,6 +32,8 @@ pub enum ElevationIndex {
     ElevatedSurface,
     /// A surface above the [ElevationIndex::ElevatedSurface] that is used for dialogs, alerts, modals, etc.
     ModalSurface,
+    /// The highest elevation, used for elements that are being dragged, like tabs or project entries.
+    DraggedElement,
 }
 
 impl Display for ElevationIndex {
@@ -34,62 +44,129 @@ impl Display for ElevationIndex {
             ElevationIndex::EditorSurface => write!(f, "Editor Surface"),
             ElevationIndex::ElevatedSurface => write!(f, "Elevated Surface"),
             ElevationIndex::ModalSurface => write!(f, "Modal Surface"),
+            ElevationIndex::DraggedElement => write!(f, "Dragged Element"),
         }
     }
 }
 
+impl FromStr for ElevationIndex {
+    type Err = strum::ParseError;
+
+    /// Parses the names written by [ElevationIndex::name] and, with their spaces, by the [Display]
+    /// implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+
+        ElevationIndex::all()
+            .into_iter()
+            .find(|elevation| elevation.name() == name)
+            .ok_or(strum::ParseError::VariantNotFound)
+    }
+}
+
+impl Serialize for ElevationIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for ElevationIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse()
+            .map_err(|_| serde::de::Error::custom(format!("unknown elevation index \"{name}\"")))
+    }
+}
+
 impl ElevationIndex {
+    /// Returns the name used for this elevation index in themes and settings, which is the
+    /// [Display] name without its spaces.
+    pub const fn name(self) -> &'static str {
+        match self {
+            ElevationIndex::Background => "Background",
+            ElevationIndex::Surface => "Surface",
+            ElevationIndex::EditorSurface => "EditorSurface",
+            ElevationIndex::ElevatedSurface => "ElevatedSurface",
+            ElevationIndex::ModalSurface => "ModalSurface",
+            ElevationIndex::DraggedElement => "DraggedElement",
+        }
+    }
+
+    /// Returns every elevation index, from the lowest to the highest.
+    pub const fn all() -> [ElevationIndex; ElevationIndex::COUNT] {
+        [
+            ElevationIndex::Background,
+            ElevationIndex::Surface,
+            ElevationIndex::EditorSurface,
+            ElevationIndex::ElevatedSurface,
+            ElevationIndex::ModalSurface,
+            ElevationIndex::DraggedElement,
+        ]
+    }
+
     /// Returns an appropriate shadow for the given elevation index.
+    ///
+    /// The shadows are defined by the active theme.
     pub fn shadow(self, cx: &App) -> SmallVec<[BoxShadow; 2]> {
-        let is_light = cx.theme().appearance() == Appearance::Light;
+        let shadows = cx.theme().shadows();
 
         match self {
-            ElevationIndex::Surface => smallvec![],
-            ElevationIndex::EditorSurface => smallvec![],
-
-            ElevationIndex::ElevatedSurface => smallvec![
-                BoxShadow {
-                    color: hsla(0., 0., 0., 0.12),
-                    offset: point(px(0.), px(2.)),
-                    blur_radius: px(3.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., if is_light { 0.03 } else { 0.06 }),
-                    offset: point(px(1.), px(1.)),
-                    blur_radius: px(0.),
-                    spread_radius: px(0.),
-                }
-            ],
-
-            ElevationIndex::ModalSurface => smallvec![
-                BoxShadow {
-                    color: hsla(0., 0., 0., if is_light { 0.06 } else { 0.12 }),
-                    offset: point(px(0.), px(2.)),
-                    blur_radius: px(3.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., if is_light { 0.06 } else { 0.08 }),
-                    offset: point(px(0.), px(3.)),
-                    blur_radius: px(6.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., 0.04),
-                    offset: point(px(0.), px(6.)),
-                    blur_radius: px(12.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., if is_light { 0.04 } else { 0.12 }),
-                    offset: point(px(1.), px(1.)),
-                    blur_radius: px(0.),
-                    spread_radius: px(0.),
-                },
-            ],
-
-            _ => smallvec![],
+            ElevationIndex::ElevatedSurface => SmallVec::from(shadows.elevated_surface.as_slice()),
+            ElevationIndex::ModalSurface => SmallVec::from(shadows.modal_surface.as_slice()),
+            ElevationIndex::DraggedElement => SmallVec::from(shadows.dragged_element.as_slice()),
+            ElevationIndex::Background
+            | ElevationIndex::Surface
+            | ElevationIndex::EditorSurface => smallvec![],
+        }
+    }
+
+    /// Returns the shadow partway between two elevation indices, for animating an element as it
+    /// is raised or lowered.
+    ///
+    /// A `progress` of `0.0` returns the shadow of `from` and `1.0` the shadow of `to`.
+    pub fn blended_shadow(
+        from: ElevationIndex,
+        to: ElevationIndex,
+        progress: f32,
+        cx: &App,
+    ) -> SmallVec<[BoxShadow; 2]> {
+        blend_shadows(&from.shadow(cx), &to.shadow(cx), progress)
+    }
+
+    /// Returns an inset shadow for sunken containers at the given elevation index.
+    ///
+    /// Only surfaces and editor surfaces have one, as higher elevations are never rendered as wells.
+    pub fn inner_shadow(self, cx: &App) -> SmallVec<[BoxShadow; 2]> {
+        let shadows = cx.theme().shadows();
+
+        match self {
+            ElevationIndex::Surface => SmallVec::from(shadows.surface_inner.as_slice()),
+            ElevationIndex::EditorSurface => {
+                SmallVec::from(shadows.editor_surface_inner.as_slice())
+            }
+            ElevationIndex::Background
+            | ElevationIndex::ElevatedSurface
+            | ElevationIndex::ModalSurface
+            | ElevationIndex::DraggedElement => smallvec![],
+        }
+    }
+
+    /// Returns the color of the scrim drawn behind an element at the given elevation, dimming
+    /// everything beneath it.
+    ///
+    /// Only modals are drawn over a scrim, so every other elevation returns a transparent color.
+    pub fn scrim(self, cx: &App) -> Hsla {
+        match self {
+            ElevationIndex::ModalSurface => cx
+                .theme()
+                .colors()
+                .elevated_surface_background
+                .opacity(cx.theme().shadows().modal_scrim_opacity),
+            ElevationIndex::Background
+            | ElevationIndex::Surface
+            | ElevationIndex::EditorSurface
+            | ElevationIndex::ElevatedSurface
+            | ElevationIndex::DraggedElement => transparent_black(),
         }
     }
 
@@ -101,6 +178,19 @@ impl ElevationIndex {
             ElevationIndex::EditorSurface => cx.theme().colors().editor_background,
             ElevationIndex::ElevatedSurface => cx.theme().colors().elevated_surface_background,
             ElevationIndex::ModalSurface => cx.theme().colors().elevated_surface_background,
+            ElevationIndex::DraggedElement => cx.theme().colors().elevated_surface_background,
+        }
+    }
+
+    /// Returns a border color that reads well against this elevation index's background.
+    pub fn border_color(&self, cx: &App) -> Hsla {
+        match self {
+            ElevationIndex::Background => cx.theme().colors().border,
+            ElevationIndex::Surface => cx.theme().colors().border_variant,
+            ElevationIndex::EditorSurface => cx.theme().colors().border,
+            ElevationIndex::ElevatedSurface => cx.theme().colors().border_variant,
+            ElevationIndex::ModalSurface => cx.theme().colors().border_variant,
+            ElevationIndex::DraggedElement => cx.theme().colors().border_variant,
         }
     }
 
@@ -112,6 +202,7 @@ impl ElevationIndex {
             ElevationIndex::EditorSurface => cx.theme().colors().surface_background,
             ElevationIndex::ElevatedSurface => cx.theme().colors().background,
             ElevationIndex::ModalSurface => cx.theme().colors().background,
+            ElevationIndex::DraggedElement => cx.theme().colors().background,
         }
     }
 
@@ -125,6 +216,156 @@ impl ElevationIndex {
             ElevationIndex::EditorSurface => cx.theme().colors().surface_background,
             ElevationIndex::ElevatedSurface => cx.theme().colors().editor_background,
             ElevationIndex::ModalSurface => cx.theme().colors().editor_background,
+            ElevationIndex::DraggedElement => cx.theme().colors().editor_background,
+        }
+    }
+}
+
+impl Component for ElevationIndex {
+    fn scope() -> ComponentScope {
+        ComponentScope::None
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("The backgrounds and shadow of each elevation, from lowest to highest.")
+    }
+
+    fn preview(_window: &mut Window, cx: &mut App) -> Option<AnyElement> {
+        let swatch = |label: &'static str, swatch: Div| {
+            v_flex()
+                .gap_1()
+                .items_center()
+                .child(swatch.size_16().rounded_md())
+                .child(Label::new(label).size(LabelSize::XSmall))
+        };
+
+        let examples = ElevationIndex::all()
+            .into_iter()
+            .map(|elevation| {
+                let bg = elevation.bg(cx);
+                single_example(
+                    elevation.to_string(),
+                    h_flex()
+                        .gap_4()
+                        .p_4()
+                        .bg(elevation.on_elevation_bg(cx))
+                        .child(swatch("bg", div().bg(bg)))
+                        .child(swatch("darker_bg", div().bg(elevation.darker_bg(cx))))
+                        .child(swatch(
+                            "shadow",
+                            div()
+                                .bg(bg)
+                                .border_1()
+                                .border_color(elevation.border_color(cx))
+                                .shadow(elevation.shadow(cx)),
+                        ))
+                        .into_any_element(),
+                )
+            })
+            .collect();
+
+        Some(example_group_with_title("Elevations", examples).into_any_element())
+    }
+}
+
+/// Interpolates between two lists of shadows layer by layer.
+///
+/// When one list is longer, its extra layers fade in or out from a transparent copy of themselves.
+fn blend_shadows(from: &[BoxShadow], to: &[BoxShadow], progress: f32) -> SmallVec<[BoxShadow; 2]> {
+    let progress = progress.clamp(0., 1.);
+    let transparent = |shadow: &BoxShadow| BoxShadow {
+        color: shadow.color.opacity(0.),
+        ..shadow.clone()
+    };
+
+    (0..from.len().max(to.len()))
+        .filter_map(|ix| {
+            let (from, to) = match (from.get(ix), to.get(ix)) {
+                (Some(from), Some(to)) => (from.clone(), to.clone()),
+                (Some(from), None) => (from.clone(), transparent(from)),
+                (None, Some(to)) => (transparent(to), to.clone()),
+                (None, None) => return None,
+            };
+            let lerp = |a: f32, b: f32| a * (1. - progress) + b * progress;
+            Some(BoxShadow {
+                color: hsla(
+                    lerp(from.color.h, to.color.h),
+                    lerp(from.color.s, to.color.s),
+                    lerp(from.color.l, to.color.l),
+                    lerp(from.color.a, to.color.a),
+                ),
+                offset: point(
+                    px(lerp(from.offset.x.0, to.offset.x.0)),
+                    px(lerp(from.offset.y.0, to.offset.y.0)),
+                ),
+                blur_radius: px(lerp(from.blur_radius.0, to.blur_radius.0)),
+                spread_radius: px(lerp(from.spread_radius.0, to.spread_radius.0)),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shadow(alpha: f32, y: f32, blur: f32) -> BoxShadow {
+        BoxShadow {
+            color: hsla(0., 0., 0., alpha),
+            offset: point(px(0.), px(y)),
+            blur_radius: px(blur),
+            spread_radius: px(0.),
         }
     }
+
+    #[test]
+    fn test_blend_shadows() {
+        let from = [shadow(0.1, 2., 4.)];
+        let to = [shadow(0.3, 6., 12.), shadow(0.2, 1., 0.)];
+
+        assert_eq!(
+            blend_shadows(&from, &to, 0.).as_slice(),
+            &[from[0].clone(), shadow(0., 1., 0.)]
+        );
+        assert_eq!(blend_shadows(&from, &to, 1.).as_slice(), &to);
+
+        let halfway = blend_shadows(&from, &to, 0.5);
+        assert_eq!(halfway.len(), 2);
+        assert!((halfway[0].color.a - 0.2).abs() < f32::EPSILON);
+        assert_eq!(halfway[0].offset, point(px(0.), px(4.)));
+        assert_eq!(halfway[0].blur_radius, px(8.));
+        assert!((halfway[1].color.a - 0.1).abs() < f32::EPSILON);
+        assert_eq!(halfway[1].offset, point(px(0.), px(1.)));
+
+        assert!(blend_shadows(&[], &[], 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_elevation_index_round_trips_through_display() {
+        for elevation in ElevationIndex::all() {
+            assert_eq!(elevation.to_string().parse(), Ok(elevation));
+        }
+
+        assert_eq!("ModalSurface".parse(), Ok(ElevationIndex::ModalSurface));
+        assert!("Modal".parse::<ElevationIndex>().is_err());
+    }
+
+    #[test]
+    fn test_elevation_index_round_trips_through_serde() {
+        for elevation in ElevationIndex::all() {
+            let json = serde_json::to_string(&elevation).unwrap();
+            assert_eq!(json, format!("\"{}\"", elevation.name()));
+            assert_eq!(
+                serde_json::from_str::<ElevationIndex>(&json).unwrap(),
+                elevation
+            );
+            assert_eq!(elevation.name().parse(), Ok(elevation));
+        }
+
+        assert_eq!(
+            serde_json::to_string(&ElevationIndex::ModalSurface).unwrap(),
+            "\"ModalSurface\""
+        );
+        assert!(serde_json::from_str::<ElevationIndex>("\"Modal\"").is_err());
+    }
 }
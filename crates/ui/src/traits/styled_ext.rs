@@ -7,7 +7,7 @@ fn elevated<E: Styled>(this: E, cx: &App, index: ElevationIndex) -> E {
     this.bg(cx.theme().colors().elevated_surface_background)
         .rounded_lg()
         .border_1()
-        .border_color(cx.theme().colors().border_variant)
+        .border_color(index.border_color(cx))
         .shadow(index.shadow(cx))
 }
 
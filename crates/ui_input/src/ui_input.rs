@@ -10,7 +10,7 @@ use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{App, Entity, FocusHandle, Focusable, FontStyle, Hsla, TextStyle};
 use settings::Settings;
 use theme::ThemeSettings;
-use ui::prelude::*;
+use ui::{ElevationIndex, prelude::*};
 
 pub struct SingleLineInputStyle {
     text_color: Hsla,
@@ -155,6 +155,7 @@ impl Render for SingleLineInput {
                     .rounded_md()
                     .border_1()
                     .border_color(style.border_color)
+                    .inner_shadow(ElevationIndex::EditorSurface.inner_shadow(cx))
                     .min_w_48()
                     .w_full()
                     .flex_grow()
@@ -2,7 +2,7 @@ use gpui::{
     AnyView, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable as _, ManagedView,
     MouseButton, Subscription,
 };
-use ui::prelude::*;
+use ui::{ElevationIndex, prelude::*};
 
 #[derive(Debug)]
 pub enum DismissDecision {
@@ -177,9 +177,7 @@ impl Render for ModalLayer {
             .top_0()
             .left_0()
             .when(active_modal.modal.fade_out_background(cx), |el| {
-                let mut background = cx.theme().colors().elevated_surface_background;
-                background.fade_out(0.2);
-                el.bg(background)
+                el.bg(ElevationIndex::ModalSurface.scrim(cx))
             })
             .on_mouse_down(
                 MouseButton::Left,
@@ -42,10 +42,10 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
-    ButtonSize, Color, ContextMenu, ContextMenuEntry, ContextMenuItem, DecoratedIcon, IconButton,
-    IconButtonShape, IconDecoration, IconDecorationKind, IconName, IconSize, Indicator, Label,
-    PopoverMenu, PopoverMenuHandle, ScrollableHandle, Tab, TabBar, TabPosition, Tooltip,
-    prelude::*, right_click_menu,
+    ButtonSize, Color, ContextMenu, ContextMenuEntry, ContextMenuItem, DecoratedIcon,
+    ElevationIndex, IconButton, IconButtonShape, IconDecoration, IconDecorationKind, IconName,
+    IconSize, Indicator, Label, PopoverMenu, PopoverMenuHandle, ScrollableHandle, Tab, TabBar,
+    TabPosition, Tooltip, prelude::*, right_click_menu,
 };
 use util::{ResultExt, debug_panic, maybe, truncate_and_remove_front};
 
@@ -3712,6 +3712,8 @@ impl Render for DraggedTab {
             .child(label)
             .render(window, cx)
             .font(ui_font)
+            .bg(ElevationIndex::DraggedElement.bg(cx))
+            .shadow(ElevationIndex::DraggedElement.shadow(cx))
     }
 }
 
@@ -148,6 +148,7 @@ impl Column for SerializedWindowBounds {
 #[derive(Debug)]
 pub struct Breakpoint {
     pub position: u32,
+    pub column: Option<u32>,
     pub message: Option<Arc<str>>,
     pub condition: Option<Arc<str>>,
     pub hit_condition: Option<Arc<str>>,
@@ -193,8 +194,8 @@ struct Breakpoints(Vec<Breakpoint>);
 
 impl sqlez::bindable::StaticColumnCount for Breakpoint {
     fn column_count() -> usize {
-        // Position, log message, condition message, and hit condition message
-        4 + BreakpointStateWrapper::column_count()
+        // Position, column, log message, condition message, and hit condition message
+        5 + BreakpointStateWrapper::column_count()
     }
 }
 
@@ -205,6 +206,7 @@ impl sqlez::bindable::Bind for Breakpoint {
         start_index: i32,
     ) -> anyhow::Result<i32> {
         let next_index = statement.bind(&self.position, start_index)?;
+        let next_index = statement.bind(&self.column, next_index)?;
         let next_index = statement.bind(&self.message, next_index)?;
         let next_index = statement.bind(&self.condition, next_index)?;
         let next_index = statement.bind(&self.hit_condition, next_index)?;
@@ -221,7 +223,8 @@ impl Column for Breakpoint {
             .column_int(start_index)
             .with_context(|| format!("Failed to read BreakPoint at index {start_index}"))?
             as u32;
-        let (message, next_index) = Option::<String>::column(statement, start_index + 1)?;
+        let (column, next_index) = Option::<u32>::column(statement, start_index + 1)?;
+        let (message, next_index) = Option::<String>::column(statement, next_index)?;
         let (condition, next_index) = Option::<String>::column(statement, next_index)?;
         let (hit_condition, next_index) = Option::<String>::column(statement, next_index)?;
         let (state, next_index) = BreakpointStateWrapper::column(statement, next_index)?;
@@ -229,6 +232,7 @@ impl Column for Breakpoint {
         Ok((
             Breakpoint {
                 position,
+                column,
                 message: message.map(Arc::from),
                 condition: condition.map(Arc::from),
                 hit_condition: hit_condition.map(Arc::from),
@@ -542,6 +546,9 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        ALTER TABLE breakpoints ADD COLUMN breakpoint_column INTEGER;
+    ),
     ];
 }
 
@@ -694,7 +701,7 @@ impl WorkspaceDb {
     fn breakpoints(&self, workspace_id: WorkspaceId) -> BTreeMap<Arc<Path>, Vec<SourceBreakpoint>> {
         let breakpoints: Result<Vec<(PathBuf, Breakpoint)>> = self
             .select_bound(sql! {
-                SELECT path, breakpoint_location, log_message, condition, hit_condition, state
+                SELECT path, breakpoint_location, breakpoint_column, log_message, condition, hit_condition, state
                 FROM breakpoints
                 WHERE workspace_id = ?
             })
@@ -712,6 +719,7 @@ impl WorkspaceDb {
                     let path: Arc<Path> = path.into();
                     map.entry(path.clone()).or_default().push(SourceBreakpoint {
                         row: breakpoint.position,
+                        column: breakpoint.column,
                         path,
                         message: breakpoint.message,
                         condition: breakpoint.condition,
@@ -755,13 +763,14 @@ impl WorkspaceDb {
                     for bp in breakpoints {
                         let state = BreakpointStateWrapper::from(bp.state);
                         match conn.exec_bound(sql!(
-                            INSERT INTO breakpoints (workspace_id, path, breakpoint_location,  log_message, condition, hit_condition, state)
-                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);))?
+                            INSERT INTO breakpoints (workspace_id, path, breakpoint_location, breakpoint_column, log_message, condition, hit_condition, state)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);))?
 
                         ((
                             workspace.id,
                             path.as_ref(),
                             bp.row,
+                            bp.column,
                             bp.message,
                             bp.condition,
                             bp.hit_condition,
@@ -1475,6 +1484,7 @@ mod tests {
 
         let breakpoint = Breakpoint {
             position: 123,
+            column: None,
             message: None,
             state: BreakpointState::Enabled,
             condition: None,
@@ -1483,6 +1493,7 @@ mod tests {
 
         let log_breakpoint = Breakpoint {
             position: 456,
+            column: None,
             message: Some("Test log message".into()),
             state: BreakpointState::Enabled,
             condition: None,
@@ -1491,6 +1502,7 @@ mod tests {
 
         let disable_breakpoint = Breakpoint {
             position: 578,
+            column: None,
             message: None,
             state: BreakpointState::Disabled,
             condition: None,
@@ -1499,6 +1511,7 @@ mod tests {
 
         let condition_breakpoint = Breakpoint {
             position: 789,
+            column: Some(8),
             message: None,
             state: BreakpointState::Enabled,
             condition: Some("x > 5".into()),
@@ -1507,6 +1520,7 @@ mod tests {
 
         let hit_condition_breakpoint = Breakpoint {
             position: 999,
+            column: None,
             message: None,
             state: BreakpointState::Enabled,
             condition: None,
@@ -1528,6 +1542,7 @@ mod tests {
                     vec![
                         SourceBreakpoint {
                             row: breakpoint.position,
+                            column: None,
                             path: Arc::from(path),
                             message: breakpoint.message.clone(),
                             state: breakpoint.state,
@@ -1536,6 +1551,7 @@ mod tests {
                         },
                         SourceBreakpoint {
                             row: log_breakpoint.position,
+                            column: None,
                             path: Arc::from(path),
                             message: log_breakpoint.message.clone(),
                             state: log_breakpoint.state,
@@ -1544,6 +1560,7 @@ mod tests {
                         },
                         SourceBreakpoint {
                             row: disable_breakpoint.position,
+                            column: None,
                             path: Arc::from(path),
                             message: disable_breakpoint.message.clone(),
                             state: disable_breakpoint.state,
@@ -1552,6 +1569,7 @@ mod tests {
                         },
                         SourceBreakpoint {
                             row: condition_breakpoint.position,
+                            column: condition_breakpoint.column,
                             path: Arc::from(path),
                             message: condition_breakpoint.message.clone(),
                             state: condition_breakpoint.state,
@@ -1560,6 +1578,7 @@ mod tests {
                         },
                         SourceBreakpoint {
                             row: hit_condition_breakpoint.position,
+                            column: None,
                             path: Arc::from(path),
                             message: hit_condition_breakpoint.message.clone(),
                             state: hit_condition_breakpoint.state,
@@ -1583,6 +1602,7 @@ mod tests {
 
         // normal breakpoint
         assert_eq!(loaded_breakpoints[0].row, breakpoint.position);
+        assert_eq!(loaded_breakpoints[0].column, breakpoint.column);
         assert_eq!(loaded_breakpoints[0].message, breakpoint.message);
         assert_eq!(loaded_breakpoints[0].condition, breakpoint.condition);
         assert_eq!(
@@ -1619,6 +1639,7 @@ mod tests {
 
         // condition breakpoint
         assert_eq!(loaded_breakpoints[3].row, condition_breakpoint.position);
+        assert_eq!(loaded_breakpoints[3].column, condition_breakpoint.column);
         assert_eq!(loaded_breakpoints[3].message, condition_breakpoint.message);
         assert_eq!(
             loaded_breakpoints[3].condition,
@@ -1660,6 +1681,7 @@ mod tests {
 
         let breakpoint_to_remove = Breakpoint {
             position: 100,
+            column: None,
             message: None,
             state: BreakpointState::Enabled,
             condition: None,
@@ -1680,6 +1702,7 @@ mod tests {
                     Arc::from(singular_path),
                     vec![SourceBreakpoint {
                         row: breakpoint_to_remove.position,
+                        column: None,
                         path: Arc::from(singular_path),
                         message: None,
                         state: BreakpointState::Enabled,
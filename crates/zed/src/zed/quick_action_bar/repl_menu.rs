@@ -30,6 +30,7 @@ struct ReplMenuState {
     status: KernelStatus,
     kernel_name: SharedString,
     kernel_language: SharedString,
+    kernel_process_id: Option<u32>,
 }
 
 impl QuickActionBar {
@@ -183,8 +184,8 @@ impl QuickActionBar {
                         },
                         {
                             let editor = editor.clone();
-                            move |window, cx| {
-                                repl::shutdown(editor.clone(), window, cx);
+                            move |_, cx| {
+                                repl::shutdown(editor.clone(), cx);
                             }
                         },
                     )
@@ -202,10 +203,26 @@ impl QuickActionBar {
                             }
                         },
                     )
+                    .custom_entry(
+                        move |_window, _cx| {
+                            Label::new("Duplicate Session")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |window, cx| {
+                                repl::duplicate_session(editor.clone(), window, cx);
+                            }
+                        },
+                    )
+                    .when(menu_state.kernel_process_id.is_some(), |menu| {
+                        menu.action("Reveal Kernel Process", Box::new(repl::RevealKernelProcess))
+                    })
                     .separator()
                     .action("View Sessions", Box::new(repl::Sessions))
-                    // TODO: Add shut down all kernels action
-                    // .action("Shut Down all Kernels", Box::new(gpui::NoAction))
+                    .action("Shut Down All Kernels", Box::new(repl::ShutdownAllKernels))
                 })
                 .into()
             })
@@ -388,6 +405,7 @@ fn session_state(session: Entity<Session>, cx: &mut App) -> ReplMenuState {
             indicator: None,
             kernel_name: kernel_name.clone(),
             kernel_language: kernel_language.clone(),
+            kernel_process_id: session.kernel.process_id(),
             // TODO: Technically not shutdown, but indeterminate
             status: KernelStatus::Shutdown,
             // current_delta: Duration::default(),
@@ -405,11 +423,19 @@ fn session_state(session: Entity<Session>, cx: &mut App) -> ReplMenuState {
             ..fill_fields()
         },
         Kernel::RunningKernel(kernel) => match &kernel.execution_state() {
-            ExecutionState::Idle => ReplMenuState {
-                tooltip: format!("Run code on {} ({})", kernel_name, kernel_language).into(),
-                indicator: Some(Indicator::dot().color(Color::Success)),
-                status: session.kernel.status(),
-                ..fill_fields()
+            ExecutionState::Idle => match session.startup_error() {
+                Some(error) => ReplMenuState {
+                    tooltip: format!("Startup code failed on {}: {}", kernel_name, error).into(),
+                    indicator: Some(Indicator::dot().color(Color::Warning)),
+                    status: session.kernel.status(),
+                    ..fill_fields()
+                },
+                None => ReplMenuState {
+                    tooltip: format!("Run code on {} ({})", kernel_name, kernel_language).into(),
+                    indicator: Some(Indicator::dot().color(Color::Success)),
+                    status: session.kernel.status(),
+                    ..fill_fields()
+                },
             },
             ExecutionState::Busy => ReplMenuState {
                 tooltip: format!("Interrupt {} ({})", kernel_name, kernel_language).into(),